@@ -0,0 +1,16 @@
+/*
+** fuzz/fuzz_targets/day_15.rs
+*/
+
+#![no_main]
+
+use aoc2022::context::Context;
+use aoc2022::parts::Parts;
+use aoc2022::puzzles::DAYS;
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    // malformed input may fail with an error, but must not panic
+    let _ = DAYS[15 - 1](input, Parts::Both, &Context::none());
+});
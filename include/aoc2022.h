@@ -0,0 +1,31 @@
+/*
+** include/aoc2022.h
+** generated by aoc2022::ffi::header(), do not edit
+*/
+
+#ifndef AOC2022_H
+#define AOC2022_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#define AOC2022_STATUS_OK 0
+#define AOC2022_STATUS_INVALID_DAY -1
+#define AOC2022_STATUS_INVALID_INPUT -2
+#define AOC2022_STATUS_FAILED -3
+
+typedef struct {
+    int32_t status;
+    /* NUL-terminated answers, or NULL for parts without an answer */
+    char *part_1;
+    char *part_2;
+    double seconds;
+} aoc2022_result;
+
+/* solves the day on the input, returning the status; release the result
+ * with aoc2022_result_free */
+int32_t aoc2022_run(uint32_t day, const uint8_t *input, size_t len, aoc2022_result *out);
+
+void aoc2022_result_free(aoc2022_result *result);
+
+#endif
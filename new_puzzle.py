@@ -2,45 +2,64 @@
 
 import os
 import pathlib
+import re
 import sys
 
-mod_template = """\
-/*
-** src/puzzles/mod.rs
-*/
-
-<M>
-
-use crate::types::Puzzle;
-
-pub const N_DAYS: usize = <N>;
-
-pub const DAYS: [Puzzle; N_DAYS] = [
-<P>
-];
-"""
-
 puzzle_template = """\
 /*
 ** src/puzzles/day_<D>.rs
 ** https://adventofcode.com/2022/day/<D>
 */
 
-use crate::types::Solution;
-use crate::utils;
+use crate::prelude::*;
+
+pub struct Day<D>;
 
-use anyhow::Result;
+impl Puzzle for Day<D> {
+    type Parsed = Vec<String>;
 
-pub fn run(input: String) -> Result<Solution> {
-    let mut solution = Solution::new();
+    const DAY: usize = <D>;
+    const TITLE: &'static str = "<T>";
+    // TODO: describe how the puzzle is solved
+    const METADATA: Metadata = Metadata {
+        algorithm: "???",
+        complexity: "O(n)",
+        tags: &[],
+    };
 
-    // part 1: ???
+    fn parse(input: PuzzleInput) -> Result<Self::Parsed> {
+        Ok(input.lines().map(String::from).collect())
+    }
 
-    // part 2: ???
+    fn part_1(_parsed: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 1: ???
+        todo!()
+    }
 
-    Ok(solution)
+    fn part_2(_parsed: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 2: ???
+        todo!()
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parts::Parts;
+    use crate::puzzles::solve;
+
+    const SAMPLE_INPUT: &str = include_str!("../../input/D<D>.dbg.txt");
+
+    #[test]
+    fn sample_input() {
+        let solution = solve::<Day<D>>(SAMPLE_INPUT, Parts::Both, &Context::none()).unwrap();
+        // TODO: fill in the example answers from the puzzle description
+        let answer_1 = solution.part_1.map(|answer| answer.to_string());
+        let answer_2 = solution.part_2.map(|answer| answer.to_string());
+        assert_eq!(answer_1.as_deref(), Some("TODO"));
+        assert_eq!(answer_2.as_deref(), Some("TODO"));
+    }
+}
 """
 
 
@@ -51,29 +70,45 @@ if __name__ == "__main__":
         sys.exit("error: missing argument DAY")
     except ValueError:
         sys.exit(f"error: invalid argument DAY")
-    add_sample = "-s" in sys.argv or "--sample" in sys.argv
 
     current_dir = os.path.dirname(os.path.abspath(__file__))
     puzzle_dir = os.path.join(current_dir, "src", "puzzles")
     input_dir = os.path.join(current_dir, "input")
 
+    # register the puzzle in mod.rs, adding its line to register_days! and
+    # taking its title from its stub
+    mod_path = os.path.join(puzzle_dir, "mod.rs")
+    with open(mod_path) as mod_file:
+        lines = mod_file.read().split("\n")
+    start = lines.index("register_days! {") + 1
+    stubs_start = lines.index("    stubs:", start)
+    end = lines.index("}", stubs_start)
+    entries = {
+        int(re.match(r"\s*(\d+) =>", line).group(1)): line
+        for line in lines[start:stubs_start]
+    }
+    stubs = {
+        int(match.group(1)): match.group(2)
+        for match in (
+            re.match(r'\s*(\d+) => "(.*)",', line)
+            for line in lines[(stubs_start + 1):end]
+        )
+    }
+    title = stubs.pop(n, "???")
+    entries[n] = f"    {n} => day_{n}::Day{n},"
+    lines[start:end] = (
+        [entries[day] for day in sorted(entries)]
+        + ["    stubs:"]
+        + [f'    {day} => "{stubs[day]}",' for day in sorted(stubs)]
+    )
+    with open(mod_path, "w") as mod_file:
+        mod_file.write("\n".join(lines))
+
     # write the puzzle source file
     with open(os.path.join(puzzle_dir, f"day_{n}.rs"), "w") as puzzle_file:
-        puzzle_file.write(puzzle_template.replace("<D>", str(n)))
-
-    # write the mod.rs file
-    with open(os.path.join(puzzle_dir, "mod.rs"), "w") as mod_file:
-        # sort to match rustfmt
-        mods = "\n".join(sorted(f"mod day_{i + 1};" for i in range(n)))
-        puzzles = "\n".join(f"    day_{i + 1}::run," for i in range(n))
-        mod_file.write(
-            mod_template
-                .replace("<N>", str(n))
-                .replace("<M>", mods)
-                .replace("<P>", puzzles))
+        puzzle_file.write(puzzle_template.replace("<D>", str(n)).replace("<T>", title))
 
     # touch the input file
     pathlib.Path(os.path.join(input_dir, f"D{n}.txt")).touch()
-    # touch the sample input file, if requested
-    if add_sample:
-        pathlib.Path(os.path.join(input_dir, f"D{n}.dbg.txt")).touch()
+    # touch the sample input file, which is used by the generated test
+    pathlib.Path(os.path.join(input_dir, f"D{n}.dbg.txt")).touch()
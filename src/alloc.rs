@@ -0,0 +1,141 @@
+/*
+** src/alloc.rs
+*/
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// wraps the system allocator to count allocations and track the peak number
+/// of bytes in use
+pub struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static CURRENT_BYTES: AtomicUsize = AtomicUsize::new(0);
+static PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+impl CountingAllocator {
+    fn add(size: usize) {
+        let current = CURRENT_BYTES.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK_BYTES.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn sub(size: usize) {
+        CURRENT_BYTES.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            Self::add(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            Self::add(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::sub(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            // count reallocations as allocations, since they usually copy
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            Self::sub(layout.size());
+            Self::add(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// allocation statistics over some span of the program
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AllocStats {
+    pub allocations: usize,
+    /// peak bytes in use above what was in use at the start of the span
+    pub peak_bytes: usize,
+}
+
+/// formats a number of bytes with a unit that suits its magnitude, so that
+/// the days which use megabytes stand out from those which use kilobytes
+fn format_bytes(bytes: usize) -> String {
+    const KIB: usize = 1024;
+    const MIB: usize = 1024 * 1024;
+    if bytes < KIB {
+        format!("{}B", bytes)
+    } else if bytes < MIB {
+        format!("{:.1}KiB", bytes as f64 / KIB as f64)
+    } else {
+        format!("{:.1}MiB", bytes as f64 / MIB as f64)
+    }
+}
+
+impl fmt::Display for AllocStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} allocations, peak {}",
+            self.allocations,
+            format_bytes(self.peak_bytes)
+        )
+    }
+}
+
+/// marks the start of a span, from which stats are measured
+pub struct AllocSpan {
+    allocations: usize,
+    base_bytes: usize,
+}
+
+impl AllocSpan {
+    /// starts a span, resetting the peak to the bytes currently in use
+    pub fn start() -> Self {
+        let base_bytes = CURRENT_BYTES.load(Ordering::Relaxed);
+        PEAK_BYTES.store(base_bytes, Ordering::Relaxed);
+        Self {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed),
+            base_bytes,
+        }
+    }
+
+    pub fn stats(&self) -> AllocStats {
+        AllocStats {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed) - self.allocations,
+            peak_bytes: PEAK_BYTES
+                .load(Ordering::Relaxed)
+                .saturating_sub(self.base_bytes),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_stats() {
+        let stats = |allocations, peak_bytes| AllocStats {
+            allocations,
+            peak_bytes,
+        };
+        assert_eq!(stats(0, 0).to_string(), "0 allocations, peak 0B");
+        assert_eq!(stats(3, 2560).to_string(), "3 allocations, peak 2.5KiB");
+        assert_eq!(
+            stats(510, 16 * 1024 * 1024 + 512 * 1024).to_string(),
+            "510 allocations, peak 16.5MiB"
+        );
+    }
+}
@@ -0,0 +1,505 @@
+/*
+** src/answers.rs
+*/
+
+use crate::client;
+use crate::history::DayRecord;
+use crate::json::Json;
+use crate::puzzles::{self, Registration};
+use crate::utils;
+
+use anyhow::{anyhow, Result};
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const PROJECT_DIR: &str = env!("CARGO_MANIFEST_DIR");
+
+/// the git-ignored file of answers to the real inputs, which takes precedence
+/// over the answers declared by each day, see Answers::real
+pub fn local_answers_path() -> PathBuf {
+    Path::new(PROJECT_DIR).join("answers.local.toml")
+}
+
+/// records an answer which the website has confirmed as correct, returning
+/// false if it was already known
+pub fn record_confirmed(path: &Path, day: usize, part: usize, answer: &str) -> Result<bool> {
+    let mut answers = if path.exists() {
+        Answers::load(path)?
+    } else {
+        Answers::new()
+    };
+    if answers.get(day, part) == Some(answer) {
+        return Ok(false);
+    }
+    answers.set(day, part, answer.to_string());
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    answers.save(path)?;
+    Ok(true)
+}
+
+/// the store of the accepted answer for each part of the real inputs, which
+/// each later run of the real inputs is checked against
+pub fn regressions_path() -> PathBuf {
+    client::cache_dir().join("answers.json")
+}
+
+/// an answer which differs from the accepted answer for the part
+#[derive(Debug, PartialEq, Eq)]
+pub struct Regression {
+    pub day: usize,
+    pub part: usize,
+    pub recorded: String,
+    pub answer: String,
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "day {} part {}: answer changed from {} to {}",
+            self.day, self.part, self.recorded, self.answer
+        )
+    }
+}
+
+/// checks the answers of a run against the store, and returns the answers
+/// which changed; an answer is only accepted into the store once it has been
+/// confirmed as correct, unless rerecord is given, which replaces the stored
+/// answers with those of the run
+pub fn check_regressions(
+    path: &Path,
+    confirmed: &Answers,
+    records: &[DayRecord],
+    rerecord: bool,
+) -> Result<Vec<Regression>> {
+    let mut store = if path.exists() {
+        Answers::load(path)?
+    } else {
+        Answers::new()
+    };
+    let mut regressions = Vec::new();
+    let mut n_recorded = 0;
+    for record in records.iter() {
+        for (part, answer) in [(1, &record.part_1), (2, &record.part_2)] {
+            let Some(answer) = answer else {
+                continue;
+            };
+            let accepted = store
+                .get(record.day, part)
+                .or_else(|| confirmed.get(record.day, part));
+            match accepted {
+                Some(recorded) if recorded != answer && !rerecord => {
+                    regressions.push(Regression {
+                        day: record.day,
+                        part,
+                        recorded: recorded.to_string(),
+                        answer: answer.clone(),
+                    });
+                    continue;
+                }
+                None if !rerecord => continue,
+                _ => {}
+            }
+            // the answer is accepted, and is stored unless it already is
+            if store.get(record.day, part) != Some(answer.as_str()) {
+                store.set(record.day, part, answer.clone());
+                n_recorded += 1;
+            }
+        }
+    }
+    if n_recorded > 0 {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        store.save(path)?;
+    }
+    Ok(regressions)
+}
+
+/// the result of checking an answer against the known answer
+#[derive(Debug, PartialEq, Eq)]
+pub enum Check {
+    Pass,
+    /// the answer did not match, or was missing, with the expected answer
+    Fail(String),
+    /// there is no known answer
+    Unknown,
+}
+
+/// known answers to each puzzle, keyed by day and part
+///
+/// these are stored in a small subset of TOML, one table per day:
+///
+/// ```toml
+/// [day_1]
+/// part_1 = 24000
+/// part_2 = "45000"
+/// ```
+///
+/// values may be integers or basic strings; multi-line answers are written as
+/// basic strings with `\n` escapes
+#[derive(Default)]
+pub struct Answers(BTreeMap<(usize, usize), String>);
+
+impl Answers {
+    pub fn new() -> Self {
+        Self(BTreeMap::new())
+    }
+
+    /// loads answers from a TOML file, or from a JSON file of the same shape
+    /// if the path ends in .json
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = utils::read_file(path)?;
+        let answers = if path.extension().is_some_and(|ext| ext == "json") {
+            Self::from_json(&contents)
+        } else {
+            Self::from_toml(&contents)
+        };
+        answers.map_err(|e| anyhow!("{}: {}", path.to_string_lossy(), e))
+    }
+
+    /// saves the answers as TOML, or as JSON if the path ends in .json
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if path.extension().is_some_and(|ext| ext == "json") {
+            fs::write(path, format!("{}\n", self.to_json()))?;
+        } else {
+            fs::write(path, self.to_toml())?;
+        }
+        Ok(())
+    }
+
+    /// the answers declared by each solved day, see types::Puzzle
+    fn declared(answers: fn(&Registration) -> [Option<&'static str>; 2]) -> Self {
+        let mut declared = Self::new();
+        for registration in puzzles::REGISTRY.iter() {
+            for (i, answer) in answers(registration).into_iter().enumerate() {
+                if let Some(answer) = answer {
+                    declared.set(registration.day, i + 1, answer.to_string());
+                }
+            }
+        }
+        declared
+    }
+
+    /// the answers to the sample inputs, as given in the puzzle descriptions
+    pub fn samples() -> Self {
+        Self::declared(|registration| registration.sample_answers)
+    }
+
+    /// the answers confirmed as correct by the website, which are recorded in
+    /// answers.local.toml when submitted, see record_confirmed
+    pub fn confirmed() -> Result<Self> {
+        let path = local_answers_path();
+        if path.exists() {
+            Self::load(&path)
+        } else {
+            Ok(Self::new())
+        }
+    }
+
+    /// the answers to the real inputs, as declared by each day, along with
+    /// those recorded in answers.local.toml, which take precedence
+    pub fn real() -> Result<Self> {
+        let mut answers = Self::declared(|registration| registration.answers);
+        answers.0.extend(Self::confirmed()?.0);
+        Ok(answers)
+    }
+
+    pub fn get(&self, day: usize, part: usize) -> Option<&str> {
+        self.0.get(&(day, part)).map(|s| s.as_str())
+    }
+
+    pub fn set(&mut self, day: usize, part: usize, answer: String) {
+        self.0.insert((day, part), answer);
+    }
+
+    /// the days which have at least one known answer, in order
+    pub fn days(&self) -> Vec<usize> {
+        let mut days = self.0.keys().map(|&(day, _)| day).collect::<Vec<_>>();
+        days.dedup();
+        days
+    }
+
+    pub fn from_toml(s: &str) -> Result<Self> {
+        let mut answers = Self::new();
+        let mut day = None;
+        for (i, line) in utils::split_lines(s).enumerate() {
+            let line = line.trim();
+            // skip blank lines and comments
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let lineno = i + 1;
+            if let Some(table) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                // table headers give the day
+                let n = table
+                    .trim()
+                    .strip_prefix("day_")
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .ok_or_else(|| anyhow!("line {}: invalid table [{}]", lineno, table))?;
+                day = Some(n);
+            } else if let Some((key, value)) = line.split_once('=') {
+                // keys give the part
+                let day = day.ok_or_else(|| anyhow!("line {}: key outside of a table", lineno))?;
+                let part = key
+                    .trim()
+                    .strip_prefix("part_")
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .ok_or_else(|| anyhow!("line {}: invalid key {}", lineno, key.trim()))?;
+                let value = parse_toml_value(value.trim())
+                    .ok_or_else(|| anyhow!("line {}: invalid value {}", lineno, value.trim()))?;
+                answers.set(day, part, value);
+            } else {
+                return Err(anyhow!("line {}: expected a table or key", lineno));
+            }
+        }
+        Ok(answers)
+    }
+
+    /// parses answers from JSON with the same shape as the TOML, such as
+    /// `{"day_1": {"part_1": 24000, "part_2": "45000"}}`
+    pub fn from_json(s: &str) -> Result<Self> {
+        let mut answers = Self::new();
+        let json = Json::parse(s)?;
+        let days = json
+            .as_object()
+            .ok_or_else(|| anyhow!("expected an object of days"))?;
+        for (table, parts) in days.iter() {
+            let day = table
+                .strip_prefix("day_")
+                .and_then(|n| n.parse::<usize>().ok())
+                .ok_or_else(|| anyhow!("invalid day {}", table))?;
+            let parts = parts
+                .as_object()
+                .ok_or_else(|| anyhow!("{}: expected an object of parts", table))?;
+            for (key, value) in parts.iter() {
+                let part = key
+                    .strip_prefix("part_")
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .ok_or_else(|| anyhow!("{}: invalid key {}", table, key))?;
+                // numbers must be integers, as in the TOML
+                let value = match value {
+                    Json::String(s) => s.clone(),
+                    Json::Integer(n) => n.to_string(),
+                    _ => return Err(anyhow!("{}.{}: invalid value {}", table, key, value)),
+                };
+                answers.set(day, part, value);
+            }
+        }
+        Ok(answers)
+    }
+
+    /// checks an answer against the known answer for the day and part
+    pub fn check(&self, day: usize, part: usize, answer: Option<&str>) -> Check {
+        match self.get(day, part) {
+            None => Check::Unknown,
+            Some(expected) if answer == Some(expected) => Check::Pass,
+            Some(expected) => Check::Fail(expected.to_string()),
+        }
+    }
+
+    pub fn to_toml(&self) -> String {
+        let mut s = String::new();
+        let mut prev_day = None;
+        for (&(day, part), answer) in self.0.iter() {
+            if prev_day != Some(day) {
+                if prev_day.is_some() {
+                    s.push('\n');
+                }
+                s.push_str(&format!("[day_{}]\n", day));
+                prev_day = Some(day);
+            }
+            s.push_str(&format!("part_{} = {}\n", part, toml_string(answer)));
+        }
+        s
+    }
+
+    /// the answers as JSON, with the same shape as read by from_json
+    pub fn to_json(&self) -> Json {
+        Json::object(self.days().into_iter().map(|day| {
+            let parts = self
+                .0
+                .range((day, 0)..=(day, usize::MAX))
+                .map(|(&(_, part), answer)| {
+                    (format!("part_{}", part), Json::from(answer.as_str()))
+                });
+            (format!("day_{}", day), Json::object(parts))
+        }))
+    }
+}
+
+/// parses an integer or a basic string, handling escapes
+fn parse_toml_value(s: &str) -> Option<String> {
+    if let Some(inner) = s.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+        let mut value = String::new();
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            if c == '\\' {
+                match chars.next()? {
+                    'n' => value.push('\n'),
+                    't' => value.push('\t'),
+                    '"' => value.push('"'),
+                    '\\' => value.push('\\'),
+                    _ => return None,
+                }
+            } else {
+                value.push(c);
+            }
+        }
+        Some(value)
+    } else {
+        s.parse::<i64>().ok().map(|n| n.to_string())
+    }
+}
+
+/// writes a value as a basic string, escaping as necessary
+fn toml_string(s: &str) -> String {
+    let escaped = s
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t");
+    format!("\"{}\"", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_answers() {
+        let input = "# comment\n[day_1]\npart_1 = 24000\npart_2 = \"45000\"\n\n[day_10]\npart_2 = \"\\n#.\\n.#\"";
+        let answers = Answers::from_toml(input).unwrap();
+        assert_eq!(answers.get(1, 1), Some("24000"));
+        assert_eq!(answers.get(1, 2), Some("45000"));
+        assert_eq!(answers.get(10, 1), None);
+        assert_eq!(answers.get(10, 2), Some("\n#.\n.#"));
+        assert_eq!(answers.days(), vec![1, 10]);
+    }
+
+    #[test]
+    fn parse_json_answers() {
+        let input = "{\"day_1\": {\"part_1\": 24000, \"part_2\": \"45000\"}, \"day_10\": {}}";
+        let answers = Answers::from_json(input).unwrap();
+        assert_eq!(answers.get(1, 1), Some("24000"));
+        assert_eq!(answers.get(1, 2), Some("45000"));
+        assert_eq!(answers.days(), vec![1]);
+        assert!(Answers::from_json("[]").is_err());
+        assert!(Answers::from_json("{\"one\": {}}").is_err());
+        assert!(Answers::from_json("{\"day_1\": {\"part_1\": 1.5}}").is_err());
+        let large = Answers::from_json("{\"day_15\": {\"part_2\": 9007199254740993}}").unwrap();
+        assert_eq!(large.get(15, 2), Some("9007199254740993"));
+    }
+
+    #[test]
+    fn check_answers() {
+        let answers = Answers::samples();
+        assert_eq!(answers.check(1, 1, Some("24000")), Check::Pass);
+        assert_eq!(
+            answers.check(1, 1, Some("24001")),
+            Check::Fail(String::from("24000"))
+        );
+        assert_eq!(
+            answers.check(1, 2, None),
+            Check::Fail(String::from("45000"))
+        );
+        assert_eq!(answers.check(25, 2, Some("x")), Check::Unknown);
+    }
+
+    #[test]
+    fn parse_answers_invalid() {
+        assert!(Answers::from_toml("part_1 = 5").is_err());
+        assert!(Answers::from_toml("[one]\npart_1 = 5").is_err());
+        assert!(Answers::from_toml("[day_1]\npart_1 = five").is_err());
+    }
+
+    #[test]
+    fn declared_answers() {
+        let answers = Answers::samples();
+        assert_eq!(answers.get(5, 1), Some("CMZ"));
+        assert_eq!(answers.get(10, 2).map(|a| a.lines().count()), Some(7));
+        assert_eq!(answers.get(25, 2), None);
+        assert_eq!(answers.days().len(), puzzles::REGISTRY.len());
+        assert!(Answers::real().unwrap().get(16, 2).is_some());
+    }
+
+    #[test]
+    fn record_confirmed_answers() {
+        let path = std::env::temp_dir().join(format!("aoc-answers-{}.toml", std::process::id()));
+        assert!(record_confirmed(&path, 1, 1, "75501").unwrap());
+        assert!(!record_confirmed(&path, 1, 1, "75501").unwrap());
+        assert!(record_confirmed(&path, 1, 2, "215594").unwrap());
+        let answers = Answers::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(answers.get(1, 1), Some("75501"));
+        assert_eq!(answers.get(1, 2), Some("215594"));
+    }
+
+    #[test]
+    fn check_answer_regressions() {
+        let path = std::env::temp_dir().join(format!("aoc-answers-{}.json", std::process::id()));
+        let record = |part_1: &str, part_2: Option<&str>| DayRecord {
+            day: 1,
+            seconds: 0.0,
+            part_1: Some(part_1.to_string()),
+            part_2: part_2.map(|answer| answer.to_string()),
+        };
+        let mut confirmed = Answers::new();
+        confirmed.set(1, 1, String::from("75501"));
+        // answers are only accepted once confirmed, and later answers are
+        // checked against them
+        assert!(
+            check_regressions(&path, &confirmed, &[record("75501", Some("215594"))], false)
+                .unwrap()
+                .is_empty()
+        );
+        let answers = Answers::load(&path).unwrap();
+        assert_eq!(answers.get(1, 1), Some("75501"));
+        assert_eq!(answers.get(1, 2), None);
+        let regressions =
+            check_regressions(&path, &Answers::new(), &[record("75502", None)], false).unwrap();
+        assert_eq!(
+            regressions,
+            [Regression {
+                day: 1,
+                part: 1,
+                recorded: String::from("75501"),
+                answer: String::from("75502"),
+            }]
+        );
+        assert_eq!(
+            regressions[0].to_string(),
+            "day 1 part 1: answer changed from 75501 to 75502"
+        );
+        // the changed answer does not replace the accepted answer unless the
+        // answers are rerecorded
+        assert_eq!(Answers::load(&path).unwrap().get(1, 1), Some("75501"));
+        assert!(
+            check_regressions(&path, &confirmed, &[record("75502", Some("215594"))], true)
+                .unwrap()
+                .is_empty()
+        );
+        let answers = Answers::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(answers.get(1, 1), Some("75502"));
+        assert_eq!(answers.get(1, 2), Some("215594"));
+    }
+
+    #[test]
+    fn answers_round_trip() {
+        let mut answers = Answers::new();
+        answers.set(5, 1, String::from("CMZ"));
+        answers.set(10, 2, String::from("\n##\n..\"\\"));
+        let output = Answers::from_toml(&answers.to_toml()).unwrap();
+        assert_eq!(output.get(5, 1), Some("CMZ"));
+        assert_eq!(output.get(10, 2), Some("\n##\n..\"\\"));
+        let output = Answers::from_json(&answers.to_json().to_string()).unwrap();
+        assert_eq!(output.get(5, 1), Some("CMZ"));
+        assert_eq!(output.get(10, 2), Some("\n##\n..\"\\"));
+    }
+}
@@ -0,0 +1,136 @@
+/*
+** src/artifacts.rs
+*/
+
+use crate::types::Point;
+use crate::viz::Animation;
+
+use anyhow::{anyhow, Result};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// artifacts are only produced once they have been enabled, as rendering them
+/// can take longer than solving
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// starts producing artifacts along with the solutions
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// an output of a solved puzzle which is not an answer, such as its final grid
+pub enum Artifact {
+    /// a grid already rendered as text
+    Grid { name: &'static str, text: String },
+    /// a rendered image, which may be animated
+    Image {
+        name: &'static str,
+        animation: Animation,
+    },
+    /// a sequence of points, such as a route through a grid
+    Path {
+        name: &'static str,
+        points: Vec<Point>,
+    },
+}
+
+impl Artifact {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Grid { name, .. } | Self::Image { name, .. } | Self::Path { name, .. } => name,
+        }
+    }
+
+    /// the artifact as text, with images shown by their final frame
+    pub fn to_text(&self) -> String {
+        match self {
+            Self::Grid { text, .. } => text.clone(),
+            Self::Image { animation, .. } => animation
+                .last()
+                .map(|frame| frame.to_text())
+                .unwrap_or_default(),
+            Self::Path { points, .. } => points
+                .iter()
+                .map(|point| point.to_string())
+                .collect::<Vec<_>>()
+                .join(" -> "),
+        }
+    }
+
+    /// writes the artifact into the directory, as a GIF for images and as
+    /// text otherwise, returning the path written to
+    pub fn save(&self, dir: &Path) -> Result<PathBuf> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(self.name());
+        match self {
+            Self::Image { animation, .. } => {
+                let path = path.with_extension("gif");
+                animation.save_gif(&path)?;
+                Ok(path)
+            }
+            Self::Grid { .. } | Self::Path { .. } => {
+                let path = path.with_extension("txt");
+                fs::write(&path, self.to_text() + "\n")?;
+                Ok(path)
+            }
+        }
+    }
+}
+
+/// what the runner does with the artifacts of each day
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Mode {
+    /// prints them after the answers
+    Print,
+    /// writes them under viz/artifacts/dayN
+    Save,
+    /// does not produce them at all
+    #[default]
+    Skip,
+}
+
+impl FromStr for Mode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "print" => Ok(Self::Print),
+            "save" => Ok(Self::Save),
+            "skip" => Ok(Self::Skip),
+            _ => Err(anyhow!(
+                "unknown artifacts mode {}, expected print, save or skip",
+                s
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_to_text() {
+        let artifact = Artifact::Path {
+            name: "route",
+            points: vec![Point::new(0, 0), Point::new(1, 0), Point::new(1, 1)],
+        };
+        assert_eq!(artifact.name(), "route");
+        assert_eq!(artifact.to_text(), "(0,0) -> (1,0) -> (1,1)");
+    }
+
+    #[test]
+    fn parse_mode() {
+        assert_eq!("print".parse::<Mode>().unwrap(), Mode::Print);
+        assert_eq!("save".parse::<Mode>().unwrap(), Mode::Save);
+        assert_eq!("skip".parse::<Mode>().unwrap(), Mode::Skip);
+        assert!("show".parse::<Mode>().is_err());
+    }
+}
@@ -0,0 +1,88 @@
+/*
+** src/badge.rs
+*/
+
+use crate::calendar::Stars;
+use crate::json::Json;
+use crate::puzzles::N_DAYS;
+
+use anyhow::Result;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// a shields.io endpoint badge, see https://shields.io/endpoint
+pub fn endpoint_json(label: &str, message: &str, color: &str) -> String {
+    let json = Json::object([
+        ("schemaVersion", Json::from(1u64)),
+        ("label", Json::from(label)),
+        ("message", Json::from(message)),
+        ("color", Json::from(color)),
+    ]);
+    format!("{}\n", json)
+}
+
+pub fn stars_badge(stars: &Stars) -> String {
+    let total = stars.total();
+    let color = if total == N_DAYS * 2 {
+        "brightgreen"
+    } else if total >= N_DAYS {
+        "yellow"
+    } else {
+        "orange"
+    };
+    endpoint_json("stars", &format!("{}/{}", total, N_DAYS * 2), color)
+}
+
+/// formats a runtime with a precision that suits its magnitude
+pub fn format_runtime(seconds: f64) -> String {
+    if seconds < 0.001 {
+        String::from("<1ms")
+    } else if seconds < 1.0 {
+        format!("{:.0}ms", seconds * 1000.0)
+    } else {
+        format!("{:.1}s", seconds)
+    }
+}
+
+pub fn runtime_badge(seconds: f64) -> String {
+    endpoint_json("total runtime", &format_runtime(seconds), "blue")
+}
+
+/// writes the badges to the given directory, skipping the runtime badge if
+/// there is no recorded runtime, and returns the paths written
+pub fn write_badges(dir: &Path, stars: &Stars, runtime: Option<f64>) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+    let mut badges = vec![(dir.join("stars.json"), stars_badge(stars))];
+    if let Some(seconds) = runtime {
+        badges.push((dir.join("runtime.json"), runtime_badge(seconds)));
+    }
+    for (path, contents) in badges.iter() {
+        fs::write(path, contents)?;
+    }
+    Ok(badges.into_iter().map(|(path, _)| path).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answers::Answers;
+
+    #[test]
+    fn stars_badge_json() {
+        let mut answers = Answers::new();
+        answers.set(1, 1, String::from("24000"));
+        let badge = stars_badge(&Stars::from_answers(&answers));
+        assert_eq!(
+            badge,
+            "{\"color\":\"orange\",\"label\":\"stars\",\"message\":\"1/50\",\"schemaVersion\":1}\n"
+        );
+    }
+
+    #[test]
+    fn runtime_formats() {
+        assert_eq!(format_runtime(0.0004), "<1ms");
+        assert_eq!(format_runtime(0.1234), "123ms");
+        assert_eq!(format_runtime(28.345), "28.3s");
+    }
+}
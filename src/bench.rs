@@ -0,0 +1,507 @@
+/*
+** src/bench.rs
+*/
+
+use crate::answers::Answers;
+use crate::context::Context;
+use crate::history::DayRecord;
+use crate::input::PuzzleInput;
+use crate::parts::Parts;
+use crate::puzzles;
+use crate::types::{Puzzle, Solution, Solver};
+
+use anyhow::Result;
+
+use std::collections::BTreeSet;
+use std::time::Instant;
+
+/// whether a day's answers match the known answers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Correct,
+    Incorrect,
+    /// there are no known answers for the day
+    Unknown,
+}
+
+impl Status {
+    pub fn check(day: usize, solution: &Solution, answers: &Answers) -> Self {
+        let computed =
+            [&solution.part_1, &solution.part_2].map(|a| a.as_ref().map(|a| a.to_string()));
+        let known = [answers.get(day, 1), answers.get(day, 2)];
+        if known.iter().all(|answer| answer.is_none()) {
+            Self::Unknown
+        } else if computed
+            .iter()
+            .zip(known)
+            .all(|(computed, known)| computed.as_deref() == known)
+        {
+            Self::Correct
+        } else {
+            Self::Incorrect
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Correct => "✓",
+            Self::Incorrect => "✗",
+            Self::Unknown => "?",
+        }
+    }
+}
+
+/// how each day is benchmarked
+#[derive(Clone, Copy, Debug)]
+pub struct Options {
+    /// untimed runs before the timed runs, to warm up the caches
+    pub warmup: usize,
+    /// timed runs, of which at least one is made
+    pub runs: usize,
+    pub parts: Parts,
+}
+
+impl Options {
+    /// times the given number of runs of both parts after the warm-up runs
+    pub fn new(warmup: usize, runs: usize) -> Self {
+        Self {
+            warmup,
+            runs,
+            parts: Parts::Both,
+        }
+    }
+}
+
+pub struct BenchResult {
+    pub day: usize,
+    /// the time taken to parse the input, for split puzzles which parse once
+    pub parse_seconds: Option<f64>,
+    /// statistics of the times of the timed runs
+    pub stats: Stats,
+    pub status: Status,
+    /// the answers of the last run
+    pub answers: [Option<String>; 2],
+}
+
+impl BenchResult {
+    /// the result as a recorded day, with the fastest time, for exporting
+    pub fn to_record(&self) -> DayRecord {
+        DayRecord {
+            day: self.day,
+            seconds: self.stats.min,
+            part_1: self.answers[0].clone(),
+            part_2: self.answers[1].clone(),
+        }
+    }
+}
+
+/// summary statistics of the times of repeated runs, in seconds
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: f64,
+    pub median: f64,
+    pub mean: f64,
+    pub max: f64,
+    /// the sample standard deviation, or 0 for a single run
+    pub stddev: f64,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[f64]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let n = sorted.len();
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
+        } else {
+            sorted[n / 2]
+        };
+        let mean = sorted.iter().sum::<f64>() / n as f64;
+        let stddev = if n > 1 {
+            let variance = sorted.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+            variance.sqrt()
+        } else {
+            0.0
+        };
+        Some(Self {
+            runs: n,
+            min: sorted[0],
+            median,
+            mean,
+            max: sorted[n - 1],
+            stddev,
+        })
+    }
+}
+
+/// times the runs of the solver after the warm-up runs, keeping the last
+/// solution
+fn measure<F>(
+    day: usize,
+    parse_seconds: Option<f64>,
+    options: Options,
+    answers: &Answers,
+    mut solve: F,
+) -> Result<BenchResult>
+where
+    F: FnMut(Parts) -> Result<Solution>,
+{
+    for _ in 0..options.warmup {
+        solve(options.parts)?;
+    }
+    let runs = options.runs.max(1);
+    let mut times = Vec::with_capacity(runs);
+    let mut solution = Solution::new();
+    for _ in 0..runs {
+        let tstart = Instant::now();
+        solution = solve(options.parts)?;
+        times.push(tstart.elapsed().as_secs_f64());
+    }
+    Ok(BenchResult {
+        day,
+        parse_seconds,
+        // there is at least one run
+        stats: Stats::from_samples(&times).unwrap(),
+        status: Status::check(day, &solution, answers),
+        answers: [&solution.part_1, &solution.part_2].map(|a| a.as_ref().map(|a| a.to_string())),
+    })
+}
+
+/// runs the puzzle, including parsing, as set by the options
+pub fn bench(
+    day: usize,
+    puzzle: Solver,
+    input: &str,
+    options: Options,
+    answers: &Answers,
+) -> Result<BenchResult> {
+    measure(day, None, options, answers, |parts| {
+        puzzle(input, parts, &Context::none())
+    })
+}
+
+/// benchmarks a puzzle, parsing the input once and then timing only the
+/// solving of the parsed input on each run; with the input cache enabled, the
+/// parse time of the days which use it is that of loading the cached parse
+pub fn bench_split<P: Puzzle>(
+    day: usize,
+    input: &str,
+    options: Options,
+    answers: &Answers,
+) -> Result<BenchResult> {
+    let tstart = Instant::now();
+    let parsed = P::parse(PuzzleInput::new(P::DAY, input))?;
+    let parse_seconds = tstart.elapsed().as_secs_f64();
+    measure(day, Some(parse_seconds), options, answers, |parts| {
+        puzzles::solve_parsed::<P>(&parsed, parts, &Context::none())
+    })
+}
+
+/// a benchmark of a puzzle, see bench_split
+pub type SplitBench = fn(usize, &str, Options, &Answers) -> Result<BenchResult>;
+
+/// formats a time with a unit that suits its magnitude
+pub(crate) fn format_time(seconds: f64) -> String {
+    if seconds < 0.001 {
+        format!("{:.0}µs", seconds * 1e6)
+    } else if seconds < 1.0 {
+        format!("{:.1}ms", seconds * 1e3)
+    } else {
+        format!("{:.2}s", seconds)
+    }
+}
+
+/// renders the results as a Markdown table, with a total row
+pub fn markdown_table(results: &[BenchResult]) -> String {
+    let mut lines = vec![
+        String::from("| Day | Parse | Best | Mean | Answers |"),
+        String::from("|----:|------:|-----:|-----:|:-------:|"),
+    ];
+    for result in results.iter() {
+        lines.push(format!(
+            "| {} | {} | {} | {} | {} |",
+            result.day,
+            result
+                .parse_seconds
+                .map(format_time)
+                .unwrap_or_else(|| String::from("-")),
+            format_time(result.stats.min),
+            format_time(result.stats.mean),
+            result.status.label()
+        ));
+    }
+    let best = results.iter().map(|r| r.stats.min).sum::<f64>();
+    let mean = results.iter().map(|r| r.stats.mean).sum::<f64>();
+    lines.push(format!(
+        "| **Total** | | **{}** | **{}** | |",
+        format_time(best),
+        format_time(mean)
+    ));
+    lines.join("\n")
+}
+
+/// renders the results as a plain text table, for the terminal
+pub fn text_table(results: &[BenchResult]) -> String {
+    let mut lines = vec![format!(
+        "{:>3}  {:>10}  {:>10}  {:>10}  {}",
+        "day", "parse", "best", "mean", "answers"
+    )];
+    for result in results.iter() {
+        let parse = result
+            .parse_seconds
+            .map(|seconds| format!("{:.3}ms", seconds * 1000.0))
+            .unwrap_or_else(|| String::from("-"));
+        lines.push(format!(
+            "{:>3}  {:>10}  {:>8.3}ms  {:>8.3}ms  {}",
+            result.day,
+            parse,
+            result.stats.min * 1000.0,
+            result.stats.mean * 1000.0,
+            result.status.label()
+        ));
+    }
+    lines.join("\n")
+}
+
+/// renders the full statistics of each day's runs as a plain text table
+pub fn stats_table(results: &[BenchResult]) -> String {
+    let mut lines = vec![format!(
+        "{:>3}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}  {}",
+        "day", "min", "median", "mean", "max", "stddev", "answers"
+    )];
+    for result in results.iter() {
+        let stats = &result.stats;
+        lines.push(format!(
+            "{:>3}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}  {}",
+            result.day,
+            format_time(stats.min),
+            format_time(stats.median),
+            format_time(stats.mean),
+            format_time(stats.max),
+            format_time(stats.stddev),
+            result.status.label()
+        ));
+    }
+    lines.join("\n")
+}
+
+/// renders the per-day speedup of the current build over a baseline build,
+/// flagging days whose answers differ
+pub fn speedup_table(baseline: &[DayRecord], current: &[DayRecord]) -> String {
+    let mut lines = vec![format!(
+        "{:>3}  {:>10}  {:>10}  {:>8}  {}",
+        "day", "baseline", "current", "speedup", "answers"
+    )];
+    for record in current.iter() {
+        let Some(base) = baseline.iter().find(|base| base.day == record.day) else {
+            lines.push(format!(
+                "{:>3}  {:>10}  {:>10}  {:>8}  -",
+                record.day,
+                "-",
+                format_time(record.seconds),
+                "-"
+            ));
+            continue;
+        };
+        let speedup = if record.seconds > 0.0 {
+            format!("{:.2}x", base.seconds / record.seconds)
+        } else {
+            String::from("-")
+        };
+        let answers = if (&base.part_1, &base.part_2) == (&record.part_1, &record.part_2) {
+            "same"
+        } else {
+            "DIFFERENT"
+        };
+        lines.push(format!(
+            "{:>3}  {:>10}  {:>10}  {:>8}  {}",
+            record.day,
+            format_time(base.seconds),
+            format_time(record.seconds),
+            speedup,
+            answers
+        ));
+    }
+    lines.join("\n")
+}
+
+/// renders the times of each day side by side for several machines, such as
+/// this one and the results fetched from peers, with a total row
+pub fn comparison_table(columns: &[(String, Vec<DayRecord>)]) -> String {
+    let width = columns
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(10);
+    let mut header = format!("{:>5}", "day");
+    for (name, _) in columns.iter() {
+        header.push_str(&format!("  {:>width$}", name));
+    }
+    let mut lines = vec![header];
+    let days = columns
+        .iter()
+        .flat_map(|(_, records)| records.iter().map(|record| record.day))
+        .collect::<BTreeSet<_>>();
+    for day in days {
+        let mut line = format!("{:>5}", day);
+        for (_, records) in columns.iter() {
+            let time = records
+                .iter()
+                .find(|record| record.day == day)
+                .map(|record| format_time(record.seconds))
+                .unwrap_or_else(|| String::from("-"));
+            line.push_str(&format!("  {:>width$}", time));
+        }
+        lines.push(line);
+    }
+    let mut total = format!("{:>5}", "total");
+    for (_, records) in columns.iter() {
+        let seconds = records.iter().map(|record| record.seconds).sum();
+        total.push_str(&format!("  {:>width$}", format_time(seconds)));
+    }
+    lines.push(total);
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_answers() -> Answers {
+        let mut answers = Answers::new();
+        answers.set(1, 1, String::from("24000"));
+        answers.set(1, 2, String::from("45000"));
+        answers
+    }
+
+    #[test]
+    fn check_status() {
+        let answers = sample_answers();
+        let solution = Solution::of(24000).and(45000);
+        assert_eq!(Status::check(1, &solution, &answers), Status::Correct);
+        let solution = solution.with_part_2(45001);
+        assert_eq!(Status::check(1, &solution, &answers), Status::Incorrect);
+        assert_eq!(Status::check(2, &solution, &answers), Status::Unknown);
+    }
+
+    #[test]
+    fn time_formats() {
+        assert_eq!(format_time(0.000042), "42µs");
+        assert_eq!(format_time(0.0125), "12.5ms");
+        assert_eq!(format_time(28.3), "28.30s");
+    }
+
+    #[test]
+    fn run_stats() {
+        let stats = Stats::from_samples(&[0.004, 0.001, 0.002, 0.005]).unwrap();
+        assert_eq!(stats.runs, 4);
+        assert_eq!((stats.min, stats.max), (0.001, 0.005));
+        assert!((stats.median - 0.003).abs() < 1e-12);
+        assert!((stats.mean - 0.003).abs() < 1e-12);
+        assert!((stats.stddev - 0.001825741858).abs() < 1e-9);
+        let single = Stats::from_samples(&[0.002]).unwrap();
+        assert_eq!((single.median, single.stddev), (0.002, 0.0));
+        assert_eq!(Stats::from_samples(&[]), None);
+        let result = BenchResult {
+            day: 1,
+            parse_seconds: None,
+            stats,
+            status: Status::Unknown,
+            answers: [None, None],
+        };
+        assert_eq!(
+            stats_table(&[result]),
+            [
+                "day         min      median        mean         max      stddev  answers",
+                "  1       1.0ms       3.0ms       3.0ms       5.0ms       1.8ms  ?",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn speedups() {
+        let record = |day, seconds, part_1: &str| DayRecord {
+            day,
+            seconds,
+            part_1: Some(String::from(part_1)),
+            part_2: None,
+        };
+        let baseline = [record(1, 0.002, "24000"), record(2, 0.001, "15")];
+        let current = [
+            record(1, 0.001, "24000"),
+            record(2, 0.004, "16"),
+            record(3, 0.001, "157"),
+        ];
+        assert_eq!(
+            speedup_table(&baseline, &current),
+            [
+                "day    baseline     current   speedup  answers",
+                "  1       2.0ms       1.0ms     2.00x  same",
+                "  2       1.0ms       4.0ms     0.25x  DIFFERENT",
+                "  3           -       1.0ms         -  -",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn compare_machines() {
+        let record = |day, seconds| DayRecord {
+            day,
+            seconds,
+            part_1: None,
+            part_2: None,
+        };
+        let columns = [
+            (
+                String::from("local"),
+                vec![record(1, 0.002), record(16, 28.0)],
+            ),
+            (String::from("ian-laptop"), vec![record(1, 0.001)]),
+        ];
+        assert_eq!(
+            comparison_table(&columns),
+            [
+                "  day       local  ian-laptop",
+                "    1       2.0ms       1.0ms",
+                "   16      28.00s           -",
+                "total      28.00s       1.0ms",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn bench_markdown() {
+        let puzzle: Solver = |_, _, _| Ok(Solution::of(24000).and(45000));
+        let result = bench(1, puzzle, "", Options::new(1, 3), &sample_answers()).unwrap();
+        assert_eq!(result.status, Status::Correct);
+        assert_eq!(result.stats.runs, 3);
+        assert!(result.stats.min <= result.stats.mean);
+
+        let stats = Stats::from_samples(&[0.0125, 0.0275]).unwrap();
+        let table = markdown_table(&[BenchResult {
+            day: 1,
+            parse_seconds: None,
+            stats,
+            status: Status::Correct,
+            answers: [None, None],
+        }]);
+        assert_eq!(
+            table,
+            [
+                "| Day | Parse | Best | Mean | Answers |",
+                "|----:|------:|-----:|-----:|:-------:|",
+                "| 1 | - | 12.5ms | 20.0ms | ✓ |",
+                "| **Total** | | **12.5ms** | **20.0ms** | |",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -0,0 +1,158 @@
+/*
+** src/calendar.rs
+*/
+
+use crate::answers::Answers;
+use crate::client;
+use crate::puzzles::N_DAYS;
+
+use anyhow::Result;
+
+use std::time::Duration;
+
+const CACHE_MAX_AGE: Duration = Duration::from_secs(15 * 60);
+
+/// stars earned for each day, from 0 to 2
+pub struct Stars([u8; N_DAYS]);
+
+impl Stars {
+    /// derives stars from recorded answers, with one star per correct part
+    pub fn from_answers(answers: &Answers) -> Self {
+        let mut stars = [0; N_DAYS];
+        for (i, day_stars) in stars.iter_mut().enumerate() {
+            let day = i + 1;
+            *day_stars = (1..=2)
+                .filter(|&part| answers.get(day, part).is_some())
+                .count() as u8;
+        }
+        // the final star is given for free once every other star is earned
+        if stars[N_DAYS - 1] == 1 && stars[..(N_DAYS - 1)].iter().all(|&s| s == 2) {
+            stars[N_DAYS - 1] = 2;
+        }
+        Self(stars)
+    }
+
+    /// parses stars from the event calendar page, where each day is labeled
+    /// as e.g. `aria-label="Day 1, two stars"`
+    pub fn from_calendar_html(html: &str) -> Self {
+        let mut stars = [0; N_DAYS];
+        for label in html.split("aria-label=\"Day ").skip(1) {
+            let label = label.split('"').next().unwrap_or_default();
+            let (day, rest) = label.split_once(',').unwrap_or((label, ""));
+            if let Ok(day) = day.trim().parse::<usize>() {
+                if (1..=N_DAYS).contains(&day) {
+                    stars[day - 1] = if rest.contains("two stars") {
+                        2
+                    } else if rest.contains("one star") {
+                        1
+                    } else {
+                        0
+                    };
+                }
+            }
+        }
+        Self(stars)
+    }
+
+    /// fetches the stars from the event calendar page, cached on disk
+    pub fn fetch(refresh: bool) -> Result<Self> {
+        let html = client::cached_get(client::BASE_URL, "calendar.html", CACHE_MAX_AGE, refresh)?;
+        Ok(Self::from_calendar_html(&html))
+    }
+
+    /// combines stars from two sources, keeping the most for each day
+    pub fn merge(&self, other: &Self) -> Self {
+        let mut stars = self.0;
+        for (a, b) in stars.iter_mut().zip(other.0.iter()) {
+            *a = (*a).max(*b);
+        }
+        Self(stars)
+    }
+
+    pub fn get(&self, day: usize) -> u8 {
+        self.0[day - 1]
+    }
+
+    pub fn total(&self) -> usize {
+        self.0.iter().map(|&s| s as usize).sum()
+    }
+
+    /// renders the days as a grid, one week per row
+    pub fn render(&self) -> String {
+        let mut lines = self
+            .0
+            .chunks(5)
+            .enumerate()
+            .map(|(row, days)| {
+                days.iter()
+                    .enumerate()
+                    .map(|(col, &stars)| {
+                        let marks = match stars {
+                            2 => "**",
+                            1 => "* ",
+                            _ => "  ",
+                        };
+                        format!("{:>2} {}", row * 5 + col + 1, marks)
+                    })
+                    .collect::<Vec<_>>()
+                    .join("   ")
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+        lines.push(format!("{}/{} stars", self.total(), N_DAYS * 2));
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stars_from_answers() {
+        let mut answers = Answers::new();
+        answers.set(1, 1, String::from("24000"));
+        answers.set(1, 2, String::from("45000"));
+        answers.set(3, 1, String::from("157"));
+        let stars = Stars::from_answers(&answers);
+        assert_eq!(stars.get(1), 2);
+        assert_eq!(stars.get(2), 0);
+        assert_eq!(stars.get(3), 1);
+        assert_eq!(stars.total(), 3);
+    }
+
+    #[test]
+    fn final_star() {
+        let mut answers = Answers::new();
+        for day in 1..N_DAYS {
+            answers.set(day, 1, String::new());
+            answers.set(day, 2, String::new());
+        }
+        answers.set(N_DAYS, 1, String::from("2=-1=0"));
+        assert_eq!(Stars::from_answers(&answers).total(), 50);
+    }
+
+    #[test]
+    fn stars_from_calendar_html() {
+        let html = r#"<a aria-label="Day 1, two stars" href="/2022/day/1">
+<a aria-label="Day 2, one star" href="/2022/day/2">
+<a aria-label="Day 3" href="/2022/day/3">"#;
+        let stars = Stars::from_calendar_html(html);
+        assert_eq!((stars.get(1), stars.get(2), stars.get(3)), (2, 1, 0));
+    }
+
+    #[test]
+    fn render_calendar() {
+        let mut answers = Answers::new();
+        answers.set(1, 1, String::new());
+        answers.set(1, 2, String::new());
+        answers.set(7, 1, String::new());
+        let calendar = Stars::from_answers(&answers).render();
+        let lines = calendar.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0], " 1 **    2       3       4       5");
+        assert_eq!(lines[1], " 6       7 *     8       9      10");
+        assert_eq!(lines[5], "3/50 stars");
+    }
+}
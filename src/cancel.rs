@@ -0,0 +1,79 @@
+/*
+** src/cancel.rs
+*/
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+
+/// the token cancelled by Ctrl-C, once the handler is installed
+static CTRL_C: OnceLock<Cancel> = OnceLock::new();
+
+/// a token through which a long solve is asked to stop early, which the
+/// slowest searches, such as the partitions of day 16, check as they go
+#[derive(Clone, Default)]
+pub struct Cancel(Arc<AtomicBool>);
+
+impl Cancel {
+    /// a token which is only cancelled by calling cancel
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the token cancelled by Ctrl-C, installing the handler on first use; a
+    /// second Ctrl-C exits at once
+    pub fn ctrl_c() -> Self {
+        CTRL_C
+            .get_or_init(|| {
+                install_handler();
+                Self::new()
+            })
+            .clone()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(unix)]
+extern "C" fn on_sigint(_signal: libc::c_int) {
+    // only atomics are touched here, which is async-signal-safe
+    if let Some(token) = CTRL_C.get() {
+        if token.0.swap(true, Ordering::Relaxed) {
+            // SAFETY: _exit is async-signal-safe
+            unsafe { libc::_exit(130) };
+        }
+    }
+}
+
+#[cfg(unix)]
+fn install_handler() {
+    let handler = on_sigint as extern "C" fn(libc::c_int);
+    // SAFETY: the handler only touches atomics and calls _exit
+    unsafe {
+        libc::signal(libc::SIGINT, handler as libc::sighandler_t);
+    }
+}
+
+/// Ctrl-C keeps its default behaviour on platforms without signals
+#[cfg(not(unix))]
+fn install_handler() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancel_clones() {
+        let cancel = Cancel::new();
+        let clone = cancel.clone();
+        assert!(!clone.is_cancelled());
+        cancel.cancel();
+        assert!(clone.is_cancelled());
+        assert!(!Cancel::new().is_cancelled());
+    }
+}
@@ -0,0 +1,264 @@
+/*
+** src/chart.rs
+*/
+
+use crate::history::RunRecord;
+
+use std::fmt::Write;
+
+const WIDTH: f64 = 800.0;
+const HEIGHT: f64 = 480.0;
+/// space around the plot for the axis labels and the legend
+const MARGIN_LEFT: f64 = 70.0;
+const MARGIN_RIGHT: f64 = 110.0;
+const MARGIN_TOP: f64 = 40.0;
+const MARGIN_BOTTOM: f64 = 60.0;
+
+const COLORS: [&str; 8] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#9c755f",
+];
+
+/// the runtimes of a single day across the recorded runs, as (run, ms)
+#[derive(Debug, PartialEq)]
+pub struct Series {
+    pub day: usize,
+    pub points: Vec<(usize, f64)>,
+}
+
+/// collects the runtime of each of the given days across the runs, or of
+/// every recorded day if none are given
+pub fn runtime_series(records: &[RunRecord], days: &[usize]) -> Vec<Series> {
+    let mut all_days = records
+        .iter()
+        .flat_map(|record| record.days.iter().map(|day| day.day))
+        .filter(|day| days.is_empty() || days.contains(day))
+        .collect::<Vec<_>>();
+    all_days.sort();
+    all_days.dedup();
+    all_days
+        .into_iter()
+        .map(|day| Series {
+            day,
+            points: records
+                .iter()
+                .enumerate()
+                .filter_map(|(run, record)| {
+                    record.day(day).map(|record| (run, record.seconds * 1000.0))
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// escapes text for use in SVG
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// the label for a run on the x-axis
+fn run_label(record: &RunRecord) -> String {
+    record.revision.clone().unwrap_or_else(|| {
+        chrono::DateTime::from_timestamp(record.timestamp, 0)
+            .map(|t| t.format("%m-%d").to_string())
+            .unwrap_or_default()
+    })
+}
+
+/// renders the series as an SVG line chart, with the runtimes on a log scale
+/// since the days differ by orders of magnitude
+pub fn render_svg(records: &[RunRecord], series: &[Series]) -> String {
+    let plot_width = WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let plot_height = HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+
+    // the y-axis covers whole powers of ten
+    let times = series
+        .iter()
+        .flat_map(|s| s.points.iter().map(|&(_, ms)| ms.max(0.001).log10()))
+        .collect::<Vec<_>>();
+    let (min_exp, max_exp) = if times.is_empty() {
+        (0.0, 1.0)
+    } else {
+        let min_exp = times.iter().cloned().fold(f64::MAX, f64::min).floor();
+        let max_exp = times.iter().cloned().fold(f64::MIN, f64::max).ceil();
+        (min_exp, max_exp.max(min_exp + 1.0))
+    };
+    let n_runs = records.len().max(2);
+    let x = |run: usize| MARGIN_LEFT + plot_width * run as f64 / (n_runs - 1) as f64;
+    let y = |ms: f64| {
+        let fraction = (ms.max(0.001).log10() - min_exp) / (max_exp - min_exp);
+        MARGIN_TOP + plot_height * (1.0 - fraction)
+    };
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"sans-serif\" font-size=\"12\">",
+        WIDTH, HEIGHT
+    );
+    let _ = writeln!(
+        svg,
+        "<rect width=\"{}\" height=\"{}\" fill=\"white\"/>",
+        WIDTH, HEIGHT
+    );
+    let _ = writeln!(
+        svg,
+        "<text x=\"{}\" y=\"24\" text-anchor=\"middle\" font-size=\"16\">Runtime per day</text>",
+        WIDTH / 2.0
+    );
+
+    // horizontal grid lines at each power of ten
+    for exp in (min_exp as i32)..=(max_exp as i32) {
+        let ms = 10f64.powi(exp);
+        let label = if ms < 1.0 {
+            format!("{}ms", ms)
+        } else if ms < 1000.0 {
+            format!("{:.0}ms", ms)
+        } else {
+            format!("{:.0}s", ms / 1000.0)
+        };
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"#ddd\"/>",
+            MARGIN_LEFT,
+            y(ms),
+            MARGIN_LEFT + plot_width,
+            y(ms)
+        );
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>",
+            MARGIN_LEFT - 6.0,
+            y(ms) + 4.0,
+            label
+        );
+    }
+
+    // x-axis labels, thinned out so that they do not overlap
+    let step = records.len().div_ceil(12).max(1);
+    for (run, record) in records.iter().enumerate().step_by(step) {
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\" transform=\"rotate(-35 {:.1} {:.1})\">{}</text>",
+            x(run),
+            HEIGHT - MARGIN_BOTTOM + 16.0,
+            x(run),
+            HEIGHT - MARGIN_BOTTOM + 16.0,
+            escape(&run_label(record))
+        );
+    }
+    let _ = writeln!(
+        svg,
+        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"#333\"/>",
+        MARGIN_LEFT, MARGIN_TOP, plot_width, plot_height
+    );
+
+    // a line and a legend entry for each day
+    for (i, series) in series.iter().enumerate() {
+        let color = COLORS[i % COLORS.len()];
+        let points = series
+            .points
+            .iter()
+            .map(|&(run, ms)| format!("{:.1},{:.1}", x(run), y(ms)))
+            .collect::<Vec<_>>();
+        let _ = writeln!(
+            svg,
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>",
+            points.join(" "),
+            color
+        );
+        for &(run, ms) in series.points.iter() {
+            let _ = writeln!(
+                svg,
+                "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"3\" fill=\"{}\"/>",
+                x(run),
+                y(ms),
+                color
+            );
+        }
+        let legend_y = MARGIN_TOP + 10.0 + 18.0 * i as f64;
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"12\" height=\"12\" fill=\"{}\"/>",
+            WIDTH - MARGIN_RIGHT + 12.0,
+            legend_y - 10.0,
+            color
+        );
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\">day {}</text>",
+            WIDTH - MARGIN_RIGHT + 30.0,
+            legend_y,
+            series.day
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::DayRecord;
+
+    fn run(revision: &str, days: &[(usize, f64)]) -> RunRecord {
+        RunRecord {
+            timestamp: 1670000000,
+            revision: Some(String::from(revision)),
+            input: None,
+            days: days
+                .iter()
+                .map(|&(day, seconds)| DayRecord {
+                    day,
+                    seconds,
+                    part_1: None,
+                    part_2: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn series_per_day() {
+        let records = [
+            run("aaa", &[(14, 0.5), (15, 2.0)]),
+            run("bbb", &[(15, 1.0), (16, 28.0)]),
+        ];
+        assert_eq!(
+            runtime_series(&records, &[15, 16]),
+            vec![
+                Series {
+                    day: 15,
+                    points: vec![(0, 2000.0), (1, 1000.0)],
+                },
+                Series {
+                    day: 16,
+                    points: vec![(1, 28000.0)],
+                },
+            ]
+        );
+        assert_eq!(runtime_series(&records, &[]).len(), 3);
+    }
+
+    #[test]
+    fn svg_chart() {
+        let records = [run("aaa", &[(14, 0.5)]), run("<b>", &[(14, 0.25)])];
+        let svg = render_svg(&records, &runtime_series(&records, &[]));
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<polyline").count(), 1);
+        assert_eq!(svg.matches("<circle").count(), 2);
+        assert!(svg.contains(">day 14<"));
+        assert!(svg.contains("&lt;b&gt;"));
+        assert!(!svg.contains("NaN"));
+    }
+
+    #[test]
+    fn svg_chart_empty() {
+        let svg = render_svg(&[], &[]);
+        assert!(!svg.contains("NaN"));
+        assert!(!svg.contains("inf"));
+    }
+}
@@ -0,0 +1,95 @@
+/*
+** src/clean.rs
+*/
+
+use crate::client;
+use crate::history;
+
+use anyhow::Result;
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const PROJECT_DIR: &str = env!("CARGO_MANIFEST_DIR");
+
+/// a kind of file which is generated while running, and which can be removed
+/// without losing anything which cannot be fetched or generated again
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Artifact {
+    /// fetched pages, puzzle descriptions and parsed inputs
+    Cache,
+    /// rendered visualizations and charts
+    Viz,
+    /// the recorded runs
+    History,
+}
+
+impl Artifact {
+    pub const ALL: [Self; 3] = [Self::Cache, Self::Viz, Self::History];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Cache => "cache",
+            Self::Viz => "visualizations",
+            Self::History => "history",
+        }
+    }
+
+    /// the files and directories holding the artifact
+    pub fn paths(&self) -> Vec<PathBuf> {
+        match self {
+            Self::Cache => vec![client::cache_dir()],
+            Self::Viz => vec![
+                Path::new(PROJECT_DIR).join("viz"),
+                Path::new(PROJECT_DIR).join("charts"),
+            ],
+            Self::History => vec![history::history_path()],
+        }
+    }
+}
+
+/// the total size of the files under the path, in bytes
+pub fn size(path: &Path) -> io::Result<u64> {
+    let metadata = fs::symlink_metadata(path)?;
+    if !metadata.is_dir() {
+        return Ok(metadata.len());
+    }
+    let mut total = 0;
+    for entry in fs::read_dir(path)? {
+        total += size(&entry?.path())?;
+    }
+    Ok(total)
+}
+
+/// removes the file or directory, returning the number of bytes freed, or
+/// None if there was nothing to remove
+pub fn remove(path: &Path) -> Result<Option<u64>> {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return Ok(None);
+    };
+    let bytes = size(path)?;
+    if metadata.is_dir() {
+        fs::remove_dir_all(path)?;
+    } else {
+        fs::remove_file(path)?;
+    }
+    Ok(Some(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remove_artifacts() {
+        let dir = std::env::temp_dir().join(format!("aoc-clean-{}", std::process::id()));
+        fs::create_dir_all(dir.join("parsed")).unwrap();
+        fs::write(dir.join("day1.md"), "## Day 1").unwrap();
+        fs::write(dir.join("parsed").join("day15.bin"), [0; 16]).unwrap();
+        assert_eq!(size(&dir).unwrap(), 24);
+        assert_eq!(remove(&dir).unwrap(), Some(24));
+        assert!(!dir.exists());
+        assert_eq!(remove(&dir).unwrap(), None);
+    }
+}
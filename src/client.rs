@@ -0,0 +1,169 @@
+/*
+** src/client.rs
+*/
+
+use anyhow::{anyhow, Context, Result};
+use log::debug;
+
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime};
+
+pub const BASE_URL: &str = "https://adventofcode.com/2022";
+const USER_AGENT: &str = "github.com/ianbrault/aoc2022";
+const PROJECT_DIR: &str = env!("CARGO_MANIFEST_DIR");
+
+/// directory for responses cached on disk
+pub fn cache_dir() -> PathBuf {
+    Path::new(PROJECT_DIR).join(".cache")
+}
+
+/// gets the session cookie, from AOC_SESSION or the git-ignored .session file
+pub fn session() -> Result<String> {
+    if let Ok(session) = env::var("AOC_SESSION") {
+        return Ok(session.trim().to_string());
+    }
+    let path = Path::new(PROJECT_DIR).join(".session");
+    fs::read_to_string(&path)
+        .map(|session| session.trim().to_string())
+        .map_err(|_| anyhow!("no session cookie, set AOC_SESSION or write it to .session"))
+}
+
+/// runs curl, passing the session cookie, if given, as a config file on
+/// stdin so that it does not show up in the process list, and returns the
+/// response
+fn curl(method: &str, url: &str, args: &[String], session: Option<&str>) -> Result<String> {
+    debug!("{} {}", method, url);
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--location"])
+        .args(["--user-agent", USER_AGENT])
+        .args(["--config", "-"])
+        .args(args)
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("failed to run curl")?;
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow!("failed to open curl stdin"))?;
+    if let Some(session) = session {
+        writeln!(stdin, "cookie = \"session={}\"", config_escape(session))?;
+    }
+    // closes stdin, so that curl stops reading its config
+    drop(stdin);
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("{} {} failed: {}", method, url, stderr.trim()));
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// escapes a value for a quoted string in a curl config file
+fn config_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// makes a GET request, authenticated with the session cookie if given, using
+/// curl to avoid pulling in an HTTP client and TLS stack
+fn curl_get(url: &str, session: Option<&str>) -> Result<String> {
+    curl("GET", url, &[], session)
+}
+
+/// makes an authenticated GET request to the Advent of Code website
+pub fn get(url: &str, session: &str) -> Result<String> {
+    curl_get(url, Some(session))
+}
+
+/// makes an unauthenticated GET request, for anything other than the Advent
+/// of Code website, which must never see the session cookie
+pub fn get_public(url: &str) -> Result<String> {
+    curl_get(url, None)
+}
+
+/// checks whether the file exists and was modified within the given age
+pub fn is_fresh(path: &Path, max_age: Duration) -> bool {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .map(|age| age < max_age)
+        .unwrap_or(false)
+}
+
+/// makes a GET request, reusing the cached response if it is recent enough
+pub fn cached_get(url: &str, cache_name: &str, max_age: Duration, refresh: bool) -> Result<String> {
+    let cache_path = cache_dir().join(cache_name);
+    if !refresh && is_fresh(&cache_path, max_age) {
+        debug!("using cached {}", cache_path.to_string_lossy());
+        return Ok(fs::read_to_string(&cache_path)?);
+    }
+    let body = get(url, &session()?)?;
+    fs::create_dir_all(cache_dir())?;
+    fs::write(&cache_path, &body)?;
+    Ok(body)
+}
+
+/// makes an authenticated POST request of a form to the Advent of Code
+/// website, returning the response
+pub fn post_form(url: &str, session: &str, fields: &[(&str, &str)]) -> Result<String> {
+    let args = fields
+        .iter()
+        .flat_map(|(name, value)| {
+            [
+                String::from("--data-urlencode"),
+                format!("{}={}", name, value),
+            ]
+        })
+        .collect::<Vec<_>>();
+    curl("POST", url, &args, Some(session))
+}
+
+/// checks whether the response to an answer submission accepted the answer
+pub fn is_correct_response(html: &str) -> bool {
+    html.contains("That's the right answer")
+}
+
+/// sends a JSON body with the given method, such as POST or PUT
+fn send_json(method: &str, url: &str, body: &str) -> Result<()> {
+    debug!("{} {}", method, url);
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail"])
+        .args(["--request", method])
+        .args(["--user-agent", USER_AGENT])
+        .args(["--header", "Content-Type: application/json"])
+        .args(["--data-binary", "@-"])
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("failed to run curl")?;
+    child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow!("failed to open curl stdin"))?
+        .write_all(body.as_bytes())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("{} {} failed: {}", method, url, stderr.trim()));
+    }
+    Ok(())
+}
+
+/// makes a POST request with a JSON body, such as to a webhook
+pub fn post_json(url: &str, body: &str) -> Result<()> {
+    send_json("POST", url, body)
+}
+
+/// makes a PUT request with a JSON body, such as to upload a file
+pub fn put_json(url: &str, body: &str) -> Result<()> {
+    send_json("PUT", url, body)
+}
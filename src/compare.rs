@@ -0,0 +1,152 @@
+/*
+** src/compare.rs
+*/
+
+use crate::history::{DayRecord, RunRecord};
+
+/// finds the most recent record of the day from a run at the given revision,
+/// which may be abbreviated
+pub fn find_at_revision<'a>(
+    records: &'a [RunRecord],
+    revision: &str,
+    day: usize,
+) -> Option<&'a DayRecord> {
+    records
+        .iter()
+        .rev()
+        .filter(|record| {
+            record.revision.as_deref().is_some_and(|r| {
+                !revision.is_empty() && (r.starts_with(revision) || revision.starts_with(r))
+            })
+        })
+        .find_map(|record| record.day(day))
+}
+
+/// splits an answer into its lines, so that multi-line answers such as the
+/// letters drawn by day 10 can be shown side by side
+fn answer_lines(answer: &Option<String>) -> Vec<String> {
+    match answer {
+        Some(answer) => answer
+            .trim_start_matches('\n')
+            .lines()
+            .map(|line| line.to_string())
+            .collect(),
+        None => vec![String::from("-")],
+    }
+}
+
+fn format_ms(seconds: f64) -> String {
+    format!("{:.3}ms", seconds * 1000.0)
+}
+
+/// renders the answers and timings of two results side by side, marking the
+/// rows which differ
+pub fn render(label_a: &str, a: &DayRecord, label_b: &str, b: &DayRecord) -> String {
+    let parts = [
+        ("part 1", answer_lines(&a.part_1), answer_lines(&b.part_1)),
+        ("part 2", answer_lines(&a.part_2), answer_lines(&b.part_2)),
+    ];
+    let width = parts
+        .iter()
+        .flat_map(|(_, a, _)| a.iter().map(|line| line.len()))
+        .chain([label_a.len(), format_ms(a.seconds).len()])
+        .max()
+        .unwrap_or(0);
+    let mut lines = vec![format!("{:<6}  {:<width$}  {}", "", label_a, label_b)];
+    for (label, a, b) in parts.iter() {
+        let marker = if a != b { "  <- differs" } else { "" };
+        for i in 0..a.len().max(b.len()) {
+            let line_a = a.get(i).map(|s| s.as_str()).unwrap_or("");
+            let line_b = b.get(i).map(|s| s.as_str()).unwrap_or("");
+            let (label, marker) = if i == 0 { (*label, marker) } else { ("", "") };
+            lines.push(format!(
+                "{:<6}  {:<width$}  {}{}",
+                label, line_a, line_b, marker
+            ));
+        }
+    }
+    let ratio = if a.seconds > 0.0 {
+        format!("  ({:.2}x)", b.seconds / a.seconds)
+    } else {
+        String::new()
+    };
+    lines.push(format!(
+        "{:<6}  {:<width$}  {}{}",
+        "time",
+        format_ms(a.seconds),
+        format_ms(b.seconds),
+        ratio
+    ));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(seconds: f64, part_1: &str, part_2: Option<&str>) -> DayRecord {
+        DayRecord {
+            day: 15,
+            seconds,
+            part_1: Some(part_1.to_string()),
+            part_2: part_2.map(|s| s.to_string()),
+        }
+    }
+
+    #[test]
+    fn render_comparison() {
+        let a = record(0.002, "26", Some("56000011"));
+        let b = record(0.5, "4811413", None);
+        assert_eq!(
+            render("sample", &a, "real", &b),
+            [
+                "        sample    real",
+                "part 1  26        4811413  <- differs",
+                "part 2  56000011  -  <- differs",
+                "time    2.000ms   500.000ms  (250.00x)",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn render_multi_line_answers() {
+        let a = record(0.001, "1", Some("\n#.\n.#"));
+        let b = record(0.001, "1", Some("\n#.\n##"));
+        assert_eq!(
+            render("a", &a, "b", &b),
+            [
+                "        a        b",
+                "part 1  1        1",
+                "part 2  #.       #.  <- differs",
+                "        .#       ##",
+                "time    1.000ms  1.000ms  (1.00x)",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn find_by_revision() {
+        let run = |revision: &str, seconds| RunRecord {
+            timestamp: 0,
+            revision: Some(revision.to_string()),
+            input: None,
+            days: vec![record(seconds, "26", None)],
+        };
+        let records = [
+            run("abc1234", 1.0),
+            run("def5678", 2.0),
+            run("abc1234", 3.0),
+        ];
+        assert_eq!(find_at_revision(&records, "abc", 15).unwrap().seconds, 3.0);
+        assert_eq!(
+            find_at_revision(&records, "def5678aaaa", 15)
+                .unwrap()
+                .seconds,
+            2.0
+        );
+        assert!(find_at_revision(&records, "abc", 16).is_none());
+        assert!(find_at_revision(&records, "", 15).is_none());
+    }
+}
@@ -0,0 +1,174 @@
+/*
+** src/complexity.rs
+*/
+
+use crate::context::Context;
+use crate::generators;
+use crate::parts::Parts;
+use crate::progress::Progress;
+use crate::types::Solver;
+use crate::utils;
+
+use anyhow::{anyhow, Result};
+use log::warn;
+
+use std::time::Instant;
+
+/// the runtime of a puzzle on a generated input of the given size
+pub struct Sample {
+    pub size: usize,
+    pub seconds: f64,
+}
+
+/// the default sizes for a day, doubling up to the generator's default size
+pub fn default_sizes(day: usize) -> Result<Vec<usize>> {
+    let (_, _, default_size) = generators::GENERATORS
+        .iter()
+        .find(|(d, _, _)| *d == day)
+        .ok_or_else(|| anyhow!("no input generator for day {}", day))?;
+    let mut sizes = (0..5)
+        .rev()
+        .map(|shift| (default_size >> shift).max(1))
+        .collect::<Vec<_>>();
+    sizes.dedup();
+    Ok(sizes)
+}
+
+/// times the puzzle on a generated input of each size, keeping the best of
+/// the given number of runs
+pub fn profile(
+    day: usize,
+    puzzle: Solver,
+    sizes: &[usize],
+    runs: usize,
+    seed: u64,
+) -> Result<Vec<Sample>> {
+    // sizes are timed one at a time, since timings taken in parallel would
+    // contend for the cores
+    let label = format!("day {} sizes", day);
+    let samples = utils::par_map_with_progress(
+        sizes,
+        1,
+        |&size| {
+            let input = generators::generate(day, Some(size), seed)?;
+            let mut best = f64::MAX;
+            for run in 0..runs.max(1) {
+                let tstart = Instant::now();
+                let result = puzzle(&input, Parts::Both, &Context::none());
+                // generated inputs do not always have an answer, such as day 15
+                // with too few sensors to leave a single gap, but the work done
+                // to find that out is still worth timing
+                if let (0, Err(err)) = (run, result) {
+                    warn!("day {} failed on size {}: {}", day, size, err);
+                }
+                best = best.min(tstart.elapsed().as_secs_f64());
+            }
+            Ok(Sample {
+                size,
+                seconds: best,
+            })
+        },
+        Progress::log().tracker(&label),
+    );
+    samples.into_iter().collect()
+}
+
+/// fits t = c * n^k by least squares on the logs of the samples, returning
+/// the exponent k, or None if there are too few distinct sizes
+pub fn fit_exponent(samples: &[Sample]) -> Option<f64> {
+    let points = samples
+        .iter()
+        .filter(|s| s.size > 0 && s.seconds > 0.0)
+        .map(|s| ((s.size as f64).ln(), s.seconds.ln()))
+        .collect::<Vec<_>>();
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let sxx = points
+        .iter()
+        .map(|(x, _)| (x - mean_x).powi(2))
+        .sum::<f64>();
+    let sxy = points
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum::<f64>();
+    if points.len() < 2 || sxx == 0.0 {
+        None
+    } else {
+        Some(sxy / sxx)
+    }
+}
+
+/// names the complexity class nearest to the fitted exponent
+pub fn describe(exponent: f64) -> &'static str {
+    if exponent < 0.5 {
+        "O(1), or dominated by fixed costs"
+    } else if exponent < 0.95 {
+        "sub-linear, such as O(sqrt n)"
+    } else if exponent < 1.1 {
+        "O(n)"
+    } else if exponent < 1.5 {
+        "O(n log n), or slightly super-linear"
+    } else if exponent < 2.5 {
+        "O(n^2)"
+    } else if exponent < 3.5 {
+        "O(n^3)"
+    } else {
+        "worse than O(n^3), possibly exponential"
+    }
+}
+
+/// renders the samples and the fitted growth curve
+pub fn render(samples: &[Sample]) -> String {
+    let mut lines = vec![format!("{:>8}  {:>12}  {:>8}", "size", "time", "ratio")];
+    let mut prev: Option<&Sample> = None;
+    for sample in samples.iter() {
+        let ratio = prev
+            .map(|prev| format!("{:.2}x", sample.seconds / prev.seconds))
+            .unwrap_or_default();
+        lines.push(format!(
+            "{:>8}  {:>10.3}ms  {:>8}",
+            sample.size,
+            sample.seconds * 1000.0,
+            ratio
+        ));
+        prev = Some(sample);
+    }
+    match fit_exponent(samples) {
+        Some(k) => lines.push(format!("fit: t ~ n^{:.2}, {}", k, describe(k))),
+        None => lines.push(String::from("fit: not enough samples")),
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(f: fn(f64) -> f64) -> Vec<Sample> {
+        [100, 200, 400, 800]
+            .into_iter()
+            .map(|size| Sample {
+                size,
+                seconds: f(size as f64),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fit_power_laws() {
+        let linear = fit_exponent(&samples(|n| 2e-6 * n)).unwrap();
+        assert!((linear - 1.0).abs() < 1e-9);
+        let quadratic = fit_exponent(&samples(|n| 3e-9 * n * n)).unwrap();
+        assert!((quadratic - 2.0).abs() < 1e-9);
+        let n_log_n = fit_exponent(&samples(|n| 1e-7 * n * n.ln())).unwrap();
+        assert_eq!(describe(n_log_n), "O(n log n), or slightly super-linear");
+        assert!(fit_exponent(&samples(|n| n)[..1]).is_none());
+    }
+
+    #[test]
+    fn sizes_for_generated_days() {
+        assert_eq!(default_sizes(15).unwrap(), vec![1, 3, 7, 15, 30]);
+        assert!(default_sizes(1).is_err());
+    }
+}
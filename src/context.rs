@@ -0,0 +1,96 @@
+/*
+** src/context.rs
+*/
+
+use crate::cancel::Cancel;
+use crate::progress::Progress;
+
+use anyhow::{anyhow, Result};
+
+use std::collections::HashMap;
+
+/// the settings a day is solved with, handed to each part along with the
+/// parsed input
+#[derive(Clone)]
+pub struct Context {
+    /// whether the input is a sample input, for the days whose sample is
+    /// solved with different parameters, such as the row checked by day 15
+    pub sample: bool,
+    /// overrides of the days' tunable constants, by name
+    constants: HashMap<String, i64>,
+    /// reports the progress of the slowest computations to the runner
+    pub progress: Progress,
+    /// asks the slowest computations to stop early with the best answer
+    /// found so far
+    pub cancel: Cancel,
+}
+
+impl Context {
+    /// a context for the input of the build, the sample input if built with
+    /// the sample feature and the real input otherwise
+    pub fn new(progress: Progress) -> Self {
+        Self {
+            sample: cfg!(feature = "sample"),
+            constants: HashMap::new(),
+            progress,
+            cancel: Cancel::new(),
+        }
+    }
+
+    /// discards the progress, for benchmarks and tests
+    pub fn none() -> Self {
+        Self::new(Progress::none())
+    }
+
+    pub fn with_sample(self, sample: bool) -> Self {
+        Self { sample, ..self }
+    }
+
+    pub fn with_cancel(self, cancel: Cancel) -> Self {
+        Self { cancel, ..self }
+    }
+
+    pub fn with_constant(mut self, name: &str, value: i64) -> Self {
+        self.constants.insert(name.to_string(), value);
+        self
+    }
+
+    /// the value of the named constant, which is overridden if given and is
+    /// otherwise the default for either the sample or the real input
+    pub fn constant(&self, name: &str, sample: i64, real: i64) -> i64 {
+        match self.constants.get(name) {
+            Some(&value) => value,
+            None if self.sample => sample,
+            None => real,
+        }
+    }
+}
+
+/// parses a constant override given as NAME=VALUE
+pub fn parse_constant(s: &str) -> Result<(String, i64)> {
+    let (name, value) = s
+        .split_once('=')
+        .ok_or_else(|| anyhow!("expected NAME=VALUE, found {:?}", s))?;
+    let value = value
+        .parse()
+        .map_err(|e| anyhow!("invalid value for {}: {}", name, e))?;
+    Ok((name.to_string(), value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constants() {
+        let context = Context::none().with_sample(true);
+        assert_eq!(context.constant("row", 10, 2000000), 10);
+        let context = context.with_sample(false);
+        assert_eq!(context.constant("row", 10, 2000000), 2000000);
+        let context = context.with_constant("row", 7);
+        assert_eq!(context.constant("row", 10, 2000000), 7);
+        assert_eq!(parse_constant("row=-3").unwrap(), (String::from("row"), -3));
+        assert!(parse_constant("row").is_err());
+        assert!(parse_constant("row=x").is_err());
+    }
+}
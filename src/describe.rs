@@ -0,0 +1,271 @@
+/*
+** src/describe.rs
+*/
+
+use crate::client;
+
+use anyhow::{anyhow, Result};
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// the puzzle page only changes once part 1 is solved, which refresh covers
+const CACHE_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+/// column at which paragraphs are wrapped
+pub const WIDTH: usize = 80;
+
+const BOLD: &str = "\x1b[1m";
+const CODE: &str = "\x1b[36m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+/// where the Markdown description of the day is stored
+pub fn markdown_path(day: usize) -> PathBuf {
+    client::cache_dir().join(format!("day{}.md", day))
+}
+
+/// the descriptions of the unlocked parts of the puzzle, as the inner HTML of
+/// each `<article class="day-desc">`; part 2 only appears once part 1 has
+/// been solved
+pub fn articles(html: &str) -> Vec<&str> {
+    html.split("<article class=\"day-desc\">")
+        .skip(1)
+        .filter_map(|article| article.split("</article>").next())
+        .collect()
+}
+
+fn unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// converts the HTML of a puzzle description into Markdown, covering the
+/// handful of tags which the descriptions use
+pub fn to_markdown(html: &str) -> String {
+    let mut markdown = String::new();
+    let mut in_pre = false;
+    let mut rest = html;
+    // whitespace between blocks would otherwise add blank lines
+    let push_text = |markdown: &mut String, text: &str, in_pre: bool| {
+        if in_pre || !text.trim().is_empty() || !markdown.ends_with('\n') {
+            markdown.push_str(&unescape(text));
+        }
+    };
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            push_text(&mut markdown, rest, in_pre);
+            break;
+        };
+        push_text(&mut markdown, &rest[..start], in_pre);
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = &rest[(start + 1)..(start + end)];
+        rest = &rest[(start + end + 1)..];
+        // only the tag name matters, not its attributes
+        let name = tag.split_whitespace().next().unwrap_or_default();
+        match name {
+            "h2" => markdown.push_str("## "),
+            "/h2" | "/p" | "/ul" => markdown.push_str("\n\n"),
+            "pre" => {
+                in_pre = true;
+                markdown.push_str("```\n");
+            }
+            "/pre" => {
+                in_pre = false;
+                if !markdown.ends_with('\n') {
+                    markdown.push('\n');
+                }
+                markdown.push_str("```\n\n");
+            }
+            "code" | "/code" if !in_pre => markdown.push('`'),
+            "em" | "/em" => markdown.push_str("**"),
+            "li" => markdown.push_str("- "),
+            "/li" => markdown.push('\n'),
+            _ => {}
+        }
+    }
+    markdown.trim_end().to_string() + "\n"
+}
+
+/// fetches the puzzle page and stores its description as Markdown, returning
+/// the Markdown
+pub fn fetch(day: usize, refresh: bool) -> Result<String> {
+    let url = format!("{}/day/{}", client::BASE_URL, day);
+    let html = client::cached_get(&url, &format!("day{}.html", day), CACHE_MAX_AGE, refresh)?;
+    let parts = articles(&html);
+    if parts.is_empty() {
+        return Err(anyhow!("no puzzle description found for day {}", day));
+    }
+    let markdown = parts
+        .iter()
+        .map(|article| to_markdown(article))
+        .collect::<Vec<_>>()
+        .join("\n");
+    fs::write(markdown_path(day), &markdown)?;
+    Ok(markdown)
+}
+
+/// styles the inline **bold** and `code` spans of a line
+fn render_inline(line: &str) -> String {
+    let mut rendered = String::new();
+    let (mut bold, mut code) = (false, false);
+    let mut rest = line;
+    while let Some(i) = rest.find(['*', '`']) {
+        rendered.push_str(&rest[..i]);
+        if rest[i..].starts_with("**") && !code {
+            bold = !bold;
+            rendered.push_str(if bold { BOLD } else { RESET });
+            rest = &rest[(i + 2)..];
+        } else if rest[i..].starts_with('`') {
+            code = !code;
+            rendered.push_str(if code { CODE } else { RESET });
+            // code within bold text stays bold once the code ends
+            if !code && bold {
+                rendered.push_str(BOLD);
+            }
+            rest = &rest[(i + 1)..];
+        } else {
+            rendered.push('*');
+            rest = &rest[(i + 1)..];
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// the length of the text once styled, without the markers
+fn visible_len(word: &str) -> usize {
+    word.replace("**", "").replace('`', "").chars().count()
+}
+
+/// wraps the words of a paragraph to the width
+fn wrap(text: &str, width: usize, indent: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut len = 0;
+    for word in text.split_whitespace() {
+        let word_len = visible_len(word);
+        if len > 0 && len + 1 + word_len > width {
+            lines.push(std::mem::take(&mut line));
+            len = 0;
+        }
+        if len == 0 {
+            line.push_str(indent);
+            len = indent.len();
+        } else {
+            line.push(' ');
+            len += 1;
+        }
+        line.push_str(word);
+        len += word_len;
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// renders the Markdown for the terminal, with styled headings, emphasis and
+/// code, and paragraphs wrapped to the width
+pub fn render(markdown: &str, width: usize) -> String {
+    let mut lines = Vec::new();
+    let mut in_fence = false;
+    let mut paragraph = Vec::new();
+    let flush = |paragraph: &mut Vec<&str>, lines: &mut Vec<String>| {
+        if !paragraph.is_empty() {
+            let text = paragraph.join(" ");
+            // list items hang under their bullet
+            let (text, indent) = match text.strip_prefix("- ") {
+                Some(item) => (item.to_string(), "    "),
+                None => (text, ""),
+            };
+            for (i, line) in wrap(&text, width, indent).into_iter().enumerate() {
+                let line = match (i, indent.is_empty()) {
+                    (0, false) => format!("  - {}", &line[indent.len()..]),
+                    _ => line,
+                };
+                lines.push(render_inline(&line));
+            }
+            paragraph.clear();
+        }
+    };
+    for line in markdown.lines() {
+        if line.starts_with("```") {
+            flush(&mut paragraph, &mut lines);
+            in_fence = !in_fence;
+        } else if in_fence {
+            lines.push(format!("{}    {}{}", DIM, line, RESET));
+        } else if let Some(heading) = line.strip_prefix("## ") {
+            flush(&mut paragraph, &mut lines);
+            lines.push(format!("{}{}{}", BOLD, heading, RESET));
+            lines.push(String::new());
+        } else if line.starts_with("- ") {
+            // list items are wrapped on their own, rather than joined
+            flush(&mut paragraph, &mut lines);
+            paragraph.push(line);
+            flush(&mut paragraph, &mut lines);
+        } else if line.trim().is_empty() {
+            flush(&mut paragraph, &mut lines);
+            if lines.last().is_some_and(|line| !line.is_empty()) {
+                lines.push(String::new());
+            }
+        } else {
+            paragraph.push(line);
+        }
+    }
+    flush(&mut paragraph, &mut lines);
+    lines.join("\n").trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str =
+        "<main>\n<article class=\"day-desc\"><h2>--- Day 1: Calorie Counting ---</h2>\
+        <p>The Elves take turns writing down the number of <em>Calories</em> in the \
+        <code>input</code> &amp; more.</p>\n<pre><code>1000\n2000\n</code></pre>\n\
+        <ul>\n<li>The first Elf</li>\n</ul>\n</article>\n<p>Answer: 24000</p></main>";
+
+    #[test]
+    fn extract_articles() {
+        assert_eq!(articles(PAGE).len(), 1);
+        assert!(articles(PAGE)[0].starts_with("<h2>"));
+        assert!(articles("<main></main>").is_empty());
+    }
+
+    #[test]
+    fn html_to_markdown() {
+        assert_eq!(
+            to_markdown(articles(PAGE)[0]),
+            "## --- Day 1: Calorie Counting ---\n\n\
+             The Elves take turns writing down the number of **Calories** in the `input` & more.\n\n\
+             ```\n1000\n2000\n```\n\n- The first Elf\n"
+        );
+    }
+
+    #[test]
+    fn render_markdown() {
+        let rendered = render(&to_markdown(articles(PAGE)[0]), 40);
+        assert_eq!(
+            rendered,
+            [
+                "\x1b[1m--- Day 1: Calorie Counting ---\x1b[0m",
+                "",
+                "The Elves take turns writing down the",
+                "number of \x1b[1mCalories\x1b[0m in the \x1b[36minput\x1b[0m & more.",
+                "",
+                "\x1b[2m    1000\x1b[0m",
+                "\x1b[2m    2000\x1b[0m",
+                "",
+                "  - The first Elf",
+            ]
+            .join("\n")
+        );
+    }
+}
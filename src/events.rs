@@ -0,0 +1,114 @@
+/*
+** src/events.rs
+*/
+
+use crate::json::Json;
+
+use anyhow::{anyhow, Result};
+
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// events are only written once the JSONL log format has been selected
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// how log messages are written
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFormat {
+    /// timestamped lines for people
+    Text,
+    /// one JSON object per line, for dashboards and jq
+    Jsonl,
+}
+
+impl FromStr for LogFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "text" => Ok(Self::Text),
+            "jsonl" => Ok(Self::Jsonl),
+            _ => Err(anyhow!("unknown log format {}, expected text or jsonl", s)),
+        }
+    }
+}
+
+/// starts writing events, see emit
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+fn timestamp() -> String {
+    chrono::Local::now().to_rfc3339()
+}
+
+/// formats an event as a JSON line, with the time and name of the event
+/// alongside its fields
+pub fn line<'a, I>(time: &str, event: &str, fields: I) -> String
+where
+    I: IntoIterator<Item = (&'a str, Json)>,
+{
+    let mut object = Json::object(fields);
+    if let Json::Object(map) = &mut object {
+        map.insert(String::from("time"), Json::from(time));
+        map.insert(String::from("event"), Json::from(event));
+    }
+    object.to_string()
+}
+
+/// formats a log message as a JSON line, for the logger in JSONL mode
+pub fn log_line(record: &log::Record) -> String {
+    line(
+        &timestamp(),
+        "log",
+        [
+            ("level", Json::from(record.level().as_str().to_lowercase())),
+            ("message", Json::from(record.args().to_string())),
+        ],
+    )
+}
+
+/// writes a progress event, such as a day starting or finishing, as a JSON
+/// line to stdout, if enabled
+pub fn emit<'a, I>(event: &str, fields: I)
+where
+    I: IntoIterator<Item = (&'a str, Json)>,
+{
+    if is_enabled() {
+        println!("{}", line(&timestamp(), event, fields));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_lines() {
+        let s = line(
+            "2022-12-16T06:00:00+00:00",
+            "day_finished",
+            [("day", Json::from(16usize)), ("seconds", Json::from(1.5))],
+        );
+        assert_eq!(
+            s,
+            "{\"day\":16,\"event\":\"day_finished\",\"seconds\":1.5,\
+             \"time\":\"2022-12-16T06:00:00+00:00\"}"
+        );
+        assert_eq!(
+            Json::parse(&s).unwrap().get("day").unwrap().as_i64(),
+            Some(16)
+        );
+    }
+
+    #[test]
+    fn log_formats() {
+        assert_eq!("jsonl".parse::<LogFormat>().unwrap(), LogFormat::Jsonl);
+        assert_eq!("text".parse::<LogFormat>().unwrap(), LogFormat::Text);
+        assert!("xml".parse::<LogFormat>().is_err());
+    }
+}
@@ -0,0 +1,68 @@
+/*
+** src/explain.rs
+*/
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// steps are only recorded once explanations have been enabled
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// steps are kept per thread so that days solved in parallel do not
+    /// interleave their explanations
+    static STEPS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// starts recording explanation steps
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// records a step of the explanation for the puzzle being solved on this
+/// thread; prefer the explain! macro, which skips formatting when disabled
+pub fn step<S: Into<String>>(step: S) {
+    if is_enabled() {
+        STEPS.with(|steps| steps.borrow_mut().push(step.into()));
+    }
+}
+
+/// takes the steps recorded on this thread so far
+pub fn take() -> Vec<String> {
+    STEPS.with(|steps| steps.take())
+}
+
+/// records a human-readable milestone of the solution, shown with --explain
+#[macro_export]
+macro_rules! explain {
+    ($($arg:tt)*) => {
+        if $crate::explain::is_enabled() {
+            $crate::explain::step(format!($($arg)*));
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_steps() {
+        enable();
+        take();
+        explain!("row {} covered by {} ranges", 10, 2);
+        step("found the beacon");
+        assert_eq!(
+            take(),
+            vec![
+                String::from("row 10 covered by 2 ranges"),
+                String::from("found the beacon")
+            ]
+        );
+        assert!(take().is_empty());
+    }
+}
@@ -0,0 +1,334 @@
+/*
+** src/ffi.rs
+*/
+
+// C API for embedding the solutions in other tooling; build the library with
+//   cargo rustc --release --lib --features ffi --crate-type cdylib
+// or with --crate-type staticlib for a static library, which is written to
+// target/release, and include include/aoc2022.h, which is generated from
+// header() below
+
+use crate::context::Context;
+use crate::parts::Parts;
+use crate::puzzles;
+
+use std::ffi::{c_char, CString};
+use std::panic;
+use std::ptr;
+use std::slice;
+use std::time::Instant;
+
+pub const STATUS_OK: i32 = 0;
+pub const STATUS_INVALID_DAY: i32 = -1;
+pub const STATUS_INVALID_INPUT: i32 = -2;
+pub const STATUS_FAILED: i32 = -3;
+
+/// the answers to a day, filled in by aoc2022_run and released with
+/// aoc2022_result_free
+#[repr(C)]
+pub struct Aoc2022Result {
+    /// one of the STATUS_* codes, as also returned by aoc2022_run
+    pub status: i32,
+    /// NUL-terminated answers, or NULL for parts without an answer
+    pub part_1: *mut c_char,
+    pub part_2: *mut c_char,
+    /// time taken to solve the day
+    pub seconds: f64,
+}
+
+fn into_c_string(answer: Option<String>) -> Result<*mut c_char, i32> {
+    match answer {
+        Some(answer) => CString::new(answer)
+            .map(CString::into_raw)
+            .map_err(|_| STATUS_FAILED),
+        None => Ok(ptr::null_mut()),
+    }
+}
+
+fn run(day: u32, input: &[u8], out: &mut Aoc2022Result) -> i32 {
+    let day = day as usize;
+    if !(1..=puzzles::N_DAYS).contains(&day) {
+        return STATUS_INVALID_DAY;
+    }
+    let Ok(input) = std::str::from_utf8(input) else {
+        return STATUS_INVALID_INPUT;
+    };
+    let tstart = Instant::now();
+    // solvers may panic on malformed input, which must not unwind into C
+    let Ok(Ok(solution)) =
+        panic::catch_unwind(|| puzzles::DAYS[day - 1](input, Parts::Both, &Context::none()))
+    else {
+        return STATUS_FAILED;
+    };
+    out.seconds = tstart.elapsed().as_secs_f64();
+    let part_1 = into_c_string(solution.part_1.map(|a| a.to_string()));
+    let part_2 = into_c_string(solution.part_2.map(|a| a.to_string()));
+    match (part_1, part_2) {
+        (Ok(part_1), Ok(part_2)) => {
+            out.part_1 = part_1;
+            out.part_2 = part_2;
+            STATUS_OK
+        }
+        (part_1, part_2) => {
+            // SAFETY: any answer which was converted came from into_raw
+            for answer in [part_1, part_2].into_iter().flatten() {
+                if !answer.is_null() {
+                    unsafe { drop(CString::from_raw(answer)) };
+                }
+            }
+            STATUS_FAILED
+        }
+    }
+}
+
+/// solves the day on the input, writing the answers to out
+///
+/// # Safety
+///
+/// input must point to len readable bytes, and out to a writable result,
+/// which must later be released with aoc2022_result_free
+#[no_mangle]
+pub unsafe extern "C" fn aoc2022_run(
+    day: u32,
+    input: *const u8,
+    len: usize,
+    out: *mut Aoc2022Result,
+) -> i32 {
+    if out.is_null() {
+        return STATUS_INVALID_INPUT;
+    }
+    let out = &mut *out;
+    *out = Aoc2022Result {
+        status: STATUS_INVALID_INPUT,
+        part_1: ptr::null_mut(),
+        part_2: ptr::null_mut(),
+        seconds: 0.0,
+    };
+    if input.is_null() && len > 0 {
+        return out.status;
+    }
+    let input = if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(input, len)
+    };
+    out.status = run(day, input, out);
+    out.status
+}
+
+/// releases the answers of a result filled in by aoc2022_run
+///
+/// # Safety
+///
+/// result must be NULL or have been filled in by aoc2022_run, and must not be
+/// freed twice
+#[no_mangle]
+pub unsafe extern "C" fn aoc2022_result_free(result: *mut Aoc2022Result) {
+    let Some(result) = result.as_mut() else {
+        return;
+    };
+    for answer in [&mut result.part_1, &mut result.part_2] {
+        if !answer.is_null() {
+            drop(CString::from_raw(*answer));
+            *answer = ptr::null_mut();
+        }
+    }
+}
+
+/// the C header for the API above
+pub fn header() -> String {
+    format!(
+        "\
+/*
+** include/aoc2022.h
+** generated by aoc2022::ffi::header(), do not edit
+*/
+
+#ifndef AOC2022_H
+#define AOC2022_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#define AOC2022_STATUS_OK {}
+#define AOC2022_STATUS_INVALID_DAY {}
+#define AOC2022_STATUS_INVALID_INPUT {}
+#define AOC2022_STATUS_FAILED {}
+
+typedef struct {{
+    int32_t status;
+    /* NUL-terminated answers, or NULL for parts without an answer */
+    char *part_1;
+    char *part_2;
+    double seconds;
+}} aoc2022_result;
+
+/* solves the day on the input, returning the status; release the result
+ * with aoc2022_result_free */
+int32_t aoc2022_run(uint32_t day, const uint8_t *input, size_t len, aoc2022_result *out);
+
+void aoc2022_result_free(aoc2022_result *result);
+
+#endif
+",
+        STATUS_OK, STATUS_INVALID_DAY, STATUS_INVALID_INPUT, STATUS_FAILED
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::ffi::CStr;
+    use std::fs;
+    use std::path::Path;
+
+    fn header_path() -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("include")
+            .join("aoc2022.h")
+    }
+
+    fn empty_result() -> Aoc2022Result {
+        Aoc2022Result {
+            status: 0,
+            part_1: ptr::null_mut(),
+            part_2: ptr::null_mut(),
+            seconds: 0.0,
+        }
+    }
+
+    #[test]
+    fn run_day() {
+        let input = include_str!("../input/D1.dbg.txt");
+        let mut result = empty_result();
+        let status = unsafe { aoc2022_run(1, input.as_ptr(), input.len(), &mut result) };
+        assert_eq!(status, STATUS_OK);
+        assert_eq!(result.status, STATUS_OK);
+        let part_1 = unsafe { CStr::from_ptr(result.part_1) };
+        let part_2 = unsafe { CStr::from_ptr(result.part_2) };
+        assert_eq!(part_1.to_str().unwrap(), "24000");
+        assert_eq!(part_2.to_str().unwrap(), "45000");
+        unsafe { aoc2022_result_free(&mut result) };
+        assert!(result.part_1.is_null());
+        assert!(result.part_2.is_null());
+    }
+
+    #[test]
+    fn run_invalid() {
+        let mut result = empty_result();
+        let status = unsafe { aoc2022_run(26, ptr::null(), 0, &mut result) };
+        assert_eq!(status, STATUS_INVALID_DAY);
+        let input = [0xff, 0xfe];
+        let status = unsafe { aoc2022_run(1, input.as_ptr(), input.len(), &mut result) };
+        assert_eq!(status, STATUS_INVALID_INPUT);
+        let input = "not a number";
+        let status = unsafe { aoc2022_run(1, input.as_ptr(), input.len(), &mut result) };
+        assert_eq!(status, STATUS_FAILED);
+        assert!(result.part_1.is_null());
+        unsafe { aoc2022_result_free(&mut result) };
+        unsafe { aoc2022_result_free(ptr::null_mut()) };
+    }
+
+    /// the size and alignment of the C types used in the header, on this
+    /// platform
+    fn c_layout(c_type: &str) -> (usize, usize) {
+        match c_type {
+            "int32_t" | "uint32_t" => (4, 4),
+            "double" => (8, 8),
+            _ if c_type.ends_with('*') => (
+                std::mem::size_of::<*mut c_char>(),
+                std::mem::align_of::<*mut c_char>(),
+            ),
+            _ => panic!("unknown C type {}", c_type),
+        }
+    }
+
+    #[test]
+    fn header_matches_layout() {
+        // lay out the fields of the struct in the header as a C compiler
+        // would, and check them against the Rust struct
+        let header = super::header();
+        let body = header
+            .split("typedef struct {")
+            .nth(1)
+            .and_then(|rest| rest.split("} aoc2022_result;").next())
+            .unwrap();
+        let mut offset: usize = 0;
+        let mut max_align: usize = 1;
+        let mut fields = Vec::new();
+        for line in body.lines().map(str::trim) {
+            let Some(declaration) = line.strip_suffix(';') else {
+                continue;
+            };
+            let (c_type, name) = declaration.rsplit_once(' ').unwrap();
+            let (c_type, name) = match name.strip_prefix('*') {
+                Some(name) => (format!("{}*", c_type.trim()), name),
+                None => (c_type.trim().to_string(), name),
+            };
+            let (size, align) = c_layout(&c_type);
+            offset = offset.next_multiple_of(align);
+            fields.push((name.to_string(), offset, size));
+            offset += size;
+            max_align = max_align.max(align);
+        }
+        let rust_fields = [
+            (
+                "status",
+                std::mem::offset_of!(Aoc2022Result, status),
+                std::mem::size_of::<i32>(),
+            ),
+            (
+                "part_1",
+                std::mem::offset_of!(Aoc2022Result, part_1),
+                std::mem::size_of::<*mut c_char>(),
+            ),
+            (
+                "part_2",
+                std::mem::offset_of!(Aoc2022Result, part_2),
+                std::mem::size_of::<*mut c_char>(),
+            ),
+            (
+                "seconds",
+                std::mem::offset_of!(Aoc2022Result, seconds),
+                std::mem::size_of::<f64>(),
+            ),
+        ]
+        .map(|(name, offset, size)| (name.to_string(), offset, size));
+        assert_eq!(fields, rust_fields);
+        assert_eq!(
+            offset.next_multiple_of(max_align),
+            std::mem::size_of::<Aoc2022Result>()
+        );
+        assert_eq!(max_align, std::mem::align_of::<Aoc2022Result>());
+    }
+
+    #[test]
+    fn header_declares_functions() {
+        // the declarations must match the signatures of the extern functions
+        let _: unsafe extern "C" fn(u32, *const u8, usize, *mut Aoc2022Result) -> i32 = aoc2022_run;
+        let _: unsafe extern "C" fn(*mut Aoc2022Result) = aoc2022_result_free;
+        let header = super::header();
+        assert!(header.contains(
+            "int32_t aoc2022_run(uint32_t day, const uint8_t *input, size_t len, aoc2022_result *out);"
+        ));
+        assert!(header.contains("void aoc2022_result_free(aoc2022_result *result);"));
+    }
+
+    #[test]
+    fn header_is_up_to_date() {
+        let header = fs::read_to_string(header_path()).unwrap();
+        assert_eq!(
+            header,
+            super::header(),
+            "regenerate with cargo test --features ffi write_header -- --ignored"
+        );
+    }
+
+    #[test]
+    #[ignore]
+    fn write_header() {
+        fs::create_dir_all(header_path().parent().unwrap()).unwrap();
+        fs::write(header_path(), super::header()).unwrap();
+    }
+}
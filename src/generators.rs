@@ -0,0 +1,254 @@
+/*
+** src/generators.rs
+*/
+
+use anyhow::{anyhow, Result};
+
+use std::collections::HashSet;
+
+/// bounds of the day 15 search area, matching the solver
+#[cfg(feature = "sample")]
+const DAY_15_COORD_MAX: i64 = 20;
+#[cfg(not(feature = "sample"))]
+const DAY_15_COORD_MAX: i64 = 4000000;
+/// the day 16 search is exponential in the number of flowing valves, so keep
+/// this at the same scale as the real input regardless of the graph size
+const DAY_16_MAX_FLOWING: usize = 15;
+
+/// input generator, given a random number generator and a size
+pub type Generator = fn(&mut Rng, usize) -> String;
+
+/// days which can have inputs generated, with their default sizes
+pub const GENERATORS: [(usize, Generator, usize); 5] = [
+    (8, day_8, 99),
+    (12, day_12, 41),
+    (14, day_14, 150),
+    (15, day_15, 30),
+    (16, day_16, 60),
+];
+
+/// xorshift random number generator, so that generated inputs are
+/// reproducible from their seed
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // xorshift gets stuck at zero
+        Self(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// generates a number in the range [min, max)
+    pub fn range(&mut self, min: i64, max: i64) -> i64 {
+        min + (self.next_u64() % (max - min) as u64) as i64
+    }
+
+    /// generates an index in the range [0, n)
+    pub fn index(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+/// generates an input for the given day, using the default size if none is
+/// provided
+pub fn generate(day: usize, size: Option<usize>, seed: u64) -> Result<String> {
+    let (_, generator, default_size) = GENERATORS
+        .iter()
+        .find(|(d, _, _)| *d == day)
+        .ok_or_else(|| anyhow!("no input generator for day {}", day))?;
+    let mut rng = Rng::new(seed);
+    Ok(generator(&mut rng, size.unwrap_or(*default_size)))
+}
+
+/// generates a square of tree heights with the given number of rows
+fn day_8(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| char::from(b'0' + rng.range(0, 10) as u8))
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// generates a height-map with the given number of rows, four times as wide,
+/// whose first row climbs steadily from the start to the summit so that the
+/// summit is always reachable
+fn day_12(rng: &mut Rng, size: usize) -> String {
+    let width = (size * 4).max(26);
+    (0..size)
+        .map(|row| {
+            (0..width)
+                .map(|col| match (row, col) {
+                    (0, 0) => 'S',
+                    (0, col) if col == width - 1 => 'E',
+                    (0, col) => char::from(b'a' + (col * 25 / (width - 1)) as u8),
+                    _ => char::from(b'a' + rng.range(0, 26) as u8),
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// generates the given number of rock paths below the sand source
+fn day_14(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            // start somewhere below the sand source, then alternate between
+            // horizontal and vertical segments
+            let mut x = rng.range(440, 560);
+            let mut y = rng.range(10, 170);
+            let mut points = vec![format!("{},{}", x, y)];
+            let n_segments = rng.range(1, 6);
+            for i in 0..n_segments {
+                if (i + n_segments) % 2 == 0 {
+                    x += rng.range(-8, 9);
+                } else {
+                    y = (y + rng.range(-5, 6)).max(1);
+                }
+                points.push(format!("{},{}", x, y));
+            }
+            points.join(" -> ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// generates the given number of sensors, placed so that their ranges all
+/// stop just short of a hidden distress beacon
+///
+/// note that the sensors are not guaranteed to cover the rest of the search
+/// area, so a solver may find a different uncovered point
+fn day_15(rng: &mut Rng, size: usize) -> String {
+    let distress_x = rng.range(0, DAY_15_COORD_MAX + 1);
+    let distress_y = rng.range(0, DAY_15_COORD_MAX + 1);
+    (0..size)
+        .map(|_| {
+            // keep the sensor at least 2 away so that its range is non-empty
+            let (x, y) = loop {
+                let x = rng.range(0, DAY_15_COORD_MAX + 1);
+                let y = rng.range(0, DAY_15_COORD_MAX + 1);
+                if (x - distress_x).abs() + (y - distress_y).abs() >= 2 {
+                    break (x, y);
+                }
+            };
+            // place the beacon on the edge of the range
+            let distance = (x - distress_x).abs() + (y - distress_y).abs() - 1;
+            let dx = rng.range(0, distance + 1);
+            let dy = distance - dx;
+            let beacon_x = if rng.next_u64().is_multiple_of(2) {
+                x + dx
+            } else {
+                x - dx
+            };
+            let beacon_y = if rng.next_u64().is_multiple_of(2) {
+                y + dy
+            } else {
+                y - dy
+            };
+            format!(
+                "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
+                x, y, beacon_x, beacon_y
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn valve_name(i: usize) -> String {
+    let first = (b'A' + (i / 26) as u8) as char;
+    let second = (b'A' + (i % 26) as u8) as char;
+    format!("{}{}", first, second)
+}
+
+/// generates a connected graph with the given number of valves
+fn day_16(rng: &mut Rng, size: usize) -> String {
+    let size = size.clamp(2, 26 * 26);
+    // connect the valves with a random spanning tree, then add a few extra
+    // tunnels to create cycles
+    let mut tunnels = vec![Vec::new(); size];
+    let mut add_tunnel = |a: usize, b: usize| {
+        if a != b && !tunnels[a].contains(&b) {
+            tunnels[a].push(b);
+            tunnels[b].push(a);
+        }
+    };
+    for i in 1..size {
+        add_tunnel(i, rng.index(i));
+    }
+    for _ in 0..(size / 4) {
+        add_tunnel(rng.index(size), rng.index(size));
+    }
+    // pick which valves are flowing, never including the start valve
+    let n_flowing = (size / 3).clamp(1, DAY_16_MAX_FLOWING).min(size - 1);
+    let mut flowing = HashSet::new();
+    while flowing.len() < n_flowing {
+        flowing.insert(1 + rng.index(size - 1));
+    }
+    (0..size)
+        .map(|i| {
+            let flow_rate = if flowing.contains(&i) {
+                rng.range(1, 26)
+            } else {
+                0
+            };
+            let connections = tunnels[i]
+                .iter()
+                .map(|&v| valve_name(v))
+                .collect::<Vec<_>>();
+            let tunnel_str = if connections.len() == 1 {
+                "tunnel leads to valve"
+            } else {
+                "tunnels lead to valves"
+            };
+            format!(
+                "Valve {} has flow rate={}; {} {}",
+                valve_name(i),
+                flow_rate,
+                tunnel_str,
+                connections.join(", ")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::Context;
+    use crate::parts::Parts;
+    use crate::puzzles;
+
+    #[test]
+    fn generated_inputs_are_reproducible() {
+        for &(day, _, _) in GENERATORS.iter() {
+            let a = generate(day, Some(10), 7).unwrap();
+            let b = generate(day, Some(10), 7).unwrap();
+            assert_eq!(a, b, "day {}", day);
+        }
+    }
+
+    #[test]
+    fn generated_inputs_are_solvable() {
+        for &(day, _, _) in GENERATORS.iter() {
+            let input = generate(day, Some(12), 1).unwrap();
+            let solution = puzzles::DAYS[day - 1](&input, Parts::Both, &Context::none()).unwrap();
+            assert!(solution.part_1.is_some(), "day {}", day);
+            assert!(solution.part_2.is_some(), "day {}", day);
+        }
+    }
+
+    #[test]
+    fn no_generator() {
+        assert!(generate(1, None, 1).is_err());
+    }
+}
@@ -0,0 +1,119 @@
+/*
+** src/grid_diff.rs
+*/
+
+use crate::types::Point;
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// how a cell differs between two states of a grid
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Change<T> {
+    Added(T),
+    Removed(T),
+    Modified(T, T),
+}
+
+/// the cells of a dense grid, stored as rows, with x as the column and y as
+/// the row
+pub fn cells<T: Clone>(grid: &[Vec<T>]) -> impl Iterator<Item = (Point, T)> + '_ {
+    grid.iter().enumerate().flat_map(|(y, row)| {
+        row.iter()
+            .enumerate()
+            .map(move |(x, cell)| (Point::new(x as i64, y as i64), cell.clone()))
+    })
+}
+
+/// diffs two states of a grid, given as their occupied cells, such as the
+/// entries of a sparse grid or the cells of a dense grid; the changed cells
+/// are returned in reading order, top to bottom and then left to right
+pub fn diff<T, A, B>(before: A, after: B) -> Vec<(Point, Change<T>)>
+where
+    T: PartialEq,
+    A: IntoIterator<Item = (Point, T)>,
+    B: IntoIterator<Item = (Point, T)>,
+{
+    let mut before = before.into_iter().collect::<HashMap<_, _>>();
+    let mut changes = Vec::new();
+    for (point, cell) in after {
+        match before.remove(&point) {
+            Some(old) if old == cell => {}
+            Some(old) => changes.push((point, Change::Modified(old, cell))),
+            None => changes.push((point, Change::Added(cell))),
+        }
+    }
+    changes.extend(
+        before
+            .into_iter()
+            .map(|(point, cell)| (point, Change::Removed(cell))),
+    );
+    changes.sort_by_key(|(point, _)| (point.y, point.x));
+    changes
+}
+
+/// renders the changed cells one per line, as "+" for added, "-" for removed
+/// and "~" for modified cells, followed by a count of each
+pub fn render<T: fmt::Display>(changes: &[(Point, Change<T>)]) -> String {
+    let (mut added, mut removed, mut modified) = (0, 0, 0);
+    let mut lines = changes
+        .iter()
+        .map(|(point, change)| match change {
+            Change::Added(cell) => {
+                added += 1;
+                format!("+ {} {}", point, cell)
+            }
+            Change::Removed(cell) => {
+                removed += 1;
+                format!("- {} {}", point, cell)
+            }
+            Change::Modified(old, new) => {
+                modified += 1;
+                format!("~ {} {} -> {}", point, old, new)
+            }
+        })
+        .collect::<Vec<_>>();
+    lines.push(format!(
+        "{} added, {} removed, {} modified",
+        added, removed, modified
+    ));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_dense_grids() {
+        let before = vec![vec!['.', '#', '.'], vec!['.', '.', '.']];
+        let after = vec![vec!['.', 'o', '.'], vec!['.', '.', 'o']];
+        let changes = diff(cells(&before), cells(&after));
+        assert_eq!(
+            changes,
+            vec![
+                (Point::new(1, 0), Change::Modified('#', 'o')),
+                (Point::new(2, 1), Change::Modified('.', 'o')),
+            ]
+        );
+        // rows of different lengths add and remove cells
+        let changes = diff(cells(&before), cells(&after[..1]));
+        assert_eq!(changes.len(), 4);
+        assert_eq!(changes[1], (Point::new(0, 1), Change::Removed('.')));
+    }
+
+    #[test]
+    fn diff_sparse_grids() {
+        let before = [(Point::new(500, 8), "sand"), (Point::new(498, 4), "rock")];
+        let after = [(Point::new(498, 4), "rock"), (Point::new(499, 8), "sand")];
+        let changes = diff(before, after);
+        assert_eq!(
+            render(&changes),
+            "+ (499,8) sand\n- (500,8) sand\n1 added, 1 removed, 0 modified"
+        );
+        assert_eq!(
+            render(&diff(before, before)),
+            "0 added, 0 removed, 0 modified"
+        );
+    }
+}
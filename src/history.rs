@@ -0,0 +1,320 @@
+/*
+** src/history.rs
+*/
+
+use crate::json::Json;
+
+use anyhow::{anyhow, Result};
+
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const PROJECT_DIR: &str = env!("CARGO_MANIFEST_DIR");
+
+/// the git-ignored file which runs are recorded to, one JSON object per line
+pub fn history_path() -> PathBuf {
+    Path::new(PROJECT_DIR).join(".aoc").join("history.jsonl")
+}
+
+/// the current git revision, if the project is a git checkout
+pub fn git_revision() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .current_dir(PROJECT_DIR)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let revision = String::from_utf8(output.stdout).ok()?;
+    Some(revision.trim().to_string())
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DayRecord {
+    pub day: usize,
+    pub seconds: f64,
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+}
+
+/// the results of a single run of the puzzles
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunRecord {
+    /// unix timestamp of the run
+    pub timestamp: i64,
+    pub revision: Option<String>,
+    /// the input variant the days were run on, or None for the real input
+    pub input: Option<String>,
+    pub days: Vec<DayRecord>,
+}
+
+impl RunRecord {
+    pub fn new(days: Vec<DayRecord>) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().timestamp(),
+            revision: git_revision(),
+            input: None,
+            days,
+        }
+    }
+
+    pub fn total_seconds(&self) -> f64 {
+        self.days.iter().map(|day| day.seconds).sum()
+    }
+
+    pub fn day(&self, day: usize) -> Option<&DayRecord> {
+        self.days.iter().find(|record| record.day == day)
+    }
+
+    pub fn to_json(&self) -> Json {
+        let days = self
+            .days
+            .iter()
+            .map(|day| {
+                Json::object([
+                    ("day", Json::from(day.day)),
+                    ("seconds", Json::from(day.seconds)),
+                    ("part_1", Json::from(day.part_1.clone())),
+                    ("part_2", Json::from(day.part_2.clone())),
+                ])
+            })
+            .collect::<Vec<_>>();
+        Json::object([
+            ("timestamp", Json::from(self.timestamp)),
+            ("revision", Json::from(self.revision.clone())),
+            ("input", Json::from(self.input.clone())),
+            ("days", Json::Array(days)),
+        ])
+    }
+
+    pub fn from_json(json: &Json) -> Result<Self> {
+        let invalid = || anyhow!("invalid run record");
+        let string = |json: Option<&Json>| json.and_then(|s| s.as_str()).map(|s| s.to_string());
+        let days = json
+            .get("days")
+            .and_then(|days| days.as_array())
+            .ok_or_else(invalid)?
+            .iter()
+            .map(|day| {
+                Ok(DayRecord {
+                    day: day
+                        .get("day")
+                        .and_then(|d| d.as_i64())
+                        .ok_or_else(invalid)? as usize,
+                    seconds: day
+                        .get("seconds")
+                        .and_then(|s| s.as_f64())
+                        .ok_or_else(invalid)?,
+                    part_1: string(day.get("part_1")),
+                    part_2: string(day.get("part_2")),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            timestamp: json
+                .get("timestamp")
+                .and_then(|t| t.as_i64())
+                .ok_or_else(invalid)?,
+            revision: string(json.get("revision")),
+            // runs recorded before inputs were tracked were on the real input
+            input: string(json.get("input")),
+            days,
+        })
+    }
+}
+
+/// sums the most recently recorded time for each day, since runs may only
+/// cover some of the days
+pub fn latest_total_seconds(records: &[RunRecord]) -> Option<f64> {
+    let mut latest = BTreeMap::new();
+    for record in records.iter() {
+        for day in record.days.iter() {
+            latest.insert(day.day, day.seconds);
+        }
+    }
+    if latest.is_empty() {
+        None
+    } else {
+        Some(latest.values().sum())
+    }
+}
+
+/// the most recently recorded answers for the day on the input, where None
+/// is the real input
+pub fn latest_day<'a>(
+    records: &'a [RunRecord],
+    day: usize,
+    input: Option<&str>,
+) -> Option<&'a DayRecord> {
+    records
+        .iter()
+        .rev()
+        .filter(|record| record.input.as_deref() == input)
+        .find_map(|record| record.day(day))
+}
+
+/// compares the answers of each part of the day against a previous record,
+/// as lines such as "day 15 part 2: CHANGED (1 -> 2)"
+pub fn diff_answers(previous: &DayRecord, current: &DayRecord) -> Vec<String> {
+    let show = |answer: &Option<String>| answer.as_deref().unwrap_or("none").to_string();
+    [
+        (1, &previous.part_1, &current.part_1),
+        (2, &previous.part_2, &current.part_2),
+    ]
+    .into_iter()
+    .filter(|(_, before, after)| before.is_some() || after.is_some())
+    .map(|(part, before, after)| {
+        if before == after {
+            format!("day {} part {}: UNCHANGED", current.day, part)
+        } else {
+            format!(
+                "day {} part {}: CHANGED ({} -> {})",
+                current.day,
+                part,
+                show(before),
+                show(after)
+            )
+        }
+    })
+    .collect()
+}
+
+/// appends the run to the history file
+pub fn append(path: &Path, record: &RunRecord) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", record.to_json())?;
+    Ok(())
+}
+
+/// loads every recorded run, oldest first; a missing file is an empty history
+pub fn load(path: &Path) -> Result<Vec<RunRecord>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    fs::read_to_string(path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(i, line)| {
+            Json::parse(line)
+                .and_then(|json| RunRecord::from_json(&json))
+                .map_err(|e| anyhow!("{} line {}: {}", path.to_string_lossy(), i + 1, e))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_record_round_trip() {
+        let record = RunRecord {
+            timestamp: 1670000000,
+            revision: Some(String::from("abc1234")),
+            input: Some(String::from("edge")),
+            days: vec![
+                DayRecord {
+                    day: 1,
+                    seconds: 0.25,
+                    part_1: Some(String::from("24000")),
+                    part_2: None,
+                },
+                DayRecord {
+                    day: 10,
+                    seconds: 1.5,
+                    part_1: Some(String::from("13140")),
+                    part_2: Some(String::from("\n##..\n..##")),
+                },
+            ],
+        };
+        let json = Json::parse(&record.to_json().to_string()).unwrap();
+        assert_eq!(RunRecord::from_json(&json).unwrap(), record);
+        assert_eq!(record.total_seconds(), 1.75);
+    }
+
+    #[test]
+    fn latest_total() {
+        let day = |day, seconds| DayRecord {
+            day,
+            seconds,
+            part_1: None,
+            part_2: None,
+        };
+        let records = [
+            RunRecord {
+                timestamp: 1,
+                revision: None,
+                input: None,
+                days: vec![day(1, 1.0), day(2, 2.0)],
+            },
+            RunRecord {
+                timestamp: 2,
+                revision: None,
+                input: None,
+                days: vec![day(2, 0.5)],
+            },
+        ];
+        assert_eq!(latest_total_seconds(&records), Some(1.5));
+        assert_eq!(latest_total_seconds(&[]), None);
+    }
+
+    #[test]
+    fn diff_latest_answers() {
+        let day = |part_1: &str, part_2: Option<&str>| DayRecord {
+            day: 16,
+            seconds: 1.0,
+            part_1: Some(part_1.to_string()),
+            part_2: part_2.map(|s| s.to_string()),
+        };
+        let records = [
+            RunRecord {
+                timestamp: 1,
+                revision: None,
+                input: None,
+                days: vec![day("1651", Some("1707"))],
+            },
+            RunRecord {
+                timestamp: 2,
+                revision: None,
+                input: Some(String::from("edge")),
+                days: vec![day("0", None)],
+            },
+        ];
+        let previous = latest_day(&records, 16, None).unwrap();
+        assert_eq!(previous.part_1.as_deref(), Some("1651"));
+        assert!(latest_day(&records, 15, None).is_none());
+        assert_eq!(
+            diff_answers(previous, &day("1651", Some("1706"))),
+            vec![
+                "day 16 part 1: UNCHANGED",
+                "day 16 part 2: CHANGED (1707 -> 1706)"
+            ]
+        );
+        let previous = latest_day(&records, 16, Some("edge")).unwrap();
+        assert_eq!(
+            diff_answers(previous, &day("0", None)),
+            vec!["day 16 part 1: UNCHANGED"]
+        );
+        assert_eq!(
+            diff_answers(previous, &day("0", Some("1"))),
+            vec![
+                "day 16 part 1: UNCHANGED",
+                "day 16 part 2: CHANGED (none -> 1)"
+            ]
+        );
+    }
+
+    #[test]
+    fn load_missing_history() {
+        let path = Path::new("/nonexistent/history.jsonl");
+        assert!(load(path).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+/*
+** src/input.rs
+*/
+
+use crate::types::Grid;
+use crate::utils;
+
+use anyhow::Result;
+
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+
+/// a day's puzzle input, without any trailing newlines, with accessors for
+/// the common shapes of input; the lines of a block are numbered as in the
+/// whole input in the errors of its parsers
+#[derive(Clone, Copy, Debug)]
+pub struct PuzzleInput<'a> {
+    day: usize,
+    text: &'a str,
+    /// the line of the whole input which the text starts on, from 1
+    first_line: usize,
+}
+
+impl<'a> PuzzleInput<'a> {
+    pub fn new(day: usize, text: &'a str) -> Self {
+        Self {
+            day,
+            text: text.trim_end_matches('\n'),
+            first_line: 1,
+        }
+    }
+
+    pub fn day(&self) -> usize {
+        self.day
+    }
+
+    pub fn as_str(&self) -> &'a str {
+        self.text
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = &'a str> {
+        utils::split_lines(self.text)
+    }
+
+    pub fn chars(&self) -> impl Iterator<Item = char> + 'a {
+        self.text.chars()
+    }
+
+    /// the blocks of lines, which are separated by blank lines
+    pub fn blocks(&self) -> impl Iterator<Item = PuzzleInput<'a>> {
+        let day = self.day;
+        let mut first_line = self.first_line;
+        self.text.split("\n\n").map(move |text| {
+            let block = Self {
+                day,
+                text,
+                first_line,
+            };
+            // skip the lines of the block and the blank line after it
+            first_line += utils::split_lines(text).count() + 1;
+            block
+        })
+    }
+
+    /// parses each line, see utils::parse_lines
+    pub fn parse_lines<T, F>(&self, parse: F) -> Result<Vec<T>>
+    where
+        F: Fn(&'a str) -> std::result::Result<T, String>,
+    {
+        utils::parse_lines_from(self.day, self.first_line, self.text, parse)
+    }
+
+    /// parses a number from each line
+    pub fn numbers<T>(&self) -> Result<Vec<T>>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: fmt::Display,
+    {
+        self.parse_lines(|line| utils::parse_field(line, "number"))
+    }
+
+    /// parses each character as a cell of a grid, see Grid::from_input
+    pub fn grid<T, F>(&self, parse: F) -> Result<Grid<T>>
+    where
+        F: Fn(char) -> std::result::Result<T, String>,
+    {
+        Grid::from_input(self.day, self.text, parse)
+    }
+}
+
+impl Deref for PuzzleInput<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trailing_newlines() {
+        let input = PuzzleInput::new(1, "1000\n2000\n\n");
+        assert_eq!(input.as_str(), "1000\n2000");
+        assert_eq!(input.lines().collect::<Vec<_>>(), ["1000", "2000"]);
+        assert_eq!(input.numbers::<u64>().unwrap(), [1000, 2000]);
+        assert_eq!(input.chars().count(), 9);
+    }
+
+    #[test]
+    fn blocks() {
+        let input = PuzzleInput::new(1, "1000\n2000\n\n3000\n\nx\n");
+        let blocks = input.blocks().collect::<Vec<_>>();
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[1].numbers::<u64>().unwrap(), [3000]);
+        // errors are numbered by the line of the whole input
+        let err = blocks[2].numbers::<u64>().unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("day 1 input, line 6: invalid number"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn grid() {
+        let input = PuzzleInput::new(8, "12\n34\n");
+        let digit = |c: char| c.to_digit(10).ok_or_else(|| String::from("not a digit"));
+        let grid = input.grid(digit).unwrap();
+        assert_eq!((grid.width(), grid.height()), (2, 2));
+        assert!(input.grid(|_| Err::<u32, _>(String::from("x"))).is_err());
+    }
+}
@@ -0,0 +1,201 @@
+/*
+** src/input_cache.rs
+*/
+
+use crate::client;
+use crate::input::PuzzleInput;
+use crate::manifest;
+use crate::types::Point;
+
+use anyhow::{anyhow, Result};
+use log::{debug, warn};
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// parsed inputs are only cached once caching has been enabled
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// written at the start of each cache file, and bumped whenever the encoding
+/// of a parsed input changes so that stale files are parsed again
+const MAGIC: &[u8] = b"AOCP\x01";
+
+/// starts loading parsed inputs from, and saving them to, the cache
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// reads values back out of an encoded buffer
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N]> {
+        if self.bytes.len() < N {
+            return Err(anyhow!("truncated cache file"));
+        }
+        let (head, rest) = self.bytes.split_at(N);
+        self.bytes = rest;
+        Ok(head.try_into().unwrap())
+    }
+}
+
+/// a compact binary encoding, for parsed inputs
+pub trait Encode: Sized {
+    fn encode(&self, buf: &mut Vec<u8>);
+    fn decode(reader: &mut Reader) -> Result<Self>;
+}
+
+impl Encode for u16 {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn decode(reader: &mut Reader) -> Result<Self> {
+        Ok(Self::from_le_bytes(reader.take()?))
+    }
+}
+
+impl Encode for u64 {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn decode(reader: &mut Reader) -> Result<Self> {
+        Ok(Self::from_le_bytes(reader.take()?))
+    }
+}
+
+impl Encode for i64 {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.to_le_bytes());
+    }
+
+    fn decode(reader: &mut Reader) -> Result<Self> {
+        Ok(Self::from_le_bytes(reader.take()?))
+    }
+}
+
+impl Encode for Point {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        self.x.encode(buf);
+        self.y.encode(buf);
+    }
+
+    fn decode(reader: &mut Reader) -> Result<Self> {
+        Ok(Self::new(i64::decode(reader)?, i64::decode(reader)?))
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        (self.len() as u64).encode(buf);
+        for item in self.iter() {
+            item.encode(buf);
+        }
+    }
+
+    fn decode(reader: &mut Reader) -> Result<Self> {
+        let len = u64::decode(reader)? as usize;
+        // cap the capacity so that a corrupt length cannot exhaust memory
+        let mut items = Vec::with_capacity(len.min(reader.bytes.len()));
+        for _ in 0..len {
+            items.push(T::decode(reader)?);
+        }
+        Ok(items)
+    }
+}
+
+/// a parsed input which can be cached, see parse
+pub trait Cached: Encode {
+    /// prefix for the cache files, such as "day_16"
+    const NAME: &'static str;
+    /// bumped whenever the parsing, or anything precomputed while parsing,
+    /// changes, so that inputs parsed by older code are parsed again
+    const VERSION: u32;
+
+    /// parses the input text, which loading from the cache skips
+    fn parse(input: PuzzleInput) -> Result<Self>;
+}
+
+/// the cache file for the parsed input, keyed by the version of the parsing
+/// and by the digest of the crate version and the input
+fn cache_path<P: Cached>(input: &str) -> PathBuf {
+    let mut key = format!("{}\n", env!("CARGO_PKG_VERSION")).into_bytes();
+    key.extend_from_slice(input.as_bytes());
+    let digest = manifest::sha256(&key);
+    client::cache_dir().join("parsed").join(format!(
+        "{}-v{}-{}.bin",
+        P::NAME,
+        P::VERSION,
+        &digest[..16]
+    ))
+}
+
+pub fn to_bytes<P: Encode>(parsed: &P) -> Vec<u8> {
+    let mut buf = MAGIC.to_vec();
+    parsed.encode(&mut buf);
+    buf
+}
+
+pub fn from_bytes<P: Encode>(bytes: &[u8]) -> Result<P> {
+    let bytes = bytes
+        .strip_prefix(MAGIC)
+        .ok_or_else(|| anyhow!("not a cache file of this version"))?;
+    let mut reader = Reader::new(bytes);
+    let parsed = P::decode(&mut reader)?;
+    if !reader.bytes.is_empty() {
+        return Err(anyhow!("trailing bytes in cache file"));
+    }
+    Ok(parsed)
+}
+
+/// parses the input, or, once enabled, loads it from the binary cache of
+/// earlier parses, so that repeated runs skip parsing the text
+pub fn parse<P: Cached>(input: PuzzleInput) -> Result<P> {
+    if !is_enabled() {
+        return P::parse(input);
+    }
+    let path = cache_path::<P>(&input);
+    if let Ok(bytes) = fs::read(&path) {
+        match from_bytes(&bytes) {
+            Ok(parsed) => {
+                debug!("loaded parsed input from {}", path.to_string_lossy());
+                return Ok(parsed);
+            }
+            Err(e) => warn!("ignoring {}: {}", path.to_string_lossy(), e),
+        }
+    }
+    let parsed = P::parse(input)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, to_bytes(&parsed))?;
+    debug!("cached parsed input to {}", path.to_string_lossy());
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_round_trip() {
+        let values = vec![vec![Point::new(-3, 4)], vec![], vec![Point::origin(); 2]];
+        let bytes = to_bytes(&values);
+        assert_eq!(from_bytes::<Vec<Vec<Point>>>(&bytes).unwrap(), values);
+        assert!(from_bytes::<Vec<Vec<Point>>>(&bytes[..bytes.len() - 1]).is_err());
+        assert!(from_bytes::<Vec<Vec<Point>>>(&bytes[1..]).is_err());
+        assert!(from_bytes::<u16>(&to_bytes(&7u64)).is_err());
+    }
+}
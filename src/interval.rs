@@ -0,0 +1,314 @@
+/*
+** src/interval.rs
+*/
+
+use std::cmp;
+use std::fmt;
+
+/// a range of integers, including both of its ends
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Interval {
+    pub min: i64,
+    pub max: i64,
+}
+
+impl Interval {
+    pub fn new(min: i64, max: i64) -> Self {
+        debug_assert!(min <= max, "empty interval {}..={}", min, max);
+        Self { min, max }
+    }
+
+    /// the interval, or None if it would be empty, for ends which are not
+    /// known to be in order, such as those read from an input
+    pub fn try_new(min: i64, max: i64) -> Option<Self> {
+        (min <= max).then_some(Self { min, max })
+    }
+
+    /// the number of integers in the interval
+    pub fn length(&self) -> i64 {
+        self.max - self.min + 1
+    }
+
+    pub fn contains(&self, x: i64) -> bool {
+        self.min <= x && x <= self.max
+    }
+
+    pub fn contains_interval(&self, other: &Self) -> bool {
+        self.min <= other.min && other.max <= self.max
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.min <= other.max && other.min <= self.max
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        if self.overlaps(other) {
+            Some(Self::new(
+                cmp::max(self.min, other.min),
+                cmp::min(self.max, other.max),
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}..={}", self.min, self.max)
+    }
+}
+
+/// a set of integers, stored as sorted intervals which neither overlap nor
+/// touch one another
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the disjoint intervals of the set, in ascending order
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// the number of integers in the set
+    pub fn total_length(&self) -> i64 {
+        self.intervals.iter().map(Interval::length).sum()
+    }
+
+    pub fn contains(&self, x: i64) -> bool {
+        // the last interval starting at or before x is the only one which can
+        // contain it
+        let i = self.intervals.partition_point(|interval| interval.min <= x);
+        i > 0 && self.intervals[i - 1].contains(x)
+    }
+
+    /// adds the interval, merging it with any intervals it overlaps or touches
+    pub fn insert(&mut self, interval: Interval) {
+        let Interval { mut min, mut max } = interval;
+        // the intervals between the first which ends no earlier than min - 1
+        // and the last which starts no later than max + 1 overlap or touch
+        // the new interval
+        let start = self
+            .intervals
+            .partition_point(|other| other.max < min.saturating_sub(1));
+        let end = self
+            .intervals
+            .partition_point(|other| other.min <= max.saturating_add(1));
+        if start < end {
+            min = cmp::min(min, self.intervals[start].min);
+            max = cmp::max(max, self.intervals[end - 1].max);
+        }
+        self.intervals.drain(start..end);
+        self.intervals.insert(start, Interval::new(min, max));
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for &interval in other.intervals.iter() {
+            union.insert(interval);
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        // sweep over both sets together, advancing past whichever interval
+        // ends first
+        let mut intervals = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let a = self.intervals[i];
+            let b = other.intervals[j];
+            if let Some(overlap) = a.intersection(&b) {
+                intervals.push(overlap);
+            }
+            if a.max < b.max {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { intervals }
+    }
+
+    /// the integers of the set which are not in the other set
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let mut j = 0;
+        for &interval in self.intervals.iter() {
+            let mut min = interval.min;
+            // skip the intervals of the other set which end before this one
+            while j < other.intervals.len() && other.intervals[j].max < min {
+                j += 1;
+            }
+            // then cut out each interval of the other set which overlaps
+            let mut k = j;
+            while k < other.intervals.len() && other.intervals[k].min <= interval.max {
+                let cut = other.intervals[k];
+                if cut.min > min {
+                    intervals.push(Interval::new(min, cut.min - 1));
+                }
+                min = cut.max.saturating_add(1);
+                k += 1;
+            }
+            if min <= interval.max {
+                intervals.push(Interval::new(min, interval.max));
+            }
+        }
+        Self { intervals }
+    }
+}
+
+impl From<Interval> for IntervalSet {
+    fn from(interval: Interval) -> Self {
+        Self {
+            intervals: vec![interval],
+        }
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Interval>,
+    {
+        let mut intervals = iter.into_iter().collect::<Vec<_>>();
+        intervals.sort_by_key(|interval| interval.min);
+        // sweep over the sorted intervals, merging each into the last while
+        // they overlap or touch
+        let mut merged = Vec::<Interval>::with_capacity(intervals.len());
+        for interval in intervals.into_iter() {
+            match merged.last_mut() {
+                Some(last) if interval.min <= last.max.saturating_add(1) => {
+                    last.max = cmp::max(last.max, interval.max);
+                }
+                _ => merged.push(interval),
+            }
+        }
+        Self { intervals: merged }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::Rng;
+
+    fn set(intervals: &[(i64, i64)]) -> IntervalSet {
+        intervals
+            .iter()
+            .map(|&(min, max)| Interval::new(min, max))
+            .collect()
+    }
+
+    #[test]
+    fn interval_queries() {
+        let a = Interval::new(2, 4);
+        let b = Interval::new(4, 8);
+        assert_eq!(a.length(), 3);
+        assert!(a.overlaps(&b));
+        assert!(!a.overlaps(&Interval::new(5, 6)));
+        assert_eq!(a.intersection(&b), Some(Interval::new(4, 4)));
+        assert!(b.contains_interval(&Interval::new(5, 8)));
+        assert!(!b.contains_interval(&a));
+        assert_eq!(Interval::try_new(3, 3), Some(Interval::new(3, 3)));
+        assert_eq!(Interval::try_new(5, 3), None);
+    }
+
+    #[test]
+    fn merge_intervals() {
+        let merged = set(&[(1, 3), (2, 4), (3, 5), (4, 6)]);
+        assert_eq!(merged, set(&[(1, 6)]));
+        assert_eq!(merged.intervals().len(), 1);
+
+        let merged = set(&[(2, 2), (11, 13), (3, 13), (-3, 3), (15, 25), (15, 17)]);
+        assert_eq!(merged.intervals(), set(&[(-3, 13), (15, 25)]).intervals());
+        assert_eq!(merged.total_length(), 17 + 11);
+
+        // overlapping intervals which do not start out next to one another
+        let merged = set(&[(1, 2), (5, 7), (6, 9)]);
+        assert_eq!(
+            merged.intervals(),
+            [Interval::new(1, 2), Interval::new(5, 9)]
+        );
+        // intervals which touch are merged
+        assert_eq!(set(&[(4, 6), (1, 3)]).intervals(), [Interval::new(1, 6)]);
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 22), (28, 40)]);
+        assert_eq!(a.union(&b), set(&[(0, 40)]));
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 22), (28, 30)]));
+        assert_eq!(a.difference(&b), set(&[(0, 4), (23, 27)]));
+        assert_eq!(b.difference(&a), set(&[(11, 19), (31, 40)]));
+        assert!(a.difference(&a).is_empty());
+        assert!(a.contains(25) && !a.contains(15));
+    }
+
+    /// random intervals which are small and close together, so that there
+    /// are many overlapping, nested, and touching intervals, along with the
+    /// set of them built up one at a time
+    fn random_set(rng: &mut Rng) -> (Vec<Interval>, IntervalSet) {
+        let n_intervals = rng.range(0, 12);
+        let intervals = (0..n_intervals)
+            .map(|_| {
+                let min = rng.range(-20, 20);
+                Interval::new(min, min + rng.range(0, 8))
+            })
+            .collect::<Vec<_>>();
+        let mut set = IntervalSet::new();
+        for &interval in intervals.iter() {
+            set.insert(interval);
+        }
+        (intervals, set)
+    }
+
+    /// checks the set operations of random intervals against the integers
+    /// they contain, seeded so that any failing case is reproducible
+    #[test]
+    fn set_properties() {
+        let mut rng = Rng::new(0x2022);
+        for _ in 0..1000 {
+            let (intervals_a, a) = random_set(&mut rng);
+            let (_, b) = random_set(&mut rng);
+
+            // inserting one at a time matches collecting all at once
+            let collected = intervals_a.iter().copied().collect::<IntervalSet>();
+            assert_eq!(a, collected, "{:?}", intervals_a);
+            // the intervals are sorted and neither overlap nor touch
+            for pair in a.intervals().windows(2) {
+                assert!(
+                    pair[0].max + 1 < pair[1].min,
+                    "{:?} -> {:?}",
+                    intervals_a,
+                    a
+                );
+            }
+
+            let union = a.union(&b);
+            let intersection = a.intersection(&b);
+            let difference = a.difference(&b);
+            let mut n_in_a = 0;
+            for x in -25..=30 {
+                let in_input = intervals_a.iter().any(|i| i.contains(x));
+                assert_eq!(a.contains(x), in_input, "{:?} -> {:?}", intervals_a, a);
+                assert_eq!(union.contains(x), a.contains(x) || b.contains(x));
+                assert_eq!(intersection.contains(x), a.contains(x) && b.contains(x));
+                assert_eq!(difference.contains(x), a.contains(x) && !b.contains(x));
+                n_in_a += a.contains(x) as i64;
+            }
+            assert_eq!(a.total_length(), n_in_a);
+        }
+    }
+}
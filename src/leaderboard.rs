@@ -0,0 +1,135 @@
+/*
+** src/leaderboard.rs
+*/
+
+use crate::client;
+use crate::json::Json;
+
+use anyhow::{anyhow, Result};
+use chrono::{Local, TimeZone};
+
+use std::time::Duration;
+
+/// the API asks that leaderboards be fetched at most once every 15 minutes
+const CACHE_MAX_AGE: Duration = Duration::from_secs(15 * 60);
+
+pub struct Member {
+    pub name: String,
+    pub local_score: i64,
+    pub stars: i64,
+    /// unix timestamp of the most recent star, 0 if there are none
+    pub last_star_ts: i64,
+}
+
+/// parses the members of a private leaderboard, sorted by local score and then
+/// by who reached their stars first
+pub fn parse(s: &str) -> Result<Vec<Member>> {
+    let json = Json::parse(s)?;
+    let members = json
+        .get("members")
+        .and_then(|members| members.as_object())
+        .ok_or_else(|| anyhow!("leaderboard is missing its members"))?;
+    let mut members = members
+        .iter()
+        .map(|(id, member)| {
+            let int = |key| member.get(key).and_then(|v| v.as_i64()).unwrap_or(0);
+            let name = member
+                .get("name")
+                .and_then(|name| name.as_str())
+                .map(|name| name.to_string())
+                .unwrap_or_else(|| format!("(anonymous user #{})", id));
+            Member {
+                name,
+                local_score: int("local_score"),
+                stars: int("stars"),
+                last_star_ts: int("last_star_ts"),
+            }
+        })
+        .collect::<Vec<_>>();
+    members.sort_by(|a, b| {
+        b.local_score
+            .cmp(&a.local_score)
+            .then(a.last_star_ts.cmp(&b.last_star_ts))
+            .then(a.name.cmp(&b.name))
+    });
+    Ok(members)
+}
+
+fn format_timestamp(ts: i64) -> String {
+    if ts == 0 {
+        return String::from("-");
+    }
+    Local
+        .timestamp_opt(ts, 0)
+        .single()
+        .map(|time| time.format("%b %d %H:%M:%S").to_string())
+        .unwrap_or_else(|| String::from("-"))
+}
+
+/// renders the members as a table
+pub fn render(members: &[Member]) -> String {
+    let mut lines = vec![format!(
+        "{:>4}  {:>5}  {:>5}  {:<15}  {}",
+        "rank", "score", "stars", "last star", "name"
+    )];
+    for (i, member) in members.iter().enumerate() {
+        lines.push(format!(
+            "{:>4}  {:>5}  {:>5}  {:<15}  {}",
+            i + 1,
+            member.local_score,
+            member.stars,
+            format_timestamp(member.last_star_ts),
+            member.name
+        ));
+    }
+    lines.join("\n")
+}
+
+/// fetches the private leaderboard with the given ID, cached on disk
+pub fn fetch(id: &str, refresh: bool) -> Result<Vec<Member>> {
+    let url = format!("{}/leaderboard/private/view/{}.json", client::BASE_URL, id);
+    let cache_name = format!("leaderboard_{}.json", id);
+    let body = client::cached_get(&url, &cache_name, CACHE_MAX_AGE, refresh)?;
+    parse(&body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEADERBOARD: &str = r#"{
+        "event": "2022",
+        "owner_id": 1,
+        "members": {
+            "1": {"id": 1, "name": "alice", "local_score": 40, "stars": 8, "last_star_ts": 1670300000, "global_score": 0, "completion_day_level": {}},
+            "2": {"id": 2, "name": null, "local_score": 52, "stars": 8, "last_star_ts": 1670200000, "global_score": 0, "completion_day_level": {}},
+            "3": {"id": 3, "name": "carol", "local_score": 40, "stars": 7, "last_star_ts": 1670100000, "global_score": 0, "completion_day_level": {}},
+            "4": {"id": 4, "name": "dave", "local_score": 0, "stars": 0, "last_star_ts": 0, "global_score": 0, "completion_day_level": {}}
+        }
+    }"#;
+
+    #[test]
+    fn parse_leaderboard() {
+        let members = parse(LEADERBOARD).unwrap();
+        let names = members.iter().map(|m| m.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["(anonymous user #2)", "carol", "alice", "dave"]);
+        assert_eq!(members[0].local_score, 52);
+        assert_eq!(members[1].stars, 7);
+    }
+
+    #[test]
+    fn render_leaderboard() {
+        let members = parse(LEADERBOARD).unwrap();
+        let table = render(&members);
+        let lines = table.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[1].starts_with("   1     52      8  "));
+        assert!(lines[4].ends_with("-                dave"));
+    }
+
+    #[test]
+    fn parse_leaderboard_invalid() {
+        assert!(parse("{}").is_err());
+        assert!(parse("not json").is_err());
+    }
+}
@@ -0,0 +1,58 @@
+/*
+** src/lib.rs
+*/
+
+#[cfg(feature = "alloc-stats")]
+pub mod alloc;
+pub mod answers;
+pub mod artifacts;
+pub mod badge;
+pub mod bench;
+pub mod calendar;
+pub mod cancel;
+pub mod chart;
+pub mod clean;
+pub mod client;
+pub mod compare;
+pub mod complexity;
+pub mod context;
+pub mod describe;
+pub mod events;
+pub mod explain;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod generators;
+pub mod grid_diff;
+pub mod history;
+pub mod input;
+pub mod input_cache;
+pub mod interval;
+pub mod json;
+pub mod leaderboard;
+pub mod manifest;
+#[cfg(unix)]
+pub mod mmap;
+pub mod notify;
+pub mod ocr;
+pub mod output;
+pub mod parts;
+#[cfg(unix)]
+pub mod plugin;
+pub mod pool;
+pub mod porcelain;
+pub mod prelude;
+pub mod progress;
+pub mod puzzles;
+pub mod report;
+pub mod results;
+pub mod samples;
+#[cfg(unix)]
+pub mod sandbox;
+pub mod serve;
+pub mod status;
+pub mod style;
+pub mod submit;
+pub mod trace;
+pub mod types;
+pub mod utils;
+pub mod viz;
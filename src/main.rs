@@ -2,143 +2,1996 @@
 ** src/main.rs
 */
 
-mod puzzles;
-mod types;
-mod utils;
+#[cfg(feature = "alloc-stats")]
+use aoc2022::alloc::{AllocSpan, AllocStats, CountingAllocator};
+use aoc2022::answers::{self, Answers, Check};
+use aoc2022::bench::BenchResult;
+use aoc2022::calendar::Stars;
+use aoc2022::cancel::Cancel;
+use aoc2022::clean::{self, Artifact};
+use aoc2022::context::{self, Context};
+use aoc2022::events::LogFormat;
+use aoc2022::history::{self, DayRecord, RunRecord};
+use aoc2022::json::Json;
+use aoc2022::manifest::{self, Manifest, Status};
+use aoc2022::notify::{self, Summary};
+use aoc2022::parts::Parts;
+#[cfg(unix)]
+use aoc2022::plugin;
+use aoc2022::progress::Progress;
+use aoc2022::puzzles::Days;
+#[cfg(unix)]
+use aoc2022::sandbox;
+use aoc2022::submit::{self, Outcome, Submission};
+use aoc2022::types::{Answer, Error, Solution, Solver};
+use aoc2022::utils::Input;
+use aoc2022::viz::Backend;
+use aoc2022::{
+    artifacts, badge, bench, chart, compare, complexity, describe, events, explain, generators,
+    input_cache, leaderboard, output, pool, porcelain, puzzles, report, results, samples, serve,
+    status, style, trace, utils, viz,
+};
 
-use anyhow::Result;
-use clap::Parser;
+use anyhow::{anyhow, Result};
+use clap::{Parser, Subcommand};
 use log::{debug, info, warn};
 
 use std::collections::HashMap;
 use std::env;
-use std::path::Path;
-use std::time::Instant;
+use std::fmt;
+use std::fs;
+use std::io::{self, IsTerminal, Read};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 const PROJECT_DIR: &str = env!("CARGO_MANIFEST_DIR");
 #[cfg(feature = "sample")]
 const INPUT_EXT: &str = ".dbg.txt";
 #[cfg(not(feature = "sample"))]
 const INPUT_EXT: &str = ".txt";
+/// number of times each build is run with --bench-against
+const BENCH_AGAINST_RUNS: usize = 3;
+/// how often watch mode checks the sources for changes
+#[cfg(unix)]
+const WATCH_INTERVAL: Duration = Duration::from_millis(250);
+/// number of untimed runs of each day before the timed runs of a benchmark
+const BENCH_WARMUP_RUNS: usize = 1;
 
 #[derive(Parser)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Args {
-    /// Day, runs all if not provided
-    day: Option<usize>,
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// Days to run, such as 5, "latest", 1-10 or 3,5,13; runs all if not
+    /// provided
+    #[arg(value_parser = puzzles::parse_days)]
+    days: Option<Days>,
+    /// Run only the days tagged with the given tag, such as "graph" or
+    /// "simulation"; may be repeated to run the days with any of the tags
+    #[arg(long, value_name = "TAG", conflicts_with_all = ["days", "bench_against"])]
+    tag: Vec<String>,
+    /// Solve only part 1 or part 2 of the days, skipping the work of the other
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2), conflicts_with = "bench_against")]
+    part: Option<u8>,
     /// Enable debug output
     #[arg(short, long)]
     debug: bool,
     /// Time the runtime of each puzzle
     #[arg(short, long)]
     time: bool,
+    /// Print auxiliary puzzle outputs, such as schedules
+    #[arg(short, long)]
+    extras: bool,
+    /// Do not color the output, which is otherwise colored on a terminal
+    /// unless NO_COLOR is set
+    #[arg(long, global = true)]
+    no_color: bool,
+    /// Print one tab-separated line per answer, day<TAB>part<TAB>answer<TAB>micros,
+    /// in place of the usual output; this format is stable for scripts
+    #[arg(long)]
+    porcelain: bool,
+    /// Also write the answers to the given file, as JSON for .json files,
+    /// porcelain with --porcelain, and text otherwise
+    #[arg(long, value_name = "PATH")]
+    output: Option<PathBuf>,
+    /// Also write a table of the answers and times of each day to the given
+    /// file, as Markdown for .md files or CSV for .csv files
+    #[arg(long, value_name = "PATH")]
+    report: Option<PathBuf>,
+    /// Write log messages as text, or as one JSON object per line along with
+    /// events for each day started and finished
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        default_value = "text",
+        conflicts_with = "porcelain"
+    )]
+    log_format: LogFormat,
+    /// Explain the steps each day took to reach its answers
+    #[arg(long)]
+    explain: bool,
+    /// Solver variant to run, or "all" to check that every variant agrees
+    #[arg(short, long, default_value = puzzles::DEFAULT_VARIANT, value_parser = puzzles::parse_variant)]
+    algo: String,
+    /// Write a Chrome trace of the run timings to the given file
+    #[arg(long, value_name = "FILE", global = true)]
+    trace_out: Option<PathBuf>,
+    /// Post a summary to the given Discord or Slack webhook after the run,
+    /// defaults to AOC_WEBHOOK_URL
+    #[arg(long, value_name = "URL", global = true)]
+    webhook: Option<String>,
+    /// Run on a named input variant, such as "edge" for input/D16.edge.txt,
+    /// rather than on the real input
+    #[arg(long, value_name = "NAME")]
+    input_variant: Option<String>,
+    /// Run the day on the given input file, or on standard input for "-",
+    /// rather than on an input from the input directory
+    #[arg(
+        long,
+        value_name = "PATH",
+        requires = "days",
+        conflicts_with_all = ["input_variant", "tag"]
+    )]
+    input: Option<PathBuf>,
+    /// Override a tunable constant of the days, as NAME=VALUE, such as
+    /// target_y=10 for the row checked by day 15; may be repeated
+    #[arg(long = "constant", value_name = "NAME=VALUE", value_parser = context::parse_constant)]
+    constants: Vec<(String, i64)>,
+    /// Cache the parsed inputs of days split into parsing and solving, such as
+    /// days 15 and 16, in .cache/parsed and load them on later runs
+    #[arg(long, global = true)]
+    cache_parsed: bool,
+    /// Memory-map the inputs rather than reading them into memory
+    #[arg(long)]
+    mmap: bool,
+    /// Number of threads to solve days on in parallel, which is also the
+    /// number of threads the work within a day is split across
+    #[arg(long, default_value_t = 1)]
+    threads: usize,
+    /// Solve the two parts of each day on separate threads
+    #[arg(long)]
+    parallel_parts: bool,
+    /// Pin each thread to its own core, for more reproducible timings
+    #[arg(long)]
+    pin: bool,
+    /// Solve each day in a child process, so that a day which crashes or
+    /// exceeds the limits does not take down the run
+    #[arg(long)]
+    sandbox: bool,
+    /// Memory limit for each sandboxed day, in MB
+    #[arg(long, value_name = "MB", requires = "sandbox")]
+    memory_limit: Option<u64>,
+    /// CPU time limit for each sandboxed day, in seconds
+    #[arg(long, value_name = "SECONDS", requires = "sandbox")]
+    cpu_limit: Option<u64>,
+    /// Record the answers and timings of the run to the run history
+    #[arg(long)]
+    record: bool,
+    /// After the run, compare each answer against the most recently recorded
+    /// run of the day on the same input, and report which have changed
+    #[arg(long)]
+    diff_answers: bool,
+    /// Accept the answers of this run of the real inputs as the answers later
+    /// runs are checked against for regressions, replacing any which changed
+    #[arg(long)]
+    rerecord: bool,
+    /// After the run, check each answer against the known answers and exit
+    /// with an error if any do not match
+    #[arg(long)]
+    check: bool,
+    /// File of known answers for --check, in TOML or JSON, defaults to the
+    /// answers declared by each day, with those in answers.local.toml taking
+    /// precedence for the real inputs
+    #[arg(long, value_name = "FILE", requires = "check")]
+    answers: Option<PathBuf>,
+    /// Run each day N times after a warm-up run, timing only the solving, and
+    /// print the min, median, mean, max and standard deviation of the times
+    #[arg(
+        long = "bench",
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..),
+        conflicts_with_all = ["bench_against", "sandbox", "porcelain"]
+    )]
+    bench_runs: Option<u32>,
+    /// Run the days with both this build and the given aoc2022 binary, such
+    /// as one built from main, and print the speedup of each day
+    #[arg(long, value_name = "BINARY")]
+    bench_against: Option<PathBuf>,
+    /// Render the days which have a visualization, either to the terminal, to
+    /// viz/dayN.gif, or to an asciinema cast at viz/dayN.cast
+    #[arg(long, value_name = "BACKEND")]
+    visualize: Option<Backend>,
+    /// Print the renderings which days attach to their solutions, such as
+    /// grids and routes, save them under viz/artifacts/dayN, or skip them
+    #[arg(long, value_name = "MODE", default_value = "skip")]
+    artifacts: artifacts::Mode,
+}
+
+impl Args {
+    /// the parts selected with --part
+    fn parts(&self) -> Parts {
+        Parts::new(self.part.map(usize::from))
+    }
 }
 
-/// initializes the fern logger
-fn setup_logger(debug: bool) -> Result<(), fern::InitError> {
+#[derive(Subcommand)]
+enum Command {
+    /// Generate a random input for a day, printed to stdout
+    Gen {
+        /// Day to generate an input for
+        #[arg(value_parser = puzzles::parse_day)]
+        day: usize,
+        /// Size of the input, such as the number of lines; defaults per day
+        #[arg(short, long)]
+        size: Option<usize>,
+        /// Seed for the random number generator
+        #[arg(long, default_value_t = 2022)]
+        seed: u64,
+    },
+    /// List the days with their puzzle titles and how each is solved
+    List,
+    /// Check every day against both its sample and real answers
+    VerifyAll,
+    /// Run every day several times and print a table of the best times
+    Bench {
+        /// Number of times to run each day
+        #[arg(short = 'n', long, default_value_t = 10)]
+        runs: usize,
+        /// Print the table as Markdown, to stdout
+        #[arg(long)]
+        markdown: bool,
+        /// Upload the results, in the shareable results schema, to the
+        /// endpoint under this machine's name
+        #[arg(long)]
+        publish: bool,
+        /// Download the results of the named peer from the endpoint and
+        /// compare them side by side; may be repeated
+        #[arg(long, value_name = "NAME")]
+        fetch: Vec<String>,
+        /// Base URL which results are shared under as NAME.json, accepting PUT
+        /// requests to publish; defaults to AOC_RESULTS_URL
+        #[arg(long, value_name = "URL")]
+        endpoint: Option<String>,
+        /// Name to publish the results under, defaults to AOC_RESULTS_NAME or
+        /// the user name
+        #[arg(long)]
+        name: Option<String>,
+    },
+    /// Time a day on generated inputs of increasing size and fit a growth curve
+    Complexity {
+        /// Day to profile, which must have an input generator
+        #[arg(value_parser = puzzles::parse_day)]
+        day: usize,
+        /// Input sizes to run, defaults to doubling up to the default size
+        #[arg(long, value_delimiter = ',')]
+        sizes: Vec<usize>,
+        /// Number of runs per size, of which the best is kept
+        #[arg(short = 'n', long, default_value_t = 3)]
+        runs: usize,
+        /// Seed for the input generator
+        #[arg(long, default_value_t = 2022)]
+        seed: u64,
+    },
+    /// Compare the answers and timings of a day on two inputs, or on the real
+    /// input against a run recorded at another git revision
+    Compare {
+        /// Day to compare
+        #[arg(value_parser = puzzles::parse_day)]
+        day: usize,
+        /// Inputs to compare, as paths or as input variant names such as "dbg",
+        /// where "real" is the real input
+        #[arg(num_args = 0..=2)]
+        inputs: Vec<String>,
+        /// Compare against the run recorded at this revision instead
+        #[arg(long, value_name = "REV")]
+        rev: Option<String>,
+    },
+    /// Check the inputs against the SHA-256 digests in input/manifest.sha256
+    VerifyInputs {
+        /// Rewrite the manifest from the current inputs
+        #[arg(long)]
+        update: bool,
+    },
+    /// Show the puzzle description for a day, covering part 2 once part 1 is
+    /// solved
+    Describe {
+        /// Day to describe
+        #[arg(value_parser = puzzles::parse_day)]
+        day: usize,
+        /// Fetch the description even if the cached copy is recent
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// Show a private leaderboard
+    Leaderboard {
+        /// Leaderboard ID, defaults to AOC_LEADERBOARD
+        id: Option<String>,
+        /// Fetch the leaderboard even if the cached copy is recent
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// Show the stars earned for each day
+    Calendar {
+        /// Include the stars from the event calendar on the website
+        #[arg(long)]
+        fetch: bool,
+        /// Fetch the calendar even if the cached copy is recent
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// Serve the solvers over HTTP, answering POST /solve/<day> with the input
+    /// as the body with the solution as JSON; ?part=1 or ?part=2 solves one part
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8022")]
+        addr: String,
+    },
+    /// Show which days are implemented, have inputs, and have verified answers
+    Status {
+        /// Also write the days as a Markdown table, for the README
+        #[arg(long, value_name = "FILE")]
+        out: Option<PathBuf>,
+    },
+    /// Write shields.io endpoint badges for the stars and recorded runtime
+    Badge {
+        /// Directory to write the badge JSON files to
+        #[arg(long, value_name = "DIR")]
+        out_dir: Option<PathBuf>,
+    },
+    /// Solve the days and export the answers, timings and machine details in
+    /// the shareable results schema, see aoc2022::results
+    Export {
+        /// Days to export, defaults to every day with an input
+        #[arg(value_parser = puzzles::parse_day)]
+        days: Vec<usize>,
+        /// File to write the results to
+        #[arg(long, value_name = "FILE", default_value = "results.json")]
+        out: PathBuf,
+        /// Share SHA-256 digests of the answers rather than the answers
+        #[arg(long)]
+        hash_answers: bool,
+    },
+    /// Remove cached and generated files
+    Clean {
+        /// Remove fetched pages, puzzle descriptions and parsed inputs
+        #[arg(long)]
+        cache: bool,
+        /// Remove rendered visualizations and charts
+        #[arg(long)]
+        viz: bool,
+        /// Remove the recorded run history
+        #[arg(long)]
+        history: bool,
+        /// Remove all of the above
+        #[arg(long)]
+        all: bool,
+    },
+    /// Solve a part of a day on the real input and submit the answer,
+    /// recording the outcome
+    Submit {
+        /// Day to submit
+        #[arg(value_parser = puzzles::parse_day)]
+        day: usize,
+        /// Part to submit
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+    },
+    /// Chart the recorded runtime of each day across runs as an SVG
+    Chart {
+        /// Days to chart, defaults to every recorded day
+        #[arg(value_parser = puzzles::parse_day)]
+        days: Vec<usize>,
+        /// File to write the chart to, defaults to charts/runtime.svg
+        #[arg(long, value_name = "FILE")]
+        out: Option<PathBuf>,
+    },
+    /// Rebuild a day whenever its source changes and rerun it on its input
+    #[cfg(unix)]
+    Watch {
+        /// Day to watch
+        #[arg(value_parser = puzzles::parse_day)]
+        day: usize,
+    },
+}
+
+/// initializes the fern logger, which only shows warnings and goes to stderr
+/// when stdout is reserved for porcelain output
+fn setup_logger(debug: bool, porcelain: bool, format: LogFormat) -> Result<(), fern::InitError> {
     let level = if debug {
         log::LevelFilter::Debug
+    } else if porcelain {
+        log::LevelFilter::Warn
     } else {
         log::LevelFilter::Info
     };
+    let output: fern::Output = if porcelain {
+        std::io::stderr().into()
+    } else {
+        std::io::stdout().into()
+    };
 
     fern::Dispatch::new()
-        .format(|out, message, _| {
-            out.finish(format_args!(
-                "[{}] {}",
-                chrono::Local::now().format("%Y%m%dT%H:%M:%S"),
+        .format(move |out, message, record| match format {
+            LogFormat::Text => out.finish(format_args!(
+                "{} {}",
+                style::dim(format!(
+                    "[{}]",
+                    chrono::Local::now().format("%Y%m%dT%H:%M:%S")
+                )),
                 message
-            ))
+            )),
+            LogFormat::Jsonl => out.finish(format_args!("{}", events::log_line(record))),
         })
         .level(level)
-        .chain(std::io::stdout())
+        .chain(output)
         .apply()?;
 
     Ok(())
 }
 
-/// loads puzzle input
-fn load_input(day: usize) -> Result<String> {
+/// reads the input file, memory-mapping it if requested
+fn open_input(path: &Path, mmap: bool) -> Result<Input> {
+    if mmap {
+        // SAFETY: inputs are not modified while the puzzles run on them
+        unsafe { Input::map(path) }
+    } else {
+        Input::read(path)
+    }
+}
+
+/// where the input of a day is read from
+#[derive(Clone, Copy)]
+enum Source<'a> {
+    /// the default input for the build, either the real or the sample input
+    Default,
+    /// a named input variant, such as "dbg" for D1.dbg.txt
+    Variant(&'a str),
+    /// a file given with --input
+    File(&'a Path),
+    /// standard input, given with --input -
+    Stdin,
+}
+
+impl<'a> Source<'a> {
+    fn from_args(args: &'a Args) -> Self {
+        match (&args.input, &args.input_variant) {
+            (Some(path), _) if path.as_os_str() == "-" => Self::Stdin,
+            (Some(path), _) => Self::File(path),
+            (None, Some(variant)) => Self::Variant(variant),
+            (None, None) => Self::Default,
+        }
+    }
+
+    /// whether the input is a sample input, which some days solve with
+    /// different parameters; inputs given as files are assumed to be of the
+    /// same kind as the default input
+    fn is_sample(&self) -> bool {
+        match self {
+            Self::Variant(variant) => *variant == "dbg",
+            _ => cfg!(feature = "sample"),
+        }
+    }
+}
+
+/// reads standard input, which can only be read once, so that it can be
+/// loaded again such as for --visualize
+fn read_stdin() -> Result<String> {
+    static STDIN: OnceLock<String> = OnceLock::new();
+    if let Some(input) = STDIN.get() {
+        return Ok(input.clone());
+    }
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+    Ok(STDIN.get_or_init(|| input).clone())
+}
+
+/// loads puzzle input, either the default input for the build, the named
+/// input variant, such as "dbg" for D1.dbg.txt or "edge" for D1.edge.txt, or
+/// the input given with --input
+fn load_input(day: usize, source: Source, mmap: bool) -> Result<Input> {
+    let variant = match source {
+        Source::Default => None,
+        Source::Variant(variant) => Some(variant),
+        Source::File(path) => {
+            debug!(
+                "loading input for day {} from {}",
+                day,
+                path.to_string_lossy()
+            );
+            if !path.exists() {
+                return Err(anyhow!("missing input {}", path.to_string_lossy()));
+            }
+            return open_input(path, mmap);
+        }
+        Source::Stdin => {
+            debug!("loading input for day {} from stdin", day);
+            return Ok(Input::Owned(read_stdin()?));
+        }
+    };
+    let file_name = match variant {
+        Some(variant) => format!("D{}.{}.txt", day, variant),
+        None => format!("D{}{}", day, INPUT_EXT),
+    };
     // get a path to the input from the top-level directory
-    let input_path = Path::new(PROJECT_DIR)
-        .join("input")
-        .join(format!("D{}{}", day, INPUT_EXT));
+    let input_path = Path::new(PROJECT_DIR).join("input").join(file_name);
     debug!(
         "loading input for day {} from {}",
         day,
         input_path.to_string_lossy()
     );
-    // skip if the sample input is requested but not present
-    if cfg!(feature = "sample") && !input_path.exists() {
-        warn!("missing sample input for day {}", day);
-        Ok(String::new())
+    // fall back to the embedded sample, for sample runs outside of the repo
+    let is_sample = variant.map_or(cfg!(feature = "sample"), |v| v == "dbg");
+    if !input_path.exists() && is_sample {
+        if let Some(input) = samples::embedded(day) {
+            debug!("using embedded sample input for day {}", day);
+            return Ok(Input::Owned(input.to_string()));
+        }
+    }
+    // skip if the input is not present; runs of all days skip days without
+    // inputs quietly, and warn only for days which were asked for
+    if !input_path.exists() {
+        if let Some(variant) = variant {
+            debug!("missing {} input for day {}", variant, day);
+        } else if cfg!(feature = "sample") {
+            debug!("missing sample input for day {}", day);
+        } else {
+            debug!("missing input for day {}", day);
+        }
+        Ok(Input::Owned(String::new()))
     } else {
-        let input = utils::read_file(&input_path)?;
-        Ok(input)
+        open_input(&input_path, mmap)
+    }
+}
+
+/// selects the solver variant for the day, falling back to the default for
+/// days which do not have a variant with the given name
+fn select_variant(day: usize, algo: &str) -> Solver {
+    let variant = puzzles::variants(day)
+        .into_iter()
+        .find(|&(name, _)| name == algo)
+        .map(|(_, puzzle)| puzzle);
+    if variant.is_none() && algo != puzzles::ALL_VARIANTS {
+        info!("day {}: no {} variant, using the default solver", day, algo);
+    }
+    variant.unwrap_or(puzzles::DAYS[day - 1])
+}
+
+/// result of checking a day against known answers
+enum Verdict {
+    Pass,
+    Fail,
+    Skip(&'static str),
+}
+
+impl Verdict {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Pass => "ok",
+            Self::Fail => "FAIL",
+            Self::Skip(reason) => reason,
+        }
     }
 }
 
-/// runs the puzzle and returns the time elapsed as milliseconds
-fn run_puzzle(day: usize) -> Result<f64> {
+/// runs the day on the given input, either a sample or a real input, and
+/// compares the answers against those known for the day, which must have at
+/// least one answer
+fn verify(day: usize, input_path: &Path, sample: bool, answers: &Answers) -> Result<Verdict> {
+    if !input_path.exists() {
+        return Ok(Verdict::Skip("no input"));
+    }
+    if answers.get(day, 1).is_none() && answers.get(day, 2).is_none() {
+        return Ok(Verdict::Skip("no answers"));
+    }
+    let name = input_path.file_name().unwrap_or_default().to_string_lossy();
+    let _span = trace::span(format!("day {} ({})", day, name), "day");
+    let input = utils::read_file(input_path)?;
+    let context = Context::new(Progress::log()).with_sample(sample);
+    let solution = puzzles::DAYS[day - 1](&input, Parts::Both, &context)?;
+    let computed = [solution.part_1, solution.part_2].map(|a| a.map(|a| a.to_string()));
+    for (i, computed) in computed.iter().enumerate() {
+        if computed.as_deref() != answers.get(day, i + 1) {
+            return Ok(Verdict::Fail);
+        }
+    }
+    Ok(Verdict::Pass)
+}
+
+/// checks every day against its sample answers and its real answers, see
+/// Answers::real, and prints a matrix of the results
+fn verify_all(summary: &mut Summary) -> Result<()> {
+    let project_dir = Path::new(PROJECT_DIR);
+    let input_dir = project_dir.join("input");
+    let sample_answers = Answers::samples();
+    let real_answers = Answers::real()?;
+
+    info!("day  sample      real");
+    let tstart = Instant::now();
+    let mut n_failed = 0;
+    for day in 1..=puzzles::N_DAYS {
+        let input_path = input_dir.join(format!("D{}.dbg.txt", day));
+        let sample = verify(day, &input_path, true, &sample_answers)?;
+        let input_path = input_dir.join(format!("D{}.txt", day));
+        let real = verify(day, &input_path, false, &real_answers)?;
+        for (verdict, kind) in [(&sample, "sample"), (&real, "real")] {
+            match verdict {
+                Verdict::Pass => summary.answers += 1,
+                Verdict::Fail => {
+                    n_failed += 1;
+                    summary.failures.push(format!("day {} ({})", day, kind));
+                }
+                Verdict::Skip(_) => {}
+            }
+        }
+        if !matches!((&sample, &real), (Verdict::Skip(_), Verdict::Skip(_))) {
+            summary.days += 1;
+        }
+        info!("{:>3}  {:<10}  {}", day, sample.label(), real.label());
+    }
+    summary.seconds = tstart.elapsed().as_secs_f64();
+
+    if n_failed > 0 {
+        Err(anyhow!("{} checks failed", n_failed))
+    } else {
+        Ok(())
+    }
+}
+
+/// posts the run summary to the webhook, if one is configured; failures to
+/// notify are only warned about so as not to fail the run itself
+fn send_notification(url: Option<&str>, summary: &Summary) {
+    let url = url
+        .map(|url| url.to_string())
+        .or_else(|| env::var("AOC_WEBHOOK_URL").ok());
+    if let Some(url) = url {
+        if let Err(e) = notify::send(&url, summary) {
+            warn!("failed to send webhook notification: {}", e);
+        }
+    }
+}
+
+/// writes the recorded trace, if requested
+fn write_trace(path: Option<&Path>) -> Result<()> {
+    if let Some(path) = path {
+        trace::write(path)?;
+        info!("wrote trace to {}", path.to_string_lossy());
+    }
+    Ok(())
+}
+
+/// measurements for a single puzzle, reported with --time
+#[derive(Default)]
+struct Timing {
+    seconds: f64,
+    /// see Solution::parse_seconds and Solution::part_seconds
+    parse_seconds: Option<f64>,
+    part_seconds: [Option<f64>; 2],
+    #[cfg(feature = "alloc-stats")]
+    allocs: AllocStats,
+}
+
+impl fmt::Display for Timing {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // convert to ms for higher precision, padded to any width given so
+        // that the times line up
+        let ms = format!("{:.03}ms", self.seconds * 1000.0);
+        write!(f, "{:>1$}", ms, f.width().unwrap_or(0))?;
+        // then break the time down into parsing and each part, when known
+        let phases = [
+            ("parse", self.parse_seconds),
+            ("part 1", self.part_seconds[0]),
+            ("part 2", self.part_seconds[1]),
+        ]
+        .into_iter()
+        .filter_map(|(phase, seconds)| {
+            seconds.map(|seconds| format!("{} {:.03}ms", phase, seconds * 1000.0))
+        })
+        .collect::<Vec<_>>();
+        if !phases.is_empty() {
+            write!(f, " ({})", phases.join(", "))?;
+        }
+        #[cfg(feature = "alloc-stats")]
+        write!(f, ", {}", self.allocs)?;
+        Ok(())
+    }
+}
+
+/// draws progress bars for the long computations of the days on a terminal,
+/// and otherwise logs their progress at the debug level
+fn progress_for(args: &Args) -> Progress {
+    let plain = args.porcelain || args.debug || args.log_format != LogFormat::Text;
+    if !plain && io::stderr().is_terminal() {
+        Progress::bar()
+    } else {
+        Progress::log()
+    }
+}
+
+/// the context the days are solved with, given the input source, any
+/// overridden constants, and the progress display, which Ctrl-C cancels
+fn context_for(args: &Args, source: Source) -> Context {
+    let sample = source.is_sample();
+    args.constants.iter().fold(
+        Context::new(progress_for(args))
+            .with_sample(sample)
+            .with_cancel(Cancel::ctrl_c()),
+        |context, (name, value)| context.with_constant(name, *value),
+    )
+}
+
+/// loads the input and runs the puzzle, returning its solution and timing or
+/// None if the input is missing
+fn solve_puzzle(
+    day: usize,
+    algo: &str,
+    source: Source,
+    parts: Parts,
+    mmap: bool,
+    context: &Context,
+) -> Result<Option<(Solution, Timing)>> {
     // load the puzzle input
-    let input = load_input(day)?;
-    // skip if the sample input is requested but not present
-    if cfg!(feature = "sample") && input.is_empty() {
-        return Ok(0.0);
+    let input = {
+        let _span = trace::span(format!("load day {}", day), "load");
+        load_input(day, source, mmap)?
+    };
+    // skip if the input is not present
+    if input.is_empty() {
+        return Ok(None);
     }
-    info!("Day {}", day);
+    events::emit("day_started", [("day", Json::from(day))]);
+    let _span = trace::span(format!("day {}", day), "day");
+    #[cfg(feature = "alloc-stats")]
+    let alloc_span = AllocSpan::start();
     let tstart = Instant::now();
-    let solution = puzzles::DAYS[day - 1](input)?;
-    let duration = tstart.elapsed();
-    if let Some(answer) = solution.part_1 {
-        info!("part 1: {}", answer);
+    // discard steps left over from a previous day on this thread
+    explain::take();
+    let mut solution = {
+        let _span = trace::span("solve", "phase");
+        select_variant(day, algo)(&input, parts, context)?
+    };
+    solution.explanation = explain::take();
+    let timing = Timing {
+        seconds: tstart.elapsed().as_secs_f64(),
+        parse_seconds: solution.parse_seconds,
+        part_seconds: solution.part_seconds,
+        #[cfg(feature = "alloc-stats")]
+        allocs: alloc_span.stats(),
+    };
+    // check the other variants against the default, if requested
+    if algo == puzzles::ALL_VARIANTS {
+        let _span = trace::span("check variants", "phase");
+        let diverging = puzzles::diverging_variants(day, &input, parts, context)?;
+        for name in diverging.iter() {
+            warn!("day {}: variant {} diverges from the default", day, name);
+        }
+        if !diverging.is_empty() {
+            return Err(anyhow!("day {}: solver variants disagree", day));
+        }
+    }
+    Ok(Some((solution, timing)))
+}
+
+/// builds the day's plugin and solves the day's input with it; the plugin is
+/// only kept if it solves the input, so that a day which fails to build or
+/// panics can be fixed without restarting
+#[cfg(unix)]
+fn rerun_plugin(day: usize, source: Source) -> Result<plugin::Plugin> {
+    let input = load_input(day, source, false)?;
+    if input.is_empty() {
+        return Err(Error::MissingInput { day }.into());
+    }
+    let plugin = plugin::Plugin::load(&plugin::build(day)?)?;
+    let start = Instant::now();
+    let answers = plugin.solve(&input, source.is_sample())?;
+    let elapsed = start.elapsed();
+    let mut solution = Solution::new();
+    solution.part_1 = answers.part_1.map(Answer::Str);
+    solution.part_2 = answers.part_2.map(Answer::Str);
+    report_puzzle(day, solution, Parts::Both, false, false);
+    info!("day {}: {:.3}ms", day, elapsed.as_secs_f64() * 1000.0);
+    Ok(plugin)
+}
+
+/// reruns the day each time the sources change, until interrupted; errors
+/// are reported and the sources watched for the next change
+#[cfg(unix)]
+fn watch_day(day: usize, source: Source) -> Result<()> {
+    let mut last_modified = None;
+    // the plugin which solved the input last, kept loaded until replaced
+    let mut _loaded = None;
+    loop {
+        let modified = plugin::last_modified();
+        if modified != last_modified {
+            last_modified = modified;
+            match rerun_plugin(day, source) {
+                Ok(plugin) => _loaded = Some(plugin),
+                Err(e) => warn!("{}", e),
+            }
+            info!("watching for changes to the sources of day {}", day);
+        }
+        thread::sleep(WATCH_INTERVAL);
+    }
+}
+
+/// solves the puzzle in a child process under the resource limits, passing
+/// along the options which affect solving
+#[cfg(unix)]
+fn solve_sandboxed(day: usize, args: &Args) -> Result<Option<(Solution, Timing)>> {
+    let mut child_args = vec![String::from("--algo"), args.algo.clone()];
+    match Source::from_args(args) {
+        Source::Default => {}
+        Source::Variant(variant) => {
+            child_args.extend([String::from("--input-variant"), variant.to_string()]);
+        }
+        Source::File(path) => {
+            child_args.extend([String::from("--input"), path.to_string_lossy().to_string()]);
+        }
+        Source::Stdin => return Err(anyhow!("--sandbox cannot read the input from stdin")),
+    }
+    if let Some(part) = args.part {
+        child_args.extend([String::from("--part"), part.to_string()]);
+    }
+    child_args.extend([String::from("--threads"), args.threads.to_string()]);
+    for (name, value) in args.constants.iter() {
+        child_args.extend([String::from("--constant"), format!("{}={}", name, value)]);
+    }
+    for (enabled, flag) in [
+        (args.mmap, "--mmap"),
+        (args.parallel_parts, "--parallel-parts"),
+        (args.cache_parsed, "--cache-parsed"),
+    ] {
+        if enabled {
+            child_args.push(flag.to_string());
+        }
+    }
+    let limits = sandbox::Limits::new(args.memory_limit, args.cpu_limit);
+    // errors of the crate already name the day, and are kept as they are so
+    // that the runner can tell them apart
+    let record = sandbox::solve(&env::current_exe()?, day, &child_args, limits).map_err(|e| {
+        if e.is::<Error>() {
+            e
+        } else {
+            anyhow!("day {}: {}", day, e)
+        }
+    })?;
+    Ok(record.map(|record| {
+        let solution = Solution {
+            part_1: record.part_1.map(Answer::from),
+            part_2: record.part_2.map(Answer::from),
+            ..Solution::new()
+        };
+        let timing = Timing {
+            seconds: record.seconds,
+            // allocations and the time of each phase are measured in the
+            // child, and not reported back
+            ..Timing::default()
+        };
+        (solution, timing)
+    }))
+}
+
+#[cfg(not(unix))]
+fn solve_sandboxed(_day: usize, _args: &Args) -> Result<Option<(Solution, Timing)>> {
+    Err(anyhow!("--sandbox is only supported on unix"))
+}
+
+/// prints the answers and, if requested, the extras for the puzzle
+fn report_puzzle(
+    day: usize,
+    solution: Solution,
+    parts: Parts,
+    show_extras: bool,
+    show_explanation: bool,
+) {
+    info!("{}", style::header(puzzles::title(day)));
+    for (part, answer) in [(1, &solution.part_1), (2, &solution.part_2)] {
+        match answer {
+            Some(answer) => info!("part {}: {}", part, style::answer(answer)),
+            None if !parts.includes(part) => info!("part {}: {}", part, style::dim("skipped")),
+            None => info!("part {}: {}", part, style::dim("no answer")),
+        }
+    }
+    if show_explanation && !solution.explanation.is_empty() {
+        let steps = solution
+            .explanation
+            .iter()
+            .enumerate()
+            .map(|(i, step)| format!("  {}. {}", i + 1, step))
+            .collect::<Vec<_>>();
+        info!("explanation:\n{}", steps.join("\n"));
+    }
+    if show_extras {
+        // answers read from letters are shown as they were drawn
+        for (part, answer) in [(1, &solution.part_1), (2, &solution.part_2)] {
+            if let Some(pixels) = answer.as_ref().and_then(Answer::pixels) {
+                info!("part {} letters:\n{}", part, pixels);
+            }
+        }
+        for (label, contents) in solution.extras.iter() {
+            if contents.contains('\n') {
+                info!("{}:\n{}", label, contents);
+            } else {
+                info!("{}: {}", label, contents);
+            }
+        }
+    }
+}
+
+/// renders the visualization for the day, if it has one
+fn visualize_puzzle(day: usize, backend: Backend, source: Source, mmap: bool) -> Result<()> {
+    let Some(visualizer) = puzzles::visualizer(day) else {
+        debug!("day {} has no visualization", day);
+        return Ok(());
+    };
+    let input = load_input(day, source, mmap)?;
+    if input.is_empty() {
+        return Ok(());
+    }
+    let animation = visualizer(&input)?;
+    let path = Path::new(PROJECT_DIR)
+        .join("viz")
+        .join(format!("day{}", day));
+    if let Some(path) = viz::render(&animation, backend, &path)? {
+        info!("wrote visualization to {}", path.to_string_lossy());
+    }
+    Ok(())
+}
+
+/// prints or saves the artifacts attached to the solution of the day
+fn output_artifacts(
+    day: usize,
+    artifacts: &[artifacts::Artifact],
+    mode: artifacts::Mode,
+) -> Result<()> {
+    let dir = Path::new(PROJECT_DIR)
+        .join("viz")
+        .join("artifacts")
+        .join(format!("day{}", day));
+    for artifact in artifacts.iter() {
+        match mode {
+            artifacts::Mode::Print => info!("{}:\n{}", artifact.name(), artifact.to_text()),
+            artifacts::Mode::Save => {
+                let path = artifact.save(&dir)?;
+                info!("wrote {} to {}", artifact.name(), path.to_string_lossy());
+            }
+            artifacts::Mode::Skip => {}
+        }
+    }
+    Ok(())
+}
+
+/// the days tagged with any of the tags, in order
+fn days_with_tags(tags: &[String]) -> Result<Vec<usize>> {
+    let mut days = Vec::new();
+    for tag in tags.iter() {
+        let tagged = puzzles::days_with_tag(tag);
+        if tagged.is_empty() {
+            return Err(anyhow!(
+                "no days are tagged {}, expected one of: {}",
+                tag,
+                puzzles::tags().join(", ")
+            ));
+        }
+        days.extend(tagged);
+    }
+    days.sort();
+    days.dedup();
+    Ok(days)
+}
+
+/// the known answers which --check compares against
+fn check_answers_for(args: &Args) -> Result<Answers> {
+    match (&args.answers, recorded_input(args)) {
+        (Some(path), _) => Answers::load(path),
+        (None, Some("dbg")) => Ok(Answers::samples()),
+        (None, Some(variant)) => Err(anyhow!(
+            "no known answers for the {} input, pass --answers",
+            variant
+        )),
+        (None, None) => Answers::real(),
+    }
+}
+
+/// checks each answer against the known answers, reporting PASS or FAIL for
+/// each part, and returns the number which failed
+fn check_answers(records: &[DayRecord], answers: &Answers, parts: Parts) -> usize {
+    let mut failed = 0;
+    for record in records.iter() {
+        for (part, answer) in [(1, &record.part_1), (2, &record.part_2)] {
+            if !parts.includes(part) {
+                continue;
+            }
+            match answers.check(record.day, part, answer.as_deref()) {
+                Check::Pass => info!("day {} part {}: PASS", record.day, part),
+                Check::Fail(expected) => {
+                    failed += 1;
+                    warn!(
+                        "day {} part {}: FAIL, expected {} but got {}",
+                        record.day,
+                        part,
+                        expected,
+                        answer.as_deref().unwrap_or("no answer")
+                    );
+                }
+                Check::Unknown => debug!("day {} part {}: no known answer", record.day, part),
+            }
+        }
+    }
+    failed
+}
+
+/// the input recorded in the run history, which is None for the real input
+fn recorded_input(args: &Args) -> Option<&str> {
+    match Source::from_args(args) {
+        Source::Variant(variant) => Some(variant),
+        Source::File(path) => Some(path.to_str().unwrap_or("file")),
+        Source::Stdin => Some("stdin"),
+        Source::Default if cfg!(feature = "sample") => Some("dbg"),
+        Source::Default => None,
+    }
+}
+
+/// reports whether the answers of each day changed since the previous run
+fn diff_answers(previous_runs: &[RunRecord], records: &[DayRecord], input: Option<&str>) {
+    for record in records.iter() {
+        match history::latest_day(previous_runs, record.day, input) {
+            Some(previous) => {
+                for line in history::diff_answers(previous, record) {
+                    info!("{}", line);
+                }
+            }
+            None => info!("day {}: no previous run recorded", record.day),
+        }
+    }
+}
+
+/// resolves an input given to compare, which is either a path or the name of
+/// an input variant for the day
+fn resolve_input(day: usize, input: &str) -> PathBuf {
+    let path = PathBuf::from(input);
+    if path.is_file() {
+        return path;
+    }
+    let file_name = match input {
+        "real" => format!("D{}.txt", day),
+        variant => format!("D{}.{}.txt", day, variant),
+    };
+    Path::new(PROJECT_DIR).join("input").join(file_name)
+}
+
+/// the source of an input given to compare, for the context it is solved
+/// with; the real input is named as the variant "real", which is not a sample
+fn compare_source(input: &str) -> Source<'_> {
+    let path = Path::new(input);
+    if path.is_file() {
+        Source::File(path)
+    } else {
+        Source::Variant(input)
+    }
+}
+
+/// runs the day on an input given to compare, recording its answers and
+/// timing
+fn solve_file(day: usize, input: &str, args: &Args) -> Result<DayRecord> {
+    let path = resolve_input(day, input);
+    if !path.exists() {
+        return Err(anyhow!("missing input {}", path.to_string_lossy()));
+    }
+    let context = context_for(args, compare_source(input));
+    let input = utils::read_file(&path)?;
+    let tstart = Instant::now();
+    let solution = select_variant(day, &args.algo)(&input, Parts::Both, &context)?;
+    Ok(DayRecord {
+        day,
+        seconds: tstart.elapsed().as_secs_f64(),
+        part_1: solution.part_1.map(|a| a.to_string()),
+        part_2: solution.part_2.map(|a| a.to_string()),
+    })
+}
+
+/// compares a day on two inputs, or on one input against a recorded run
+fn compare_day(day: usize, inputs: &[String], rev: Option<&str>, args: &Args) -> Result<()> {
+    let (label_a, a, label_b, b) = if let Some(rev) = rev {
+        if inputs.len() > 1 {
+            return Err(anyhow!("only one input can be compared against a revision"));
+        }
+        let records = history::load(&history::history_path())?;
+        let recorded = compare::find_at_revision(&records, rev, day)
+            .ok_or_else(|| anyhow!("no recorded run of day {} at revision {}", day, rev))?
+            .clone();
+        let input = inputs.first().map(|s| s.as_str()).unwrap_or("real");
+        let current = solve_file(day, input, args)?;
+        (rev.to_string(), recorded, String::from("current"), current)
     } else {
-        info!("part 1: no answer");
+        let [a, b] = inputs else {
+            return Err(anyhow!("expected two inputs to compare, or --rev"));
+        };
+        let record_a = solve_file(day, a, args)?;
+        let record_b = solve_file(day, b, args)?;
+        (a.clone(), record_a, b.clone(), record_b)
+    };
+    info!(
+        "day {}:\n{}",
+        day,
+        compare::render(&label_a, &a, &label_b, &b)
+    );
+    Ok(())
+}
+
+/// benchmarks each day which has an input; split days parse once, unless
+/// another variant was requested
+fn bench_days(
+    days: &[usize],
+    source: Source,
+    mmap: bool,
+    algo: &str,
+    options: bench::Options,
+    answers: &Answers,
+) -> Result<Vec<BenchResult>> {
+    let mut results = Vec::new();
+    for &day in days {
+        // loaded before timing, so that only the solving is measured
+        let input = load_input(day, source, mmap)?;
+        if input.is_empty() {
+            continue;
+        }
+        debug!("benchmarking day {} with {} runs", day, options.runs);
+        let result = match puzzles::split_bench(day) {
+            Some(split_bench) if algo == puzzles::DEFAULT_VARIANT => {
+                split_bench(day, &input, options, answers)?
+            }
+            _ => bench::bench(day, select_variant(day, algo), &input, options, answers)?,
+        };
+        results.push(result);
     }
-    if let Some(answer) = solution.part_2 {
-        info!("part 2: {}", answer);
+    Ok(results)
+}
+
+/// benchmarks every day with an input, checking the answers against the known
+/// answers for the build's inputs
+fn bench_all(runs: usize, algo: &str, markdown: bool) -> Result<Vec<BenchResult>> {
+    let answers = if cfg!(feature = "sample") {
+        Answers::samples()
+    } else {
+        Answers::real()?
+    };
+    let days = (1..=puzzles::N_DAYS)
+        .filter(|&day| puzzles::is_implemented(day))
+        .collect::<Vec<_>>();
+    let options = bench::Options::new(BENCH_WARMUP_RUNS, runs);
+    let results = bench_days(&days, Source::Default, false, algo, options, &answers)?;
+    if markdown {
+        println!("{}", bench::markdown_table(&results));
     } else {
-        info!("part 2: no answer");
+        info!("{} runs per day:\n{}", runs, bench::text_table(&results));
     }
-    Ok(duration.as_secs_f64())
+    Ok(results)
 }
 
-fn main() -> Result<()> {
-    // parse command-line args
-    let args = Args::parse();
+/// publishes the benchmark results to the shared endpoint, and compares them
+/// against the results fetched from the peers
+fn share_bench(
+    results: &[BenchResult],
+    runs: usize,
+    publish: bool,
+    peers: &[String],
+    endpoint: Option<String>,
+    name: Option<String>,
+) -> Result<()> {
+    let endpoint = endpoint
+        .or_else(|| env::var("AOC_RESULTS_URL").ok())
+        .ok_or_else(|| anyhow!("no results endpoint, pass --endpoint or set AOC_RESULTS_URL"))?;
+    let name = name
+        .or_else(|| env::var("AOC_RESULTS_NAME").ok())
+        .or_else(|| env::var("USER").ok())
+        .unwrap_or_else(|| String::from("local"));
+    let records = results
+        .iter()
+        .map(|result| result.to_record())
+        .collect::<Vec<_>>();
+    if publish {
+        // answers differ between accounts and should not be made public, so
+        // only their digests are shared
+        let json = results::export(
+            &records,
+            &results::Machine::current(),
+            chrono::Utc::now().timestamp(),
+            history::git_revision(),
+            true,
+        );
+        results::publish(&endpoint, &name, &json)?;
+        info!(
+            "published results to {}",
+            results::shared_url(&endpoint, &name)
+        );
+    }
+    if !peers.is_empty() {
+        let mut columns = vec![(name, records)];
+        for peer in peers.iter() {
+            let imported = results::fetch(&endpoint, peer)?;
+            let machine = &imported.machine;
+            info!(
+                "{}: {} {}, {} threads, {}",
+                peer,
+                machine.os,
+                machine.arch,
+                machine.threads,
+                machine.cpu.as_deref().unwrap_or("unknown CPU")
+            );
+            columns.push((peer.clone(), imported.days));
+        }
+        info!(
+            "best of {} runs against peers:\n{}",
+            runs,
+            bench::comparison_table(&columns)
+        );
+    }
+    Ok(())
+}
 
-    // set up the logger
-    if let Err(e) = setup_logger(args.debug) {
+/// solves the part of the day and submits its answer, unless an earlier
+/// submission already judged the part or the answer
+fn submit_answer(day: usize, part: usize, algo: &str) -> Result<()> {
+    if cfg!(feature = "sample") {
+        return Err(anyhow!(
+            "answers are submitted from the real inputs, build without the sample feature"
+        ));
+    }
+    let (solution, _) = solve_puzzle(
+        day,
+        algo,
+        Source::Default,
+        Parts::Only(part),
+        false,
+        &Context::new(Progress::log()),
+    )?
+    .ok_or(Error::MissingInput { day })?;
+    let answer = match part {
+        1 => solution.part_1,
+        _ => solution.part_2,
+    }
+    .ok_or_else(|| anyhow!("day {} part {} has no answer", day, part))?
+    .to_string();
+    let path = submit::submissions_path();
+    let submissions = submit::load(&path)?;
+    if let Some(earlier) = submit::judged(&submissions, day, part, &answer) {
+        info!(
+            "day {} part {}: not submitting {}, {} was already submitted and was {}",
+            day, part, answer, earlier.answer, earlier.outcome
+        );
+        return Ok(());
+    }
+    info!("day {} part {}: submitting {}", day, part, answer);
+    let outcome = submit::submit(day, part, &answer)?;
+    submit::append(
+        &path,
+        &Submission {
+            timestamp: chrono::Utc::now().timestamp(),
+            day,
+            part,
+            answer: answer.clone(),
+            outcome: outcome.clone(),
+        },
+    )?;
+    match outcome {
+        Outcome::Correct => {
+            info!("day {} part {}: {}", day, part, outcome);
+            let path = answers::local_answers_path();
+            if answers::record_confirmed(&path, day, part, &answer)? {
+                info!("recorded the answer in {}", path.to_string_lossy());
+            } else {
+                info!("the answer was already in {}", path.to_string_lossy());
+            }
+            // later runs are checked against the confirmed answer, even if
+            // another was accepted with --rerecord
+            answers::record_confirmed(&answers::regressions_path(), day, part, &answer)?;
+        }
+        _ => warn!("day {} part {}: {}", day, part, outcome),
+    }
+    Ok(())
+}
+
+/// runs a build of the solutions with --porcelain and parses its output
+fn run_porcelain(binary: &Path, days: Option<&Days>, algo: &str) -> Result<Vec<DayRecord>> {
+    let mut command = process::Command::new(binary);
+    if let Some(days) = days {
+        command.arg(days.to_string());
+    }
+    let output = command
+        .args(["--porcelain", "--algo", algo])
+        .output()
+        .map_err(|e| anyhow!("failed to run {}: {}", binary.to_string_lossy(), e))?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "{} failed: {}",
+            binary.to_string_lossy(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    porcelain::parse(&String::from_utf8(output.stdout)?)
+}
+
+/// keeps the fastest time of each day across the runs
+fn fastest_runs(runs: Vec<Vec<DayRecord>>) -> Vec<DayRecord> {
+    let mut fastest: Vec<DayRecord> = Vec::new();
+    for record in runs.into_iter().flatten() {
+        match fastest.iter_mut().find(|r| r.day == record.day) {
+            Some(r) if record.seconds < r.seconds => *r = record,
+            Some(_) => {}
+            None => fastest.push(record),
+        }
+    }
+    fastest
+}
+
+/// benchmarks the selected days for --bench, and reports the statistics of
+/// the times of each
+fn bench_selected(days: &[usize], runs: usize, args: &Args) -> Result<()> {
+    let options = bench::Options {
+        parts: args.parts(),
+        ..bench::Options::new(BENCH_WARMUP_RUNS, runs)
+    };
+    let answers = check_answers_for(args).unwrap_or_default();
+    let results = bench_days(
+        days,
+        Source::from_args(args),
+        args.mmap,
+        &args.algo,
+        options,
+        &answers,
+    )?;
+    info!("{} runs per day:\n{}", runs, bench::stats_table(&results));
+    Ok(())
+}
+
+/// runs the days with this build and with the baseline binary, alternating
+/// between them so that both see the same system load
+fn bench_against(baseline: &Path, days: Option<&Days>, algo: &str) -> Result<()> {
+    let current = env::current_exe()?;
+    let (mut baseline_runs, mut current_runs) = (Vec::new(), Vec::new());
+    for run in 1..=BENCH_AGAINST_RUNS {
+        debug!("run {} of {}", run, BENCH_AGAINST_RUNS);
+        baseline_runs.push(run_porcelain(baseline, days, algo)?);
+        current_runs.push(run_porcelain(&current, days, algo)?);
+    }
+    let table = bench::speedup_table(&fastest_runs(baseline_runs), &fastest_runs(current_runs));
+    info!(
+        "best of {} runs against {}:\n{}",
+        BENCH_AGAINST_RUNS,
+        baseline.to_string_lossy(),
+        table
+    );
+    Ok(())
+}
+
+/// checks the inputs against the manifest, or rewrites it if requested
+fn verify_inputs(update: bool) -> Result<()> {
+    let input_dir = Path::new(PROJECT_DIR).join("input");
+    let manifest_path = input_dir.join(manifest::MANIFEST_NAME);
+    if update {
+        let manifest = Manifest::build(&input_dir)?;
+        manifest.write(&manifest_path)?;
+        info!(
+            "wrote {} digests to {}",
+            manifest.len(),
+            manifest_path.to_string_lossy()
+        );
+        return Ok(());
+    }
+    if !manifest_path.exists() {
+        return Err(anyhow!(
+            "missing {}, create it with verify-inputs --update",
+            manifest_path.to_string_lossy()
+        ));
+    }
+    let mut n_bad = 0;
+    for (name, status) in Manifest::load(&manifest_path)?.verify(&input_dir)? {
+        match status {
+            Status::Ok => debug!("{}: {}", name, status),
+            Status::Untracked => warn!("{}: {}", name, status),
+            Status::Modified | Status::Missing => {
+                n_bad += 1;
+                warn!("{}: {}", name, status);
+            }
+        }
+    }
+    if n_bad > 0 {
+        Err(anyhow!("{} inputs do not match the manifest", n_bad))
+    } else {
+        info!("all inputs match the manifest");
+        Ok(())
+    }
+}
+
+/// prints a generated input for the day to stdout
+fn run_gen(day: usize, size: Option<usize>, seed: u64) -> Result<()> {
+    print!("{}", generators::generate(day, size, seed)?);
+    Ok(())
+}
+
+/// sets up the logger along with the modules enabled by the global options
+fn setup(args: &Args) {
+    if args.log_format == LogFormat::Jsonl {
+        events::enable();
+    } else if !args.no_color && !args.porcelain && style::is_wanted() {
+        style::enable();
+    }
+    if let Err(e) = setup_logger(args.debug, args.porcelain, args.log_format) {
         panic!("failed to initialize logger: {}", e);
     }
     info!("Advent of Code 2022");
+    let gaps = puzzles::gaps();
+    if !gaps.is_empty() {
+        debug!(
+            "no solver registered for days {}",
+            puzzles::format_days(gaps)
+        );
+    }
+    if args.trace_out.is_some() {
+        trace::enable();
+    }
+    if args.explain {
+        explain::enable();
+    }
+    if args.artifacts != artifacts::Mode::Skip {
+        artifacts::enable();
+    }
+    pool::set_threads(args.threads);
+    pool::set_parallel_parts(args.parallel_parts);
+    if args.cache_parsed {
+        input_cache::enable();
+    }
+}
+
+/// shows the description of the day, falling back to the stored copy
+fn run_describe(day: usize, refresh: bool) -> Result<()> {
+    // the stored description is still readable without a session
+    let markdown = match describe::fetch(day, refresh) {
+        Ok(markdown) => markdown,
+        Err(e) if describe::markdown_path(day).exists() => {
+            warn!("failed to fetch the description of day {}: {}", day, e);
+            fs::read_to_string(describe::markdown_path(day))?
+        }
+        Err(e) => return Err(e),
+    };
+    println!("{}", describe::render(&markdown, describe::WIDTH));
+    if markdown
+        .lines()
+        .filter(|line| line.starts_with("## "))
+        .count()
+        < 2
+    {
+        info!("part 2 is shown once part 1 has been solved");
+    }
+    Ok(())
+}
+
+/// shows the private leaderboard with the given ID, or with AOC_LEADERBOARD
+fn run_leaderboard(id: Option<&str>, refresh: bool) -> Result<()> {
+    let id = id
+        .map(String::from)
+        .or_else(|| env::var("AOC_LEADERBOARD").ok())
+        .ok_or_else(|| anyhow!("no leaderboard ID, pass one or set AOC_LEADERBOARD"))?;
+    let members = leaderboard::fetch(&id, refresh)?;
+    info!("leaderboard {}:\n{}", id, leaderboard::render(&members));
+    Ok(())
+}
+
+/// shows the stars earned for each day
+fn run_calendar(fetch: bool, refresh: bool) -> Result<()> {
+    let mut stars = Stars::from_answers(&Answers::real()?);
+    if fetch {
+        stars = stars.merge(&Stars::fetch(refresh)?);
+    }
+    info!("calendar:\n{}", stars.render());
+    Ok(())
+}
+
+/// shows the status of each day, also writing it as Markdown if requested
+fn run_status(out: Option<&Path>) -> Result<()> {
+    let input_dir = Path::new(PROJECT_DIR).join("input");
+    let statuses = status::collect(&input_dir, &Answers::real()?);
+    info!("status:\n{}", status::summary(&statuses));
+    if let Some(out) = out {
+        fs::write(out, status::markdown(&statuses))?;
+        info!("wrote the status table to {}", out.to_string_lossy());
+    }
+    Ok(())
+}
+
+/// writes the stars and runtime badges
+fn run_badge(out_dir: Option<&Path>) -> Result<()> {
+    let stars = Stars::from_answers(&Answers::real()?);
+    let runtime = history::latest_total_seconds(&history::load(&history::history_path())?);
+    if runtime.is_none() {
+        warn!("no recorded runs, run with --record to add a runtime badge");
+    }
+    let out_dir = out_dir.map_or_else(|| Path::new(PROJECT_DIR).join("badges"), Path::to_path_buf);
+    for path in badge::write_badges(&out_dir, &stars, runtime)? {
+        info!("wrote {}", path.to_string_lossy());
+    }
+    Ok(())
+}
+
+/// solves the days and writes their results in the shareable results schema
+fn run_export(args: &Args, days: &[usize], out: &Path, hash_answers: bool) -> Result<()> {
+    let days = if days.is_empty() {
+        (1..=puzzles::N_DAYS).collect()
+    } else {
+        days.to_vec()
+    };
+    let context = context_for(args, Source::Default);
+    let mut records = Vec::new();
+    for day in days {
+        if let Some((solution, timing)) = solve_puzzle(
+            day,
+            &args.algo,
+            Source::Default,
+            Parts::Both,
+            args.mmap,
+            &context,
+        )? {
+            records.push(DayRecord {
+                day,
+                seconds: timing.seconds,
+                part_1: solution.part_1.map(|a| a.to_string()),
+                part_2: solution.part_2.map(|a| a.to_string()),
+            });
+        }
+    }
+    let results = results::export(
+        &records,
+        &results::Machine::current(),
+        chrono::Utc::now().timestamp(),
+        history::git_revision(),
+        hash_answers,
+    );
+    std::fs::write(out, format!("{}\n", results))?;
+    info!(
+        "wrote results for {} days to {}",
+        records.len(),
+        out.to_string_lossy()
+    );
+    Ok(())
+}
+
+/// removes the selected cached and generated files
+fn run_clean(cache: bool, viz: bool, history: bool, all: bool) -> Result<()> {
+    let artifacts = Artifact::ALL
+        .into_iter()
+        .zip([cache, viz, history])
+        .filter(|&(_, selected)| selected || all)
+        .map(|(artifact, _)| artifact)
+        .collect::<Vec<_>>();
+    if artifacts.is_empty() {
+        return Err(anyhow!(
+            "nothing to clean, pass --cache, --viz, --history or --all"
+        ));
+    }
+    for artifact in artifacts {
+        for path in artifact.paths() {
+            if let Some(bytes) = clean::remove(&path)? {
+                info!(
+                    "removed {} ({}, {:.1}KiB)",
+                    path.to_string_lossy(),
+                    artifact.name(),
+                    bytes as f64 / 1024.0
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// charts the recorded runtime of the days
+fn run_chart(days: &[usize], out: Option<&Path>) -> Result<()> {
+    let records = history::load(&history::history_path())?;
+    let series = chart::runtime_series(&records, days);
+    if series.is_empty() {
+        return Err(anyhow!("no recorded runs to chart, run with --record"));
+    }
+    let out = out.map_or_else(
+        || Path::new(PROJECT_DIR).join("charts").join("runtime.svg"),
+        Path::to_path_buf,
+    );
+    if let Some(dir) = out.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&out, chart::render_svg(&records, &series))?;
+    info!("wrote chart to {}", out.to_string_lossy());
+    Ok(())
+}
+
+/// benchmarks every day, then publishes and compares the results if requested
+fn run_bench(
+    args: &Args,
+    runs: usize,
+    markdown: bool,
+    publish: bool,
+    fetch: &[String],
+    endpoint: Option<&str>,
+    name: Option<&str>,
+) -> Result<()> {
+    let results = bench_all(runs, &args.algo, markdown)?;
+    if publish || !fetch.is_empty() {
+        share_bench(
+            &results,
+            runs,
+            publish,
+            fetch,
+            endpoint.map(String::from),
+            name.map(String::from),
+        )?;
+    }
+    Ok(())
+}
+
+/// profiles the day on generated inputs of increasing size
+fn run_complexity(args: &Args, day: usize, sizes: &[usize], runs: usize, seed: u64) -> Result<()> {
+    let sizes = if sizes.is_empty() {
+        complexity::default_sizes(day)?
+    } else {
+        sizes.to_vec()
+    };
+    let puzzle = select_variant(day, &args.algo);
+    let samples = complexity::profile(day, puzzle, &sizes, runs, seed)?;
+    info!("day {}:\n{}", day, complexity::render(&samples));
+    Ok(())
+}
+
+/// lists the days with their titles and how each is solved
+fn run_list() -> Result<()> {
+    let days = (1..=puzzles::N_DAYS)
+        .map(|day| {
+            let status = match puzzles::metadata(day) {
+                Some(metadata) => format!("\n    {}", metadata),
+                None if puzzles::is_implemented(day) => String::new(),
+                None => String::from("  (unsolved)"),
+            };
+            format!("{}{}", puzzles::title(day), status)
+        })
+        .collect::<Vec<_>>();
+    info!("days:\n{}", days.join("\n"));
+    Ok(())
+}
+
+/// checks every day against its answers, notifying the webhook of the outcome
+fn run_verify_all(args: &Args) -> Result<()> {
+    let mut summary = Summary::new("verify-all");
+    let result = verify_all(&mut summary);
+    if let Err(e) = &result {
+        if summary.failures.is_empty() {
+            summary.failures.push(e.to_string());
+        }
+    }
+    send_notification(args.webhook.as_deref(), &summary);
+    write_trace(args.trace_out.as_deref())?;
+    result
+}
+
+/// solves and reports the days selected by the arguments, when no subcommand
+/// is given
+fn run_days(args: &Args) -> Result<()> {
+    // loaded before this run is recorded, so that it is compared against the
+    // previous run
+    let previous_runs = if args.diff_answers {
+        history::load(&history::history_path())?
+    } else {
+        Vec::new()
+    };
 
     // track the time elapsed for each puzzle
     let mut times = HashMap::new();
+    let mut summary = Summary::new("run");
+    let mut records = Vec::new();
+    let mut timed_out = 0;
 
-    if let Some(day) = args.day {
-        // run a single puzzle if provided
-        let t = run_puzzle(day)?;
-        times.insert(day, t);
+    // run a single puzzle if provided, otherwise run all puzzles
+    let days = match &args.days {
+        Some(days) => days.0.clone(),
+        None if !args.tag.is_empty() => days_with_tags(&args.tag)?,
+        // stubs are run along with the solved days, and reported as not
+        // implemented when solved
+        None => (1..=puzzles::N_DAYS)
+            .filter(|&day| puzzles::is_implemented(day) || puzzles::is_stub(day))
+            .collect::<Vec<_>>(),
+    };
+    let n_given = args.days.as_ref().map_or(0, |days| days.0.len());
+    if args.input.is_some() && n_given != 1 {
+        return Err(anyhow!(
+            "--input is the input of a single day, but {} days were given",
+            n_given
+        ));
+    }
+    if let Some(runs) = args.bench_runs {
+        let days = days
+            .iter()
+            .copied()
+            .filter(|&day| puzzles::is_implemented(day))
+            .collect::<Vec<_>>();
+        return bench_selected(&days, runs as usize, args);
+    }
+    // checked before the run, rather than failing once it has finished
+    let report_format = args
+        .report
+        .as_deref()
+        .map(report::Format::for_path)
+        .transpose()?;
+    let context = context_for(args, Source::from_args(args));
+    let solve = |&day: &usize| {
+        // checked before the input is loaded, as days which are not
+        // implemented are reported even without an input
+        if !puzzles::is_implemented(day) {
+            return Err(anyhow!(Error::Unimplemented { day }));
+        }
+        if args.sandbox {
+            solve_sandboxed(day, args)
+        } else {
+            solve_puzzle(
+                day,
+                &args.algo,
+                Source::from_args(args),
+                args.parts(),
+                args.mmap,
+                &context,
+            )
+        }
+    };
+    let results = if args.threads > 1 {
+        // solve in parallel, then report in order once all have finished
+        #[cfg(feature = "alloc-stats")]
+        warn!("allocation counts are shared between threads");
+        pool::map(&days, args.threads, args.pin, solve)
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>()
     } else {
-        // otherwise run all puzzles
-        for day in 1..=puzzles::N_DAYS {
-            let t = run_puzzle(day)?;
-            times.insert(day, t);
+        if args.pin && !pool::pin_to_core(0) {
+            warn!("failed to pin to a core");
+        }
+        days.iter().map(|_| None).collect::<Vec<_>>()
+    };
+    for (&day, result) in days.iter().zip(results) {
+        // a day interrupted by Ctrl-C has reported its best answer so far, and
+        // the rest of the run is skipped but still summarized
+        if context.cancel.is_cancelled() {
+            warn!("interrupted, skipping day {} onwards", day);
+            break;
+        }
+        // solve sequentially as each day is reported
+        match result.unwrap_or_else(|| solve(&day)) {
+            Ok(Some((mut solution, timing))) => {
+                summary.days += 1;
+                summary.answers +=
+                    solution.part_1.is_some() as usize + solution.part_2.is_some() as usize;
+                summary.seconds += timing.seconds;
+                for (part, answer) in [(1, &solution.part_1), (2, &solution.part_2)] {
+                    if let Some(answer) = answer {
+                        events::emit(
+                            "part_finished",
+                            [
+                                ("day", Json::from(day)),
+                                ("part", Json::from(part as usize)),
+                                ("answer", Json::from(answer)),
+                            ],
+                        );
+                    }
+                }
+                events::emit(
+                    "day_finished",
+                    [
+                        ("day", Json::from(day)),
+                        ("seconds", Json::from(timing.seconds)),
+                    ],
+                );
+                records.push(DayRecord {
+                    day,
+                    seconds: timing.seconds,
+                    part_1: solution.part_1.as_ref().map(|a| a.to_string()),
+                    part_2: solution.part_2.as_ref().map(|a| a.to_string()),
+                });
+                // rewritten after each day so that a failed or interrupted
+                // run still leaves the answers found so far
+                if let Some(path) = &args.output {
+                    let format = output::Format::for_path(path, args.porcelain);
+                    output::write(path, format, &records)?;
+                }
+                let solved_artifacts = mem::take(&mut solution.artifacts);
+                if args.porcelain {
+                    for line in porcelain::lines(day, &solution, timing.seconds) {
+                        println!("{}", line);
+                    }
+                } else {
+                    report_puzzle(day, solution, args.parts(), args.extras, args.explain);
+                }
+                output_artifacts(day, &solved_artifacts, args.artifacts)?;
+                times.insert(day, timing);
+                if let Some(backend) = args.visualize {
+                    visualize_puzzle(day, backend, Source::from_args(args), args.mmap)?;
+                }
+            }
+            Ok(None) => {
+                if args.days.is_some() {
+                    warn!("{}", Error::MissingInput { day });
+                }
+            }
+            Err(e) => {
+                let error = e.downcast_ref::<Error>();
+                if error.is_some_and(Error::is_skip) {
+                    warn!("{}", e);
+                    continue;
+                }
+                events::emit(
+                    "day_failed",
+                    [
+                        ("day", Json::from(day)),
+                        ("kind", Json::from(error.map_or("other", Error::kind))),
+                        ("error", Json::from(e.to_string())),
+                    ],
+                );
+                // a day stopped by its time limit fails the run, but once the
+                // remaining days have been run
+                if let Some(Error::Timeout { .. }) = error {
+                    warn!("{}", e);
+                    summary.failures.push(e.to_string());
+                    timed_out += 1;
+                    continue;
+                }
+                summary.failures.push(format!("day {}: {}", day, e));
+                send_notification(args.webhook.as_deref(), &summary);
+                return Err(e);
+            }
+        }
+    }
+    events::emit(
+        "run_finished",
+        [
+            ("days", Json::from(summary.days)),
+            ("answers", Json::from(summary.answers)),
+            ("seconds", Json::from(summary.seconds)),
+        ],
+    );
+    send_notification(args.webhook.as_deref(), &summary);
+    if let (Some(path), Some(format)) = (&args.report, report_format) {
+        report::write(path, format, &records)?;
+        info!("wrote the report to {}", path.to_string_lossy());
+    }
+    if args.diff_answers {
+        diff_answers(&previous_runs, &records, recorded_input(args));
+    }
+    // answers to the real inputs are checked after every run against the
+    // answers confirmed for them, so that refactors which change an answer
+    // are caught even without --check; the answers of an interrupted run may
+    // not be final, so are never recorded
+    if recorded_input(args).is_none() && !context.cancel.is_cancelled() {
+        let regressions = answers::check_regressions(
+            &answers::regressions_path(),
+            &Answers::confirmed()?,
+            &records,
+            args.rerecord,
+        )?;
+        for regression in regressions {
+            warn!("REGRESSION {}", regression);
         }
+    }
+    let failed_checks = if args.check {
+        check_answers(&records, &check_answers_for(args)?, args.parts())
+    } else {
+        0
     };
+    if args.record {
+        let record = RunRecord {
+            input: recorded_input(args).map(|input| input.to_string()),
+            ..RunRecord::new(records)
+        };
+        history::append(&history::history_path(), &record)?;
+    }
 
     // log the puzzle times, if requested
-    // convert to ms for higher precision
     if args.time {
-        if let Some(day) = args.day {
-            info!("day {}: {:.03}ms", day, times[&day] * 1000.0);
-        } else {
-            // otherwise run all puzzles
-            for day in 1..=puzzles::N_DAYS {
-                info!("day {}: {:.03}ms", day, times[&day] * 1000.0);
+        // align the days and the times in columns
+        let day_width = days.iter().max().map_or(1, |day| day.to_string().len());
+        let ms_width = times
+            .values()
+            .map(|timing| format!("{:.03}ms", timing.seconds * 1000.0).len())
+            .max()
+            .unwrap_or(0);
+        for day in days {
+            if let Some(timing) = times.get(&day) {
+                info!(
+                    "day {:>2$}: {}",
+                    day,
+                    style::dim(format!("{:>1$}", timing, ms_width)),
+                    day_width
+                );
             }
-        };
+        }
     }
 
+    write_trace(args.trace_out.as_deref())?;
+    if timed_out > 0 {
+        return Err(anyhow!("{} of the days exceeded the CPU limit", timed_out));
+    }
+    if failed_checks > 0 {
+        return Err(anyhow!(
+            "{} of the checked answers did not match",
+            failed_checks
+        ));
+    }
     Ok(())
 }
+
+fn main() -> Result<()> {
+    // parse command-line args
+    let args = Args::parse();
+
+    // generated inputs go to stdout, so skip the logger entirely
+    if !matches!(args.command, Some(Command::Gen { .. })) {
+        setup(&args);
+    }
+
+    match &args.command {
+        Some(Command::Gen { day, size, seed }) => run_gen(*day, *size, *seed),
+        Some(Command::List) => run_list(),
+        Some(Command::VerifyAll) => run_verify_all(&args),
+        Some(Command::Bench {
+            runs,
+            markdown,
+            publish,
+            fetch,
+            endpoint,
+            name,
+        }) => run_bench(
+            &args,
+            *runs,
+            *markdown,
+            *publish,
+            fetch,
+            endpoint.as_deref(),
+            name.as_deref(),
+        ),
+        Some(Command::Complexity {
+            day,
+            sizes,
+            runs,
+            seed,
+        }) => run_complexity(&args, *day, sizes, *runs, *seed),
+        Some(Command::Compare { day, inputs, rev }) => {
+            compare_day(*day, inputs, rev.as_deref(), &args)
+        }
+        Some(Command::VerifyInputs { update }) => verify_inputs(*update),
+        Some(Command::Describe { day, refresh }) => run_describe(*day, *refresh),
+        Some(Command::Leaderboard { id, refresh }) => run_leaderboard(id.as_deref(), *refresh),
+        Some(Command::Calendar { fetch, refresh }) => run_calendar(*fetch, *refresh),
+        Some(Command::Serve { addr }) => serve::serve(addr),
+        Some(Command::Status { out }) => run_status(out.as_deref()),
+        Some(Command::Badge { out_dir }) => run_badge(out_dir.as_deref()),
+        Some(Command::Export {
+            days,
+            out,
+            hash_answers,
+        }) => run_export(&args, days, out, *hash_answers),
+        Some(Command::Clean {
+            cache,
+            viz,
+            history,
+            all,
+        }) => run_clean(*cache, *viz, *history, *all),
+        Some(Command::Submit { day, part }) => submit_answer(*day, *part as usize, &args.algo),
+        Some(Command::Chart { days, out }) => run_chart(days, out.as_deref()),
+        #[cfg(unix)]
+        Some(Command::Watch { day }) => watch_day(*day, Source::from_args(&args)),
+        None => match &args.bench_against {
+            Some(baseline) => bench_against(baseline, args.days.as_ref(), &args.algo),
+            None => run_days(&args),
+        },
+    }
+}
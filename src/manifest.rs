@@ -0,0 +1,264 @@
+/*
+** src/manifest.rs
+*/
+
+use anyhow::{anyhow, Result};
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// the manifest file in the input directory, in the format of sha256sum so
+/// that it can also be checked with `sha256sum -c`
+pub const MANIFEST_NAME: &str = "manifest.sha256";
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const H0: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+fn compress(state: &mut [u32; 8], block: &[u8]) {
+    let mut w = [0u32; 64];
+    for (i, word) in block.chunks(4).enumerate() {
+        w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+    }
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for i in 0..64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(K[i])
+            .wrapping_add(w[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+    for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *s = s.wrapping_add(v);
+    }
+}
+
+/// the SHA-256 digest of the data, as lowercase hex
+pub fn sha256(data: &[u8]) -> String {
+    let mut state = H0;
+    // pad with a one bit, zeros, then the message length in bits, to a
+    // multiple of the 64-byte block size
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+    for block in message.chunks(64) {
+        compress(&mut state, block);
+    }
+    state.iter().map(|word| format!("{:08x}", word)).collect()
+}
+
+/// the result of checking an input file against the manifest
+#[derive(Debug, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    /// the file differs from when the manifest was written
+    Modified,
+    /// the file is in the manifest but not in the input directory
+    Missing,
+    /// the file is not in the manifest
+    Untracked,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Self::Ok => "ok",
+            Self::Modified => "MODIFIED",
+            Self::Missing => "MISSING",
+            Self::Untracked => "untracked",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// maps the file names of the puzzle inputs to their SHA-256 digests
+#[derive(Debug, Default, PartialEq)]
+pub struct Manifest {
+    entries: BTreeMap<String, String>,
+}
+
+impl Manifest {
+    /// hashes every puzzle input in the directory
+    pub fn build(dir: &Path) -> Result<Self> {
+        let mut entries = BTreeMap::new();
+        for name in input_files(dir)? {
+            entries.insert(name.clone(), sha256(&fs::read(dir.join(&name))?));
+        }
+        Ok(Self { entries })
+    }
+
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut entries = BTreeMap::new();
+        for (i, line) in s.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            // sha256sum separates the digest and name with two spaces, or a
+            // space and an asterisk for binary mode
+            let (digest, name) = line
+                .split_once(' ')
+                .ok_or_else(|| anyhow!("invalid manifest line {}: {}", i + 1, line))?;
+            let name = name.trim_start_matches([' ', '*']);
+            if digest.len() != 64 || !digest.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(anyhow!("invalid digest on manifest line {}", i + 1));
+            }
+            entries.insert(name.to_string(), digest.to_ascii_lowercase());
+        }
+        Ok(Self { entries })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_string())?;
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// checks every input in the directory, and every input in the manifest,
+    /// returning the status of each by file name
+    pub fn verify(&self, dir: &Path) -> Result<Vec<(String, Status)>> {
+        let actual = Self::build(dir)?;
+        let mut names = self
+            .entries
+            .keys()
+            .chain(actual.entries.keys())
+            .cloned()
+            .collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+        Ok(names
+            .into_iter()
+            .map(|name| {
+                let status = match (self.entries.get(&name), actual.entries.get(&name)) {
+                    (Some(expected), Some(digest)) if expected == digest => Status::Ok,
+                    (Some(_), Some(_)) => Status::Modified,
+                    (Some(_), None) => Status::Missing,
+                    (None, _) => Status::Untracked,
+                };
+                (name, status)
+            })
+            .collect())
+    }
+}
+
+impl fmt::Display for Manifest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, digest) in self.entries.iter() {
+            writeln!(f, "{}  {}", digest, name)?;
+        }
+        Ok(())
+    }
+}
+
+/// the puzzle inputs in the directory, such as D1.txt and D1.dbg.txt
+fn input_files(dir: &Path) -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let name = entry?.file_name().to_string_lossy().to_string();
+        if name.starts_with('D') && name.ends_with(".txt") {
+            names.push(name);
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha256_digests() {
+        assert_eq!(
+            sha256(b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            sha256(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        // spans two blocks
+        assert_eq!(
+            sha256(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+    }
+
+    #[test]
+    fn manifest_round_trip() {
+        let s = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad  D1.txt\n";
+        let manifest = Manifest::parse(s).unwrap();
+        assert_eq!(manifest.len(), 1);
+        assert_eq!(manifest.to_string(), s);
+        assert!(Manifest::parse("abc  D1.txt").is_err());
+    }
+
+    #[test]
+    fn verify_inputs() {
+        let dir = std::env::temp_dir().join(format!("aoc-manifest-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("D1.txt"), "1000\n2000\n").unwrap();
+        fs::write(dir.join("D2.txt"), "A Y\n").unwrap();
+        let manifest = Manifest::build(&dir).unwrap();
+        fs::write(dir.join("D2.txt"), "A Y").unwrap();
+        fs::write(dir.join("D3.txt"), "abc\n").unwrap();
+        fs::remove_file(dir.join("D1.txt")).unwrap();
+        let statuses = manifest.verify(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            statuses,
+            vec![
+                (String::from("D1.txt"), Status::Missing),
+                (String::from("D2.txt"), Status::Modified),
+                (String::from("D3.txt"), Status::Untracked),
+            ]
+        );
+    }
+}
@@ -0,0 +1,103 @@
+/*
+** src/mmap.rs
+*/
+
+use anyhow::{anyhow, Result};
+
+use std::fs::File;
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+use std::ptr;
+use std::slice;
+
+/// a read-only memory-mapped file
+pub struct Mmap {
+    ptr: *mut libc::c_void,
+    len: usize,
+}
+
+impl Mmap {
+    /// maps the file into memory, read-only
+    ///
+    /// # Safety
+    ///
+    /// the file must not be modified or truncated, by this or any other
+    /// process, while the mapping is alive; changes may show through the
+    /// mapping, and reading past the end of a truncated file raises SIGBUS
+    pub unsafe fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len() as usize;
+        // zero-length mappings are invalid, so leave empty files unmapped
+        if len == 0 {
+            return Ok(Self {
+                ptr: ptr::null_mut(),
+                len,
+            });
+        }
+        // SAFETY: the mapping is private and read-only, and is unmapped in drop;
+        // the file descriptor can be closed once the mapping exists
+        let ptr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(anyhow!(
+                "failed to map {}: {}",
+                path.to_string_lossy(),
+                std::io::Error::last_os_error()
+            ));
+        }
+        Ok(Self { ptr, len })
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        if self.len == 0 {
+            return &[];
+        }
+        // SAFETY: the mapping is valid for len bytes for the lifetime of self,
+        // and the file is not changed while mapped, as required by open
+        unsafe { slice::from_raw_parts(self.ptr as *const u8, self.len) }
+    }
+
+    pub fn as_str(&self) -> Result<&str> {
+        Ok(std::str::from_utf8(self.as_bytes())?)
+    }
+}
+
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        if self.len > 0 {
+            // SAFETY: ptr and len are those returned by the successful mmap
+            unsafe {
+                libc::munmap(self.ptr, self.len);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_file() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("input")
+            .join("D1.dbg.txt");
+        // SAFETY: the sample input is not modified by the tests
+        let mmap = unsafe { Mmap::open(&path) }.unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(mmap.as_str().unwrap(), contents);
+    }
+
+    #[test]
+    fn map_missing_file() {
+        assert!(unsafe { Mmap::open(Path::new("/nonexistent/D1.txt")) }.is_err());
+    }
+}
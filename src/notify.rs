@@ -0,0 +1,96 @@
+/*
+** src/notify.rs
+*/
+
+use crate::client;
+use crate::json::Json;
+
+use anyhow::Result;
+
+/// summary of a run, posted to a webhook once it completes
+pub struct Summary {
+    pub command: String,
+    pub days: usize,
+    pub answers: usize,
+    pub seconds: f64,
+    pub failures: Vec<String>,
+}
+
+impl Summary {
+    pub fn new(command: &str) -> Self {
+        Self {
+            command: command.to_string(),
+            days: 0,
+            answers: 0,
+            seconds: 0.0,
+            failures: Vec::new(),
+        }
+    }
+
+    /// a compact, single message describing the run
+    pub fn message(&self) -> String {
+        let status = if self.failures.is_empty() {
+            "ok"
+        } else {
+            "FAILED"
+        };
+        let mut message = format!(
+            "aoc2022 {} {}: {} days, {} answers in {:.3}s",
+            self.command, status, self.days, self.answers, self.seconds
+        );
+        for failure in self.failures.iter() {
+            message.push_str(&format!("\n- {}", failure));
+        }
+        message
+    }
+}
+
+/// builds the webhook payload; Discord expects the message under "content"
+/// while Slack and most others expect it under "text"
+pub fn payload(url: &str, message: &str) -> String {
+    let key = if url.contains("discord") {
+        "content"
+    } else {
+        "text"
+    };
+    Json::object([(key, Json::from(message))]).to_string()
+}
+
+/// posts the summary to the webhook
+pub fn send(url: &str, summary: &Summary) -> Result<()> {
+    client::post_json(url, &payload(url, &summary.message()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_message() {
+        let mut summary = Summary::new("run");
+        summary.days = 16;
+        summary.answers = 32;
+        summary.seconds = 29.41234;
+        assert_eq!(
+            summary.message(),
+            "aoc2022 run ok: 16 days, 32 answers in 29.412s"
+        );
+        summary.failures.push(String::from("day 3: no solution"));
+        assert_eq!(
+            summary.message(),
+            "aoc2022 run FAILED: 16 days, 32 answers in 29.412s\n- day 3: no solution"
+        );
+    }
+
+    #[test]
+    fn webhook_payload() {
+        assert_eq!(
+            payload("https://discord.com/api/webhooks/1/a", "hi \"there\""),
+            r#"{"content":"hi \"there\""}"#
+        );
+        assert_eq!(
+            payload("https://hooks.slack.com/services/a", "hi"),
+            r#"{"text":"hi"}"#
+        );
+    }
+}
@@ -0,0 +1,77 @@
+/*
+** src/ocr.rs
+*/
+
+/// the height of the letters drawn by the CRT-style puzzles
+pub const GLYPH_HEIGHT: usize = 6;
+/// the width of each letter, which is followed by a blank column
+pub const GLYPH_WIDTH: usize = 4;
+
+/// the letters which appear in answers, with their rows of pixels
+const FONT: [(char, [&str; GLYPH_HEIGHT]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...", "#...", ".#.#", "..#.", "..#.", "..#."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// reads the letters drawn in the pixels, given as rows of '#' and '.', or
+/// None if the pixels are not all letters
+pub fn decode(pixels: &str) -> Option<String> {
+    let rows = pixels.lines().collect::<Vec<_>>();
+    let width = rows.first()?.len();
+    if rows.len() != GLYPH_HEIGHT || rows.iter().any(|row| row.len() != width) {
+        return None;
+    }
+    (0..width)
+        .step_by(GLYPH_WIDTH + 1)
+        .map(|col| {
+            let glyph = rows
+                .iter()
+                .map(|row| row.get(col..col + GLYPH_WIDTH))
+                .collect::<Option<Vec<_>>>()?;
+            FONT.iter()
+                .find(|(_, font_glyph)| font_glyph[..] == glyph[..])
+                .map(|&(letter, _)| letter)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_letters() {
+        let pixels = [
+            "#..#.####.#....#.....##..",
+            "#..#.#....#....#....#..#.",
+            "####.###..#....#....#..#.",
+            "#..#.#....#....#....#..#.",
+            "#..#.#....#....#....#..#.",
+            "#..#.####.####.####..##..",
+        ];
+        assert_eq!(decode(&pixels.join("\n")).as_deref(), Some("HELLO"));
+        // neither a letter nor six rows
+        assert_eq!(
+            decode(&pixels.join("\n").replace("####.###", "#####.##")),
+            None
+        );
+        assert_eq!(decode(&pixels[1..].join("\n")), None);
+        assert_eq!(decode(""), None);
+    }
+}
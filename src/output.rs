@@ -0,0 +1,120 @@
+/*
+** src/output.rs
+*/
+
+use crate::history::{DayRecord, RunRecord};
+use crate::porcelain;
+use crate::puzzles;
+
+use anyhow::Result;
+
+use std::fs;
+use std::path::Path;
+
+/// the format of the answers written to a file with --output
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Porcelain,
+}
+
+impl Format {
+    /// matches the console output, so porcelain if requested, and otherwise
+    /// JSON for .json files or plain text
+    pub fn for_path(path: &Path, porcelain: bool) -> Self {
+        if porcelain {
+            Self::Porcelain
+        } else if path.extension().is_some_and(|ext| ext == "json") {
+            Self::Json
+        } else {
+            Self::Text
+        }
+    }
+}
+
+/// formats the answers of the solved days
+pub fn render(format: Format, records: &[DayRecord]) -> String {
+    match format {
+        Format::Text => records
+            .iter()
+            .map(|record| {
+                let answer = |part: &Option<String>| {
+                    part.clone().unwrap_or_else(|| String::from("no answer"))
+                };
+                format!(
+                    "{}\npart 1: {}\npart 2: {}\n",
+                    puzzles::title(record.day),
+                    answer(&record.part_1),
+                    answer(&record.part_2)
+                )
+            })
+            .collect(),
+        // include the timestamp and revision, as in the run history, so that
+        // the file says where the answers came from
+        Format::Json => format!("{}\n", RunRecord::new(records.to_vec()).to_json()),
+        Format::Porcelain => records
+            .iter()
+            .flat_map(porcelain::record_lines)
+            .map(|line| format!("{}\n", line))
+            .collect(),
+    }
+}
+
+/// writes the answers of the solved days to the file, replacing it
+pub fn write(path: &Path, format: Format, records: &[DayRecord]) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, render(format, records))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::Json;
+
+    fn records() -> Vec<DayRecord> {
+        vec![
+            DayRecord {
+                day: 1,
+                seconds: 0.0005,
+                part_1: Some(String::from("24000")),
+                part_2: Some(String::from("45000")),
+            },
+            DayRecord {
+                day: 25,
+                seconds: 0.001,
+                part_1: Some(String::from("2=-1=0")),
+                part_2: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn formats_from_path() {
+        assert_eq!(Format::for_path(Path::new("out.json"), false), Format::Json);
+        assert_eq!(Format::for_path(Path::new("out.txt"), false), Format::Text);
+        assert_eq!(
+            Format::for_path(Path::new("out.json"), true),
+            Format::Porcelain
+        );
+    }
+
+    #[test]
+    fn render_formats() {
+        assert_eq!(
+            render(Format::Text, &records()),
+            "Day 1: Calorie Counting\npart 1: 24000\npart 2: 45000\n\
+             Day 25: Full of Hot Air\npart 1: 2=-1=0\npart 2: no answer\n"
+        );
+        assert_eq!(
+            render(Format::Porcelain, &records()),
+            "1\t1\t24000\t500\n1\t2\t45000\t500\n25\t1\t2=-1=0\t1000\n"
+        );
+        let json = Json::parse(&render(Format::Json, &records())).unwrap();
+        let run = RunRecord::from_json(&json).unwrap();
+        assert_eq!(run.days, records());
+    }
+}
@@ -0,0 +1,42 @@
+/*
+** src/parts.rs
+*/
+
+/// the parts of a day to solve; days skip the work of a part which is not
+/// included, leaving its answer unset
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Parts {
+    #[default]
+    Both,
+    Only(usize),
+}
+
+impl Parts {
+    /// only the given part, or both parts if None
+    pub fn new(part: Option<usize>) -> Self {
+        match part {
+            Some(part) => Self::Only(part),
+            None => Self::Both,
+        }
+    }
+
+    pub fn includes(&self, part: usize) -> bool {
+        match self {
+            Self::Both => true,
+            Self::Only(only) => *only == part,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn select_parts() {
+        assert!(!Parts::new(Some(2)).includes(1));
+        assert!(Parts::new(Some(2)).includes(2));
+        assert!(Parts::new(None).includes(1));
+        assert!(Parts::Both.includes(2));
+    }
+}
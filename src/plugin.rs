@@ -0,0 +1,531 @@
+/*
+** src/plugin.rs
+*/
+
+// watch mode solves a day with a plugin: a shared library built from that
+// day's module alone, which links against a snapshot of the rest of the crate
+// and is reloaded into the running process whenever the sources change; an
+// edit to the day only recompiles the plugin, while an edit to the shared
+// code refreshes the snapshot, which cargo then rebuilds once
+
+use crate::context::Context;
+use crate::parts::Parts;
+use crate::types::Solver;
+
+use anyhow::{anyhow, Result};
+use log::debug;
+
+use std::env;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::fs;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::ptr;
+use std::slice;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::SystemTime;
+
+const PROJECT_DIR: &str = env!("CARGO_MANIFEST_DIR");
+
+/// the answers of a day, or the reason it failed, as passed from a plugin to
+/// the process which loaded it
+#[repr(C)]
+pub struct PluginResult {
+    /// NUL-terminated answers, or NULL for parts without an answer
+    part_1: *mut c_char,
+    part_2: *mut c_char,
+    /// NUL-terminated error, or NULL if the day was solved
+    error: *mut c_char,
+}
+
+type SolveFn = unsafe extern "C" fn(*const u8, usize, bool, *mut PluginResult);
+type FreeFn = unsafe extern "C" fn(*mut PluginResult);
+
+fn into_c_string(s: Option<String>) -> *mut c_char {
+    // answers and errors do not contain NUL, but are dropped if they do
+    s.and_then(|s| CString::new(s).ok())
+        .map_or(ptr::null_mut(), CString::into_raw)
+}
+
+fn from_c_string(s: *mut c_char) -> Option<String> {
+    // SAFETY: non-NULL strings of a result are NUL-terminated
+    (!s.is_null()).then(|| unsafe { CStr::from_ptr(s) }.to_string_lossy().into_owned())
+}
+
+/// solves the input with the day, for the functions exported by a plugin;
+/// sample is whether the input is a sample input, see Context
+///
+/// # Safety
+///
+/// input must point to len readable bytes, and out to a writable result,
+/// which must later be released with free
+pub unsafe fn solve(
+    solver: Solver,
+    input: *const u8,
+    len: usize,
+    sample: bool,
+    out: *mut PluginResult,
+) {
+    let input = if len == 0 {
+        Ok("")
+    } else {
+        std::str::from_utf8(slice::from_raw_parts(input, len))
+    };
+    let (part_1, part_2, error) = match input {
+        // panics must not unwind into the process which loaded the plugin
+        Ok(input) => match panic::catch_unwind(|| {
+            solver(input, Parts::Both, &Context::none().with_sample(sample))
+        }) {
+            Ok(Ok(solution)) => (
+                solution.part_1.map(|a| a.to_string()),
+                solution.part_2.map(|a| a.to_string()),
+                None,
+            ),
+            Ok(Err(e)) => (None, None, Some(e.to_string())),
+            Err(_) => (None, None, Some(String::from("panicked"))),
+        },
+        Err(_) => (None, None, Some(String::from("input is not UTF-8"))),
+    };
+    *out = PluginResult {
+        part_1: into_c_string(part_1),
+        part_2: into_c_string(part_2),
+        error: into_c_string(error),
+    };
+}
+
+/// releases a result filled in by solve
+///
+/// # Safety
+///
+/// result must have been filled in by solve, and must not be freed twice
+pub unsafe fn free(result: *mut PluginResult) {
+    let result = &mut *result;
+    for s in [&mut result.part_1, &mut result.part_2, &mut result.error] {
+        if !s.is_null() {
+            drop(CString::from_raw(*s));
+            *s = ptr::null_mut();
+        }
+    }
+}
+
+/// the directory which the snapshot of the crate and the plugins are built in
+fn plugin_dir() -> PathBuf {
+    Path::new(PROJECT_DIR).join("target").join("plugin")
+}
+
+/// the module of the day, which the plugin is built from
+fn day_source(day: usize) -> PathBuf {
+    Path::new(PROJECT_DIR)
+        .join("src")
+        .join("puzzles")
+        .join(format!("day_{}.rs", day))
+}
+
+/// the latest modification of the sources, for watching them for changes
+pub fn last_modified() -> Option<SystemTime> {
+    fn latest(dir: &Path) -> Option<SystemTime> {
+        fs::read_dir(dir)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                if metadata.is_dir() {
+                    latest(&entry.path())
+                } else {
+                    metadata.modified().ok()
+                }
+            })
+            .max()
+    }
+    let manifest = fs::metadata(Path::new(PROJECT_DIR).join("Cargo.toml"))
+        .and_then(|metadata| metadata.modified())
+        .ok();
+    latest(&Path::new(PROJECT_DIR).join("src")).max(manifest)
+}
+
+/// writes the file unless it already has the contents, so that cargo only
+/// sees the files which changed as modified
+fn write_if_changed(path: &Path, contents: &[u8]) -> Result<()> {
+    if fs::read(path).ok().as_deref() == Some(contents) {
+        return Ok(());
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+/// copies the changed files of the source directory to the snapshot, and
+/// removes those which no longer exist; the file which is skipped is only
+/// copied once, when the snapshot is first made
+fn sync_dir(from: &Path, to: &Path, skip: &Path) -> Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let (from, to) = (entry.path(), to.join(entry.file_name()));
+        if entry.file_type()?.is_dir() {
+            sync_dir(&from, &to, skip)?;
+        } else if from != skip || !to.exists() {
+            write_if_changed(&to, &fs::read(&from)?)?;
+        }
+    }
+    for entry in fs::read_dir(to)? {
+        let entry = entry?;
+        if !from.join(entry.file_name()).exists() {
+            if entry.file_type()?.is_dir() {
+                fs::remove_dir_all(entry.path())?;
+            } else {
+                fs::remove_file(entry.path())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// the lines of a section of the manifest, such as [dependencies]
+fn manifest_section<'a>(manifest: &'a str, name: &str) -> Vec<&'a str> {
+    manifest
+        .lines()
+        .skip_while(|line| line.trim() != format!("[{}]", name))
+        .skip(1)
+        .take_while(|line| !line.starts_with('['))
+        .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .collect()
+}
+
+/// the manifest of the day's plugin, which has the dependencies of the crate,
+/// as the day may use them directly, and forwards the features of the crate
+fn plugin_manifest(day: usize, manifest: &str) -> String {
+    let dependencies = manifest_section(manifest, "dependencies");
+    let features = manifest_section(manifest, "features")
+        .into_iter()
+        .filter_map(|line| line.split_once('=').map(|(name, _)| name.trim()))
+        .map(|name| format!("{} = [\"aoc2022/{}\"]", name, name))
+        .collect::<Vec<_>>();
+    format!(
+        "\
+[package]
+name = \"aoc2022-plugin-day-{day}\"
+version = \"0.1.0\"
+edition = \"2021\"
+
+[lib]
+name = \"day_{day}_plugin\"
+path = \"lib.rs\"
+crate-type = [\"cdylib\"]
+
+[dependencies]
+aoc2022 = {{ path = \"../aoc2022\" }}
+{}
+
+[features]
+{}
+
+[workspace]
+",
+        dependencies.join("\n"),
+        features.join("\n"),
+    )
+}
+
+/// the source of the day's plugin, which includes the day's module from the
+/// source tree and exports its solver
+fn plugin_lib(day: usize) -> String {
+    format!(
+        "\
+// generated by aoc2022::plugin, do not edit
+#![allow(dead_code, unused_imports)]
+
+// the paths of the day's module resolve to the snapshot of the crate
+pub use aoc2022::*;
+
+#[path = {:?}]
+mod day_{day};
+
+#[no_mangle]
+pub unsafe extern \"C\" fn aoc2022_plugin_solve(
+    input: *const u8,
+    len: usize,
+    sample: bool,
+    out: *mut aoc2022::plugin::PluginResult,
+) {{
+    let solver = aoc2022::puzzles::solve::<day_{day}::Day{day}>;
+    aoc2022::plugin::solve(solver, input, len, sample, out)
+}}
+
+#[no_mangle]
+pub unsafe extern \"C\" fn aoc2022_plugin_free(result: *mut aoc2022::plugin::PluginResult) {{
+    aoc2022::plugin::free(result)
+}}
+",
+        day_source(day).to_string_lossy(),
+    )
+}
+
+/// builds the day's plugin, in the profile and with the sample feature of
+/// the running binary, and returns the path to the library
+pub fn build(day: usize) -> Result<PathBuf> {
+    let project_dir = Path::new(PROJECT_DIR);
+    let plugin_dir = plugin_dir();
+    // the snapshot of the crate, which keeps the day's module as it was when
+    // the snapshot was made, so that edits to the day leave it unchanged
+    let snapshot_dir = plugin_dir.join("aoc2022");
+    let manifest = fs::read_to_string(project_dir.join("Cargo.toml"))?;
+    write_if_changed(&snapshot_dir.join("Cargo.toml"), manifest.as_bytes())?;
+    sync_dir(
+        &project_dir.join("src"),
+        &snapshot_dir.join("src"),
+        &day_source(day),
+    )?;
+    // files included from the input directory, such as the sample answers
+    let input_link = snapshot_dir.join("input");
+    if !input_link.exists() {
+        std::os::unix::fs::symlink(project_dir.join("input"), &input_link)?;
+    }
+
+    let crate_dir = plugin_dir.join(format!("day_{}", day));
+    write_if_changed(
+        &crate_dir.join("Cargo.toml"),
+        plugin_manifest(day, &manifest).as_bytes(),
+    )?;
+    write_if_changed(&crate_dir.join("lib.rs"), plugin_lib(day).as_bytes())?;
+    // resolve the dependencies to the versions the crate is built with
+    if let Ok(lock) = fs::read(project_dir.join("Cargo.lock")) {
+        write_if_changed(&crate_dir.join("Cargo.lock"), &lock)?;
+    }
+
+    let target_dir = plugin_dir.join("target");
+    let mut command = Command::new(env::var_os("CARGO").unwrap_or_else(|| "cargo".into()));
+    command
+        .args(["build", "--lib", "--manifest-path"])
+        .arg(crate_dir.join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(&target_dir);
+    if !cfg!(debug_assertions) {
+        command.arg("--release");
+    }
+    if cfg!(feature = "sample") {
+        command.args(["--features", "sample"]);
+    }
+    debug!("building the plugin for day {}", day);
+    let status = command
+        .status()
+        .map_err(|e| anyhow!("failed to run cargo: {}", e))?;
+    if !status.success() {
+        return Err(anyhow!(
+            "failed to build the plugin for day {}: cargo {}",
+            day,
+            status
+        ));
+    }
+    let profile = if cfg!(debug_assertions) {
+        "debug"
+    } else {
+        "release"
+    };
+    Ok(target_dir.join(profile).join(format!(
+        "{}day_{}_plugin{}",
+        env::consts::DLL_PREFIX,
+        day,
+        env::consts::DLL_SUFFIX
+    )))
+}
+
+/// the last error of the dynamic loader
+fn dl_error() -> String {
+    // SAFETY: dlerror returns NULL or a NUL-terminated string, which is valid
+    // until the next call into the loader
+    unsafe {
+        let error = libc::dlerror();
+        if error.is_null() {
+            String::from("unknown error")
+        } else {
+            CStr::from_ptr(error).to_string_lossy().into_owned()
+        }
+    }
+}
+
+/// the answers of a day solved by a plugin
+#[derive(Debug, PartialEq)]
+pub struct PluginSolution {
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+}
+
+/// a loaded copy of a day's plugin, which is unloaded when dropped
+pub struct Plugin {
+    handle: *mut c_void,
+    solve: SolveFn,
+    free: FreeFn,
+    /// the copy which was loaded, removed when unloaded
+    path: PathBuf,
+}
+
+impl Plugin {
+    /// loads a copy of the library, as the loader would otherwise return the
+    /// library which was loaded before from the same path
+    pub fn load(library: &Path) -> Result<Self> {
+        static N_LOADED: AtomicUsize = AtomicUsize::new(0);
+        let n = N_LOADED.fetch_add(1, Ordering::Relaxed);
+        let path = env::temp_dir().join(format!(
+            "aoc2022-plugin-{}-{}{}",
+            std::process::id(),
+            n,
+            env::consts::DLL_SUFFIX
+        ));
+        fs::copy(library, &path)
+            .map_err(|e| anyhow!("failed to copy {}: {}", library.to_string_lossy(), e))?;
+        debug!("loading plugin {}", path.to_string_lossy());
+        let c_path = CString::new(path.to_string_lossy().as_bytes())?;
+        // SAFETY: the path is NUL-terminated
+        let handle = unsafe { libc::dlopen(c_path.as_ptr(), libc::RTLD_NOW | libc::RTLD_LOCAL) };
+        if handle.is_null() {
+            let error = dl_error();
+            let _ = fs::remove_file(&path);
+            return Err(anyhow!("failed to load the plugin: {}", error));
+        }
+        let symbol = |name: &CStr| {
+            // SAFETY: the handle was returned by dlopen, and the name is
+            // NUL-terminated
+            let symbol = unsafe { libc::dlsym(handle, name.as_ptr()) };
+            if symbol.is_null() {
+                Err(anyhow!("the plugin has no {}", name.to_string_lossy()))
+            } else {
+                Ok(symbol)
+            }
+        };
+        match (
+            symbol(c"aoc2022_plugin_solve"),
+            symbol(c"aoc2022_plugin_free"),
+        ) {
+            (Ok(solve), Ok(free)) => Ok(Self {
+                handle,
+                // SAFETY: the symbols are the functions exported by
+                // plugin_lib, which have these signatures
+                solve: unsafe { std::mem::transmute::<*mut c_void, SolveFn>(solve) },
+                free: unsafe { std::mem::transmute::<*mut c_void, FreeFn>(free) },
+                path,
+            }),
+            (Err(e), _) | (_, Err(e)) => {
+                // SAFETY: nothing was taken from the library
+                unsafe { libc::dlclose(handle) };
+                let _ = fs::remove_file(&path);
+                Err(e)
+            }
+        }
+    }
+
+    /// solves the day on the input with the loaded plugin
+    pub fn solve(&self, input: &str, sample: bool) -> Result<PluginSolution> {
+        let mut result = PluginResult {
+            part_1: ptr::null_mut(),
+            part_2: ptr::null_mut(),
+            error: ptr::null_mut(),
+        };
+        // SAFETY: the input is valid for its length, and the result is
+        // released below
+        unsafe { (self.solve)(input.as_ptr(), input.len(), sample, &mut result) };
+        let solution = PluginSolution {
+            part_1: from_c_string(result.part_1),
+            part_2: from_c_string(result.part_2),
+        };
+        let error = from_c_string(result.error);
+        // SAFETY: the result was filled in by the plugin
+        unsafe { (self.free)(&mut result) };
+        match error {
+            Some(error) => Err(anyhow!(error)),
+            None => Ok(solution),
+        }
+    }
+}
+
+impl Drop for Plugin {
+    fn drop(&mut self) {
+        debug!("unloading plugin {}", self.path.to_string_lossy());
+        // SAFETY: the handle was returned by dlopen, and no function of the
+        // library is used once the plugin is dropped
+        unsafe { libc::dlclose(self.handle) };
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Solution;
+
+    #[test]
+    fn manifest_of_plugin() {
+        let manifest = "\
+[package]
+name = \"aoc2022\"
+
+[dependencies]
+anyhow = \"1.0\"
+# a comment
+itertools = \"0.10\"
+
+[features]
+sample = []
+";
+        let plugin = plugin_manifest(7, manifest);
+        assert!(plugin.contains("name = \"day_7_plugin\""));
+        assert!(plugin.contains(
+            "[dependencies]\naoc2022 = { path = \"../aoc2022\" }\nanyhow = \"1.0\"\nitertools = \"0.10\"\n"
+        ));
+        assert!(plugin.contains("[features]\nsample = [\"aoc2022/sample\"]\n"));
+        assert!(plugin_lib(7).contains("solve::<day_7::Day7>"));
+    }
+
+    #[test]
+    fn solve_and_free() {
+        fn puzzle(input: &str, _parts: Parts, context: &Context) -> Result<Solution> {
+            if input.is_empty() {
+                return Err(anyhow!("empty input"));
+            }
+            let mut solution = Solution::new();
+            solution.set_part_1(input.len());
+            solution.set_part_2(context.sample);
+            Ok(solution)
+        }
+        let mut result = PluginResult {
+            part_1: ptr::null_mut(),
+            part_2: ptr::null_mut(),
+            error: ptr::null_mut(),
+        };
+        unsafe { solve(puzzle, "abc".as_ptr(), 3, true, &mut result) };
+        assert_eq!(from_c_string(result.part_1).as_deref(), Some("3"));
+        assert_eq!(from_c_string(result.part_2).as_deref(), Some("true"));
+        assert!(result.error.is_null());
+        unsafe { free(&mut result) };
+        assert!(result.part_1.is_null());
+        unsafe { solve(puzzle, ptr::null(), 0, false, &mut result) };
+        assert_eq!(from_c_string(result.error).as_deref(), Some("empty input"));
+        unsafe { free(&mut result) };
+        let invalid = [b'a', 0xff];
+        unsafe { solve(puzzle, invalid.as_ptr(), invalid.len(), false, &mut result) };
+        assert!(result.part_1.is_null());
+        assert_eq!(
+            from_c_string(result.error).as_deref(),
+            Some("input is not UTF-8")
+        );
+        unsafe { free(&mut result) };
+    }
+
+    #[test]
+    fn load_invalid_library() {
+        let library = env::temp_dir().join(format!("aoc2022-not-a-plugin-{}", std::process::id()));
+        fs::write(&library, "not a shared library").unwrap();
+        let result = Plugin::load(&library);
+        fs::remove_file(&library).unwrap();
+        let err = result.err().unwrap();
+        assert!(
+            err.to_string().starts_with("failed to load the plugin: "),
+            "{}",
+            err
+        );
+        assert!(Plugin::load(&library).is_err());
+    }
+}
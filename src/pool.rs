@@ -0,0 +1,159 @@
+/*
+** src/pool.rs
+*/
+
+use crate::explain;
+
+use log::warn;
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// the number of threads to use when none is given
+pub fn default_threads() -> usize {
+    thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+/// pins the current thread to the given core, modulo the number of cores
+#[cfg(target_os = "linux")]
+pub fn pin_to_core(core: usize) -> bool {
+    let core = core % default_threads();
+    // SAFETY: the CPU set is zero-initialized before use and only refers to
+    // the calling thread
+    unsafe {
+        let mut set = std::mem::zeroed::<libc::cpu_set_t>();
+        libc::CPU_ZERO(&mut set);
+        libc::CPU_SET(core, &mut set);
+        libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) == 0
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn pin_to_core(_core: usize) -> bool {
+    false
+}
+
+/// the number of threads which the work within a day, such as the rows
+/// searched by day 15, is split across
+static THREADS: AtomicUsize = AtomicUsize::new(1);
+
+pub fn set_threads(threads: usize) {
+    THREADS.store(threads.max(1), Ordering::Relaxed);
+}
+
+/// the number of threads set with --threads, which is 1 by default so that
+/// timings, such as those of the benchmarks, are reproducible
+pub fn threads() -> usize {
+    THREADS.load(Ordering::Relaxed)
+}
+
+/// whether days run their two parts on separate threads
+static PARALLEL_PARTS: AtomicBool = AtomicBool::new(false);
+
+pub fn set_parallel_parts(enabled: bool) {
+    PARALLEL_PARTS.store(enabled, Ordering::Relaxed);
+}
+
+/// runs two independent computations, such as the two parts of a day, which
+/// run on separate threads if parallel parts are enabled and otherwise run in
+/// order on the current thread
+///
+/// every day solves its parts through this, see puzzles::solve_parsed, so
+/// under --parallel-parts the parts of all days run on separate threads
+pub fn join<A, B, RA, RB>(a: A, b: B) -> (RA, RB)
+where
+    A: FnOnce() -> RA,
+    B: FnOnce() -> RB + Send,
+    RB: Send,
+{
+    if !PARALLEL_PARTS.load(Ordering::Relaxed) {
+        let ra = a();
+        return (ra, b());
+    }
+    thread::scope(|scope| {
+        let handle = scope.spawn(|| {
+            let rb = b();
+            // hand the explanation steps back to the calling thread
+            (rb, explain::take())
+        });
+        let ra = a();
+        let (rb, steps) = handle.join().unwrap();
+        for step in steps {
+            explain::step(step);
+        }
+        (ra, rb)
+    })
+}
+
+/// maps the function over the items on a pool of worker threads, which pull
+/// items in order; the results are returned in the order of the items
+///
+/// if pinning is requested then each worker is pinned to its own core
+pub fn map<T, R, F>(items: &[T], threads: usize, pin: bool, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new((0..items.len()).map(|_| None).collect::<Vec<_>>());
+    let n_workers = threads.clamp(1, items.len().max(1));
+    thread::scope(|scope| {
+        for worker in 0..n_workers {
+            let (next, results, f) = (&next, &results, &f);
+            scope.spawn(move || {
+                if pin && !pin_to_core(worker) {
+                    warn!("failed to pin worker {} to a core", worker);
+                }
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    if i >= items.len() {
+                        break;
+                    }
+                    let result = f(&items[i]);
+                    results.lock().unwrap()[i] = Some(result);
+                }
+            });
+        }
+    });
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|result| result.unwrap())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_in_order() {
+        let items = (0..100).collect::<Vec<u64>>();
+        for threads in [1, 3, 8] {
+            let output = map(&items, threads, false, |n| n * n);
+            let expected = items.iter().map(|n| n * n).collect::<Vec<_>>();
+            assert_eq!(output, expected, "{} threads", threads);
+        }
+    }
+
+    #[test]
+    fn join_parts() {
+        for parallel in [false, true] {
+            set_parallel_parts(parallel);
+            let (a, b) = join(|| 2 + 2, || "four");
+            assert_eq!((a, b), (4, "four"));
+        }
+        set_parallel_parts(false);
+    }
+
+    #[test]
+    fn map_empty() {
+        let output = map(&[] as &[u64], 4, false, |n| *n);
+        assert!(output.is_empty());
+    }
+}
@@ -0,0 +1,153 @@
+/*
+** src/porcelain.rs
+*/
+
+use crate::history::DayRecord;
+use crate::types::Solution;
+
+use anyhow::{anyhow, Result};
+
+/// escapes an answer so that it does not contain tabs or newlines
+pub fn escape(answer: &str) -> String {
+    answer
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+}
+
+/// reverses escape
+pub fn unescape(answer: &str) -> String {
+    let mut s = String::with_capacity(answer.len());
+    let mut chars = answer.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            s.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => s.push('\t'),
+            Some('n') => s.push('\n'),
+            Some(c) => s.push(c),
+            None => s.push('\\'),
+        }
+    }
+    s
+}
+
+/// formats the answered parts of the solution for --porcelain, a format
+/// which is stable for use by scripts
+///
+/// each answered part is one line of four tab-separated fields:
+///
+/// ```text
+/// day<TAB>part<TAB>answer<TAB>micros
+/// ```
+///
+/// where micros is the time taken to solve the whole day, in microseconds;
+/// parts without an answer are skipped, and answers are escaped so that they
+/// fit on one line. fields will never be removed or reordered
+pub fn lines(day: usize, solution: &Solution, seconds: f64) -> Vec<String> {
+    record_lines(&DayRecord {
+        day,
+        seconds,
+        part_1: solution.part_1.as_ref().map(|a| a.to_string()),
+        part_2: solution.part_2.as_ref().map(|a| a.to_string()),
+    })
+}
+
+/// formats the answered parts of a recorded day, as for lines
+pub fn record_lines(record: &DayRecord) -> Vec<String> {
+    let micros = (record.seconds * 1e6).round() as u64;
+    [&record.part_1, &record.part_2]
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, answer)| {
+            answer
+                .as_ref()
+                .map(|answer| format!("{}\t{}\t{}\t{}", record.day, i + 1, escape(answer), micros))
+        })
+        .collect()
+}
+
+/// parses porcelain output back into a record per day, such as to compare
+/// with the output of another build
+pub fn parse(output: &str) -> Result<Vec<DayRecord>> {
+    let mut records: Vec<DayRecord> = Vec::new();
+    for line in output.lines().filter(|line| !line.is_empty()) {
+        let invalid = || anyhow!("invalid porcelain line: {}", line);
+        let fields = line.split('\t').collect::<Vec<_>>();
+        let [day, part, answer, micros, ..] = fields[..] else {
+            return Err(invalid());
+        };
+        let day = day.parse::<usize>().map_err(|_| invalid())?;
+        let seconds = micros.parse::<u64>().map_err(|_| invalid())? as f64 / 1e6;
+        let index = match records.iter().position(|record| record.day == day) {
+            Some(index) => index,
+            None => {
+                records.push(DayRecord {
+                    day,
+                    seconds,
+                    part_1: None,
+                    part_2: None,
+                });
+                records.len() - 1
+            }
+        };
+        let answer = Some(unescape(answer));
+        match part {
+            "1" => records[index].part_1 = answer,
+            "2" => records[index].part_2 = answer,
+            _ => return Err(invalid()),
+        }
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn porcelain_lines() {
+        let solution = Solution::of(13140).and("\n##\t\\\n..");
+        assert_eq!(
+            lines(10, &solution, 0.0012345),
+            vec![
+                String::from("10\t1\t13140\t1235"),
+                String::from("10\t2\t\\n##\\t\\\\\\n..\t1235"),
+            ]
+        );
+    }
+
+    #[test]
+    fn porcelain_skips_unanswered() {
+        let solution = Solution::new().with_part_2(5);
+        assert_eq!(lines(25, &solution, 0.0), vec![String::from("25\t2\t5\t0")]);
+    }
+
+    #[test]
+    fn porcelain_round_trip() {
+        let solution = Solution::of(13140).and("\n##\t\\\n..");
+        let mut output = lines(10, &solution, 0.0012345).join("\n");
+        output.push_str("\n25\t1\t2=-1=0\t7\n");
+        assert_eq!(
+            parse(&output).unwrap(),
+            vec![
+                DayRecord {
+                    day: 10,
+                    seconds: 0.001235,
+                    part_1: Some(String::from("13140")),
+                    part_2: Some(String::from("\n##\t\\\n..")),
+                },
+                DayRecord {
+                    day: 25,
+                    seconds: 0.000007,
+                    part_1: Some(String::from("2=-1=0")),
+                    part_2: None,
+                },
+            ]
+        );
+        assert!(parse("10\t1\t13140").is_err());
+        assert!(parse("10\t3\t13140\t5").is_err());
+    }
+}
@@ -0,0 +1,17 @@
+/*
+** src/prelude.rs
+*/
+
+// the types and helpers used by most days, so that each day starts with
+// use crate::prelude::*
+
+pub use crate::artifacts::Artifact;
+pub use crate::context::Context;
+pub use crate::input::PuzzleInput;
+pub use crate::types::{
+    Answer, Coord, Direction, Error, Grid, Metadata, Point, Puzzle, Solution, SparseGrid,
+};
+pub use crate::utils::{self, GroupBy2, GroupBy3, GroupByN};
+
+pub use anyhow::{anyhow, Result};
+pub use log::debug;
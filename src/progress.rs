@@ -0,0 +1,137 @@
+/*
+** src/progress.rs
+*/
+
+use log::debug;
+
+use std::io::{self, Write};
+use std::sync::Arc;
+
+/// receives the label of a computation, the number of its steps completed,
+/// and its total number of steps
+type Sink = dyn Fn(&str, usize, usize) + Send + Sync;
+
+const BAR_WIDTH: usize = 30;
+
+/// a handle through which long-running days report the progress of their
+/// slowest computations, such as the rows searched by day 15, to the runner
+#[derive(Clone)]
+pub struct Progress(Option<Arc<Sink>>);
+
+impl Progress {
+    pub fn new<F>(sink: F) -> Self
+    where
+        F: Fn(&str, usize, usize) + Send + Sync + 'static,
+    {
+        Self(Some(Arc::new(sink)))
+    }
+
+    /// discards the progress, for benchmarks and tests
+    pub fn none() -> Self {
+        Self(None)
+    }
+
+    /// logs each tenth of the steps completed at the debug level, so that long
+    /// computations show that they are alive
+    pub fn log() -> Self {
+        Self::new(|label, completed, total| {
+            if crossed(completed, total, 10) {
+                debug!("{}: {}/{} done", label, completed, total);
+            }
+        })
+    }
+
+    /// draws a progress bar on stderr, which is redrawn as each percent of the
+    /// steps completes and cleared once all of them have
+    pub fn bar() -> Self {
+        Self::new(|label, completed, total| {
+            if !crossed(completed, total, 100) {
+                return;
+            }
+            let mut stderr = io::stderr().lock();
+            let _ = if completed >= total {
+                write!(stderr, "\r\x1b[2K")
+            } else {
+                write!(stderr, "\r{}", render_bar(label, completed, total))
+            };
+            let _ = stderr.flush();
+        })
+    }
+
+    /// reports that the given number of the total steps have completed
+    pub fn report(&self, label: &str, completed: usize, total: usize) {
+        if let Some(sink) = &self.0 {
+            sink(label, completed, total);
+        }
+    }
+
+    /// a progress callback for utils::par_map_with_progress, which reports
+    /// under the given label
+    pub fn tracker<'a>(&'a self, label: &'a str) -> impl Fn(usize, usize) + Sync + 'a {
+        move |completed, total| self.report(label, completed, total)
+    }
+}
+
+/// checks whether completing a step moved the progress into the next of the
+/// given number of divisions
+fn crossed(completed: usize, total: usize, divisions: usize) -> bool {
+    total > 0 && completed * divisions / total != completed.saturating_sub(1) * divisions / total
+}
+
+/// renders a bar such as `day 16 partitions [=======>       ] 50%`
+pub fn render_bar(label: &str, completed: usize, total: usize) -> String {
+    let filled = (completed * BAR_WIDTH).checked_div(total).unwrap_or(0);
+    let bar = match filled {
+        0 => " ".repeat(BAR_WIDTH),
+        n if n >= BAR_WIDTH => "=".repeat(BAR_WIDTH),
+        n => format!("{}>{}", "=".repeat(n - 1), " ".repeat(BAR_WIDTH - n)),
+    };
+    let percent = (completed * 100).checked_div(total).unwrap_or(0);
+    format!("{} [{}] {:>3}%", label, bar, percent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Mutex;
+
+    #[test]
+    fn render_bars() {
+        assert_eq!(
+            render_bar("rows", 0, 10),
+            format!("rows [{}]   0%", " ".repeat(30))
+        );
+        assert_eq!(
+            render_bar("rows", 5, 10),
+            format!("rows [{}>{}]  50%", "=".repeat(14), " ".repeat(15))
+        );
+        assert_eq!(
+            render_bar("rows", 10, 10),
+            format!("rows [{}] 100%", "=".repeat(30))
+        );
+    }
+
+    #[test]
+    fn report_progress() {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let progress = Progress::new(move |label, completed, total| {
+            sink.lock()
+                .unwrap()
+                .push((label.to_string(), completed, total))
+        });
+        let track = progress.tracker("rows");
+        track(1, 2);
+        track(2, 2);
+        Progress::none().report("rows", 1, 2);
+        assert_eq!(
+            *reports.lock().unwrap(),
+            vec![(String::from("rows"), 1, 2), (String::from("rows"), 2, 2)]
+        );
+        assert!(crossed(1, 10, 10));
+        assert!(!crossed(1, 1000, 10));
+        assert!(crossed(100, 1000, 10));
+        assert!(!crossed(0, 0, 10));
+    }
+}
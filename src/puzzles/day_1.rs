@@ -3,30 +3,47 @@
 ** https://adventofcode.com/2022/day/1
 */
 
-use crate::types::Solution;
-use crate::utils;
+use crate::prelude::*;
 
-use anyhow::Result;
+pub struct Day1;
 
-pub fn run(input: String) -> Result<Solution> {
-    let mut solution = Solution::new();
-    // sum the calorie counts for each elf
-    let mut elf_calories = utils::split_and_parse_lines_double::<u64>(&input)
-        .iter()
-        .map(|elf| elf.iter().sum::<u64>())
-        .collect::<Vec<_>>();
-    elf_calories.sort();
-    let n_elves = elf_calories.len();
+impl Puzzle for Day1 {
+    /// the calorie counts carried by each elf, in ascending order
+    type Parsed = Vec<u64>;
 
-    // part 1: Find the Elf carrying the most Calories. How many total Calories
-    // is that Elf carrying?
-    let elf_most_cals = elf_calories[n_elves - 1];
-    solution.set_part_1(elf_most_cals);
+    const DAY: usize = 1;
+    const TITLE: &'static str = "Calorie Counting";
+    const METADATA: Metadata = Metadata {
+        algorithm: "sum and sort",
+        complexity: "O(n log n)",
+        tags: &["sorting"],
+    };
+    const SAMPLE_ANSWERS: [Option<&'static str>; 2] = [Some("24000"), Some("45000")];
+    const ANSWERS: [Option<&'static str>; 2] = [Some("75501"), Some("215594")];
 
-    // part 2: Find the top three Elves carrying the most Calories. How many
-    // Calories are those Elves carrying in total?
-    let elf_top_3_cals = elf_calories[(n_elves - 3)..n_elves].iter().sum::<u64>();
-    solution.set_part_2(elf_top_3_cals);
+    fn parse(input: PuzzleInput) -> Result<Self::Parsed> {
+        // sum the calorie counts for each elf
+        let mut elf_calories = input
+            .blocks()
+            .map(|elf| elf.numbers::<u64>().map(|calories| calories.iter().sum()))
+            .collect::<Result<Vec<u64>>>()?;
+        elf_calories.sort();
+        Ok(elf_calories)
+    }
 
-    Ok(solution)
+    fn part_1(elf_calories: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 1: Find the Elf carrying the most Calories. How many total
+        // Calories is that Elf carrying?
+        let n_elves = elf_calories.len();
+        let elf_most_cals = elf_calories[n_elves - 1];
+        Ok(elf_most_cals.into())
+    }
+
+    fn part_2(elf_calories: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 2: Find the top three Elves carrying the most Calories. How many
+        // Calories are those Elves carrying in total?
+        let n_elves = elf_calories.len();
+        let elf_top_3_cals = elf_calories[(n_elves - 3)..n_elves].iter().sum::<u64>();
+        Ok(elf_top_3_cals.into())
+    }
 }
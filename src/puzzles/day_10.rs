@@ -3,10 +3,7 @@
 ** https://adventofcode.com/2022/day/10
 */
 
-use crate::types::Solution;
-use crate::utils;
-
-use anyhow::Result;
+use crate::prelude::*;
 
 #[derive(Debug)]
 enum Instruction {
@@ -14,26 +11,24 @@ enum Instruction {
     Addx(i64),
 }
 
-impl From<&str> for Instruction {
-    fn from(s: &str) -> Self {
-        let sep = if let Some(i) = s.find(' ') {
-            i
-        } else {
-            s.len()
-        };
-        match &s[..sep] {
-            "noop" => Self::Noop,
-            "addx" => {
-                let n = s[(sep + 1)..].parse().unwrap();
-                Self::Addx(n)
+impl TryFrom<&str> for Instruction {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s.split_once(' ') {
+            None if s == "noop" => Ok(Self::Noop),
+            // values fit in an i32, so that the register cannot overflow
+            Some(("addx", n)) => {
+                let n = utils::parse_field::<i32>(n, "addx value")?;
+                Ok(Self::Addx(n.into()))
             }
-            _ => unreachable!(),
+            _ => Err(format!("expected an instruction, found {:?}", s)),
         }
     }
 }
 
 #[allow(clippy::upper_case_acronyms)]
-struct CPU {
+pub struct CPU {
     register: i64,
     cycle: u64,
     signal_strengths: Vec<i64>,
@@ -51,9 +46,15 @@ impl CPU {
         }
     }
 
+    /// the image drawn on the CRT, without the trailing newline and the
+    /// pixel drawn after the last instruction
+    fn screen(&self) -> &str {
+        &self.image[..self.image.len() - 2]
+    }
+
     fn draw_pixel(&mut self) {
         // move to the next line of the image on each 40th cycle
-        if self.cycle % 40 == 0 {
+        if self.cycle.is_multiple_of(40) {
             self.image.push('\n');
         }
         let pixel_pos = self.cycle as i64 % 40;
@@ -104,24 +105,75 @@ impl CPU {
     }
 }
 
-pub fn run(input: String) -> Result<Solution> {
-    let mut solution = Solution::new();
-    // parse instructions
-    let instructions = utils::split_lines(&input)
-        .map(Instruction::from)
-        .collect::<Vec<_>>();
-    let mut cpu = CPU::new();
-
-    // part 1: Find the signal strength during the 20th, 60th, 100th, 140th,
-    // 180th, and 220th cycles. What is the sum of these six signal strengths?
-    cpu.run_program(&instructions);
-    let signal_strength_sum = cpu.signal_strengths.iter().sum::<i64>();
-    solution.set_part_1(signal_strength_sum);
-
-    // part 2: Render the image given by your program. What eight capital
-    // letters appear on your CRT?
-    let image = "\n".to_owned() + &cpu.image[..cpu.image.len() - 2];
-    solution.set_part_2(image);
-
-    Ok(solution)
+pub struct Day10;
+
+impl Puzzle for Day10 {
+    /// the CPU after running the program, as both parts come from running
+    /// the program once
+    type Parsed = CPU;
+
+    const DAY: usize = 10;
+    const TITLE: &'static str = "Cathode-Ray Tube";
+    const METADATA: Metadata = Metadata {
+        algorithm: "cpu simulation",
+        complexity: "O(cycles)",
+        tags: &["simulation"],
+    };
+    const SAMPLE_ANSWERS: [Option<&'static str>; 2] = [
+        Some("13140"),
+        Some(concat!(
+            "\n##..##..##..##..##..##..##..##..##..##..",
+            "\n###...###...###...###...###...###...###.",
+            "\n####....####....####....####....####....",
+            "\n#####.....#####.....#####.....#####.....",
+            "\n######......######......######......####",
+            "\n#######.......#######.......#######.....",
+        )),
+    ];
+    const ANSWERS: [Option<&'static str>; 2] = [Some("14060"), Some("PAPKFKEJ")];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Parsed> {
+        // parse instructions
+        let instructions = input.parse_lines(Instruction::try_from)?;
+        let mut cpu = CPU::new();
+        cpu.run_program(&instructions);
+        Ok(cpu)
+    }
+
+    fn part_1(cpu: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 1: Find the signal strength during the 20th, 60th, 100th, 140th,
+        // 180th, and 220th cycles. What is the sum of these six signal
+        // strengths?
+        let signal_strength_sum = cpu.signal_strengths.iter().sum::<i64>();
+        Ok(signal_strength_sum.into())
+    }
+
+    fn part_2(cpu: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 2: Render the image given by your program. What eight capital
+        // letters appear on your CRT?
+        Ok(Answer::letters(cpu.screen()))
+    }
+
+    fn artifacts(cpu: &Self::Parsed, _solution: &Solution) -> Vec<Artifact> {
+        vec![Artifact::Grid {
+            name: "crt",
+            text: cpu.screen().to_string(),
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malformed_instructions() {
+        let e = Day10::parse(PuzzleInput::new(10, "noop\naddx 3\naddx")).err().unwrap();
+        assert_eq!(
+            e.to_string(),
+            "day 10 input, line 3: expected an instruction, found \"addx\""
+        );
+        let e = Day10::parse(PuzzleInput::new(10, "addx x")).err().unwrap();
+        assert!(e.to_string().starts_with("day 10 input, line 1: invalid addx value \"x\""));
+    }
 }
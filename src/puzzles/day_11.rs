@@ -3,29 +3,30 @@
 ** https://adventofcode.com/2022/day/11
 */
 
-use crate::types::Solution;
-use crate::utils;
+use crate::prelude::*;
+use crate::utils::math;
 
-use anyhow::Result;
-
-#[cfg(feature = "sample")]
-const N_MONKEYS: usize = 4;
-#[cfg(not(feature = "sample"))]
-const N_MONKEYS: usize = 8;
+use std::fmt;
+use std::str::FromStr;
 
 const N_ROUNDS_1: usize = 20;
 const N_ROUNDS_2: usize = 10000;
 
-// constants used for parsing monkey specifications
+// the fields of each monkey's spec, in order, one per line
 const LINES_PER_MONKEY: usize = 6;
-const STARTING_ITEMS_PFIX: usize = 18;
-const OPERATION_PFIX: usize = 23;
-const TEST_PFIX: usize = 21;
-const MONKEY_IF_TRUE_PFIX: usize = 29;
-const MONKEY_IF_FALSE_PFIX: usize = 30;
+const FIELDS: [&str; LINES_PER_MONKEY] = [
+    "Monkey ",
+    "Starting items:",
+    "Operation: new = old ",
+    "Test: divisible by ",
+    "If true: throw to monkey ",
+    "If false: throw to monkey ",
+];
 
-type Operation = Box<dyn Fn(u64) -> u64>;
+/// the new worry level, or None if it overflows
+type Operation = Box<dyn Fn(u64) -> Option<u64> + Send + Sync>;
 
+#[derive(Clone)]
 struct Item {
     monkey: usize,
     item: u64,
@@ -37,65 +38,150 @@ impl Item {
     }
 }
 
-fn parse_items(lines: &[&str]) -> Vec<Item> {
+/// a line of the input, along with its number for the parse errors
+type Line<'a> = (usize, &'a str);
+
+/// the lines of a single monkey's spec, one for each of FIELDS
+type Spec<'a> = [Line<'a>];
+
+fn parse_error(line: usize, reason: String) -> anyhow::Error {
+    Error::Parse {
+        day: 11,
+        line,
+        reason,
+    }
+    .into()
+}
+
+/// the part of the spec's line which follows the prefix of the field
+fn field<'a>(spec: &Spec<'a>, field: usize) -> Result<&'a str> {
+    let (line, s) = spec[field];
+    s.trim_start().strip_prefix(FIELDS[field]).ok_or_else(|| {
+        parse_error(
+            line,
+            format!("expected {:?}, found {:?}", FIELDS[field].trim_end(), s),
+        )
+    })
+}
+
+/// parses the value of the field, such as the divisor of a test
+fn parse_value<T>(spec: &Spec, index: usize, name: &str) -> Result<T>
+where
+    T: FromStr,
+    <T as FromStr>::Err: fmt::Display,
+{
+    utils::parse_field(field(spec, index)?, name).map_err(|r| parse_error(spec[index].0, r))
+}
+
+/// splits the lines into the spec of each monkey, which must be complete and
+/// in order
+fn monkey_specs<'a, 'b>(lines: &'b [Line<'a>]) -> Result<Vec<&'b Spec<'a>>> {
+    let specs = lines.chunks(LINES_PER_MONKEY).collect::<Vec<_>>();
+    for (monkey, spec) in specs.iter().enumerate() {
+        for index in 0..spec.len() {
+            field(spec, index)?;
+        }
+        if spec.len() < LINES_PER_MONKEY {
+            return Err(parse_error(
+                spec[spec.len() - 1].0,
+                format!(
+                    "truncated spec of monkey {}, expected {:?}",
+                    monkey,
+                    FIELDS[spec.len()].trim_end()
+                ),
+            ));
+        }
+        if field(spec, 0)? != format!("{}:", monkey) {
+            return Err(parse_error(
+                spec[0].0,
+                format!("expected monkey {}, found {:?}", monkey, spec[0].1),
+            ));
+        }
+    }
+    Ok(specs)
+}
+
+fn parse_items(specs: &[&Spec]) -> Result<Vec<Item>> {
     let mut items = Vec::new();
-    for (monkey, item_line) in lines.iter().skip(1).step_by(LINES_PER_MONKEY).enumerate() {
-        for item in item_line[STARTING_ITEMS_PFIX..].split(", ") {
-            items.push(Item::new(monkey, item.parse().unwrap()));
+    for (monkey, spec) in specs.iter().enumerate() {
+        let s = field(spec, 1)?.trim();
+        // a monkey may start without any items
+        if s.is_empty() {
+            continue;
+        }
+        for item in s.split(',') {
+            let item = utils::parse_field(item.trim(), "item")
+                .map_err(|r| parse_error(spec[1].0, r))?;
+            items.push(Item::new(monkey, item));
         }
     }
-    items
+    Ok(items)
 }
 
-fn parse_operation(s: &str) -> Operation {
-    let op = s.chars().next().unwrap();
-    let value = &s[2..];
-    match op {
-        '+' => {
-            let x = value.parse::<u64>().unwrap();
-            Box::new(move |n| n + x)
+fn parse_operation(spec: &Spec) -> Result<Operation> {
+    let s = field(spec, 2)?;
+    let line = spec[2].0;
+    let parse_operand =
+        |value| utils::parse_field::<u64>(value, "operand").map_err(|r| parse_error(line, r));
+    match s.split_once(' ') {
+        Some(("+", value)) => {
+            let x = parse_operand(value)?;
+            Ok(Box::new(move |n| n.checked_add(x)))
         }
-        '*' => match value {
-            "old" => Box::new(|n| n * n),
-            _ => {
-                let x = value.parse::<u64>().unwrap();
-                Box::new(move |n| n * x)
-            }
-        },
-        _ => unreachable!(),
+        Some(("*", "old")) => Ok(Box::new(|n| n.checked_mul(n))),
+        Some(("*", value)) => {
+            let x = parse_operand(value)?;
+            Ok(Box::new(move |n| n.checked_mul(x)))
+        }
+        _ => Err(parse_error(
+            line,
+            format!("expected an operation, found {:?}", s),
+        )),
     }
 }
 
-fn parse_operations(lines: &[&str]) -> Vec<Operation> {
-    lines
+fn parse_operations(specs: &[&Spec]) -> Result<Vec<Operation>> {
+    specs.iter().map(|spec| parse_operation(spec)).collect()
+}
+
+fn parse_divisors(specs: &[&Spec]) -> Result<Vec<u64>> {
+    specs
         .iter()
-        .skip(2)
-        .step_by(LINES_PER_MONKEY)
-        .map(|s| parse_operation(&s[OPERATION_PFIX..]))
+        .map(|spec| {
+            let divisor = parse_value(spec, 3, "divisor")?;
+            if divisor == 0 {
+                return Err(parse_error(spec[3].0, String::from("divisor of 0")));
+            }
+            Ok(divisor)
+        })
         .collect()
 }
 
-fn parse_divisors(lines: &[&str]) -> Vec<u64> {
-    lines
+fn parse_next_monkeys(specs: &[&Spec]) -> Result<Vec<(usize, usize)>> {
+    // each monkey throws to one of the monkeys of the specs
+    let parse_monkey = |spec: &Spec, index| {
+        let monkey = parse_value(spec, index, "monkey")?;
+        if monkey >= specs.len() {
+            return Err(parse_error(
+                spec[index].0,
+                format!("no monkey {} to throw to", monkey),
+            ));
+        }
+        Ok(monkey)
+    };
+    specs
         .iter()
-        .skip(3)
-        .step_by(LINES_PER_MONKEY)
-        .map(|s| s[TEST_PFIX..].parse().unwrap())
+        .map(|spec| Ok((parse_monkey(spec, 4)?, parse_monkey(spec, 5)?)))
         .collect()
 }
 
-fn parse_next_monkeys(lines: &[&str]) -> Vec<(usize, usize)> {
-    let monkeys_if_true = lines
-        .iter()
-        .skip(4)
-        .step_by(LINES_PER_MONKEY)
-        .map(|s| s[MONKEY_IF_TRUE_PFIX..].parse().unwrap());
-    let monkeys_if_false = lines
-        .iter()
-        .skip(5)
-        .step_by(LINES_PER_MONKEY)
-        .map(|s| s[MONKEY_IF_FALSE_PFIX..].parse().unwrap());
-    monkeys_if_true.zip(monkeys_if_false).collect()
+/// the lines of the monkey specs, without the blank lines between them
+fn spec_lines(input: &str) -> Vec<Line<'_>> {
+    utils::split_lines(input)
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .filter(|(_, line)| !line.is_empty())
+        .collect()
 }
 
 fn do_round(
@@ -105,13 +191,13 @@ fn do_round(
     next_monkey: (usize, usize),
     monkey: usize,
     inspections: &mut u64,
-) {
+) -> Result<()> {
     let (if_true, if_false) = next_monkey;
     // only consider items for the current monkey
     for item in items.iter_mut().filter(|i| i.monkey == monkey) {
         *inspections += 1;
         // the monkey modifies the worry level according to its operation
-        item.item = operation(item.item);
+        item.item = operation(item.item).ok_or(Error::NoSolution)?;
         // worry level is divided by 3 as the monkey gets bored
         item.item /= 3;
         // now apply the divisibility test and throw to another monkey
@@ -121,6 +207,7 @@ fn do_round(
             if_false
         };
     }
+    Ok(())
 }
 
 fn do_rounds(
@@ -129,12 +216,13 @@ fn do_rounds(
     divisors: &[u64],
     next_monkeys: &[(usize, usize)],
     n_rounds: usize,
-) -> u64 {
-    let mut inspections = vec![0; N_MONKEYS];
+) -> Result<u64> {
+    let n_monkeys = operations.len();
+    let mut inspections = vec![0; n_monkeys];
 
     // run all rounds, for each monkey
     for _ in 0..n_rounds {
-        for monkey in 0..N_MONKEYS {
+        for monkey in 0..n_monkeys {
             do_round(
                 items,
                 &operations[monkey],
@@ -142,13 +230,13 @@ fn do_rounds(
                 next_monkeys[monkey],
                 monkey,
                 &mut inspections[monkey],
-            );
+            )?;
         }
     }
 
     // calculate and return the monkey business
     inspections.sort();
-    inspections[N_MONKEYS - 1] * inspections[N_MONKEYS - 2]
+    Ok(inspections[n_monkeys - 1] * inspections[n_monkeys - 2])
 }
 
 fn do_round_extra_worry(
@@ -159,13 +247,13 @@ fn do_round_extra_worry(
     reduction: u64,
     monkey: usize,
     inspections: &mut u64,
-) {
+) -> Result<()> {
     let (if_true, if_false) = next_monkey;
     // only consider items for the current monkey
     for item in items.iter_mut().filter(|i| i.monkey == monkey) {
         *inspections += 1;
         // the monkey modifies the worry level according to its operation
-        item.item = operation(item.item);
+        item.item = operation(item.item).ok_or(Error::NoSolution)?;
         // we can apply the reduction here, see below for details
         item.item %= reduction;
         // now apply the divisibility test and throw to another monkey
@@ -175,6 +263,7 @@ fn do_round_extra_worry(
             if_false
         };
     }
+    Ok(())
 }
 
 fn do_rounds_extra_worry(
@@ -184,12 +273,13 @@ fn do_rounds_extra_worry(
     next_monkeys: &[(usize, usize)],
     reduction: u64,
     n_rounds: usize,
-) -> u64 {
-    let mut inspections = vec![0; N_MONKEYS];
+) -> Result<u64> {
+    let n_monkeys = operations.len();
+    let mut inspections = vec![0; n_monkeys];
 
     // run all rounds, for each monkey
     for _ in 0..n_rounds {
-        for monkey in 0..N_MONKEYS {
+        for monkey in 0..n_monkeys {
             do_round_extra_worry(
                 items,
                 &operations[monkey],
@@ -198,58 +288,182 @@ fn do_rounds_extra_worry(
                 reduction,
                 monkey,
                 &mut inspections[monkey],
-            );
+            )?;
         }
     }
 
     // calculate and return the monkey business
     inspections.sort();
-    inspections[N_MONKEYS - 1] * inspections[N_MONKEYS - 2]
-}
-
-pub fn run(input: String) -> Result<Solution> {
-    let mut solution = Solution::new();
-    // parse the monkeys
-    let lines = utils::split_lines(&input)
-        .filter(|l| !l.is_empty())
-        .collect::<Vec<_>>();
-    let mut items_a = parse_items(&lines);
-    let mut items_b = parse_items(&lines);
-    let operations = parse_operations(&lines);
-    let divisors = parse_divisors(&lines);
-    let next_monkeys = parse_next_monkeys(&lines);
-
-    // part 1: Figure out which monkeys to chase by counting how many items
-    // they inspect over 20 rounds. What is the level of monkey business after
-    // 20 rounds of stuff-slinging simian shenanigans?
-    let monkey_business = do_rounds(
-        &mut items_a,
-        &operations,
-        &divisors,
-        &next_monkeys,
-        N_ROUNDS_1,
-    );
-    solution.set_part_1(monkey_business);
-
-    // part 2: Worry levels are no longer divided by three after each item is
-    // inspected; you'll need to find another way to keep your worry levels
-    // manageable. Starting again from the initial state in your puzzle input,
-    // what is the level of monkey business after 10000 rounds?
-    // had to do quite a bit of Googling to figure this out...
-    // to keep the worry levels manageable, the items can be reduced by taking
-    // the modulo of the product of all divisbility tests; observe that these
-    // are all prime numbers, then we can use the fact that, if A and B are
-    // prime numbers, N % A == (N % (A*B)) % A and N % B == (N % (A*B)) % B
-    let reduction = divisors.iter().product();
-    let monkey_business = do_rounds_extra_worry(
-        &mut items_b,
-        &operations,
-        &divisors,
-        &next_monkeys,
-        reduction,
-        N_ROUNDS_2,
-    );
-    solution.set_part_2(monkey_business);
-
-    Ok(solution)
+    Ok(inspections[n_monkeys - 1] * inspections[n_monkeys - 2])
+}
+
+/// the monkeys' starting items, along with how each monkey inspects and
+/// throws them
+pub struct Monkeys {
+    items: Vec<Item>,
+    operations: Vec<Operation>,
+    divisors: Vec<u64>,
+    next_monkeys: Vec<(usize, usize)>,
+}
+
+pub struct Day11;
+
+impl Puzzle for Day11 {
+    type Parsed = Monkeys;
+
+    const DAY: usize = 11;
+    const TITLE: &'static str = "Monkey in the Middle";
+    const METADATA: Metadata = Metadata {
+        algorithm: "modular simulation",
+        complexity: "O(rounds * items)",
+        tags: &["simulation", "math"],
+    };
+    const SAMPLE_ANSWERS: [Option<&'static str>; 2] = [Some("10605"), Some("2713310158")];
+    const ANSWERS: [Option<&'static str>; 2] = [Some("182293"), Some("54832778815")];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Parsed> {
+        // parse the monkeys
+        let lines = spec_lines(&input);
+        let specs = monkey_specs(&lines)?;
+        // the monkey business is that of the two most active monkeys
+        if specs.len() < 2 {
+            return Err(parse_error(
+                lines.last().map_or(0, |&(line, _)| line),
+                format!("expected at least 2 monkeys, found {}", specs.len()),
+            ));
+        }
+        Ok(Monkeys {
+            items: parse_items(&specs)?,
+            operations: parse_operations(&specs)?,
+            divisors: parse_divisors(&specs)?,
+            next_monkeys: parse_next_monkeys(&specs)?,
+        })
+    }
+
+    fn part_1(monkeys: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 1: Figure out which monkeys to chase by counting how many items
+        // they inspect over 20 rounds. What is the level of monkey business
+        // after 20 rounds of stuff-slinging simian shenanigans?
+        let monkey_business = do_rounds(
+            &mut monkeys.items.clone(),
+            &monkeys.operations,
+            &monkeys.divisors,
+            &monkeys.next_monkeys,
+            N_ROUNDS_1,
+        )?;
+        Ok(monkey_business.into())
+    }
+
+    fn part_2(monkeys: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 2: Worry levels are no longer divided by three after each item
+        // is inspected; you'll need to find another way to keep your worry
+        // levels manageable. Starting again from the initial state in your
+        // puzzle input, what is the level of monkey business after 10000
+        // rounds? had to do quite a bit of Googling to figure this out... to
+        // keep the worry levels manageable, the items can be reduced by taking
+        // the modulo of the product of all divisbility tests; observe that
+        // these are all prime numbers, then we can use the fact that, if A and
+        // B are prime numbers, N % A == (N % (A*B)) % A and N % B == (N %
+        // (A*B)) % B; more generally the lcm of the divisors works for any
+        // divisors, which is their product when they are all prime
+        let reduction = monkeys
+            .divisors
+            .iter()
+            .try_fold(1, |m, &d| math::lcm(m, d))
+            .ok_or(Error::NoSolution)?;
+        let monkey_business = do_rounds_extra_worry(
+            &mut monkeys.items.clone(),
+            &monkeys.operations,
+            &monkeys.divisors,
+            &monkeys.next_monkeys,
+            reduction,
+            N_ROUNDS_2,
+        )?;
+        Ok(monkey_business.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONKEYS: &str = "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 1
+    If false: throw to monkey 1
+
+Monkey 1:
+  Starting items: 54
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 0
+    If false: throw to monkey 0";
+
+    fn parse_err(input: &str) -> String {
+        match Day11::parse(PuzzleInput::new(11, input)) {
+            Ok(_) => panic!("parsed {:?}", input),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn valid_monkeys() {
+        let monkeys = Day11::parse(PuzzleInput::new(11, MONKEYS)).unwrap();
+        assert_eq!(monkeys.items.len(), 3);
+        assert_eq!(monkeys.divisors, [23, 19]);
+        assert_eq!(monkeys.next_monkeys, [(1, 1), (0, 0)]);
+    }
+
+    #[test]
+    fn truncated_monkey() {
+        let input = MONKEYS.rsplit_once('\n').unwrap().0;
+        assert_eq!(
+            parse_err(input),
+            "day 11 input, line 12: truncated spec of monkey 1, expected \"If false: throw to monkey\""
+        );
+        let input = MONKEYS.replacen("  Test: divisible by 23\n", "", 1);
+        assert_eq!(
+            parse_err(&input),
+            "day 11 input, line 4: expected \"Test: divisible by\", found \"    If true: throw to monkey 1\""
+        );
+        let input = MONKEYS.replace("Monkey 1:", "Monkey 2:");
+        assert_eq!(
+            parse_err(&input),
+            "day 11 input, line 8: expected monkey 1, found \"Monkey 2:\""
+        );
+    }
+
+    #[test]
+    fn zero_divisor() {
+        let input = MONKEYS.replace("divisible by 19", "divisible by 0");
+        assert_eq!(parse_err(&input), "day 11 input, line 11: divisor of 0");
+    }
+
+    #[test]
+    fn throw_out_of_range() {
+        let input = MONKEYS.replace("If false: throw to monkey 0", "If false: throw to monkey 2");
+        assert_eq!(
+            parse_err(&input),
+            "day 11 input, line 13: no monkey 2 to throw to"
+        );
+    }
+
+    #[test]
+    fn malformed_items() {
+        let input = MONKEYS.replace("79, 98", "79, x");
+        assert!(parse_err(&input).starts_with("day 11 input, line 2: invalid item \"x\""));
+        let input = MONKEYS.replace("Starting items: 54", "Starting items:");
+        let monkeys = Day11::parse(PuzzleInput::new(11, &input)).unwrap();
+        assert_eq!(monkeys.items.len(), 2);
+    }
+
+    #[test]
+    fn overflowing_worry() {
+        let input = MONKEYS.replace("old * 19", "old * 18446744073709551615");
+        let monkeys = Day11::parse(PuzzleInput::new(11, &input)).unwrap();
+        let err = Day11::part_1(&monkeys, &Context::none()).unwrap_err();
+        assert!(matches!(err.downcast_ref::<Error>(), Some(Error::NoSolution)));
+    }
 }
@@ -3,258 +3,323 @@
 ** https://adventofcode.com/2022/day/12
 */
 
-use crate::types::Solution;
-use crate::utils;
-
-use anyhow::Result;
-use log::debug;
-
-use std::cmp;
-use std::collections::HashSet;
-use std::fmt;
-
-#[cfg(feature = "sample")]
-const WIDTH: usize = 8;
-#[cfg(feature = "sample")]
-const HEIGHT: usize = 5;
-#[cfg(not(feature = "sample"))]
-const WIDTH: usize = 101;
-#[cfg(not(feature = "sample"))]
-const HEIGHT: usize = 41;
-
-#[cfg(feature = "sample")]
-const BOTTOM: (usize, usize) = (0, 0);
-#[cfg(feature = "sample")]
-const TOP: (usize, usize) = (2, 5);
-#[cfg(not(feature = "sample"))]
-const BOTTOM: (usize, usize) = (20, 0);
-#[cfg(not(feature = "sample"))]
-const TOP: (usize, usize) = (20, 77);
+use crate::prelude::*;
+use crate::utils::DistanceMap;
+use crate::viz::{Animation, Canvas, Color, Visualize};
 
 const MAX_HEIGHT: i64 = 25;
 
-#[derive(Clone, Eq, Hash, PartialEq)]
-struct Coord {
-    i: usize,
-    j: usize,
+fn elevation(c: char) -> Result<i64, String> {
+    match c {
+        'S' => Ok(0),
+        'E' => Ok(MAX_HEIGHT),
+        // a is 0, z is 25
+        'a'..='z' => Ok(c as i64 - 'a' as i64),
+        _ => Err(format!("invalid elevation {:?}", c)),
+    }
 }
 
-impl Coord {
-    fn new(i: usize, j: usize) -> Self {
-        Self { i, j }
-    }
+/// the square marked with the character, such as S for the current position
+fn find_square(input: &str, marker: char) -> Result<Coord> {
+    input
+        .lines()
+        .enumerate()
+        .find_map(|(i, line)| line.find(marker).map(|j| Coord::new(i, j)))
+        .ok_or_else(|| anyhow!("day 12 input has no {} square", marker))
+}
 
-    fn up(&self) -> Option<Self> {
-        if self.i > 0 {
-            Some(Self::new(self.i - 1, self.j))
-        } else {
-            None
-        }
-    }
+fn is_reachable(heightmap: &Grid<i64>, current: &Coord, destination: &Coord) -> bool {
+    let height_curr = heightmap[*current];
+    let height_dest = heightmap[*destination];
+    height_curr - height_dest <= 1
+}
 
-    fn down(&self) -> Option<Self> {
-        if self.i < HEIGHT - 1 {
-            Some(Self::new(self.i + 1, self.j))
-        } else {
-            None
-        }
-    }
+/// the squares which can be climbed down to from the current square, as the
+/// searches go down from the top so that every square's distance is found
+fn descents(heightmap: &Grid<i64>, current: Coord) -> impl Iterator<Item = Coord> + '_ {
+    heightmap
+        .neighbors(current)
+        .filter(move |node| is_reachable(heightmap, &current, node))
+}
 
-    fn left(&self) -> Option<Self> {
-        if self.j > 0 {
-            Some(Self::new(self.i, self.j - 1))
-        } else {
-            None
-        }
+/// the distance of each square from the top, with the unreachable squares at
+/// infinity
+fn distance_grid(heightmap: &Grid<i64>, distances: DistanceMap<Coord>) -> Grid<i64> {
+    let mut grid = heightmap.map(|_| i64::MAX);
+    for (node, distance) in distances {
+        grid[node] = distance as i64;
     }
+    grid
+}
 
-    fn right(&self) -> Option<Self> {
-        if self.j < WIDTH - 1 {
-            Some(Self::new(self.i, self.j + 1))
-        } else {
-            None
+fn bfs(heightmap: &Grid<i64>, bottom: Coord, top: Coord) -> (Grid<i64>, Vec<Coord>) {
+    // every step has the same cost so the nodes are visited in order of their
+    // distance from the top
+    let tree = utils::bfs(top, |&current_node| descents(heightmap, current_node));
+    // the path is found going down, so is reversed to climb from the bottom
+    let mut path = tree.path_to(&bottom).unwrap_or_else(|| vec![bottom]);
+    path.reverse();
+    (distance_grid(heightmap, tree.distances), path)
+}
+
+fn dijkstra(heightmap: &Grid<i64>, bottom: Coord, top: Coord) -> (Grid<i64>, Vec<Coord>) {
+    let reached = utils::dijkstra(top, |&current_node| {
+        descents(heightmap, current_node).map(|node| (node, 1))
+    });
+    let distances = distance_grid(heightmap, reached);
+    let path = follow_distances(heightmap, &distances, bottom);
+    (distances, path)
+}
+
+/// follows the decreasing distances from the bottom to the top
+fn follow_distances(heightmap: &Grid<i64>, distances: &Grid<i64>, bottom: Coord) -> Vec<Coord> {
+    let mut current = bottom;
+    let mut path = vec![current];
+    while distances[current] > 0 && distances[current] != i64::MAX {
+        let distance = distances[current];
+        let next = heightmap
+            .neighbors(current)
+            .find(|c| distances[*c] == distance - 1 && is_reachable(heightmap, c, &current));
+        match next {
+            Some(next) => current = next,
+            None => break,
         }
+        path.push(current);
     }
+    path
 }
 
-impl From<(usize, usize)> for Coord {
-    fn from(c: (usize, usize)) -> Self {
-        Coord::new(c.0, c.1)
-    }
+/// a search for the distances from each square of the height-map to the top,
+/// and the shortest path from the current position, given the height-map,
+/// the current position and the top
+type Search = fn(&Grid<i64>, Coord, Coord) -> (Grid<i64>, Vec<Coord>);
+
+/// the height-map, along with the current position and the top
+pub struct Route {
+    heightmap: Grid<i64>,
+    /// the current position, marked S
+    bottom: Coord,
+    /// the location with the best signal, marked E
+    top: Coord,
 }
 
-impl fmt::Debug for Coord {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{},{}", self.i, self.j)
-    }
+/// the coordinate on the canvas, with rows going down
+fn to_point(c: &Coord) -> Point {
+    Point::new(c.col as i64, c.row as i64)
 }
 
-impl fmt::Display for Coord {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+impl Visualize for Route {
+    fn visualize(&self) -> Animation {
+        let (_, path) = bfs(&self.heightmap, self.bottom, self.top);
+        self.draw(&path)
     }
 }
 
-struct Grid {
-    grid: [[i64; WIDTH]; HEIGHT],
+/// renders the height-map and the shortest path of part 1
+pub fn visualize(input: &str) -> Result<Animation> {
+    Ok(Route::parse(PuzzleInput::new(12, input))?.visualize())
 }
 
-impl Grid {
-    fn get(&self, coord: &Coord) -> i64 {
-        self.grid[coord.i][coord.j]
+impl Route {
+    fn parse(input: PuzzleInput) -> Result<Self> {
+        let heightmap = input.grid(elevation)?;
+        let bottom = find_square(&input, 'S')?;
+        let top = find_square(&input, 'E')?;
+        Ok(Self {
+            heightmap,
+            bottom,
+            top,
+        })
     }
 
-    fn set(&mut self, coord: &Coord, value: i64) {
-        self.grid[coord.i][coord.j] = value;
+    /// the fewest steps from the current position to the top
+    fn fewest_steps_from_start(&self, search: Search) -> Result<Answer> {
+        let (distances, _) = search(&self.heightmap, self.bottom, self.top);
+        match distances[self.bottom] {
+            i64::MAX => Err(Error::NoSolution.into()),
+            steps => Ok(steps.into()),
+        }
     }
-}
 
-impl From<i64> for Grid {
-    fn from(n: i64) -> Self {
-        let grid = [[n; WIDTH]; HEIGHT];
-        Self { grid }
+    /// the fewest steps from any square with elevation a to the top
+    fn fewest_steps_from_bottom(&self, search: Search) -> Result<Answer> {
+        let (distances, _) = search(&self.heightmap, self.bottom, self.top);
+        self.heightmap
+            .iter()
+            .filter(|(_, &height)| height == 0)
+            .map(|(p, _)| distances[p])
+            .filter(|&steps| steps != i64::MAX)
+            .min()
+            .map(Answer::from)
+            .ok_or_else(|| Error::NoSolution.into())
     }
-}
 
-impl From<[[i64; WIDTH]; HEIGHT]> for Grid {
-    fn from(grid: [[i64; WIDTH]; HEIGHT]) -> Self {
-        Self { grid }
+    fn draw(&self, path: &[Coord]) -> Animation {
+        let mut canvas = Canvas::new(self.heightmap.width(), self.heightmap.height(), 8);
+        for (coord, height) in self.heightmap.iter() {
+            // shade the elevations in three bands
+            let color = match height * 3 / (MAX_HEIGHT + 1) {
+                0 => Color::Green,
+                1 => Color::Orange,
+                _ => Color::White,
+            };
+            canvas.draw_cell(&to_point(&coord), color);
+        }
+        let path = path.iter().map(to_point).collect::<Vec<_>>();
+        canvas.draw_cells(&path, Color::Blue);
+        canvas.draw_cell(&to_point(&self.bottom), Color::Red);
+        canvas.draw_cell(&to_point(&self.top), Color::Yellow);
+        Animation::from(canvas)
     }
-}
 
-fn elevation(c: char) -> i64 {
-    let base = 'a' as i64;
-    match c {
-        'S' => 0,
-        'E' => MAX_HEIGHT,
-        // a is 0, z is 25
-        _ => c as i64 - base,
+    /// the shortest path from the current position, found with the given
+    /// search, drawn over the height-map
+    fn artifacts(&self, search: Search) -> Vec<Artifact> {
+        let (_, path) = search(&self.heightmap, self.bottom, self.top);
+        vec![
+            Artifact::Path {
+                name: "shortest-path",
+                points: path.iter().map(to_point).collect(),
+            },
+            Artifact::Image {
+                name: "heightmap",
+                animation: self.draw(&path),
+            },
+        ]
     }
 }
 
-fn parse_heightmap(s: &str) -> Grid {
-    let mut heightmap = [[0; WIDTH]; HEIGHT];
-    for (i, row) in utils::split_lines(s).enumerate() {
-        for (j, c) in row.chars().enumerate() {
-            heightmap[i][j] = elevation(c);
-        }
+pub struct Day12;
+
+impl Puzzle for Day12 {
+    type Parsed = Route;
+
+    const DAY: usize = 12;
+    const TITLE: &'static str = "Hill Climbing Algorithm";
+    const METADATA: Metadata = Metadata {
+        algorithm: "breadth-first search",
+        complexity: "O(V)",
+        tags: &["graph", "grid", "search"],
+    };
+    const SAMPLE_ANSWERS: [Option<&'static str>; 2] = [Some("31"), Some("29")];
+    const ANSWERS: [Option<&'static str>; 2] = [Some("361"), Some("354")];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Parsed> {
+        Route::parse(input)
     }
-    Grid::from(heightmap)
-}
 
-fn get_unvisited_set() -> HashSet<Coord> {
-    let mut set = HashSet::new();
-    for i in 0..HEIGHT {
-        for j in 0..WIDTH {
-            set.insert(Coord::new(i, j));
-        }
+    fn part_1(route: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 1: What is the fewest steps required to move from your current
+        // position to the location that should get the best signal?
+        route.fewest_steps_from_start(bfs)
     }
-    set
-}
 
-fn search_is_done(destination: &Coord, distances: &Grid, unvisited_set: &HashSet<Coord>) -> bool {
-    // iterate until the top has been visited or the smallest tentative
-    // distance in the unvisited set is infinity
-    // also terminate if the unvisited set is empty
-    unvisited_set.is_empty()
-        || !unvisited_set.contains(destination)
-        || unvisited_set
-            .iter()
-            .map(|p| distances.get(p))
-            .min()
-            .unwrap_or(i64::MAX)
-            == i64::MAX
-}
+    fn part_2(route: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 2: What is the fewest steps required to move starting from any
+        // square with elevation a to the location that should get the best
+        // signal?
+        route.fewest_steps_from_bottom(bfs)
+    }
 
-fn is_reachable(heightmap: &Grid, current: &Coord, destination: &Coord) -> bool {
-    let height_curr = heightmap.get(current);
-    let height_dest = heightmap.get(destination);
-    height_curr - height_dest <= 1
+    fn artifacts(route: &Self::Parsed, _solution: &Solution) -> Vec<Artifact> {
+        route.artifacts(bfs)
+    }
 }
 
-fn unvisited_neighbors(
-    point: &Coord,
-    heightmap: &Grid,
-    unvisited_set: &HashSet<Coord>,
-) -> Vec<Coord> {
-    let neighbors = vec![point.up(), point.down(), point.left(), point.right()];
-    neighbors
-        .into_iter()
-        .flatten()
-        .filter(|p| is_reachable(heightmap, point, p))
-        .filter(|p| unvisited_set.contains(p))
-        .collect()
-}
+/// variant which searches with dijkstra, rather than breadth-first
+pub struct Day12Dijkstra;
+
+impl Puzzle for Day12Dijkstra {
+    type Parsed = Route;
 
-fn next_node(unvisited_set: &HashSet<Coord>, distances: &Grid) -> Option<Coord> {
-    // select the unvisited node with the smallest tentative distance
-    if let Some((point, _)) = unvisited_set
-        .iter()
-        .map(|p| (p, distances.get(p)))
-        .min_by(|(_, da), (_, db)| da.cmp(db))
-    {
-        Some(point.clone())
-    } else {
-        None
+    const DAY: usize = 12;
+    const TITLE: &'static str = Day12::TITLE;
+    const METADATA: Metadata = Metadata {
+        algorithm: "dijkstra",
+        complexity: "O(V log V)",
+        ..Day12::METADATA
+    };
+
+    fn parse(input: PuzzleInput) -> Result<Self::Parsed> {
+        Route::parse(input)
     }
-}
 
-fn dijkstra(heightmap: &Grid) -> Grid {
-    let bottom = Coord::from(BOTTOM);
-    let top = Coord::from(TOP);
-    let mut unvisited_set = get_unvisited_set();
-
-    // set all tentative distances to infinity and set the top to 0
-    let mut distances = Grid::from(i64::MAX);
-    distances.set(&top, 0);
-
-    // start with the top
-    let mut current_node = top.clone();
-    // iterate until the bottom has been visited or the smallest tentative
-    // distance in the unvisited set is infinity
-    while !search_is_done(&bottom, &distances, &unvisited_set) {
-        debug!("visiting node {}", current_node);
-        let distance = distances.get(&current_node);
-        // consider all unvisited neighbors
-        for node in unvisited_neighbors(&current_node, heightmap, &unvisited_set).iter() {
-            // calculate their tentative distance thru the current node
-            let node_distance = distances.get(node);
-            let new_distance = distance + 1;
-            distances.set(node, cmp::min(node_distance, new_distance));
-        }
-        // remove the current node from the unvisited set
-        unvisited_set.remove(&current_node);
-        // select the unvisited node with the smallest tentative distance
-        if let Some(node) = next_node(&unvisited_set, &distances) {
-            current_node = node;
-        }
+    fn part_1(route: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        route.fewest_steps_from_start(dijkstra)
+    }
+
+    fn part_2(route: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        route.fewest_steps_from_bottom(dijkstra)
     }
 
-    distances
+    fn artifacts(route: &Self::Parsed, _solution: &Solution) -> Vec<Artifact> {
+        route.artifacts(dijkstra)
+    }
 }
 
-pub fn run(input: String) -> Result<Solution> {
-    let mut solution = Solution::new();
-    // parse the height-map
-    let heightmap = parse_heightmap(&input);
-    // and calculate the distances to the top
-    let distances = dijkstra(&heightmap);
-
-    // part 1: What is the fewest steps required to move from your current
-    // position to the location that should get the best signal?
-    let bottom = Coord::from(BOTTOM);
-    let best_path_from_start = distances.get(&bottom);
-    solution.set_part_1(best_path_from_start);
-
-    // part 2: What is the fewest steps required to move starting from any
-    // square with elevation a to the location that should get the best signal?
-    let best_path_from_bottom = get_unvisited_set()
-        .into_iter()
-        .filter(|p| heightmap.get(p) == 0)
-        .map(|p| distances.get(&p))
-        .min()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_INPUT: &str = "\
+Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi";
+
+    /// the squares which can be climbed to from the current square
+    fn climbs(heightmap: &Grid<i64>, current: Coord) -> impl Iterator<Item = (Coord, u64)> + '_ {
+        heightmap
+            .neighbors(current)
+            .filter(move |next| is_reachable(heightmap, next, &current))
+            .map(|next| (next, 1))
+    }
+
+    #[test]
+    fn astar_heightmap() {
+        let route = Route::parse(PuzzleInput::new(12, SAMPLE_INPUT)).unwrap();
+        // climb from the bottom, guided by the distance left to the top
+        let top = route.top;
+        let (path, cost) = utils::astar(
+            route.bottom,
+            &top,
+            |&current| climbs(&route.heightmap, current),
+            |c| (c.row.abs_diff(top.row) + c.col.abs_diff(top.col)) as u64,
+        )
         .unwrap();
-    solution.set_part_2(best_path_from_bottom);
+        let (distances, _) = dijkstra(&route.heightmap, route.bottom, top);
+        assert_eq!(cost as i64, distances[route.bottom]);
+        assert_eq!(cost, 31);
+        assert_eq!(path.len(), 32);
+        assert_eq!((path[0], path[31]), (route.bottom, top));
+        // the breadth-first search finds a path of the same length
+        let (distances, path) = bfs(&route.heightmap, route.bottom, top);
+        assert_eq!(path.len(), 32);
+        assert_eq!(distances[route.bottom], 31);
+        // the top cannot be reached past a square which is too high
+        let route = Route::parse(PuzzleInput::new(12, "SzE")).unwrap();
+        let path = utils::astar(
+            route.bottom,
+            &route.top,
+            |&current| climbs(&route.heightmap, current),
+            |_| 0,
+        );
+        assert!(path.is_none());
+    }
 
-    Ok(solution)
+    #[test]
+    fn unreachable_top() {
+        let route = Day12::parse(PuzzleInput::new(12, "SzE")).unwrap();
+        let context = Context::none();
+        for result in [
+            Day12::part_1(&route, &context),
+            Day12::part_2(&route, &context),
+            Day12Dijkstra::part_1(&route, &context),
+            Day12Dijkstra::part_2(&route, &context),
+        ] {
+            let err = result.unwrap_err();
+            assert!(matches!(err.downcast_ref(), Some(Error::NoSolution)));
+        }
+    }
 }
@@ -3,71 +3,53 @@
 ** https://adventofcode.com/2022/day/13
 */
 
-use crate::types::Solution;
-use crate::utils::{self, GroupBy2};
-
-use anyhow::Result;
-use log::debug;
+use crate::prelude::*;
 
 use std::cmp;
 use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
-enum PacketData {
+pub enum PacketData {
     Integer(u8),
     List(Vec<PacketData>),
 }
 
 impl PacketData {
-    fn find_list_end(s: &str) -> usize {
-        let mut n_open = 0;
-        for (i, c) in s.chars().enumerate() {
-            if c == '[' {
-                n_open += 1;
-            } else if c == ']' {
-                n_open -= 1;
-                if n_open == 0 {
-                    return i;
+    /// parses a list or an integer from the start of the characters
+    fn parse_item(chars: &mut Peekable<Chars>) -> Result<Self, String> {
+        match chars.peek() {
+            Some('[') => Self::parse_list(chars),
+            Some(c) if c.is_ascii_digit() => {
+                let mut digits = String::new();
+                while let Some(c) = chars.next_if(char::is_ascii_digit) {
+                    digits.push(c);
                 }
+                Ok(Self::Integer(utils::parse_field(&digits, "integer")?))
             }
+            Some(c) => Err(format!("expected a list or an integer, found {:?}", c)),
+            None => Err(String::from("unexpected end of the packet")),
         }
-        s.len()
     }
 
-    fn parse_list(s: &str) -> Self {
+    fn parse_list(chars: &mut Peekable<Chars>) -> Result<Self, String> {
         let mut items = Vec::new();
-        // ignore the opening and closing brackets
-        let s = &s[1..(s.len() - 1)];
-        let chars = s.chars().collect::<Vec<_>>();
-
-        let mut i = 0;
-        while i < s.len() {
-            let c = chars[i];
-            if c == ',' {
-                // skip the comma separators
-                i += 1;
-            } else if c == '[' {
-                // parse a sub-list if one is found
-                let end = Self::find_list_end(&s[i..]) + i;
-                let sublist = Self::parse_list(&s[i..=end]);
-                items.push(sublist);
-                i = end + 1;
-            } else {
-                // otherwise, parse the number
-                // NOTE: these are no larger than 10
-                if i + 1 < s.len() && chars[i + 1].is_ascii_digit() {
-                    let n = s[i..(i + 2)].parse().unwrap();
-                    items.push(Self::Integer(n));
-                    i += 2;
-                } else {
-                    let n = c.to_digit(10).unwrap() as u8;
-                    items.push(Self::Integer(n));
-                    i += 1;
-                };
+        // skip the opening bracket
+        chars.next();
+        if chars.next_if_eq(&']').is_some() {
+            return Ok(Self::List(items));
+        }
+        loop {
+            items.push(Self::parse_item(chars)?);
+            // the items are separated by commas until the closing bracket
+            match chars.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(Self::List(items)),
+                Some(c) => return Err(format!("expected ',' or ']', found {:?}", c)),
+                None => return Err(String::from("unexpected end of the packet")),
             }
         }
-
-        Self::List(items)
     }
 
     fn divider_packets() -> [Self; 2] {
@@ -101,6 +83,7 @@ impl PacketData {
     }
 }
 
+#[allow(clippy::non_canonical_partial_ord_impl)]
 impl cmp::PartialOrd for PacketData {
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
         let left = &self;
@@ -187,13 +170,24 @@ impl cmp::PartialOrd for PacketData {
 
 impl cmp::Ord for PacketData {
     fn cmp(&self, other: &Self) -> cmp::Ordering {
-        self.partial_cmp(other).unwrap()
+        // packets which cannot be told apart sort next to one another
+        self.partial_cmp(other).unwrap_or(cmp::Ordering::Equal)
     }
 }
 
-impl From<&str> for PacketData {
-    fn from(s: &str) -> Self {
-        Self::parse_list(s)
+impl TryFrom<&str> for PacketData {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let mut chars = s.chars().peekable();
+        if chars.peek() != Some(&'[') {
+            return Err(format!("expected a packet, found {:?}", s));
+        }
+        let packet = Self::parse_list(&mut chars)?;
+        match chars.next() {
+            Some(c) => Err(format!("unexpected {:?} after the packet", c)),
+            None => Ok(packet),
+        }
     }
 }
 
@@ -212,72 +206,88 @@ impl fmt::Display for PacketData {
     }
 }
 
-fn parse_packets(input: &str) -> Vec<PacketData> {
+fn parse_packets(input: PuzzleInput) -> Result<Vec<PacketData>> {
     let mut packets = Vec::new();
-    for chunk in utils::split_lines_double(input) {
-        for line in chunk {
-            packets.push(PacketData::from(line));
-        }
+    for pair in input.blocks() {
+        packets.extend(pair.parse_lines(PacketData::try_from)?);
     }
-    packets
+    Ok(packets)
 }
 
-fn pair_in_order(pair: (&PacketData, &PacketData)) -> bool {
+/// whether the pair is in the right order, which is undecided, and so has no
+/// solution, if the packets cannot be told apart
+fn pair_in_order(pair: (&PacketData, &PacketData)) -> Result<bool> {
     let (left, right) = pair;
-    // lists_in_order(left, right).unwrap()
     match left.partial_cmp(right) {
-        Some(cmp::Ordering::Less) => true,
-        Some(cmp::Ordering::Greater) => false,
-        _ => unreachable!(),
+        Some(cmp::Ordering::Less) => Ok(true),
+        Some(cmp::Ordering::Greater) => Ok(false),
+        Some(cmp::Ordering::Equal) | None => Err(Error::NoSolution.into()),
     }
 }
 
-pub fn run(input: String) -> Result<Solution> {
-    let mut solution = Solution::new();
-    // parse the packets
-    let mut packets = parse_packets(&input);
-
-    // part 1: Determine which pairs of packets are already in the right order.
-    // What is the sum of the indices of those pairs?
-    let sum = packets
-        .iter()
-        .group_by_2()
-        .enumerate()
-        .filter(|(_, pair)| pair_in_order(*pair))
-        .map(|(i, _)| i + 1)
-        .sum::<usize>();
-    solution.set_part_1(sum);
-
-    // part 2: Organize all of the packets into the correct order. What is the
-    // decoder key for the distress signal?
-    let divider_packets = PacketData::divider_packets();
-    // add the additional divider packets
-    debug!(
-        "adding divider packets {} and {}",
-        divider_packets[0], divider_packets[1]
-    );
-    packets.extend_from_slice(&divider_packets);
-    // sort so that the packets are in the correct order
-    packets.sort();
-    debug!("sorted packets:");
-    for packet in packets.iter() {
-        debug!("{}", packet);
+pub struct Day13;
+
+impl Puzzle for Day13 {
+    type Parsed = Vec<PacketData>;
+
+    const DAY: usize = 13;
+    const TITLE: &'static str = "Distress Signal";
+    const METADATA: Metadata = Metadata {
+        algorithm: "recursive comparison",
+        complexity: "O(n log n)",
+        tags: &["parsing", "recursion", "sorting"],
+    };
+    const SAMPLE_ANSWERS: [Option<&'static str>; 2] = [Some("13"), Some("140")];
+    const ANSWERS: [Option<&'static str>; 2] = [Some("5003"), Some("20280")];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Parsed> {
+        // parse the packets
+        parse_packets(input)
+    }
+
+    fn part_1(packets: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 1: Determine which pairs of packets are already in the right
+        // order. What is the sum of the indices of those pairs?
+        let mut sum = 0;
+        for (i, pair) in packets.iter().group_by_2().enumerate() {
+            if pair_in_order(pair)? {
+                sum += i + 1;
+            }
+        }
+        Ok(sum.into())
+    }
+
+    fn part_2(packets: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 2: Organize all of the packets into the correct order. What is
+        // the decoder key for the distress signal?
+        let mut packets = packets.clone();
+        let divider_packets = PacketData::divider_packets();
+        // add the additional divider packets
+        debug!(
+            "adding divider packets {} and {}",
+            divider_packets[0], divider_packets[1]
+        );
+        packets.extend_from_slice(&divider_packets);
+        // sort so that the packets are in the correct order
+        packets.sort();
+        debug!("sorted packets:");
+        for packet in packets.iter() {
+            debug!("{}", packet);
+        }
+        // find where the divider packets ended up
+        let idx_a = packets
+            .iter()
+            .position(|p| p == &divider_packets[0])
+            .unwrap()
+            + 1;
+        let idx_b = packets
+            .iter()
+            .position(|p| p == &divider_packets[1])
+            .unwrap()
+            + 1;
+        let decoder_key = idx_a * idx_b;
+        Ok(decoder_key.into())
     }
-    // find where the divider packets ended up
-    let idx_a = packets
-        .iter()
-        .position(|p| p == &divider_packets[0])
-        .unwrap()
-        + 1;
-    let idx_b = packets
-        .iter()
-        .position(|p| p == &divider_packets[1])
-        .unwrap()
-        + 1;
-    let decoder_key = idx_a * idx_b;
-    solution.set_part_2(decoder_key);
-
-    Ok(solution)
 }
 
 #[cfg(test)]
@@ -287,7 +297,7 @@ mod tests {
     #[test]
     fn parse_list_flat_list() {
         let input = "[1,10,2,10]";
-        let output = PacketData::from(input);
+        let output = PacketData::try_from(input).unwrap();
         let expected = PacketData::List(vec![
             PacketData::Integer(1),
             PacketData::Integer(10),
@@ -300,12 +310,12 @@ mod tests {
     #[test]
     fn parse_list_single_item() {
         let input = "[1]";
-        let output = PacketData::from(input);
+        let output = PacketData::try_from(input).unwrap();
         let expected = PacketData::List(vec![PacketData::Integer(1)]);
         assert_eq!(output, expected);
 
         let input = "[10]";
-        let output = PacketData::from(input);
+        let output = PacketData::try_from(input).unwrap();
         let expected = PacketData::List(vec![PacketData::Integer(10)]);
         assert_eq!(output, expected);
     }
@@ -313,12 +323,12 @@ mod tests {
     #[test]
     fn parse_list_empty() {
         let input = "[]";
-        let output = PacketData::from(input);
+        let output = PacketData::try_from(input).unwrap();
         let expected = PacketData::List(Vec::new());
         assert_eq!(output, expected);
 
         let input = "[[[]]]";
-        let output = PacketData::from(input);
+        let output = PacketData::try_from(input).unwrap();
         let expected = PacketData::List(vec![PacketData::List(vec![PacketData::List(Vec::new())])]);
         assert_eq!(output, expected);
     }
@@ -326,7 +336,7 @@ mod tests {
     #[test]
     fn parse_list_sublist() {
         let input = "[[1],[2,3,4]]";
-        let output = PacketData::from(input);
+        let output = PacketData::try_from(input).unwrap();
         let expected = PacketData::List(vec![
             PacketData::List(vec![PacketData::Integer(1)]),
             PacketData::List(vec![
@@ -338,7 +348,7 @@ mod tests {
         assert_eq!(output, expected);
 
         let input = "[[4,4],4,4]";
-        let output = PacketData::from(input);
+        let output = PacketData::try_from(input).unwrap();
         let expected = PacketData::List(vec![
             PacketData::List(vec![PacketData::Integer(4), PacketData::Integer(4)]),
             PacketData::Integer(4),
@@ -347,7 +357,7 @@ mod tests {
         assert_eq!(output, expected);
 
         let input = "[1,[2,[3,[4,[5,6,7]]]],8,9]";
-        let output = PacketData::from(input);
+        let output = PacketData::try_from(input).unwrap();
         let expected = PacketData::List(vec![
             PacketData::Integer(1),
             PacketData::List(vec![
@@ -369,4 +379,36 @@ mod tests {
         ]);
         assert_eq!(output, expected);
     }
+
+    #[test]
+    fn malformed_packets() {
+        let input = "[1,1,3,1,1]\n[1,1,5,1,1]\n\n[[1],[2,3,4]]\n[[1],4";
+        let e = parse_packets(PuzzleInput::new(13, input)).err().unwrap();
+        assert_eq!(
+            e.to_string(),
+            "day 13 input, line 5: unexpected end of the packet"
+        );
+        let e = PacketData::try_from("[1,[2]]]").unwrap_err();
+        assert_eq!(e, "unexpected ']' after the packet");
+        let e = PacketData::try_from("[1;2]").unwrap_err();
+        assert_eq!(e, "expected ',' or ']', found ';'");
+        let e = PacketData::try_from("[1,,2]").unwrap_err();
+        assert_eq!(e, "expected a list or an integer, found ','");
+        let e = PacketData::try_from("[256]").unwrap_err();
+        assert_eq!(
+            e,
+            "invalid integer \"256\": number too large to fit in target type"
+        );
+        let e = PacketData::try_from("10").unwrap_err();
+        assert_eq!(e, "expected a packet, found \"10\"");
+    }
+
+    #[test]
+    fn identical_packets() {
+        let packets = parse_packets(PuzzleInput::new(13, "[1,[2]]\n[1,[2]]\n\n[[2]]\n[3]")).unwrap();
+        let err = Day13::part_1(&packets, &Context::none()).unwrap_err();
+        assert!(matches!(err.downcast_ref::<Error>(), Some(Error::NoSolution)));
+        // identical packets still sort, next to one another
+        assert_eq!(Day13::part_2(&packets, &Context::none()).unwrap(), 18);
+    }
 }
@@ -3,14 +3,8 @@
 ** https://adventofcode.com/2022/day/14
 */
 
-use crate::types::{Point, Solution};
-use crate::utils;
-
-use anyhow::Result;
-use log::debug;
-
-use std::cmp;
-use std::collections::HashMap;
+use crate::prelude::*;
+use crate::viz::{Animation, Canvas, Color, Visualize};
 
 const FLOOR_MARGIN: i64 = 256;
 
@@ -18,16 +12,20 @@ struct RockPath {
     points: Vec<Point>,
 }
 
-impl From<&str> for RockPath {
-    fn from(s: &str) -> Self {
+impl TryFrom<&str> for RockPath {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
         let mut points = Vec::new();
         for point_str in s.split(" -> ") {
-            let sep = point_str.chars().position(|c| c == ',').unwrap();
-            let x = point_str[..sep].parse().unwrap();
-            let y = point_str[(sep + 1)..].parse().unwrap();
+            let (x, y) = point_str
+                .split_once(',')
+                .ok_or_else(|| format!("expected a point x,y, found {:?}", point_str))?;
+            let x = utils::parse_field(x, "x coordinate")?;
+            let y = utils::parse_field(y, "y coordinate")?;
             points.push(Point::new(x, y));
         }
-        Self { points }
+        Ok(Self { points })
     }
 }
 
@@ -37,7 +35,7 @@ enum Material {
     Sand,
 }
 
-#[derive(PartialEq)]
+#[derive(Clone, PartialEq)]
 enum SandState {
     NotSpawned,
     Falling,
@@ -45,83 +43,48 @@ enum SandState {
     InTheVoid,
 }
 
-struct CaveState {
+#[derive(Clone)]
+pub struct CaveState {
     // maps positions in the cave to the material that occupies them
-    state: HashMap<Point, Material>,
+    state: SparseGrid<Material>,
     sand: Option<Point>,
     sand_state: SandState,
-    lowest_rock: i64,
-    leftmost_rock: i64,
-    rightmost_rock: i64,
 }
 
 impl CaveState {
     fn new() -> Self {
         Self {
-            state: HashMap::new(),
+            state: SparseGrid::new(),
             sand: None,
             sand_state: SandState::NotSpawned,
-            lowest_rock: 0,
-            leftmost_rock: 0,
-            rightmost_rock: 0,
         }
     }
 
-    fn set_lowest_rock(&mut self) {
-        let (lowest, _) = self
-            .state
-            .iter()
-            .filter(|(_, m)| m == &&Material::Rock)
-            .max_by(|(pa, _), (pb, _)| pa.y.cmp(&pb.y))
-            .unwrap();
-        self.lowest_rock = lowest.y;
-    }
-
-    fn set_leftmost_rock(&mut self) {
-        let (leftmost, _) = self
-            .state
-            .iter()
-            .filter(|(_, m)| m == &&Material::Rock)
-            .min_by(|(pa, _), (pb, _)| pa.x.cmp(&pb.x))
-            .unwrap();
-        self.leftmost_rock = leftmost.x;
-    }
-
-    fn set_rightmost_rock(&mut self) {
-        let (rightmost, _) = self
-            .state
-            .iter()
-            .filter(|(_, m)| m == &&Material::Rock)
-            .max_by(|(pa, _), (pb, _)| pa.x.cmp(&pb.x))
-            .unwrap();
-        self.rightmost_rock = rightmost.x;
+    /// the corners of the box covering the rock and the sand at rest; sand
+    /// only comes to rest above the rock, so the bottom and sides of the box
+    /// are those of the rock
+    fn bounds(&self) -> (Point, Point) {
+        self.state
+            .bounds()
+            .unwrap_or((Point::origin(), Point::origin()))
     }
 
     fn add_rock_path(&mut self, path: RockPath) {
         for i in 0..(path.points.len() - 1) {
             let pa = path.points[i];
             let pb = path.points[i + 1];
-            // check if the line is horizontal or vertical
-            if pa.x == pb.x {
-                let y0 = cmp::min(pa.y, pb.y);
-                let y1 = cmp::max(pa.y, pb.y);
-                for y in y0..=y1 {
-                    let p = Point::new(pa.x, y);
-                    self.state.insert(p, Material::Rock);
-                }
-            } else if pa.y == pb.y {
-                let x0 = cmp::min(pa.x, pb.x);
-                let x1 = cmp::max(pa.x, pb.x);
-                for x in x0..=x1 {
-                    let p = Point::new(x, pa.y);
+            // only horizontal and vertical lines are drawn, stepping from
+            // one end towards the other
+            if pa.x == pb.x || pa.y == pb.y {
+                let step = (pb - pa).signum();
+                let mut p = pa;
+                self.state.insert(p, Material::Rock);
+                while p != pb {
+                    p += step;
                     self.state.insert(p, Material::Rock);
                 }
             }
         }
-        // set the lowest/leftmost/rightmost point of rock
-        self.set_lowest_rock();
-        self.set_leftmost_rock();
-        self.set_rightmost_rock();
     }
 
     fn sand_origin() -> Point {
@@ -134,23 +97,21 @@ impl CaveState {
     }
 
     fn is_air(&self, point: &Point) -> bool {
-        !self.state.contains_key(point)
+        !self.state.contains(point)
     }
 
     fn move_sand(&mut self) {
         if let Some(point) = self.sand {
-            let below = Point::new(point.x, point.y + 1);
-            let diag_left = Point::new(point.x - 1, point.y + 1);
-            let diag_right = Point::new(point.x + 1, point.y + 1);
             // check if the sand can fall downwards 1 step, or diagonally left,
             // or diagonally right; otherwise, it will be at rest
-            if self.is_air(&below) {
-                self.sand = Some(below);
-            } else if self.is_air(&diag_left) {
-                self.sand = Some(below);
-                self.sand = Some(diag_left);
-            } else if self.is_air(&diag_right) {
-                self.sand = Some(diag_right);
+            let below = point + Direction::Down.delta();
+            let falls = [
+                below,
+                below + Direction::Left.delta(),
+                below + Direction::Right.delta(),
+            ];
+            if let Some(&next) = falls.iter().find(|p| self.is_air(p)) {
+                self.sand = Some(next);
             } else {
                 // sand has come to rest, add the particle to the final state
                 self.state.insert(point, Material::Sand);
@@ -158,7 +119,8 @@ impl CaveState {
             }
             // check if the sand has fallen into the void
             if let Some(point) = self.sand {
-                if point.y > self.lowest_rock {
+                let (_, max) = self.bounds();
+                if point.y > max.y {
                     debug!("sand has fallen into the void at {}", point);
                     self.sand_state = SandState::InTheVoid;
                 }
@@ -196,22 +158,28 @@ impl CaveState {
 
     fn sand_at_rest(&self) -> usize {
         self.state
-            .values()
-            .filter(|v| v == &&Material::Sand)
+            .iter()
+            .filter(|(_, m)| m == &&Material::Sand)
             .count()
     }
 
     fn add_floor(&mut self) {
-        let y = self.lowest_rock + 2;
-        let x0 = self.leftmost_rock - FLOOR_MARGIN;
-        let x1 = self.rightmost_rock + FLOOR_MARGIN;
+        let (min, max) = self.bounds();
+        let y = max.y + 2;
+        let x0 = min.x - FLOOR_MARGIN;
+        let x1 = max.x + FLOOR_MARGIN;
         for x in x0..=x1 {
             let p = Point::new(x, y);
             self.state.insert(p, Material::Rock);
         }
-        self.lowest_rock = y;
-        self.leftmost_rock = x0;
-        self.rightmost_rock = x1;
+    }
+
+    /// the scan of the cave as text, with rock as # and sand at rest as o
+    fn render(&self) -> String {
+        self.state.render('.', |material| match material {
+            Material::Rock => '#',
+            Material::Sand => 'o',
+        })
     }
 }
 
@@ -225,29 +193,118 @@ impl From<Vec<RockPath>> for CaveState {
     }
 }
 
-pub fn run(input: String) -> Result<Solution> {
-    let mut solution = Solution::new();
-    // parse the rock paths
-    let rock_paths = utils::split_lines(&input)
-        .map(RockPath::from)
-        .collect::<Vec<_>>();
-    // and create the cave state object
-    let mut cave_state = CaveState::from(rock_paths);
-
-    // part 1: Using your scan, simulate the falling sand. How many units of
-    // sand come to rest before sand starts flowing into the abyss below?
+impl Visualize for CaveState {
+    fn visualize(&self) -> Animation {
+        let origin = Self::sand_origin();
+        let mut canvas = Canvas::covering(self.state.points().chain([&origin]), 4);
+        for (point, material) in self.state.iter() {
+            let color = match material {
+                Material::Rock => Color::White,
+                Material::Sand => Color::Yellow,
+            };
+            canvas.draw_cell(point, color);
+        }
+        canvas.draw_cell(&origin, Color::Red);
+        Animation::from(canvas)
+    }
+}
+
+/// the cave once the sand of part 1 has come to rest
+fn rested(cave_state: &CaveState) -> CaveState {
+    let mut cave_state = cave_state.clone();
     cave_state.run_to_completion();
-    solution.set_part_1(cave_state.sand_at_rest());
+    cave_state
+}
 
-    // reset variables in between runs
-    cave_state.sand = None;
-    cave_state.sand_state = SandState::NotSpawned;
+/// renders the cave of the input once the sand of part 1 has come to rest
+pub fn visualize(input: &str) -> Result<Animation> {
+    Ok(rested(&Day14::parse(PuzzleInput::new(14, input))?).visualize())
+}
 
-    // part 2: Using your scan, simulate the falling sand until the source of
-    // the sand becomes blocked. How many units of sand come to rest?
-    cave_state.add_floor();
-    cave_state.run_to_completion();
-    solution.set_part_2(cave_state.sand_at_rest());
+pub struct Day14;
 
-    Ok(solution)
+impl Puzzle for Day14 {
+    type Parsed = CaveState;
+
+    const DAY: usize = 14;
+    const TITLE: &'static str = "Regolith Reservoir";
+    const METADATA: Metadata = Metadata {
+        algorithm: "sand simulation",
+        complexity: "O(sand * depth)",
+        tags: &["simulation", "grid"],
+    };
+    const SAMPLE_ANSWERS: [Option<&'static str>; 2] = [Some("24"), Some("93")];
+    const ANSWERS: [Option<&'static str>; 2] = [Some("862"), Some("28744")];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Parsed> {
+        // parse the rock paths
+        let rock_paths = input.parse_lines(RockPath::try_from)?;
+        // and create the cave state object
+        Ok(CaveState::from(rock_paths))
+    }
+
+    fn part_1(cave_state: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 1: Using your scan, simulate the falling sand. How many units of
+        // sand come to rest before sand starts flowing into the abyss below?
+        let mut cave_state = cave_state.clone();
+        cave_state.run_to_completion();
+        Ok(cave_state.sand_at_rest().into())
+    }
+
+    fn part_2(cave_state: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 2: Using your scan, simulate the falling sand until the source
+        // of the sand becomes blocked. How many units of sand come to rest?
+        let mut cave_state = cave_state.clone();
+        cave_state.add_floor();
+        cave_state.run_to_completion();
+        Ok(cave_state.sand_at_rest().into())
+    }
+
+    fn artifacts(cave_state: &Self::Parsed, _solution: &Solution) -> Vec<Artifact> {
+        let cave_state = rested(cave_state);
+        vec![
+            Artifact::Grid {
+                name: "scan",
+                text: cave_state.render(),
+            },
+            Artifact::Image {
+                name: "cave",
+                animation: cave_state.visualize(),
+            },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_INPUT: &str = "\
+498,4 -> 498,6 -> 496,6
+503,4 -> 502,4 -> 502,9 -> 494,9";
+
+    #[test]
+    fn render_scan() {
+        let cave_state = Day14::parse(PuzzleInput::new(14, SAMPLE_INPUT)).unwrap();
+        let scan = [
+            "....#...##",
+            "....#...#.",
+            "..###...#.",
+            "........#.",
+            "........#.",
+            "#########.",
+        ];
+        assert_eq!(cave_state.render(), scan.join("\n"));
+        let scan = [
+            "......o...",
+            ".....ooo..",
+            "....#ooo##",
+            "...o#ooo#.",
+            "..###ooo#.",
+            "....oooo#.",
+            ".o.ooooo#.",
+            "#########.",
+        ];
+        assert_eq!(rested(&cave_state).render(), scan.join("\n"));
+    }
 }
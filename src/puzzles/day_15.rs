@@ -3,25 +3,35 @@
 ** https://adventofcode.com/2022/day/15
 */
 
-use crate::types::{Error, Point, Solution};
-use crate::utils::{self, GroupBy2};
+use crate::explain;
+use crate::input_cache::{self, Cached, Encode, Reader};
+use crate::interval::{Interval, IntervalSet};
+use crate::pool;
+use crate::prelude::*;
+use crate::progress::Progress;
 
-use anyhow::Result;
-use regex::Regex;
+use itertools::Itertools;
 
 use std::cmp;
 use std::collections::HashSet;
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
 
-#[cfg(feature = "sample")]
-const TARGET_Y: i64 = 10;
-#[cfg(not(feature = "sample"))]
+/// the row checked by part 1, for the sample and the real input, which can be
+/// overridden as the target_y constant
+const SAMPLE_TARGET_Y: i64 = 10;
 const TARGET_Y: i64 = 2000000;
 
-#[cfg(feature = "sample")]
-const DISTRESS_BEACON_COORD_MAX: i64 = 20;
-#[cfg(not(feature = "sample"))]
+/// the largest coordinate of the distress beacon, for the sample and the real
+/// input, which can be overridden as the coord_max constant
+const SAMPLE_COORD_MAX: i64 = 20;
 const DISTRESS_BEACON_COORD_MAX: i64 = 4000000;
 
+/// the number of chunks the rows are split into when searching for the
+/// distress beacon
+const ROW_CHUNKS: i64 = 64;
+
 #[derive(Debug)]
 struct Sensor {
     pos: Point,
@@ -30,7 +40,7 @@ struct Sensor {
 }
 
 impl Sensor {
-    fn visible_range_of_row(&self, y: i64) -> Range {
+    fn visible_range_of_row(&self, y: i64) -> Interval {
         let max_y = if y < self.pos.y {
             self.pos.y - self.beacon_distance
         } else {
@@ -39,182 +49,310 @@ impl Sensor {
         let y_dist = (max_y - y).abs();
         let x_min = self.pos.x - y_dist;
         let x_max = self.pos.x + y_dist;
-        Range::new(x_min, x_max)
+        Interval::new(x_min, x_max)
     }
 }
 
-impl From<&str> for Sensor {
-    fn from(s: &str) -> Self {
-        let re = Regex::new(
-            r"Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)",
+impl TryFrom<&str> for Sensor {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let (sensor_x, sensor_y, beacon_x, beacon_y) = utils::captures!(
+            s,
+            r"^Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)$",
+            (i64, i64, i64, i64)
         )
-        .unwrap();
-        let matches = re.captures(s).unwrap();
-        let sensor_x = matches[1].parse().unwrap();
-        let sensor_y = matches[2].parse().unwrap();
-        let beacon_x = matches[3].parse().unwrap();
-        let beacon_y = matches[4].parse().unwrap();
+        .ok_or_else(|| format!("expected a sensor report, found {:?}", s))?;
         let pos = Point::new(sensor_x, sensor_y);
         let closest_beacon = Point::new(beacon_x, beacon_y);
         let beacon_distance = Point::manhattan_distance(pos, closest_beacon);
-        Self {
+        Ok(Self {
             pos,
             closest_beacon,
             beacon_distance,
-        }
+        })
     }
 }
 
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
-struct Range {
-    min: i64,
-    max: i64,
+fn filter_sensors_by_y_view(sensors: &[Sensor], y: i64) -> impl Iterator<Item = &Sensor> {
+    sensors
+        .iter()
+        .filter(move |s| y >= s.pos.y - s.beacon_distance && y <= s.pos.y + s.beacon_distance)
 }
 
-impl Range {
-    fn new(min: i64, max: i64) -> Self {
-        Self { min, max }
-    }
-
-    fn size(&self) -> i64 {
-        self.max - self.min
-    }
+/// the x coordinates of the row which are in range of the sensors
+fn visible_x_ranges_of_row(sensors: &[Sensor], y: i64) -> IntervalSet {
+    filter_sensors_by_y_view(sensors, y)
+        .map(|s| s.visible_range_of_row(y))
+        .collect()
+}
 
-    fn overlaps(&self, other: &Self) -> bool {
-        (other.min >= self.min && other.min <= self.max)
-            || (other.max >= self.min && other.max <= self.max)
-    }
+fn non_beacon_points_in_row(sensors: &[Sensor], beacons: &HashSet<Point>, y: i64) -> i64 {
+    let x_ranges = visible_x_ranges_of_row(sensors, y);
+    explain!(
+        "row {} is covered by {} sensors over {} ranges",
+        y,
+        filter_sensors_by_y_view(sensors, y).count(),
+        x_ranges.intervals().len()
+    );
+    // then remove any beacons from the set
+    let beacons_in_row = beacons
+        .iter()
+        .filter(|b| b.y == y && x_ranges.contains(b.x))
+        .count() as i64;
+    explain!(
+        "{} known beacons lie within the covered ranges",
+        beacons_in_row
+    );
+    x_ranges.total_length() - beacons_in_row
+}
 
-    fn try_combine(&self, other: &Self) -> (Self, Option<Self>) {
-        if self.overlaps(other) {
-            let min = cmp::min(self.min, other.min);
-            let max = cmp::max(self.max, other.max);
-            (Self::new(min, max), None)
-        } else {
-            (self.clone(), Some(other.clone()))
+fn find_distress_beacon_in_rows(
+    sensors: &[Sensor],
+    rows: RangeInclusive<i64>,
+    coord_max: i64,
+) -> Option<Point> {
+    let search_range = IntervalSet::from(Interval::new(0, coord_max));
+    // check the visible ranges of each row and search for a single point gap
+    for y in rows {
+        // the distress beacon is the only point of the search area which is
+        // not in range of any sensor
+        let x_ranges = visible_x_ranges_of_row(sensors, y);
+        let gaps = search_range.difference(&x_ranges);
+        if gaps.total_length() == 1 {
+            let x = gaps.intervals()[0].min;
+            explain!(
+                "row {} has a single gap at x={} between the ranges {}",
+                y,
+                x,
+                x_ranges.intervals().iter().join(", ")
+            );
+            return Some(Point::new(x, y));
         }
     }
+    // the distress beacon was not found
+    None
+}
 
-    fn reduction_pass(input: Vec<Self>) -> Vec<Self> {
-        let n_ranges = input.len();
-        let mut output = Vec::with_capacity(n_ranges);
-        // attempt to reduce pairs of ranges
-        // these will be sorted so they will be candidates for overlaps
-        for (range_a, range_b) in input.iter().group_by_2() {
-            let (range_a, maybe_range_b) = range_a.try_combine(range_b);
-            output.push(range_a);
-            if let Some(range_b) = maybe_range_b {
-                output.push(range_b);
+fn find_distress_beacon(sensors: &[Sensor], coord_max: i64, progress: &Progress) -> Option<Point> {
+    // split the rows into chunks which are searched in parallel; once the
+    // distress beacon has been found, the remaining chunks are skipped
+    let chunk_size = (coord_max + 1) / ROW_CHUNKS + 1;
+    let chunks = (0..=coord_max)
+        .step_by(chunk_size as usize)
+        .map(|start| start..=cmp::min(start + chunk_size - 1, coord_max))
+        .collect::<Vec<_>>();
+    let found = AtomicBool::new(false);
+    let beacons = utils::par_map_with_progress(
+        &chunks,
+        pool::threads(),
+        |rows| {
+            if found.load(Ordering::Relaxed) {
+                return None;
             }
-        }
-        // check if the input length was odd, the last range will be hanging
-        if n_ranges % 2 != 0 {
-            output.push(input[n_ranges - 1].clone());
-        }
-        output
-    }
-
-    fn reduce(ranges: Vec<Self>) -> Vec<Self> {
-        let mut output = ranges;
-        // sort the ranges to start
-        output.sort_by(|a, b| a.min.cmp(&b.min));
-
-        let mut prev_len = output.len();
-        // loop until there is a single range remaining or if the pass does not
-        // perform any further reductions
-        loop {
-            output = Self::reduction_pass(output);
-            if output.len() == 1 || output.len() == prev_len {
-                break;
+            let beacon = find_distress_beacon_in_rows(sensors, rows.clone(), coord_max);
+            if beacon.is_some() {
+                found.store(true, Ordering::Relaxed);
             }
-            prev_len = output.len();
-        }
-
-        output
-    }
+            beacon
+        },
+        progress.tracker("day 15 rows"),
+    );
+    beacons.into_iter().flatten().next()
 }
 
-fn filter_sensors_by_y_view(sensors: &[Sensor], y: i64) -> impl Iterator<Item = &Sensor> {
+fn is_out_of_sensor_range(sensors: &[Sensor], point: Point) -> bool {
     sensors
         .iter()
-        .filter(move |s| y >= s.pos.y - s.beacon_distance && y <= s.pos.y + s.beacon_distance)
+        .all(|s| Point::manhattan_distance(s.pos, point) > s.beacon_distance)
 }
 
-fn get_visible_x_range_of_row(sensors: &[Sensor], y: i64) -> Range {
-    let mut x_min = i64::MAX;
-    let mut x_max = i64::MIN;
-    // grab all sensors that can view the target row
-    for sensor in filter_sensors_by_y_view(sensors, y) {
-        let x_range = sensor.visible_range_of_row(y);
-        x_min = cmp::min(x_min, x_range.min);
-        x_max = cmp::max(x_max, x_range.max);
+fn find_distress_beacon_perimeter(
+    sensors: &[Sensor],
+    coord_max: i64,
+    progress: &Progress,
+) -> Option<Point> {
+    // the distress beacon is the only point not covered by any sensor, so it
+    // must lie just outside of the range of some sensor; walk the perimeter
+    // of each sensor's range and check each point against all sensors
+    let in_bounds = |p: &Point| p.x >= 0 && p.x <= coord_max && p.y >= 0 && p.y <= coord_max;
+    for (i, sensor) in sensors.iter().enumerate() {
+        progress.report("day 15 perimeters", i, sensors.len());
+        let d = sensor.beacon_distance + 1;
+        for i in 0..d {
+            let perimeter = [
+                Point::new(sensor.pos.x + i, sensor.pos.y - d + i),
+                Point::new(sensor.pos.x + d - i, sensor.pos.y + i),
+                Point::new(sensor.pos.x - i, sensor.pos.y + d - i),
+                Point::new(sensor.pos.x - d + i, sensor.pos.y - i),
+            ];
+            for point in perimeter.into_iter().filter(in_bounds) {
+                if is_out_of_sensor_range(sensors, point) {
+                    explain!(
+                        "{} is just outside the range of the sensor at {}, and of every other sensor",
+                        point,
+                        sensor.pos
+                    );
+                    return Some(point);
+                }
+            }
+        }
     }
-    Range::new(x_min, x_max)
+    // the distress beacon was not found
+    None
 }
 
-fn non_beacon_points_in_row(sensors: &[Sensor], beacons: &HashSet<Point>, y: i64) -> i64 {
-    // from experimentation, this is a continuous row so iterate over the
-    // sensors to find the furthest leftmost/rightmost reaches of the range
-    let x_range = get_visible_x_range_of_row(sensors, y);
-    // then remove any beacons from the set
-    let beacons_in_row = beacons
-        .iter()
-        .filter(|b| b.y == y && b.x >= x_range.min && b.x <= x_range.max)
-        .count() as i64;
-    x_range.size() - beacons_in_row + 1
+/// the parsed sensor report
+pub struct Report {
+    sensors: Vec<Sensor>,
+    beacons: HashSet<Point>,
+    /// found by part 2, for the extras
+    distress_beacon: OnceLock<Point>,
 }
 
-fn find_distress_beacon(sensors: &[Sensor]) -> Option<Point> {
-    // check the visible range of each row and search for a single point gap
-    for y in 0..=DISTRESS_BEACON_COORD_MAX {
-        // grab all sensors that can view this row
-        let row_sensors = filter_sensors_by_y_view(sensors, y).collect::<Vec<_>>();
-        // there must be at least 2 sensors that can view the row in order for
-        // it to contain the distress beacon
-        if row_sensors.len() < 2 {
-            continue;
+impl Report {
+    fn new(sensors: Vec<Sensor>) -> Self {
+        // also gather all beacons into a set
+        let beacons = sensors
+            .iter()
+            .map(|s| s.closest_beacon)
+            .collect::<HashSet<_>>();
+        Self {
+            sensors,
+            beacons,
+            distress_beacon: OnceLock::new(),
         }
-        // get the visibility ranges of the sensors across the x-axis
-        let sensor_x_ranges = row_sensors
+    }
+
+    fn tuning_frequency(
+        &self,
+        context: &Context,
+        find_beacon: fn(&[Sensor], i64, &Progress) -> Option<Point>,
+    ) -> Result<Answer> {
+        let coord_max = context.constant("coord_max", SAMPLE_COORD_MAX, DISTRESS_BEACON_COORD_MAX);
+        let distress_beacon =
+            find_beacon(&self.sensors, coord_max, &context.progress).ok_or(Error::NoSolution)?;
+        let tuning_frequency = (distress_beacon.x * 4000000) + distress_beacon.y;
+        explain!("the distress beacon is at {}", distress_beacon);
+        let _ = self.distress_beacon.set(distress_beacon);
+        Ok(tuning_frequency.into())
+    }
+}
+
+impl Encode for Report {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        let sensors = self
+            .sensors
             .iter()
-            .map(|s| s.visible_range_of_row(y))
+            .flat_map(|sensor| [sensor.pos, sensor.closest_beacon])
             .collect::<Vec<_>>();
-        // and reduce the ranges
-        let sensors_x_range = Range::reduce(sensor_x_ranges);
-        // we are looking for a single point of separation between 2 ranges
-        // if this is found, this is the distress beacon
-        if sensors_x_range.len() == 2 && sensors_x_range[1].min == sensors_x_range[0].max + 2 {
-            return Some(Point::new(sensors_x_range[0].max + 1, y));
-        }
+        sensors.encode(buf);
+    }
+
+    fn decode(reader: &mut Reader) -> Result<Self> {
+        let points = Vec::<Point>::decode(reader)?;
+        let sensors = points
+            .chunks_exact(2)
+            .map(|pair| Sensor {
+                pos: pair[0],
+                closest_beacon: pair[1],
+                beacon_distance: Point::manhattan_distance(pair[0], pair[1]),
+            })
+            .collect::<Vec<_>>();
+        Ok(Self::new(sensors))
     }
-    // the distress beacon was not found
-    None
 }
 
-pub fn run(input: String) -> Result<Solution> {
-    let mut solution = Solution::new();
-    // parse the sensors
-    let sensors = utils::split_lines(&input)
-        .map(Sensor::from)
-        .collect::<Vec<_>>();
-    // also gather all beacons into a set
-    let beacons = sensors
-        .iter()
-        .map(|s| s.closest_beacon)
-        .collect::<HashSet<_>>();
+impl Cached for Report {
+    const NAME: &'static str = "day_15";
+    const VERSION: u32 = 1;
+
+    fn parse(input: PuzzleInput) -> Result<Self> {
+        // parse the sensors
+        let sensors = input.parse_lines(Sensor::try_from)?;
+        Ok(Self::new(sensors))
+    }
+}
+
+pub struct Day15;
 
-    // part 1: Consult the report from the sensors you just deployed. In the
-    // row where y=2000000, how many positions cannot contain a beacon?
-    let points = non_beacon_points_in_row(&sensors, &beacons, TARGET_Y);
-    solution.set_part_1(points);
+impl Puzzle for Day15 {
+    type Parsed = Report;
 
-    // part 2: Find the only possible position for the distress beacon. What is
-    // its tuning frequency?
-    let distress_beacon = find_distress_beacon(&sensors).ok_or(Error::NoSolution)?;
-    let tuning_frequency = (distress_beacon.x * 4000000) + distress_beacon.y;
-    solution.set_part_2(tuning_frequency);
+    const DAY: usize = 15;
+    const TITLE: &'static str = "Beacon Exclusion Zone";
+    const METADATA: Metadata = Metadata {
+        algorithm: "interval merging",
+        complexity: "O(rows * sensors log sensors)",
+        tags: &["intervals", "geometry"],
+    };
+    const SAMPLE_ANSWERS: [Option<&'static str>; 2] = [Some("26"), Some("56000011")];
+    const ANSWERS: [Option<&'static str>; 2] = [Some("4811413"), Some("13171855019123")];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Parsed> {
+        input_cache::parse::<Report>(input)
+    }
 
-    Ok(solution)
+    fn part_1(report: &Self::Parsed, context: &Context) -> Result<Answer> {
+        // part 1: Consult the report from the sensors you just deployed. In
+        // the row where y=2000000, how many positions cannot contain a beacon?
+        let target_y = context.constant("target_y", SAMPLE_TARGET_Y, TARGET_Y);
+        let points = non_beacon_points_in_row(&report.sensors, &report.beacons, target_y);
+        Ok(points.into())
+    }
+
+    fn part_2(report: &Self::Parsed, context: &Context) -> Result<Answer> {
+        // part 2: Find the only possible position for the distress beacon.
+        // What is its tuning frequency?
+        report.tuning_frequency(context, find_distress_beacon)
+    }
+
+    fn extras(report: &Self::Parsed, solution: &Solution) -> Vec<(String, String)> {
+        report
+            .distress_beacon
+            .get()
+            .filter(|_| solution.part_2.is_some())
+            .map(|&beacon| {
+                (
+                    String::from("distress beacon"),
+                    Answer::from(beacon).to_string(),
+                )
+            })
+            .into_iter()
+            .collect()
+    }
+}
+
+/// variant which searches the perimeters of the sensor ranges for the
+/// distress beacon, rather than scanning each row
+pub struct Day15Perimeter;
+
+impl Puzzle for Day15Perimeter {
+    type Parsed = Report;
+
+    const DAY: usize = 15;
+    const TITLE: &'static str = Day15::TITLE;
+    const METADATA: Metadata = Metadata {
+        algorithm: "sensor perimeter search",
+        complexity: "O(sensors^2 * range)",
+        ..Day15::METADATA
+    };
+
+    fn parse(input: PuzzleInput) -> Result<Self::Parsed> {
+        Day15::parse(input)
+    }
+
+    fn part_1(report: &Self::Parsed, context: &Context) -> Result<Answer> {
+        Day15::part_1(report, context)
+    }
+
+    fn part_2(report: &Self::Parsed, context: &Context) -> Result<Answer> {
+        report.tuning_frequency(context, find_distress_beacon_perimeter)
+    }
+
+    fn extras(report: &Self::Parsed, solution: &Solution) -> Vec<(String, String)> {
+        Day15::extras(report, solution)
+    }
 }
 
 #[cfg(test)]
@@ -222,45 +360,13 @@ mod tests {
     use super::*;
 
     #[test]
-    fn reduce_ranges() {
-        let input = vec![
-            Range::new(1, 3),
-            Range::new(2, 4),
-            Range::new(3, 5),
-            Range::new(4, 6),
-        ];
-        let output = Range::reduce(input);
-        assert_eq!(output.len(), 1);
-        let range = &output[0];
-        assert_eq!(range.min, 1);
-        assert_eq!(range.max, 6);
-
-        let input = vec![
-            Range::new(2, 2),
-            Range::new(11, 13),
-            Range::new(3, 13),
-            Range::new(-3, 3),
-            Range::new(15, 25),
-            Range::new(15, 17),
-        ];
-        let output = Range::reduce(input);
-        assert_eq!(output.len(), 2);
-        let range_a = &output[0];
-        assert_eq!(range_a.min, -3);
-        assert_eq!(range_a.max, 13);
-        let range_b = &output[1];
-        assert_eq!(range_b.min, 15);
-        assert_eq!(range_b.max, 25);
-    }
-
-    #[test]
-    fn reduce_ranges_disjoint() {
-        let a = Range::new(1, 4);
-        let b = Range::new(10, 12);
-        let input = vec![a.clone(), b.clone()];
-        let output = Range::reduce(input);
-        assert_eq!(output.len(), 2);
-        assert_eq!(output[0], a);
-        assert_eq!(output[1], b);
+    fn malformed_report() {
+        let input = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15\n\
+                     Sensor at x=9, y=16: closest beacon is at x=10";
+        let e = Report::parse(PuzzleInput::new(15, input)).err().unwrap();
+        assert_eq!(
+            e.to_string(),
+            format!("day 15 input, line 2: expected a sensor report, found {:?}", &input[53..])
+        );
     }
 }
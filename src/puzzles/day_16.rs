@@ -3,41 +3,47 @@
 ** https://adventofcode.com/2022/day/16
 */
 
-use crate::types::Solution;
-use crate::utils;
+use crate::cancel::Cancel;
+use crate::input_cache::{self, Cached, Encode, Reader};
+use crate::pool;
+use crate::prelude::*;
+use crate::progress::Progress;
+use crate::utils::graph::Graph;
 
-use anyhow::Result;
 use itertools::Itertools;
-use log::debug;
+use log::warn;
 
-use std::cmp;
 use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::sync::OnceLock;
 
 const CHAR_BASE: u16 = 'A' as u16;
+/// AA, see Valve
+const START_VALVE: Valve = Valve(0);
 const TIME_LIMIT: u64 = 30;
 const TIME_LIMIT_WITH_ELEPHANT: u64 = 26;
 
-// there are 26 letters, this requires 5 bits per letter
-// this means we need 10 bits per valve
-// this ends up with 1024 options
-const VALVE_BUF_SIZE: usize = 1 << 10;
-// valves are connected to at most 5 other valves
-const MAX_CONNECTIONS: usize = 5;
-
 // NOTE: converted Valve to an integer-struct to avoid lifetime complications
 // valves are 2-letter string identifiers: the first letter is the upper 5 bits
 // and the second letter is the lower 5 bits
 #[derive(Clone, Copy, Eq, Hash, PartialEq)]
 struct Valve(u16);
 
-impl From<&str> for Valve {
-    fn from(s: &str) -> Self {
-        let ca = utils::nchar(s, 0);
-        let cb = utils::nchar(s, 1);
-        let a = (ca as u16) - CHAR_BASE;
-        let b = (cb as u16) - CHAR_BASE;
-        Self(((a & 0x1F) << 5) | (b & 0x1F))
+impl TryFrom<&str> for Valve {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s.as_bytes() {
+            &[ca, cb] if ca.is_ascii_uppercase() && cb.is_ascii_uppercase() => {
+                let a = (ca as u16) - CHAR_BASE;
+                let b = (cb as u16) - CHAR_BASE;
+                Ok(Self((a << 5) | b))
+            }
+            _ => Err(format!(
+                "expected a valve name of two capital letters, found {:?}",
+                s
+            )),
+        }
     }
 }
 
@@ -51,74 +57,66 @@ impl fmt::Display for Valve {
     }
 }
 
-// maps valve IDs to their flow rates
-// this array is 8 KiB so stick it on the heap
-struct FlowRates(Vec<u64>);
-
-impl FlowRates {
-    fn new() -> Self {
-        let inner = vec![u64::MAX; VALVE_BUF_SIZE];
-        Self(inner)
-    }
-
-    fn get(&self, vid: u16) -> u64 {
-        self.0[vid as usize]
-    }
-
-    fn set(&mut self, vid: u16, value: u64) {
-        self.0[vid as usize] = value;
-    }
-}
-
-// maps valve IDs to the valve IDs that they are connected to
-// this array is 10 KiB so stick it on the heap
-struct TunnelMap(Vec<Vec<u16>>);
+// valve IDs are indices into the list of valves, in the order in which they
+// appear in the input, so that the buffers below are sized to the input
+// rather than to the space of all possible valve names
+struct ValveIds(Vec<Valve>);
 
-impl TunnelMap {
-    fn new() -> Self {
-        let inner = vec![vec![u16::MAX; MAX_CONNECTIONS]; VALVE_BUF_SIZE];
-        Self(inner)
+impl ValveIds {
+    fn len(&self) -> usize {
+        self.0.len()
     }
 
-    fn set(&mut self, vid_i: u16, vid_j: u16, value: u16) {
-        self.0[vid_i as usize][vid_j as usize] = value;
+    fn id(&self, valve: Valve) -> Option<u16> {
+        self.0.iter().position(|&v| v == valve).map(|i| i as u16)
     }
 
-    fn connections(&self, vid: u16) -> impl Iterator<Item = &u16> {
-        self.0[vid as usize].iter().take_while(|&&v| v != u16::MAX)
+    fn valve(&self, vid: u16) -> Valve {
+        self.0[vid as usize]
     }
 }
 
-// 2-D array that stores the distances between pairs of valve IDs
-// this array is at least 1 MiB (depending on usize) so stick it on the heap
-#[derive(Clone)]
-struct Distances(Vec<Vec<u64>>);
+// maps valve IDs to their flow rates
+struct FlowRates(Vec<u64>);
 
-impl Distances {
-    fn new() -> Self {
-        let inner = vec![vec![u64::MAX; VALVE_BUF_SIZE]; VALVE_BUF_SIZE];
+impl FlowRates {
+    fn new(n_valves: usize) -> Self {
+        let inner = vec![0; n_valves];
         Self(inner)
     }
 
-    fn get(&self, vid_a: u16, vid_b: u16) -> u64 {
-        self.0[vid_a as usize][vid_b as usize]
+    fn get(&self, vid: u16) -> u64 {
+        self.0[vid as usize]
     }
 
-    fn set(&mut self, vid_a: u16, vid_b: u16, value: u64) {
-        self.0[vid_a as usize][vid_b as usize] = value;
+    fn set(&mut self, vid: u16, value: u64) {
+        self.0[vid as usize] = value;
     }
 }
 
-struct VolcanoInfo {
+pub struct VolcanoInfo {
+    valve_ids: ValveIds,
     flow_rates: FlowRates,
-    distances: Distances,
+    /// the length of the shortest path between each pair of valves, by ID
+    distances: Vec<Vec<u64>>,
+    start: u16,
+    /// found by each part, for the extras
+    schedules: [OnceLock<Schedule>; 2],
 }
 
 impl VolcanoInfo {
-    fn new(flow_rates: FlowRates, distances: Distances) -> Self {
+    fn new(
+        valve_ids: ValveIds,
+        flow_rates: FlowRates,
+        distances: Vec<Vec<u64>>,
+        start: u16,
+    ) -> Self {
         Self {
+            valve_ids,
             flow_rates,
             distances,
+            start,
+            schedules: [OnceLock::new(), OnceLock::new()],
         }
     }
 
@@ -127,123 +125,188 @@ impl VolcanoInfo {
     }
 
     fn distance(&self, from: u16, to: u16) -> u64 {
-        self.distances.get(from, to)
+        self.distances[from as usize][to as usize]
     }
-}
 
-fn parse_flow_rates(input: &str) -> FlowRates {
-    debug!("parsing valve flow rates");
-    let mut flow_rates = FlowRates::new();
-    for line in utils::split_lines(input) {
-        let valve = Valve::from(&line[6..8]);
-        let flow_end = utils::find_char(line, ';').unwrap();
-        let flow = line[23..flow_end].parse().unwrap();
-        flow_rates.set(valve.0, flow);
+    // valves with non-zero flow, i.e. those worth opening
+    fn flowing_valves(&self) -> impl Iterator<Item = u16> + '_ {
+        self.flow_rates
+            .0
+            .iter()
+            .enumerate()
+            .filter(|(_, &flow)| flow != 0)
+            .map(|(vid, _)| vid as u16)
     }
-    flow_rates
 }
 
-fn parse_tunnel_map(input: &str) -> TunnelMap {
-    debug!("parsing tunnel map");
-    let mut tunnel_map = TunnelMap::new();
-    for line in utils::split_lines(input) {
-        let valve = Valve::from(&line[6..8]);
-        let flow_end = utils::find_char(line, ';').unwrap();
+/// a line of the input: a valve, its flow rate and the valves its tunnels
+/// lead to
+struct ValveReport {
+    valve: Valve,
+    flow: u64,
+    tunnels: Vec<Valve>,
+}
+
+impl TryFrom<&str> for ValveReport {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let expected = || format!("expected a valve report, found {:?}", s);
+        let (valve, tunnels) = s.split_once("; ").ok_or_else(expected)?;
+        let valve = match valve.split(' ').collect::<Vec<_>>()[..] {
+            ["Valve", valve, "has", "flow", _] => Valve::try_from(valve)?,
+            _ => return Err(expected()),
+        };
+        // the flow rate is the only number in the line
+        let flow = match utils::numbers::<u64>(s)[..] {
+            [flow] => flow,
+            _ => return Err(format!("expected a flow rate, found {:?}", s)),
+        };
         // note: valve vs. valves for plural
-        let offset = if line.contains("valves") { 25 } else { 24 };
-        for (i, v) in line[(flow_end + offset)..]
+        let tunnels = tunnels
+            .strip_prefix("tunnels lead to valves ")
+            .or_else(|| tunnels.strip_prefix("tunnel leads to valve "))
+            .ok_or_else(expected)?
             .split(", ")
-            .map(Valve::from)
-            .enumerate()
-        {
-            tunnel_map.set(valve.0, i as u16, v.0);
-        }
+            .map(Valve::try_from)
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            valve,
+            flow,
+            tunnels,
+        })
     }
-    tunnel_map
 }
 
-fn add_valve_connected_nodes(
-    flow_rates: &FlowRates,
-    tunnel_map: &TunnelMap,
-    distances: &mut Distances,
-    from: u16,
-    to: u16,
-    prev: u16,
-    distance: u64,
-) {
-    // look at all connected valves
-    for &vid in tunnel_map.connections(to) {
-        // no loopbacks
-        if vid == from || vid == prev {
-            continue;
+fn parse_valve_ids(reports: &[ValveReport]) -> Result<ValveIds> {
+    debug!("parsing valve IDs");
+    let mut valves = Vec::with_capacity(reports.len());
+    for (i, report) in reports.iter().enumerate() {
+        if valves.contains(&report.valve) {
+            return Err(Error::Parse {
+                day: 16,
+                line: i + 1,
+                reason: format!("valve {} is already reported", report.valve),
+            }
+            .into());
         }
-        // compress 0-flow nodes (except for AA)
-        if flow_rates.get(vid) == 0 && vid != 0 {
-            add_valve_connected_nodes(
-                flow_rates,
-                tunnel_map,
-                distances,
-                from,
-                vid,
-                to,
-                distance + 1,
-            );
-        } else {
-            distances.set(from, vid, distance);
+        valves.push(report.valve);
+    }
+    Ok(ValveIds(valves))
+}
+
+fn parse_flow_rates(reports: &[ValveReport], valve_ids: &ValveIds) -> FlowRates {
+    debug!("parsing valve flow rates");
+    let mut flow_rates = FlowRates::new(valve_ids.len());
+    for (vid, report) in reports.iter().enumerate() {
+        flow_rates.set(vid as u16, report.flow);
+    }
+    flow_rates
+}
+
+fn parse_tunnel_map(reports: &[ValveReport], valve_ids: &ValveIds) -> Result<Graph<u16>> {
+    debug!("parsing tunnel map");
+    // add the valves first, so that they are numbered by their IDs
+    let mut tunnel_map = Graph::new();
+    for vid in 0..valve_ids.len() {
+        tunnel_map.add_node(vid as u16);
+    }
+    for (vid, report) in reports.iter().enumerate() {
+        for &v in report.tunnels.iter() {
+            let v_id = valve_ids.id(v).ok_or_else(|| Error::Parse {
+                day: 16,
+                line: vid + 1,
+                reason: format!("tunnel leads to unknown valve {}", v),
+            })?;
+            tunnel_map.add_edge(vid as u16, v_id, 1);
         }
     }
+    Ok(tunnel_map)
 }
 
-fn get_valve_graph(flow_rates: &FlowRates, tunnel_map: &TunnelMap) -> Distances {
-    debug!("compressing valve graph to remove 0-flow nodes");
-    let mut distances = Distances::new();
+fn valve_heuristic(info: &VolcanoInfo, target: u16, from: u16) -> i64 {
+    info.flow_rate(target) as i64 - info.distance(from, target) as i64
+}
+
+/// a valve opened at a given minute
+#[derive(Clone, Copy)]
+struct ValveOpening {
+    minute: u64,
+    valve: u16,
+}
 
-    // loop thru all valves
-    for (vid, &flow_rate) in flow_rates.0.iter().enumerate() {
-        let vid = vid as u16;
-        // skip valves with 0 flow (except for AA since it is the start node)
-        if flow_rate == u64::MAX || (flow_rate == 0 && vid != 0) {
-            continue;
+/// tracks the valves opened along the current search path, along with the
+/// schedule of the path which releases the most pressure
+struct ScheduleSearch {
+    time_limit: u64,
+    path: Vec<ValveOpening>,
+    best_volume: u64,
+    best_path: Vec<ValveOpening>,
+}
+
+impl ScheduleSearch {
+    fn new(time_limit: u64) -> Self {
+        Self {
+            time_limit,
+            path: Vec::new(),
+            best_volume: 0,
+            best_path: Vec::new(),
         }
-        // add the self-connection
-        distances.set(vid, vid, 0);
-        debug!("adding connected nodes for valve {}", Valve(vid));
-        for &v in tunnel_map.connections(vid) {
-            // compress 0-flow nodes (except for AA)
-            if flow_rates.get(v) == 0 && v != 0 {
-                add_valve_connected_nodes(flow_rates, tunnel_map, &mut distances, vid, v, vid, 2);
-            } else {
-                distances.set(vid, v, 1);
-            }
+    }
+
+    fn finish_path(&mut self, flow_volume: u64) -> u64 {
+        if flow_volume > self.best_volume {
+            self.best_volume = flow_volume;
+            self.best_path = self.path.clone();
         }
+        flow_volume
     }
+}
 
-    distances
+#[derive(Clone, Copy)]
+enum Agent {
+    You,
+    Elephant,
 }
 
-fn floyd_warshall(distances: &mut Distances) {
-    for k in 0..(VALVE_BUF_SIZE as u16) {
-        for i in 0..(VALVE_BUF_SIZE as u16) {
-            let dik = distances.get(i, k);
-            if dik == u64::MAX {
-                continue;
-            }
-            for j in 0..(VALVE_BUF_SIZE as u16) {
-                let dij = distances.get(i, j);
-                let dkj = distances.get(k, j);
-                if dkj == u64::MAX {
-                    continue;
-                }
-                if dij > dik + dkj {
-                    distances.set(i, j, dik + dkj);
-                }
-            }
+impl fmt::Display for Agent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::You => write!(f, "you"),
+            Self::Elephant => write!(f, "the elephant"),
         }
     }
 }
 
-fn valve_heuristic(info: &VolcanoInfo, target: u16, from: u16) -> i64 {
-    info.flow_rate(target) as i64 - info.distance(from, target) as i64
+/// the order in which valves are opened, and by whom
+struct Schedule(Vec<(u64, Agent, Valve)>);
+
+impl Schedule {
+    fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    fn add(&mut self, agent: Agent, openings: &[ValveOpening], valve_ids: &ValveIds) {
+        self.0.extend(
+            openings
+                .iter()
+                .map(|opening| (opening.minute, agent, valve_ids.valve(opening.valve))),
+        );
+        self.0.sort_by_key(|(minute, _, _)| *minute);
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let lines = self
+            .0
+            .iter()
+            .map(|(minute, agent, valve)| {
+                format!("minute {:2}: valve {} opened by {}", minute, valve, agent)
+            })
+            .collect::<Vec<_>>();
+        write!(f, "{}", lines.join("\n"))
+    }
 }
 
 fn find_max_pressure_release_rec(
@@ -253,10 +316,11 @@ fn find_max_pressure_release_rec(
     mut time: u64,
     mut flow_rate: u64,
     mut flow_volume: u64,
-    time_limit: u64,
+    search: &mut ScheduleSearch,
 ) -> u64 {
-    // if this is not the start valve AA, open the valve
-    if valve != 0 {
+    let time_limit = search.time_limit;
+    // if this is not the start valve, open the valve
+    if valve != info.start {
         time += 1;
         flow_volume += flow_rate;
         flow_rate += info.flow_rate(valve);
@@ -267,7 +331,7 @@ fn find_max_pressure_release_rec(
                 "time limit reached with flow_rate={} flow_volume={}",
                 flow_rate, flow_volume,
             );
-            return flow_volume;
+            return search.finish_path(flow_volume);
         }
     }
 
@@ -280,7 +344,7 @@ fn find_max_pressure_release_rec(
             "all valves are open with time={} dt={} flow_rate={} flow_volume={}",
             time, dt, flow_rate, flow_volume,
         );
-        return flow_volume;
+        return search.finish_path(flow_volume);
     }
 
     // now consider all unopened valves, using a heuristic that combines their
@@ -308,9 +372,14 @@ fn find_max_pressure_release_rec(
                 "time limit reached with flow_rate={} flow_volume={}",
                 flow_rate, new_flow_volume,
             );
-            results.push(new_flow_volume);
+            results.push(search.finish_path(new_flow_volume));
         } else {
             let new_flow_volume = flow_volume + (flow_rate * distance);
+            // the valve is opened during the minute in which it is reached
+            search.path.push(ValveOpening {
+                minute: t,
+                valve: vid,
+            });
             let res = find_max_pressure_release_rec(
                 info,
                 open_valves.clone(),
@@ -318,8 +387,9 @@ fn find_max_pressure_release_rec(
                 t,
                 flow_rate,
                 new_flow_volume,
-                time_limit,
+                search,
             );
+            search.path.pop();
             results.push(res);
         }
     }
@@ -327,32 +397,26 @@ fn find_max_pressure_release_rec(
     results.into_iter().max().unwrap()
 }
 
-fn find_max_pressure_release(info: &VolcanoInfo) -> u64 {
+fn find_max_pressure_release(info: &VolcanoInfo) -> (u64, Schedule) {
     let mut open_valves = info
-        .flow_rates
-        .0
-        .iter()
-        .enumerate()
-        .filter(|(_, &flow)| flow != 0 && flow != u64::MAX)
-        .map(|(vid, _)| (vid as u16, false))
+        .flowing_valves()
+        .map(|vid| (vid, false))
         .collect::<HashMap<_, _>>();
-    open_valves.insert(0, true);
-
-    find_max_pressure_release_rec(info, open_valves, 0, 1, 0, 0, TIME_LIMIT)
+    open_valves.insert(info.start, true);
+
+    let mut search = ScheduleSearch::new(TIME_LIMIT);
+    let max_pressure =
+        find_max_pressure_release_rec(info, open_valves, info.start, 1, 0, 0, &mut search);
+    let mut schedule = Schedule::new();
+    schedule.add(Agent::You, &search.best_path, &info.valve_ids);
+    (max_pressure, schedule)
 }
 
 fn generate_valve_partitions(info: &VolcanoInfo) -> Vec<(HashSet<u16>, HashSet<u16>)> {
     // first gather the non-zero flow valves
-    let mut valves = info
-        .flow_rates
-        .0
-        .iter()
-        .enumerate()
-        .filter(|(_, &flow)| flow != 0 && flow != u64::MAX)
-        .map(|(vid, _)| vid as u16)
-        .collect::<Vec<_>>();
+    let mut valves = info.flowing_valves().collect::<Vec<_>>();
     valves.sort();
-    let valves_set = HashSet::<_>::from_iter(valves.clone().into_iter());
+    let valves_set = HashSet::<_>::from_iter(valves.clone());
     let n_valves = valves.len();
 
     // generate combinations of each partition size
@@ -368,7 +432,7 @@ fn generate_valve_partitions(info: &VolcanoInfo) -> Vec<(HashSet<u16>, HashSet<u
             partitions.push((a, b));
         } else {
             for combo in valves.clone().into_iter().combinations(n) {
-                let a = HashSet::<_>::from_iter(combo.into_iter());
+                let a = HashSet::<_>::from_iter(combo);
                 let b = valves_set.difference(&a).copied().collect();
                 partitions.push((a, b));
             }
@@ -379,25 +443,30 @@ fn generate_valve_partitions(info: &VolcanoInfo) -> Vec<(HashSet<u16>, HashSet<u
 }
 
 fn count_valves(info: &VolcanoInfo) -> usize {
-    info.flow_rates
-        .0
-        .iter()
-        .enumerate()
-        .filter(|(_, &flow)| flow != 0 && flow != u64::MAX)
-        .count()
+    info.flowing_valves().count()
 }
 
-fn get_max_pressure_release_from_valve_set(info: &VolcanoInfo, valve_set: HashSet<u16>) -> u64 {
+fn get_max_pressure_release_from_valve_set(
+    info: &VolcanoInfo,
+    valve_set: &HashSet<u16>,
+) -> (u64, Vec<ValveOpening>) {
     let mut open_valves = valve_set
-        .into_iter()
-        .map(|vid| (vid, false))
+        .iter()
+        .map(|&vid| (vid, false))
         .collect::<HashMap<_, _>>();
-    open_valves.insert(0, true);
+    open_valves.insert(info.start, true);
 
-    find_max_pressure_release_rec(info, open_valves, 0, 1, 0, 0, TIME_LIMIT_WITH_ELEPHANT)
+    let mut search = ScheduleSearch::new(TIME_LIMIT_WITH_ELEPHANT);
+    let max_pressure =
+        find_max_pressure_release_rec(info, open_valves, info.start, 1, 0, 0, &mut search);
+    (max_pressure, search.best_path)
 }
 
-fn find_max_pressure_release_with_elephant(info: &VolcanoInfo) -> u64 {
+fn find_max_pressure_release_with_elephant(
+    info: &VolcanoInfo,
+    progress: &Progress,
+    cancel: &Cancel,
+) -> (u64, Schedule) {
     // brute force: generate all partitions of valves and check which
     // permutation produces the maximum flow
     let valve_sets = generate_valve_partitions(info);
@@ -413,65 +482,278 @@ fn find_max_pressure_release_with_elephant(info: &VolcanoInfo) -> u64 {
         valve_sets_filtered.len()
     );
 
+    // the partitions are independent, so they are searched in parallel; once
+    // cancelled, the remaining partitions are skipped
+    let releases = utils::par_map_with_progress(
+        &valve_sets_filtered,
+        pool::threads(),
+        |(human_valves, elephant_valves)| {
+            if cancel.is_cancelled() {
+                return None;
+            }
+            Some((
+                get_max_pressure_release_from_valve_set(info, human_valves),
+                get_max_pressure_release_from_valve_set(info, elephant_valves),
+            ))
+        },
+        progress.tracker("day 16 partitions"),
+    );
+
     let mut max_pressure = 0;
-    for (human_valves, elephant_valves) in valve_sets_filtered.into_iter() {
-        let human_pressure = get_max_pressure_release_from_valve_set(info, human_valves);
-        let elephant_pressure = get_max_pressure_release_from_valve_set(info, elephant_valves);
-        max_pressure = cmp::max(max_pressure, human_pressure + elephant_pressure);
+    let mut schedule = Schedule::new();
+    let mut searched = 0;
+    for ((human_pressure, human_path), (elephant_pressure, elephant_path)) in
+        releases.into_iter().flatten()
+    {
+        searched += 1;
+        if human_pressure + elephant_pressure > max_pressure {
+            max_pressure = human_pressure + elephant_pressure;
+            schedule = Schedule::new();
+            schedule.add(Agent::You, &human_path, &info.valve_ids);
+            schedule.add(Agent::Elephant, &elephant_path, &info.valve_ids);
+        }
+    }
+    if searched < valve_sets_filtered.len() {
+        warn!(
+            "day 16 interrupted, the answer is the best of {} of the {} partitions",
+            searched,
+            valve_sets_filtered.len()
+        );
     }
 
-    max_pressure
+    (max_pressure, schedule)
 }
 
-pub fn run(input: String) -> Result<Solution> {
-    let mut solution = Solution::new();
-    // parse the valve flow rates and the tunnel map
-    let flow_rates = parse_flow_rates(&input);
-    let tunnel_map = parse_tunnel_map(&input);
-    // then calculate the distances between valves, first compressing the graph
-    // to remove the zero-flow nodes
-    let mut distances = get_valve_graph(&flow_rates, &tunnel_map);
-    floyd_warshall(&mut distances);
-
-    // package the info into a single struct
-    let info = VolcanoInfo::new(flow_rates, distances);
-
-    // part 1: Work out the steps to release the most pressure in 30 minutes.
-    // What is the most pressure you can release?
-    let max_pressure = find_max_pressure_release(&info);
-    solution.set_part_1(max_pressure);
-
-    // part 2: With you and an elephant working together for 26 minutes, what
-    // is the most pressure you could release?
-    let max_pressure_w_elephant = find_max_pressure_release_with_elephant(&info);
-    solution.set_part_2(max_pressure_w_elephant);
-
-    Ok(solution)
+impl Encode for VolcanoInfo {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        let valves = self.valve_ids.0.iter().map(|v| v.0).collect::<Vec<_>>();
+        valves.encode(buf);
+        self.flow_rates.0.encode(buf);
+        self.distances.encode(buf);
+        self.start.encode(buf);
+    }
+
+    fn decode(reader: &mut Reader) -> Result<Self> {
+        let valves = Vec::<u16>::decode(reader)?;
+        let flow_rates = Vec::<u64>::decode(reader)?;
+        let distances = Vec::<Vec<u64>>::decode(reader)?;
+        let start = u16::decode(reader)?;
+        let n_valves = valves.len();
+        if flow_rates.len() != n_valves
+            || distances.len() != n_valves
+            || distances.iter().any(|row| row.len() != n_valves)
+            || start as usize >= n_valves
+        {
+            return Err(anyhow!("inconsistent valve counts"));
+        }
+        Ok(Self::new(
+            ValveIds(valves.into_iter().map(Valve).collect()),
+            FlowRates(flow_rates),
+            distances,
+            start,
+        ))
+    }
+}
+
+impl Cached for VolcanoInfo {
+    const NAME: &'static str = "day_16";
+    const VERSION: u32 = 2;
+
+    fn parse(input: PuzzleInput) -> Result<Self> {
+        // parse the valve IDs, flow rates, and the tunnel map
+        let reports = input.parse_lines(ValveReport::try_from)?;
+        let valve_ids = parse_valve_ids(&reports)?;
+        let flow_rates = parse_flow_rates(&reports, &valve_ids);
+        let tunnel_map = parse_tunnel_map(&reports, &valve_ids)?;
+        // resolve the valve that the search starts from
+        let start = valve_ids
+            .id(START_VALVE)
+            .ok_or_else(|| anyhow!("missing start valve {}", START_VALVE))?;
+        // then calculate the distances between each pair of valves
+        let distances = tunnel_map.floyd_warshall();
+
+        // package the info into a single struct
+        Ok(VolcanoInfo::new(valve_ids, flow_rates, distances, start))
+    }
+}
+
+pub struct Day16;
+
+impl Puzzle for Day16 {
+    type Parsed = VolcanoInfo;
+
+    const DAY: usize = 16;
+    const TITLE: &'static str = "Proboscidea Volcanium";
+    const METADATA: Metadata = Metadata {
+        algorithm: "floyd-warshall and branch and bound",
+        complexity: "O(2^valves)",
+        tags: &["graph", "search", "optimization"],
+    };
+    const SAMPLE_ANSWERS: [Option<&'static str>; 2] = [Some("1651"), Some("1707")];
+    const ANSWERS: [Option<&'static str>; 2] = [Some("2119"), Some("2615")];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Parsed> {
+        input_cache::parse::<VolcanoInfo>(input)
+    }
+
+    fn part_1(info: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 1: Work out the steps to release the most pressure in 30
+        // minutes. What is the most pressure you can release?
+        let (max_pressure, schedule) = find_max_pressure_release(info);
+        let _ = info.schedules[0].set(schedule);
+        Ok(max_pressure.into())
+    }
+
+    fn part_2(info: &Self::Parsed, context: &Context) -> Result<Answer> {
+        // part 2: With you and an elephant working together for 26 minutes,
+        // what is the most pressure you could release?
+        let (max_pressure_w_elephant, schedule_w_elephant) =
+            find_max_pressure_release_with_elephant(info, &context.progress, &context.cancel);
+        let _ = info.schedules[1].set(schedule_w_elephant);
+        Ok(max_pressure_w_elephant.into())
+    }
+
+    fn extras(info: &Self::Parsed, solution: &Solution) -> Vec<(String, String)> {
+        let solved = [&solution.part_1, &solution.part_2];
+        info.schedules
+            .iter()
+            .zip(solved)
+            .enumerate()
+            .filter(|(_, (_, answer))| answer.is_some())
+            .filter_map(|(i, (schedule, _))| {
+                let schedule = schedule.get()?;
+                Some((format!("part {} schedule", i + 1), schedule.to_string()))
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parts::Parts;
+    use crate::puzzles::{solve, solve_parsed};
+
+    const SAMPLE_INPUT: &str = "\
+Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+Valve BB has flow rate=13; tunnels lead to valves CC, AA
+Valve CC has flow rate=2; tunnels lead to valves DD, BB
+Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
+Valve EE has flow rate=3; tunnels lead to valves FF, DD
+Valve FF has flow rate=0; tunnels lead to valves EE, GG
+Valve GG has flow rate=0; tunnels lead to valves FF, HH
+Valve HH has flow rate=22; tunnel leads to valve GG
+Valve II has flow rate=0; tunnels lead to valves AA, JJ
+Valve JJ has flow rate=21; tunnel leads to valve II";
+
+    #[test]
+    fn sample_input() {
+        let solution = solve::<Day16>(SAMPLE_INPUT, Parts::Both, &Context::none()).unwrap();
+        assert_eq!(solution.part_1.unwrap(), 1651);
+        assert_eq!(solution.part_2.unwrap(), 1707);
+    }
+
+    #[test]
+    fn cancelled_search() {
+        // a search cancelled before it starts skips every partition
+        let cancel = Cancel::new();
+        cancel.cancel();
+        let context = Context::none().with_cancel(cancel);
+        let solution = solve::<Day16>(SAMPLE_INPUT, Parts::Only(2), &context).unwrap();
+        assert_eq!(solution.part_2.unwrap(), 0);
+    }
+
+    #[test]
+    fn sample_input_start_valve_not_first() {
+        // the start valve is resolved by name rather than by position
+        let input = SAMPLE_INPUT
+            .split('\n')
+            .rev()
+            .collect::<Vec<_>>()
+            .join("\n");
+        let solution = solve::<Day16>(&input, Parts::Both, &Context::none()).unwrap();
+        assert_eq!(solution.part_1.unwrap(), 1651);
+        assert_eq!(solution.part_2.unwrap(), 1707);
+    }
+
+    #[test]
+    fn sample_input_cached() {
+        let info = VolcanoInfo::parse(PuzzleInput::new(16, SAMPLE_INPUT)).unwrap();
+        let bytes = input_cache::to_bytes(&info);
+        let info = input_cache::from_bytes::<VolcanoInfo>(&bytes).unwrap();
+        let solution = solve_parsed::<Day16>(&info, Parts::Both, &Context::none()).unwrap();
+        assert_eq!(solution.part_1.unwrap(), 1651);
+        assert_eq!(solution.part_2.unwrap(), 1707);
+        assert!(input_cache::from_bytes::<VolcanoInfo>(&bytes[..bytes.len() - 2]).is_err());
+    }
+
+    #[test]
+    fn missing_start_valve() {
+        let input = "Valve BB has flow rate=13; tunnel leads to valve CC\n\
+                     Valve CC has flow rate=2; tunnel leads to valve BB";
+        assert!(solve::<Day16>(input, Parts::Both, &Context::none()).is_err());
+    }
 
     #[test]
     fn test_valve_from_str() {
         let input = "AA";
-        let output = Valve::from(input);
+        let output = Valve::try_from(input).unwrap();
         assert_eq!(output.0, 0);
 
         let input = "AC";
-        let output = Valve::from(input);
+        let output = Valve::try_from(input).unwrap();
         assert_eq!(output.0, 2);
 
         let input = "DA";
-        let output = Valve::from(input);
+        let output = Valve::try_from(input).unwrap();
         assert_eq!(output.0, 3 << 5);
 
         let input = "FC";
-        let output = Valve::from(input);
+        let output = Valve::try_from(input).unwrap();
         assert_eq!(output.0, (5 << 5) | 2);
     }
 
+    #[test]
+    fn malformed_reports() {
+        let parse = |input: &str| {
+            VolcanoInfo::parse(PuzzleInput::new(16, input))
+                .err()
+                .unwrap()
+        };
+        let e = parse("Valve AA has flow rate=0; tunnels lead to valves BB, c");
+        assert_eq!(
+            e.to_string(),
+            "day 16 input, line 1: expected a valve name of two capital letters, found \"c\""
+        );
+        let e = parse("Valve AA has flow rate=0; tunnel leads to valve AA\nValve A has flow");
+        assert_eq!(
+            e.to_string(),
+            "day 16 input, line 2: expected a valve report, found \"Valve A has flow\""
+        );
+        let e = parse("Valve AA has flow rate=x; tunnel leads to valve AA");
+        assert_eq!(
+            e.to_string(),
+            "day 16 input, line 1: expected a flow rate, \
+             found \"Valve AA has flow rate=x; tunnel leads to valve AA\""
+        );
+        let e = parse(
+            "Valve AA has flow rate=0; tunnel leads to valve AA\n\
+                       Valve BB has flow rate=0; tunnel leads to valve CC",
+        );
+        assert_eq!(
+            e.to_string(),
+            "day 16 input, line 2: tunnel leads to unknown valve CC"
+        );
+        let e = parse(
+            "Valve AA has flow rate=0; tunnel leads to valve AA\n\
+                       Valve AA has flow rate=1; tunnel leads to valve AA",
+        );
+        assert_eq!(
+            e.to_string(),
+            "day 16 input, line 2: valve AA is already reported"
+        );
+    }
+
     #[test]
     fn test_valve_to_str() {
         let input = Valve(0);
@@ -3,10 +3,7 @@
 ** https://adventofcode.com/2022/day/2
 */
 
-use crate::types::Solution;
-use crate::utils;
-
-use anyhow::Result;
+use crate::prelude::*;
 
 /// rock/paper/scissors move
 #[derive(Clone)]
@@ -42,13 +39,15 @@ impl Move {
     }
 }
 
-impl From<char> for Move {
-    fn from(c: char) -> Self {
+impl TryFrom<char> for Move {
+    type Error = String;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
         match c {
-            'A' | 'X' => Self::Rock,
-            'B' | 'Y' => Self::Paper,
-            'C' | 'Z' => Self::Scissors,
-            _ => unreachable!(),
+            'A' | 'X' => Ok(Self::Rock),
+            'B' | 'Y' => Ok(Self::Paper),
+            'C' | 'Z' => Ok(Self::Scissors),
+            _ => Err(format!("invalid move {:?}", c)),
         }
     }
 }
@@ -83,41 +82,54 @@ impl GameResult {
     }
 }
 
-impl From<char> for GameResult {
-    fn from(c: char) -> Self {
+impl TryFrom<char> for GameResult {
+    type Error = String;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
         match c {
-            'X' => Self::Loss,
-            'Y' => Self::Draw,
-            'Z' => Self::Win,
-            _ => unreachable!(),
+            'X' => Ok(Self::Loss),
+            'Y' => Ok(Self::Draw),
+            'Z' => Ok(Self::Win),
+            _ => Err(format!("invalid result {:?}", c)),
         }
     }
 }
 
-struct Game {
+/// the two columns of a line of the strategy guide, such as "A Y"
+fn parse_columns(s: &str) -> Result<(char, char), String> {
+    let chars = s.chars().collect::<Vec<_>>();
+    match chars[..] {
+        [a, ' ', b] => Ok((a, b)),
+        _ => Err(format!("expected two columns, found {:?}", s)),
+    }
+}
+
+pub struct Game {
     player_move: Move,
     result: GameResult,
 }
 
 impl Game {
-    fn from_str_with_move(s: &str) -> Self {
-        let opponent_move = Move::from(utils::nchar(s, 0));
-        let player_move = Move::from(utils::nchar(s, 2));
+    fn from_str_with_move(s: &str) -> Result<Self, String> {
+        let (a, b) = parse_columns(s)?;
+        let opponent_move = Move::try_from(a)?;
+        let player_move = Move::try_from(b)?;
         let result = GameResult::get(&opponent_move, &player_move);
-        Self {
+        Ok(Self {
             player_move,
             result,
-        }
+        })
     }
 
-    fn from_str_with_result(s: &str) -> Self {
-        let opponent_move = Move::from(utils::nchar(s, 0));
-        let result = GameResult::from(utils::nchar(s, 2));
+    fn from_str_with_result(s: &str) -> Result<Self, String> {
+        let (a, b) = parse_columns(s)?;
+        let opponent_move = Move::try_from(a)?;
+        let result = GameResult::try_from(b)?;
         let player_move = Move::from_result(&opponent_move, &result);
-        Self {
+        Ok(Self {
             player_move,
             result,
-        }
+        })
     }
 
     fn score(&self) -> u64 {
@@ -125,30 +137,67 @@ impl Game {
     }
 }
 
-pub fn run(input: String) -> Result<Solution> {
-    let mut solution = Solution::new();
-    // parse into games with the second column being the player's move
-    let games_with_move = utils::split_lines(&input)
-        .map(Game::from_str_with_move)
-        .collect::<Vec<_>>();
-    // parse into games with the second column being the result
-    let games_with_result = utils::split_lines(&input)
-        .map(Game::from_str_with_result)
-        .collect::<Vec<_>>();
-
-    // part 1: What would your total score be if everything goes exactly
-    // according to your strategy guide?
-    let score_part_1 = games_with_move.iter().map(|game| game.score()).sum::<u64>();
-    solution.set_part_1(score_part_1);
-
-    // part 2: Following the Elf's instructions for the second column, what
-    // would your total score be if everything goes exactly according to your
-    // strategy guide?
-    let score_part_2 = games_with_result
-        .iter()
-        .map(|game| game.score())
-        .sum::<u64>();
-    solution.set_part_2(score_part_2);
-
-    Ok(solution)
+pub struct Day2;
+
+impl Puzzle for Day2 {
+    /// the games read with the second column as the player's move, and read
+    /// with the second column as the result
+    type Parsed = (Vec<Game>, Vec<Game>);
+
+    const DAY: usize = 2;
+    const TITLE: &'static str = "Rock Paper Scissors";
+    const METADATA: Metadata = Metadata {
+        algorithm: "score lookup",
+        complexity: "O(n)",
+        tags: &["simulation"],
+    };
+    const SAMPLE_ANSWERS: [Option<&'static str>; 2] = [Some("15"), Some("12")];
+    const ANSWERS: [Option<&'static str>; 2] = [Some("11767"), Some("13886")];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Parsed> {
+        // parse into games with the second column being the player's move
+        let games_with_move = input.parse_lines(Game::from_str_with_move)?;
+        // parse into games with the second column being the result
+        let games_with_result = input.parse_lines(Game::from_str_with_result)?;
+        Ok((games_with_move, games_with_result))
+    }
+
+    fn part_1((games_with_move, _): &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 1: What would your total score be if everything goes exactly
+        // according to your strategy guide?
+        let score_part_1 = games_with_move.iter().map(|game| game.score()).sum::<u64>();
+        Ok(score_part_1.into())
+    }
+
+    fn part_2((_, games_with_result): &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 2: Following the Elf's instructions for the second column, what
+        // would your total score be if everything goes exactly according to
+        // your strategy guide?
+        let score_part_2 = games_with_result
+            .iter()
+            .map(|game| game.score())
+            .sum::<u64>();
+        Ok(score_part_2.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malformed_games() {
+        let e = Day2::parse(PuzzleInput::new(2, "A \nB X\n2C Z")).err().unwrap();
+        assert_eq!(
+            e.to_string(),
+            "day 2 input, line 1: expected two columns, found \"A \""
+        );
+        let e = Day2::parse(PuzzleInput::new(2, "A Y\n<0 Y")).err().unwrap();
+        assert_eq!(
+            e.to_string(),
+            "day 2 input, line 2: expected two columns, found \"<0 Y\""
+        );
+        let e = Day2::parse(PuzzleInput::new(2, "A Y\nB W")).err().unwrap();
+        assert_eq!(e.to_string(), "day 2 input, line 2: invalid move 'W'");
+    }
 }
@@ -0,0 +1,607 @@
+/*
+** src/puzzles/day_22.rs
+** https://adventofcode.com/2022/day/22
+*/
+
+use crate::prelude::*;
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+const OPEN: u8 = b'.';
+const WALL: u8 = b'#';
+const VOID: u8 = b' ';
+
+// a cube has 6 faces, obviously
+const N_FACES: usize = 6;
+
+#[derive(Debug)]
+pub enum Step {
+    Forward(usize),
+    TurnLeft,
+    TurnRight,
+}
+
+fn parse_path(s: &str) -> std::result::Result<Vec<Step>, String> {
+    let mut steps = Vec::new();
+    let mut n = 0;
+    for c in s.chars() {
+        if let Some(digit) = c.to_digit(10) {
+            n = (n * 10) + digit as usize;
+        } else {
+            if n > 0 {
+                steps.push(Step::Forward(n));
+                n = 0;
+            }
+            match c {
+                'L' => steps.push(Step::TurnLeft),
+                'R' => steps.push(Step::TurnRight),
+                _ => return Err(format!("invalid step {:?} in the path", c)),
+            }
+        }
+    }
+    if n > 0 {
+        steps.push(Step::Forward(n));
+    }
+    Ok(steps)
+}
+
+/// parses a row of the map, of open tiles and walls with the void around them
+fn parse_row(line: &str) -> std::result::Result<Vec<u8>, String> {
+    match line.bytes().find(|t| ![OPEN, WALL, VOID].contains(t)) {
+        Some(t) => Err(format!("invalid tile {:?} in the map", t as char)),
+        None => Ok(line.as_bytes().to_vec()),
+    }
+}
+
+/// the facing scored in the final password, in the order right, down, left
+/// and up
+fn facing_score(facing: Direction) -> usize {
+    match facing {
+        Direction::Right => 0,
+        Direction::Down => 1,
+        Direction::Left => 2,
+        Direction::Up => 3,
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Position {
+    row: usize,
+    col: usize,
+    facing: Direction,
+}
+
+impl Position {
+    fn password(&self) -> usize {
+        (1000 * (self.row + 1)) + (4 * (self.col + 1)) + facing_score(self.facing)
+    }
+}
+
+pub struct Map {
+    tiles: Vec<Vec<u8>>,
+    width: usize,
+    height: usize,
+    /// the leftmost open tile of the top row, where the path starts
+    start_col: usize,
+}
+
+impl Map {
+    fn parse(input: PuzzleInput) -> Result<Self> {
+        let mut tiles = input.parse_lines(parse_row)?;
+        let Some(start_col) = tiles[0].iter().position(|&t| t == OPEN) else {
+            return Err(Error::Parse {
+                day: 22,
+                line: 1,
+                reason: String::from("no open tile on the top row to start from"),
+            }
+            .into());
+        };
+        let width = tiles.iter().map(|row| row.len()).max().unwrap_or(0);
+        let height = tiles.len();
+        // pad each row out to the full width of the map
+        for row in tiles.iter_mut() {
+            row.resize(width, VOID);
+        }
+        Ok(Self {
+            tiles,
+            width,
+            height,
+            start_col,
+        })
+    }
+
+    fn tile(&self, row: i64, col: i64) -> u8 {
+        if row < 0 || col < 0 || row as usize >= self.height || col as usize >= self.width {
+            VOID
+        } else {
+            self.tiles[row as usize][col as usize]
+        }
+    }
+
+    fn start(&self) -> Position {
+        // start at the leftmost open tile of the top row, facing right
+        Position {
+            row: 0,
+            col: self.start_col,
+            facing: Direction::Right,
+        }
+    }
+
+    fn n_tiles(&self) -> usize {
+        self.tiles
+            .iter()
+            .map(|row| row.iter().filter(|&&t| t != VOID).count())
+            .sum()
+    }
+
+    /// takes a single step forward, using the given wrapping rules if the step
+    /// goes off of the edge of the map; returns None if blocked by a wall
+    fn step<W>(&self, pos: Position, wrap: &W) -> Option<Position>
+    where
+        W: Fn(&Self, Position) -> Position,
+    {
+        let Point { x: dc, y: dr } = pos.facing.delta();
+        let row = pos.row as i64 + dr;
+        let col = pos.col as i64 + dc;
+        let next = if self.tile(row, col) == VOID {
+            wrap(self, pos)
+        } else {
+            Position {
+                row: row as usize,
+                col: col as usize,
+                facing: pos.facing,
+            }
+        };
+        if self.tiles[next.row][next.col] == WALL {
+            None
+        } else {
+            Some(next)
+        }
+    }
+
+    fn follow_path<W>(&self, path: &[Step], wrap: W) -> Position
+    where
+        W: Fn(&Self, Position) -> Position,
+    {
+        let mut pos = self.start();
+        for step in path.iter() {
+            match step {
+                Step::Forward(n) => {
+                    for _ in 0..*n {
+                        if let Some(next) = self.step(pos, &wrap) {
+                            pos = next;
+                        } else {
+                            break;
+                        }
+                    }
+                }
+                Step::TurnLeft => pos.facing = pos.facing.turn_left(),
+                Step::TurnRight => pos.facing = pos.facing.turn_right(),
+            }
+        }
+        pos
+    }
+}
+
+/// wraps around to the other side of the row/column, as on a flat map
+fn wrap_flat(map: &Map, pos: Position) -> Position {
+    let Point { x: dc, y: dr } = pos.facing.delta();
+    // walk backwards until falling off of the opposite edge
+    let mut row = pos.row as i64;
+    let mut col = pos.col as i64;
+    while map.tile(row - dr, col - dc) != VOID {
+        row -= dr;
+        col -= dc;
+    }
+    Position {
+        row: row as usize,
+        col: col as usize,
+        facing: pos.facing,
+    }
+}
+
+type Vec3 = [i64; 3];
+
+fn neg(v: Vec3) -> Vec3 {
+    [-v[0], -v[1], -v[2]]
+}
+
+/// the orientation of a face of the net once folded into a cube, given as the
+/// outward normal of the face along with the directions that the map's right
+/// and down directions point on the face
+#[derive(Clone, Copy, Debug)]
+struct Orientation {
+    normal: Vec3,
+    right: Vec3,
+    down: Vec3,
+}
+
+impl Orientation {
+    /// folds the cube over onto the neighboring face of the net
+    fn fold(&self, facing: Direction) -> Self {
+        let Self {
+            normal,
+            right,
+            down,
+        } = *self;
+        match facing {
+            Direction::Right => Self {
+                normal: right,
+                right: neg(normal),
+                down,
+            },
+            Direction::Left => Self {
+                normal: neg(right),
+                right: normal,
+                down,
+            },
+            Direction::Down => Self {
+                normal: down,
+                right,
+                down: neg(normal),
+            },
+            Direction::Up => Self {
+                normal: neg(down),
+                right,
+                down: normal,
+            },
+        }
+    }
+
+    fn facing_of(&self, v: Vec3) -> Direction {
+        if v == self.right {
+            Direction::Right
+        } else if v == neg(self.right) {
+            Direction::Left
+        } else if v == self.down {
+            Direction::Down
+        } else {
+            Direction::Up
+        }
+    }
+
+    fn direction_of(&self, facing: Direction) -> Vec3 {
+        match facing {
+            Direction::Right => self.right,
+            Direction::Left => neg(self.right),
+            Direction::Down => self.down,
+            Direction::Up => neg(self.down),
+        }
+    }
+}
+
+/// the map folded into a cube: maps the faces of the net, indexed by their
+/// (row, column) block in the map, to their orientation on the cube
+struct Cube {
+    size: usize,
+    faces: HashMap<(usize, usize), Orientation>,
+}
+
+impl Cube {
+    fn fold(map: &Map) -> Result<Self> {
+        let size = ((map.n_tiles() / N_FACES) as f64).sqrt() as usize;
+        if size == 0 || size * size * N_FACES != map.n_tiles() {
+            return Err(Error::NoSolution.into());
+        }
+        let is_face = |br: i64, bc: i64| map.tile(br * size as i64, bc * size as i64) != VOID;
+
+        // walk the net from the first face, folding over onto each neighboring
+        // face and tracking the orientation each face ends up in
+        let first = (0, map.start().col / size);
+        let mut faces = HashMap::new();
+        let mut queue = VecDeque::new();
+        let orientation = Orientation {
+            normal: [0, 0, 1],
+            right: [1, 0, 0],
+            down: [0, 1, 0],
+        };
+        faces.insert(first, orientation);
+        queue.push_back((first, orientation));
+        while let Some(((br, bc), orientation)) = queue.pop_front() {
+            for facing in Direction::ALL {
+                let Point { x: dc, y: dr } = facing.delta();
+                let nbr = br as i64 + dr;
+                let nbc = bc as i64 + dc;
+                if !is_face(nbr, nbc) {
+                    continue;
+                }
+                let block = (nbr as usize, nbc as usize);
+                if let Entry::Vacant(e) = faces.entry(block) {
+                    let folded = orientation.fold(facing);
+                    debug!("face {:?} folds to {:?}", block, folded);
+                    e.insert(folded);
+                    queue.push_back((block, folded));
+                }
+            }
+        }
+
+        // the net must fold into a cube: every block of the map is either
+        // empty or a full face reached from the first face, and the faces end
+        // up on distinct sides of the cube
+        for br in 0..map.height.div_ceil(size) {
+            for bc in 0..map.width.div_ceil(size) {
+                let n_tiles = (0..size * size)
+                    .filter(|i| {
+                        let row = br * size + i / size;
+                        let col = bc * size + i % size;
+                        map.tile(row as i64, col as i64) != VOID
+                    })
+                    .count();
+                if n_tiles > 0 && (n_tiles < size * size || !faces.contains_key(&(br, bc))) {
+                    debug!("block {:?} is not a face of the cube", (br, bc));
+                    return Err(Error::NoSolution.into());
+                }
+            }
+        }
+        let normals = faces.values().map(|o| o.normal).collect::<HashSet<_>>();
+        if faces.len() != N_FACES || normals.len() != N_FACES {
+            return Err(Error::NoSolution.into());
+        }
+        Ok(Self { size, faces })
+    }
+
+    fn face_with_normal(&self, normal: Vec3) -> ((usize, usize), Orientation) {
+        // each side of the cube has a face, see fold
+        self.faces
+            .iter()
+            .find(|(_, o)| o.normal == normal)
+            .map(|(&block, &o)| (block, o))
+            .unwrap()
+    }
+
+    /// wraps around the edge of the current face onto the adjacent face of
+    /// the cube
+    fn wrap(&self, pos: Position) -> Position {
+        let n = self.size;
+        let block = (pos.row / n, pos.col / n);
+        let (r, c) = (pos.row % n, pos.col % n);
+        let face = self.faces[&block];
+
+        // the position along the edge being crossed, and the direction on the
+        // cube in which it increases
+        let (offset, along) = match pos.facing {
+            Direction::Right | Direction::Left => (r, face.down),
+            Direction::Down | Direction::Up => (c, face.right),
+        };
+
+        // the next face is the one whose normal points in the direction of
+        // travel; travel continues away from the face that was left
+        let (next_block, next_face) = self.face_with_normal(face.direction_of(pos.facing));
+        let facing = next_face.facing_of(neg(face.normal));
+
+        // the offset may run backwards along the edge of the next face
+        let offset_on = |axis: Vec3| {
+            if axis == along {
+                offset
+            } else {
+                n - 1 - offset
+            }
+        };
+        let (r, c) = match facing {
+            Direction::Right => (offset_on(next_face.down), 0),
+            Direction::Left => (offset_on(next_face.down), n - 1),
+            Direction::Down => (0, offset_on(next_face.right)),
+            Direction::Up => (n - 1, offset_on(next_face.right)),
+        };
+        Position {
+            row: (next_block.0 * n) + r,
+            col: (next_block.1 * n) + c,
+            facing,
+        }
+    }
+}
+
+pub struct Day22;
+
+impl Puzzle for Day22 {
+    /// the map and the path
+    type Parsed = (Map, Vec<Step>);
+
+    const DAY: usize = 22;
+    const TITLE: &'static str = "Monkey Map";
+    const METADATA: Metadata = Metadata {
+        algorithm: "grid walk and cube folding",
+        complexity: "O(steps)",
+        tags: &["grid", "geometry", "simulation"],
+    };
+    const SAMPLE_ANSWERS: [Option<&'static str>; 2] = [Some("6032"), Some("5031")];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Parsed> {
+        let parse_error = |reason: &str| Error::Parse {
+            day: 22,
+            line: input.lines().count(),
+            reason: String::from(reason),
+        };
+        let [map, path] = input.blocks().collect::<Vec<_>>()[..] else {
+            return Err(parse_error("expected a blank line between the map and the path").into());
+        };
+        let mut paths = path.parse_lines(|line| parse_path(line.trim()))?;
+        if paths.len() != 1 {
+            return Err(parse_error("expected the path on a single line").into());
+        }
+        Ok((Map::parse(map)?, paths.remove(0)))
+    }
+
+    fn part_1((map, path): &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 1: Follow the path given in the monkeys' notes. What is the
+        // final password?
+        let end = map.follow_path(path, wrap_flat);
+        debug!("flat map path ends at {:?}", end);
+        Ok(end.password().into())
+    }
+
+    fn part_2((map, path): &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 2: Fold the map into a cube, then follow the path given in the
+        // monkeys' notes. What is the final password?
+        let cube = Cube::fold(map)?;
+        let end = map.follow_path(path, |_, pos| cube.wrap(pos));
+        debug!("cube path ends at {:?}", end);
+        Ok(end.password().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_MAP: &str = "        ...#
+        .#..
+        #...
+        ....
+...#.......#
+........#...
+..#....#....
+..........#.
+        ...#....
+        .....#..
+        .#......
+        ......#.";
+
+    #[test]
+    fn parse_path_steps() {
+        let path = parse_path("10R5L5").unwrap();
+        assert_eq!(path.len(), 5);
+        assert!(matches!(path[0], Step::Forward(10)));
+        assert!(matches!(path[1], Step::TurnRight));
+        assert!(matches!(path[3], Step::TurnLeft));
+        assert!(matches!(path[4], Step::Forward(5)));
+    }
+
+    #[test]
+    fn malformed_notes() {
+        let input = format!("{}\n\n10R5X5", SAMPLE_MAP);
+        let e = Day22::parse(PuzzleInput::new(22, &input)).err().unwrap();
+        assert_eq!(
+            e.to_string(),
+            "day 22 input, line 14: invalid step 'X' in the path"
+        );
+        let e = Day22::parse(PuzzleInput::new(22, SAMPLE_MAP))
+            .err()
+            .unwrap();
+        assert_eq!(
+            e.to_string(),
+            "day 22 input, line 12: expected a blank line between the map and the path"
+        );
+        let input = format!("{}\n\n10R5", SAMPLE_MAP.replacen('.', "o", 1));
+        let e = Day22::parse(PuzzleInput::new(22, &input)).err().unwrap();
+        assert_eq!(
+            e.to_string(),
+            "day 22 input, line 1: invalid tile 'o' in the map"
+        );
+        let input = format!("{}\n\n10R5", SAMPLE_MAP.replacen("...#", "####", 1));
+        let e = Day22::parse(PuzzleInput::new(22, &input)).err().unwrap();
+        assert_eq!(
+            e.to_string(),
+            "day 22 input, line 1: no open tile on the top row to start from"
+        );
+    }
+
+    #[test]
+    fn cube_wrap_sample() {
+        let map = Map::parse(PuzzleInput::new(22, SAMPLE_MAP)).unwrap();
+        let cube = Cube::fold(&map).unwrap();
+        assert_eq!(cube.size, 4);
+        // the examples given in the puzzle description
+        let pos = Position {
+            row: 5,
+            col: 11,
+            facing: Direction::Right,
+        };
+        let expected = Position {
+            row: 8,
+            col: 14,
+            facing: Direction::Down,
+        };
+        assert_eq!(cube.wrap(pos), expected);
+
+        let pos = Position {
+            row: 11,
+            col: 10,
+            facing: Direction::Down,
+        };
+        let expected = Position {
+            row: 7,
+            col: 1,
+            facing: Direction::Up,
+        };
+        assert_eq!(cube.wrap(pos), expected);
+    }
+
+    fn assert_round_trip(map: &Map) {
+        // stepping off of any edge and then back again returns to the start
+        let cube = Cube::fold(map).unwrap();
+        for row in 0..map.height {
+            for col in 0..map.width {
+                if map.tiles[row][col] == VOID {
+                    continue;
+                }
+                for facing in Direction::ALL {
+                    let Point { x: dc, y: dr } = facing.delta();
+                    if map.tile(row as i64 + dr, col as i64 + dc) != VOID {
+                        continue;
+                    }
+                    let pos = Position { row, col, facing };
+                    let mut next = cube.wrap(pos);
+                    next.facing = next.facing.opposite();
+                    let mut back = cube.wrap(next);
+                    back.facing = back.facing.opposite();
+                    assert_eq!(back, pos);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cube_wrap_round_trip() {
+        let map = Map::parse(PuzzleInput::new(22, SAMPLE_MAP)).unwrap();
+        assert_round_trip(&map);
+    }
+
+    /// a map of open tiles laid out as the net, with the given face size
+    fn net_map(net: &[&str], size: usize) -> String {
+        let mut rows = Vec::new();
+        for net_row in net.iter() {
+            let row = net_row
+                .chars()
+                .map(|c| {
+                    if c == '#' {
+                        ".".repeat(size)
+                    } else {
+                        " ".repeat(size)
+                    }
+                })
+                .collect::<String>();
+            for _ in 0..size {
+                rows.push(row.trim_end().to_string());
+            }
+        }
+        rows.join("\n")
+    }
+
+    #[test]
+    fn cube_wrap_round_trip_other_net() {
+        // the net layout used by the real puzzle inputs
+        let rows = net_map(&[" ##", " # ", "## ", "#  "], 3);
+        let map = Map::parse(PuzzleInput::new(22, &rows)).unwrap();
+        assert_eq!(Cube::fold(&map).unwrap().size, 3);
+        assert_round_trip(&map);
+    }
+
+    #[test]
+    fn invalid_nets() {
+        let fold_err = |rows: &str| {
+            let map = Map::parse(PuzzleInput::new(22, rows)).unwrap();
+            let err = Cube::fold(&map).err().unwrap();
+            assert!(matches!(err.downcast_ref::<Error>(), Some(Error::NoSolution)));
+        };
+        // six faces in a row wrap around onto the same sides
+        fold_err(&net_map(&["######"], 2));
+        // six faces which are not all connected
+        fold_err(&net_map(&["## ##", "  ## "], 2));
+        // a partial face, with the missing tile made up elsewhere
+        let rows = net_map(&[" ##", " # ", "## ", "#  "], 2);
+        let rows = rows.replacen("  ..", "  . ", 1) + "\n.";
+        fold_err(&rows);
+    }
+}
@@ -0,0 +1,147 @@
+/*
+** src/puzzles/day_25.rs
+** https://adventofcode.com/2022/day/25
+*/
+
+use crate::prelude::*;
+
+const SNAFU_BASE: i64 = 5;
+
+/// converts a SNAFU (balanced base-5) digit to its value
+fn snafu_digit_value(c: char) -> Option<i64> {
+    match c {
+        '2' => Some(2),
+        '1' => Some(1),
+        '0' => Some(0),
+        '-' => Some(-1),
+        '=' => Some(-2),
+        _ => None,
+    }
+}
+
+/// converts a value in the range [-2, 2] to a SNAFU digit
+fn snafu_digit(n: i64) -> Option<char> {
+    match n {
+        2 => Some('2'),
+        1 => Some('1'),
+        0 => Some('0'),
+        -1 => Some('-'),
+        -2 => Some('='),
+        _ => None,
+    }
+}
+
+fn snafu_to_decimal(s: &str) -> std::result::Result<i64, String> {
+    if s.is_empty() {
+        return Err(String::from("expected a SNAFU number, found an empty line"));
+    }
+    s.chars().try_fold(0, |acc, c| {
+        let value = snafu_digit_value(c).ok_or_else(|| format!("invalid SNAFU digit {:?}", c))?;
+        Ok((acc * SNAFU_BASE) + value)
+    })
+}
+
+fn decimal_to_snafu(n: i64) -> Option<String> {
+    if n == 0 {
+        return Some(String::from("0"));
+    }
+    let mut digits = Vec::new();
+    let mut n = n;
+    while n != 0 {
+        // digits 3 and 4 are represented as -2 and -1 with a carry
+        let rem = n.rem_euclid(SNAFU_BASE);
+        let digit = if rem > 2 { rem - SNAFU_BASE } else { rem };
+        digits.push(snafu_digit(digit)?);
+        n = (n - digit) / SNAFU_BASE;
+    }
+    Some(digits.iter().rev().collect())
+}
+
+pub struct Day25;
+
+impl Puzzle for Day25 {
+    /// the sum of the fuel requirements
+    type Parsed = i64;
+
+    const DAY: usize = 25;
+    const TITLE: &'static str = "Full of Hot Air";
+    const METADATA: Metadata = Metadata {
+        algorithm: "balanced base 5",
+        complexity: "O(n)",
+        tags: &["math"],
+    };
+
+    // part 2: there is no part 2, all that is needed is the other 49 stars
+    const PARTS: usize = 1;
+
+    const SAMPLE_ANSWERS: [Option<&'static str>; 2] = [Some("2=-1=0"), None];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Parsed> {
+        // parse the fuel requirements from SNAFU numbers
+        Ok(input.parse_lines(snafu_to_decimal)?.iter().sum())
+    }
+
+    fn part_1(fuel_sum: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 1: The Elves are starting to get cold. What SNAFU number do you
+        // supply to Bob's console?
+        let snafu = decimal_to_snafu(*fuel_sum).ok_or(Error::NoSolution)?;
+        Ok(snafu.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // from the table in the puzzle description
+    const CONVERSIONS: [(i64, &str); 15] = [
+        (1, "1"),
+        (2, "2"),
+        (3, "1="),
+        (4, "1-"),
+        (5, "10"),
+        (6, "11"),
+        (7, "12"),
+        (8, "2="),
+        (9, "2-"),
+        (10, "20"),
+        (15, "1=0"),
+        (20, "1-0"),
+        (2022, "1=11-2"),
+        (12345, "1-0---0"),
+        (314159265, "1121-1110-1=0"),
+    ];
+
+    #[test]
+    fn snafu_decode() {
+        for (n, s) in CONVERSIONS.iter() {
+            assert_eq!(snafu_to_decimal(s), Ok(*n));
+        }
+    }
+
+    #[test]
+    fn snafu_encode() {
+        for (n, s) in CONVERSIONS.iter() {
+            assert_eq!(decimal_to_snafu(*n).as_deref(), Some(*s));
+        }
+        assert_eq!(decimal_to_snafu(0).as_deref(), Some("0"));
+    }
+
+    #[test]
+    fn malformed_numbers() {
+        let e = Day25::parse(PuzzleInput::new(25, "1=-0-2\n12111\n2=3"))
+            .err()
+            .unwrap();
+        assert_eq!(
+            e.to_string(),
+            "day 25 input, line 3: invalid SNAFU digit '3'"
+        );
+        let e = Day25::parse(PuzzleInput::new(25, "1=-0-2\n\n12111"))
+            .err()
+            .unwrap();
+        assert_eq!(
+            e.to_string(),
+            "day 25 input, line 2: expected a SNAFU number, found an empty line"
+        );
+    }
+}
@@ -3,52 +3,61 @@
 ** https://adventofcode.com/2022/day/3
 */
 
-use crate::types::Solution;
-use crate::utils::{self, GroupBy3};
-
-use anyhow::Result;
+use crate::prelude::*;
 
 use std::collections::BTreeSet;
 
-struct Rucksack {
+pub struct Rucksack {
     compartment_a: BTreeSet<char>,
     compartment_b: BTreeSet<char>,
     full_rucksack: BTreeSet<char>,
 }
 
 impl Rucksack {
-    fn common_char(&self) -> char {
-        *self
-            .compartment_a
+    fn common_char(&self) -> Result<char> {
+        self.compartment_a
             .intersection(&self.compartment_b)
             .next()
-            .unwrap()
+            .copied()
+            .ok_or_else(|| Error::NoSolution.into())
     }
 
-    fn common_char_in_group(elf_a: &Self, elf_b: &Self, elf_c: &Self) -> char {
+    fn common_char_in_group(elf_a: &Self, elf_b: &Self, elf_c: &Self) -> Result<char> {
         let a_b_isect = elf_a
             .full_rucksack
             .intersection(&elf_b.full_rucksack)
             .cloned()
             .collect::<BTreeSet<_>>();
-        *a_b_isect.intersection(&elf_c.full_rucksack).next().unwrap()
+        a_b_isect
+            .intersection(&elf_c.full_rucksack)
+            .next()
+            .copied()
+            .ok_or_else(|| Error::NoSolution.into())
     }
 }
 
-impl From<&str> for Rucksack {
-    fn from(s: &str) -> Self {
+impl TryFrom<&str> for Rucksack {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        if let Some(c) = s.chars().find(|c| !c.is_ascii_alphabetic()) {
+            return Err(format!("invalid item {:?}", c));
+        }
         let length = s.len();
+        if !length.is_multiple_of(2) {
+            return Err(format!("uneven compartments in {:?}", s));
+        }
         let half = length / 2;
         let compartment_a_str = &s[..half];
         let compartment_b_str = &s[half..length];
         let compartment_a = compartment_a_str.chars().collect();
         let compartment_b = compartment_b_str.chars().collect();
         let full_rucksack = s.chars().collect();
-        Self {
+        Ok(Self {
             compartment_a,
             compartment_b,
             full_rucksack,
-        }
+        })
     }
 }
 
@@ -63,31 +72,67 @@ fn priority(ch: char) -> u64 {
     }
 }
 
-pub fn run(input: String) -> Result<Solution> {
-    let mut solution = Solution::new();
-    // parse into rucksacks
-    let rucksacks = utils::split_lines(&input)
-        .map(Rucksack::from)
-        .collect::<Vec<_>>();
-
-    // part 1: Find the item type that appears in both compartments of each
-    // rucksack. What is the sum of the priorities of those item types?
-    let priority_sum = rucksacks
-        .iter()
-        .map(|rucksack| rucksack.common_char())
-        .map(priority)
-        .sum::<u64>();
-    solution.set_part_1(priority_sum);
-
-    // part 2: Find the item type that corresponds to the badges of each
-    // three-Elf group. What is the sum of the priorities of those item types?
-    let elf_groups = rucksacks.iter().group_by_3().collect::<Vec<_>>();
-    let group_priority_sum = elf_groups
-        .iter()
-        .map(|(a, b, c)| Rucksack::common_char_in_group(a, b, c))
-        .map(priority)
-        .sum::<u64>();
-    solution.set_part_2(group_priority_sum);
-
-    Ok(solution)
+pub struct Day3;
+
+impl Puzzle for Day3 {
+    type Parsed = Vec<Rucksack>;
+
+    const DAY: usize = 3;
+    const TITLE: &'static str = "Rucksack Reorganization";
+    const METADATA: Metadata = Metadata {
+        algorithm: "set intersection",
+        complexity: "O(n)",
+        tags: &["sets", "strings"],
+    };
+    const SAMPLE_ANSWERS: [Option<&'static str>; 2] = [Some("157"), Some("70")];
+    const ANSWERS: [Option<&'static str>; 2] = [Some("8072"), Some("2567")];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Parsed> {
+        // parse into rucksacks
+        input.parse_lines(Rucksack::try_from)
+    }
+
+    fn part_1(rucksacks: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 1: Find the item type that appears in both compartments of each
+        // rucksack. What is the sum of the priorities of those item types?
+        let priority_sum = rucksacks
+            .iter()
+            .map(|rucksack| rucksack.common_char().map(priority))
+            .sum::<Result<u64>>()?;
+        Ok(priority_sum.into())
+    }
+
+    fn part_2(rucksacks: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 2: Find the item type that corresponds to the badges of each
+        // three-Elf group. What is the sum of the priorities of those item
+        // types?
+        let elf_groups = rucksacks.iter().group_by_n::<3>().collect::<Vec<_>>();
+        let group_priority_sum = elf_groups
+            .iter()
+            .map(|[a, b, c]| Rucksack::common_char_in_group(a, b, c).map(priority))
+            .sum::<Result<u64>>()?;
+        Ok(group_priority_sum.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malformed_rucksacks() {
+        let e = Day3::parse(PuzzleInput::new(3, "vJrwpWtwJgWrhcsFMMfFFhFp\nabc"))
+            .err()
+            .unwrap();
+        assert_eq!(
+            e.to_string(),
+            "day 3 input, line 2: uneven compartments in \"abc\""
+        );
+        let e = Day3::parse(PuzzleInput::new(3, "ab1b")).err().unwrap();
+        assert_eq!(e.to_string(), "day 3 input, line 1: invalid item '1'");
+        // compartments without an item in common
+        let rucksacks = Day3::parse(PuzzleInput::new(3, "abcd")).unwrap();
+        let err = Day3::part_1(&rucksacks, &Context::none()).unwrap_err();
+        assert!(matches!(err.downcast_ref::<Error>(), Some(Error::NoSolution)));
+    }
 }
@@ -3,75 +3,103 @@
 ** https://adventofcode.com/2022/day/4
 */
 
-use crate::types::Solution;
-use crate::utils;
+use crate::interval::Interval;
+use crate::prelude::*;
 
-use anyhow::Result;
-
-type Pair = (u8, u8);
-
-struct AssignmentPair {
-    x: Pair,
-    y: Pair,
+pub struct AssignmentPair {
+    x: Interval,
+    y: Interval,
 }
 
 impl AssignmentPair {
-    fn parse_pair(s: &str) -> Pair {
-        let split = s.find('-').unwrap();
-        let a = &s[..split].parse().unwrap();
-        let b = &s[(split + 1)..s.len()].parse().unwrap();
-        (*a, *b)
+    fn parse_interval(s: &str) -> Result<Interval, String> {
+        let (min, max) = s
+            .split_once('-')
+            .ok_or_else(|| format!("expected a range, found {:?}", s))?;
+        let min = utils::parse_field(min, "section")?;
+        let max = utils::parse_field(max, "section")?;
+        Interval::try_new(min, max).ok_or_else(|| format!("reversed range {:?}", s))
     }
 
     fn pair_contains_other(&self) -> bool {
-        // x is a smaller pair than y
-        self.y.0 <= self.x.0 && self.y.1 >= self.x.1
+        self.x.contains_interval(&self.y) || self.y.contains_interval(&self.x)
     }
 
     fn pairs_overlap(&self) -> bool {
-        if self.x.0 < self.y.0 {
-            self.y.0 <= self.x.1
-        } else {
-            self.x.0 <= self.y.1
-        }
+        self.x.overlaps(&self.y)
     }
 }
 
-impl From<&str> for AssignmentPair {
-    fn from(s: &str) -> Self {
-        let split = s.find(',').unwrap();
-        let a = Self::parse_pair(&s[..split]);
-        let b = Self::parse_pair(&s[(split + 1)..s.len()]);
-        // set the smaller pair as x and the larger as y
-        if a.1 - a.0 < b.1 - b.0 {
-            Self { x: a, y: b }
-        } else {
-            Self { x: b, y: a }
-        }
+impl TryFrom<&str> for AssignmentPair {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let (x, y) = s
+            .split_once(',')
+            .ok_or_else(|| format!("expected a pair of ranges, found {:?}", s))?;
+        let x = Self::parse_interval(x)?;
+        let y = Self::parse_interval(y)?;
+        Ok(Self { x, y })
     }
 }
 
-pub fn run(input: String) -> Result<Solution> {
-    let mut solution = Solution::new();
-    // parse into assignment pairs
-    let assignment_pairs = utils::split_lines(&input)
-        .map(AssignmentPair::from)
-        .collect::<Vec<_>>();
+pub struct Day4;
+
+impl Puzzle for Day4 {
+    type Parsed = Vec<AssignmentPair>;
+
+    const DAY: usize = 4;
+    const TITLE: &'static str = "Camp Cleanup";
+    const METADATA: Metadata = Metadata {
+        algorithm: "range overlap",
+        complexity: "O(n)",
+        tags: &["intervals"],
+    };
+    const SAMPLE_ANSWERS: [Option<&'static str>; 2] = [Some("2"), Some("4")];
+    const ANSWERS: [Option<&'static str>; 2] = [Some("518"), Some("909")];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Parsed> {
+        // parse into assignment pairs
+        input.parse_lines(AssignmentPair::try_from)
+    }
+
+    fn part_1(assignment_pairs: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 1: In how many assignment pairs does one range fully contain the
+        // other?
+        let contain_count = assignment_pairs
+            .iter()
+            .filter(|x| x.pair_contains_other())
+            .count();
+        Ok(contain_count.into())
+    }
 
-    // part 1: In how many assignment pairs does one range fully contain the
-    // other?
-    let contain_count = assignment_pairs
-        .iter()
-        .filter(|x| x.pair_contains_other())
-        .count();
-    solution.set_part_1(contain_count);
+    fn part_2(assignment_pairs: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 2: In how many assignment pairs do the ranges overlap?
+        let overlap_count = assignment_pairs
+            .iter()
+            .filter(|x| x.pairs_overlap())
+            .count();
+        Ok(overlap_count.into())
+    }
+}
 
-    // part 2: In how many assignment pairs do the ranges overlap?
-    let overlap_count = assignment_pairs
-        .iter()
-        .filter(|x| x.pairs_overlap())
-        .count();
-    solution.set_part_2(overlap_count);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    Ok(solution)
+    #[test]
+    fn malformed_pairs() {
+        let e = Day4::parse(PuzzleInput::new(4, "2-4,6-8\n5-3,1-9")).err().unwrap();
+        assert_eq!(e.to_string(), "day 4 input, line 2: reversed range \"5-3\"");
+        let e = Day4::parse(PuzzleInput::new(4, "2-4")).err().unwrap();
+        assert_eq!(
+            e.to_string(),
+            "day 4 input, line 1: expected a pair of ranges, found \"2-4\""
+        );
+        let e = Day4::parse(PuzzleInput::new(4, "2-4,6")).err().unwrap();
+        assert_eq!(
+            e.to_string(),
+            "day 4 input, line 1: expected a range, found \"6\""
+        );
+    }
 }
@@ -3,44 +3,93 @@
 ** https://adventofcode.com/2022/day/5
 */
 
-use crate::types::Solution;
-use crate::utils;
-
-use anyhow::Result;
+use crate::prelude::*;
 
 const N_STACKS: usize = 9;
 
-struct Move {
+pub struct Move {
     n_crates: u8,
     from: u8,
     to: u8,
 }
 
-impl From<&str> for Move {
-    fn from(s: &str) -> Self {
+impl TryFrom<&str> for Move {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
         let words = s.split(' ').collect::<Vec<_>>();
-        let n_crates = words[1].parse().unwrap();
-        let from = words[3].parse().unwrap();
-        let to = words[5].parse().unwrap();
-        Self { n_crates, from, to }
+        let (n_crates, from, to) = match words.as_slice() {
+            &["move", n_crates, "from", from, "to", to] => (n_crates, from, to),
+            _ => return Err(format!("expected a move, found {:?}", s)),
+        };
+        let n_crates = utils::parse_field(n_crates, "number of crates")?;
+        let from = parse_stack(from)?;
+        let to = parse_stack(to)?;
+        Ok(Self { n_crates, from, to })
+    }
+}
+
+/// parses a stack number, which counts from 1
+fn parse_stack(s: &str) -> Result<u8, String> {
+    let stack = utils::parse_field(s, "stack")?;
+    if (1..=N_STACKS as u8).contains(&stack) {
+        Ok(stack)
+    } else {
+        Err(format!("no stack {}", stack))
     }
 }
 
 #[derive(Clone)]
-struct Stacks {
+pub struct Stacks {
     stacks: [Vec<char>; N_STACKS],
+    /// the number of stacks in the drawing, which may not use all of them
+    n_stacks: usize,
     buffer: Vec<char>,
 }
 
 impl Stacks {
     fn top(&self) -> String {
-        self.stacks.iter().map(|s| s[s.len() - 1]).collect()
+        // not all stacks may be in use
+        self.stacks.iter().filter_map(|s| s.last()).collect()
+    }
+
+    /// checks that each move is between stacks of the drawing and takes no
+    /// more crates than its stack holds, numbering the moves from first_line
+    fn check_moves(&self, moves: &[Move], first_line: usize) -> Result<()> {
+        let mut heights = self.stacks.iter().map(Vec::len).collect::<Vec<_>>();
+        for (i, m) in moves.iter().enumerate() {
+            let error = |reason| Error::Parse {
+                day: 5,
+                line: first_line + i,
+                reason,
+            };
+            if let Some(stack) = [m.from, m.to]
+                .into_iter()
+                .find(|&stack| stack as usize > self.n_stacks)
+            {
+                return Err(error(format!("no stack {}", stack)).into());
+            }
+            let from = (m.from - 1) as usize;
+            let to = (m.to - 1) as usize;
+            let n_crates = m.n_crates as usize;
+            if heights[from] < n_crates {
+                return Err(error(format!(
+                    "cannot move {} crates from stack {} of {}",
+                    n_crates, m.from, heights[from]
+                ))
+                .into());
+            }
+            heights[from] -= n_crates;
+            heights[to] += n_crates;
+        }
+        Ok(())
     }
 
     fn crate_mover_9000(&mut self, m: &Move) {
         let from = (m.from - 1) as usize;
         let to = (m.to - 1) as usize;
         for _ in 0..m.n_crates {
+            // there are always enough crates, see check_moves
             let crate_name = self.stacks[from].pop().unwrap();
             self.stacks[to].push(crate_name);
         }
@@ -51,6 +100,7 @@ impl Stacks {
         let to = (m.to - 1) as usize;
         // first load crates into the buffer
         for _ in 0..m.n_crates {
+            // there are always enough crates, see check_moves
             let crate_name = self.stacks[from].pop().unwrap();
             self.buffer.push(crate_name);
         }
@@ -65,12 +115,15 @@ impl From<&str> for Stacks {
     fn from(s: &str) -> Self {
         let mut stacks: [Vec<char>; N_STACKS] = Default::default();
         let lines = utils::split_lines(s).collect::<Vec<_>>();
+        // the last line numbers the stacks
+        let n_stacks = utils::numbers::<usize>(lines[lines.len() - 1])
+            .len()
+            .min(N_STACKS);
 
         for line in lines[..(lines.len() - 1)].iter().rev() {
-            let n_cols = (line.len() + 1) / 4;
-            for (col, stack) in stacks.iter_mut().enumerate().take(n_cols) {
-                let i = col * 4 + 1;
-                let crate_name = line[i..(i + 1)].chars().next().unwrap();
+            // crate names are in every 4th column, from the 2nd
+            let crate_names = line.chars().skip(1).step_by(4);
+            for (stack, crate_name) in stacks.iter_mut().take(n_stacks).zip(crate_names) {
                 if crate_name != ' ' {
                     stack.push(crate_name);
                 }
@@ -79,42 +132,95 @@ impl From<&str> for Stacks {
 
         Self {
             stacks,
+            n_stacks,
             buffer: Vec::new(),
         }
     }
 }
 
-pub fn run(input: String) -> Result<Solution> {
-    let mut solution = Solution::new();
-    // parse the initial stacks and move list
-    let (mut stacks_1, moves) = match input.split("\n\n").collect::<Vec<_>>().as_slice() {
-        &[stacks_str, moves_str] => {
-            let stacks = Stacks::from(stacks_str);
-            let moves = utils::split_lines(moves_str)
-                .map(Move::from)
-                .collect::<Vec<_>>();
-            (stacks, moves)
+fn parse_input(input: PuzzleInput) -> Result<(Stacks, Vec<Move>)> {
+    let mut blocks = input.blocks();
+    let (Some(stacks), Some(moves)) = (blocks.next(), blocks.next()) else {
+        return Err(Error::Parse {
+            day: 5,
+            line: input.lines().count(),
+            reason: String::from("expected a blank line between the stacks and the moves"),
         }
-        _ => unreachable!(),
+        .into());
     };
-    // clone for part 2
-    let mut stacks_2 = stacks_1.clone();
+    let first_move = stacks.lines().count() + 2;
+    let stacks = Stacks::from(stacks.as_str());
+    let moves = moves.parse_lines(Move::try_from)?;
+    stacks.check_moves(&moves, first_move)?;
+    Ok((stacks, moves))
+}
 
-    // part 1: After the rearrangement procedure completes, what crate ends up
-    // on top of each stack?
-    for m in moves.iter() {
-        stacks_1.crate_mover_9000(m);
+pub struct Day5;
+
+impl Puzzle for Day5 {
+    /// the initial stacks and the move list
+    type Parsed = (Stacks, Vec<Move>);
+
+    const DAY: usize = 5;
+    const TITLE: &'static str = "Supply Stacks";
+    const METADATA: Metadata = Metadata {
+        algorithm: "stack simulation",
+        complexity: "O(moves * crates)",
+        tags: &["simulation", "parsing"],
+    };
+    const SAMPLE_ANSWERS: [Option<&'static str>; 2] = [Some("CMZ"), Some("MCD")];
+    const ANSWERS: [Option<&'static str>; 2] = [Some("RLFNRTNFB"), Some("MHQTLJRLB")];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Parsed> {
+        parse_input(input)
     }
-    solution.set_part_1(stacks_1.top());
-
-    // part 2: Before the rearrangement process finishes, update your
-    // simulation so that the Elves know where they should stand to be ready to
-    // unload the final supplies. After the rearrangement procedure completes,
-    // what crate ends up on top of each stack?
-    for m in moves.iter() {
-        stacks_2.crate_mover_9001(m);
+
+    fn part_1((stacks, moves): &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 1: After the rearrangement procedure completes, what crate ends
+        // up on top of each stack?
+        let mut stacks = stacks.clone();
+        for m in moves.iter() {
+            stacks.crate_mover_9000(m);
+        }
+        Ok(stacks.top().into())
     }
-    solution.set_part_2(stacks_2.top());
 
-    Ok(solution)
+    fn part_2((stacks, moves): &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 2: Before the rearrangement process finishes, update your
+        // simulation so that the Elves know where they should stand to be ready
+        // to unload the final supplies. After the rearrangement procedure
+        // completes, what crate ends up on top of each stack?
+        let mut stacks = stacks.clone();
+        for m in moves.iter() {
+            stacks.crate_mover_9001(m);
+        }
+        Ok(stacks.top().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malformed_moves() {
+        let stacks = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 ";
+        let input = format!("{}\n\nmove 1 from 2 to 1\nmove 3 from 0 to 3", stacks);
+        let e = parse_input(PuzzleInput::new(5, &input)).err().unwrap();
+        assert_eq!(e.to_string(), "day 5 input, line 7: no stack 0");
+        let input = format!("{}\n\nmove 1 from 2 to 1\nmove 1 from 2 to 4", stacks);
+        let e = parse_input(PuzzleInput::new(5, &input)).err().unwrap();
+        assert_eq!(e.to_string(), "day 5 input, line 7: no stack 4");
+        let input = format!("{}\n\nmove 1 from 2 to 1\nmove 4 from 1 to 3", stacks);
+        let e = parse_input(PuzzleInput::new(5, &input)).err().unwrap();
+        assert_eq!(
+            e.to_string(),
+            "day 5 input, line 7: cannot move 4 crates from stack 1 of 3"
+        );
+        let e = parse_input(PuzzleInput::new(5, stacks)).err().unwrap();
+        assert_eq!(
+            e.to_string(),
+            "day 5 input, line 4: expected a blank line between the stacks and the moves"
+        );
+    }
 }
@@ -3,9 +3,7 @@
 ** https://adventofcode.com/2022/day/6
 */
 
-use crate::types::{Error, Solution};
-
-use anyhow::Result;
+use crate::prelude::*;
 
 const N_CHARS: usize = 26;
 const CHAR_BASE: u32 = 'a' as u32;
@@ -50,66 +48,108 @@ impl UniqueCharCounter {
     }
 }
 
-pub fn run(input: String) -> Result<Solution> {
-    let mut solution = Solution::new();
-    // split input into an array of characters
-    let stream = input.chars().collect::<Vec<_>>();
+/// finds the number of characters processed before the first marker, which is
+/// the given number of unique characters
+fn find_marker(stream: &[char], marker_size: usize) -> Result<usize> {
     let size = stream.len();
-    // initialize counters for start-of-packet and start-of-message searches
-    let mut packet_char_counter = UniqueCharCounter::new();
-    let mut message_char_counter = UniqueCharCounter::new();
-
-    // part 1: How many characters need to be processed before the first
-    // start-of-packet marker is detected?
-
+    if size < marker_size {
+        return Err(Error::NoSolution.into());
+    }
+    let mut char_counter = UniqueCharCounter::new();
     // initialize with the first characters
-    for c in &stream[..PACKET_MARKER_SIZE] {
-        packet_char_counter.add(*c);
+    for c in &stream[..marker_size] {
+        char_counter.add(*c);
     }
-    // then use a sliding window to find the start-of-packet marker
+    // then use a sliding window to find the marker
     let mut wi = 0;
-    let mut wj = PACKET_MARKER_SIZE;
-    while wj < size && !packet_char_counter.all_unique() {
+    let mut wj = marker_size;
+    while wj < size && !char_counter.all_unique() {
         // add the next character to the window and remove the character from
         // the start of the old window
-        packet_char_counter.remove(stream[wi]);
-        packet_char_counter.add(stream[wj]);
+        char_counter.remove(stream[wi]);
+        char_counter.add(stream[wj]);
         wi += 1;
         wj += 1;
     }
 
-    let start_of_packet = if wj == size {
-        Err(Error::NoSolution)
-    } else {
+    if char_counter.all_unique() {
         Ok(wj)
-    };
-    solution.set_part_1(start_of_packet?);
+    } else {
+        Err(Error::NoSolution.into())
+    }
+}
 
-    // part 2: How many characters need to be processed before the first
-    // start-of-message marker is detected?
+pub struct Day6;
 
-    // initialize with the first characters
-    for c in &stream[..MESSAGE_MARKER_SIZE] {
-        message_char_counter.add(*c);
+impl Puzzle for Day6 {
+    type Parsed = Vec<char>;
+
+    const DAY: usize = 6;
+    const TITLE: &'static str = "Tuning Trouble";
+    const METADATA: Metadata = Metadata {
+        algorithm: "sliding window",
+        complexity: "O(n)",
+        tags: &["strings"],
+    };
+    const SAMPLE_ANSWERS: [Option<&'static str>; 2] = [Some("7"), Some("19")];
+    const ANSWERS: [Option<&'static str>; 2] = [Some("1723"), Some("3708")];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Parsed> {
+        // split input into an array of characters, which are all lowercase
+        // letters on a single line
+        let mut lines = input.parse_lines(|line| {
+            match line.chars().find(|c| !c.is_ascii_lowercase()) {
+                Some(c) => Err(format!("invalid character {:?} in the stream", c)),
+                None => Ok(line.chars().collect::<Vec<_>>()),
+            }
+        })?;
+        if lines.len() != 1 {
+            return Err(Error::Parse {
+                day: 6,
+                line: 2,
+                reason: String::from("expected the stream on a single line"),
+            }
+            .into());
+        }
+        Ok(lines.remove(0))
     }
-    // then use a sliding window to find the start-of-packet marker
-    let mut wi = 0;
-    let mut wj = MESSAGE_MARKER_SIZE;
-    while wj < size && !message_char_counter.all_unique() {
-        // add the next character to the window and remove the character from
-        // the start of the old window
-        message_char_counter.remove(stream[wi]);
-        message_char_counter.add(stream[wj]);
-        wi += 1;
-        wj += 1;
+
+    fn part_1(stream: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 1: How many characters need to be processed before the first
+        // start-of-packet marker is detected?
+        Ok(find_marker(stream, PACKET_MARKER_SIZE)?.into())
     }
 
-    let start_of_message = if wj == size {
-        Err(Error::NoSolution)
-    } else {
-        Ok(wj)
-    };
-    solution.set_part_2(start_of_message?);
+    fn part_2(stream: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 2: How many characters need to be processed before the first
+        // start-of-message marker is detected?
+        Ok(find_marker(stream, MESSAGE_MARKER_SIZE)?.into())
+    }
+}
 
-    Ok(solution)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malformed_streams() {
+        let e = Day6::parse(PuzzleInput::new(6, "mjqjpqmgb#j5sphdtnvjfqwrcgsmlb"))
+            .err()
+            .unwrap();
+        assert_eq!(
+            e.to_string(),
+            "day 6 input, line 1: invalid character '#' in the stream"
+        );
+        let e = Day6::parse(PuzzleInput::new(6, "mjqj\npqmg")).err().unwrap();
+        assert_eq!(
+            e.to_string(),
+            "day 6 input, line 2: expected the stream on a single line"
+        );
+        // streams too short for a marker
+        let stream = Day6::parse(PuzzleInput::new(6, "ab")).unwrap();
+        let err = Day6::part_1(&stream, &Context::none()).unwrap_err();
+        assert!(matches!(err.downcast_ref::<Error>(), Some(Error::NoSolution)));
+        // a marker which ends the stream
+        assert_eq!(find_marker(&['a', 'b', 'c', 'd'], 4).unwrap(), 4);
+    }
 }
@@ -3,19 +3,39 @@
 ** https://adventofcode.com/2022/day/7
 */
 
-use crate::types::Solution;
-use crate::utils;
-
-use anyhow::Result;
-use log::debug;
+use crate::explain;
+use crate::prelude::*;
 
 use std::collections::HashMap;
 use std::iter::FromIterator;
 use std::mem;
 use std::path::PathBuf;
 
-const CD_LEN: usize = 5;
-const DIR_LEN: usize = 4;
+/// a line of the terminal output
+enum Line<'a> {
+    Cd(&'a str),
+    Ls,
+    Dir(&'a str),
+    File(u64, &'a str),
+}
+
+impl<'a> TryFrom<&'a str> for Line<'a> {
+    type Error = String;
+
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        let words = s.split(' ').collect::<Vec<_>>();
+        match words[..] {
+            ["$", "cd", name] => Ok(Self::Cd(name)),
+            ["$", "ls"] => Ok(Self::Ls),
+            ["dir", name] => Ok(Self::Dir(name)),
+            [size, name] => Ok(Self::File(utils::parse_field(size, "file size")?, name)),
+            _ => Err(format!(
+                "expected a command or a directory entry, found {:?}",
+                s
+            )),
+        }
+    }
+}
 
 #[derive(Clone, Debug)]
 struct DirListing<'a> {
@@ -72,78 +92,93 @@ impl<T> FromIterator<T> for SinkDrainBuffer<T> {
 }
 
 fn path_from_stack(dir_stack: &[&str]) -> PathBuf {
-    PathBuf::from("/").join(&dir_stack[1..dir_stack.len()].join("/"))
+    PathBuf::from("/").join(dir_stack[1..dir_stack.len()].join("/"))
 }
 
-fn parse_dir_listings(input: &str) -> Vec<DirListing<'_>> {
-    let lines = utils::split_lines(input).collect::<Vec<_>>();
-    let nlines = lines.len();
+fn parse_dir_listings(input: PuzzleInput<'_>) -> Result<Vec<DirListing<'_>>> {
+    let parse_error = |line: usize, reason: String| Error::Parse {
+        day: 7,
+        line,
+        reason,
+    };
+    let lines = input.parse_lines(Line::try_from)?;
 
     let mut listings = Vec::new();
+    let mut listing: Option<DirListing> = None;
     let mut dir_stack = Vec::new();
+    // the line of the entry of each sub-directory which has not been listed
+    let mut unlisted = HashMap::new();
 
     // iterate over each line and group into directory listings
-    let mut i = 0;
-    while i < nlines {
-        let line = &lines[i];
-        // the first line in each directory listing is a cd into the directory
-        // grab the directory name
-        let name = &line[CD_LEN..line.len()];
-        if name == ".." {
-            // if this is a cd into the parent directory, pop the new current
-            // working off the directory stack and continue
-            let _ = dir_stack.pop().unwrap();
-            debug!(
-                "line {:03}: changing to parent directory {:?}",
-                i,
-                path_from_stack(&dir_stack)
-            );
-            i += 1;
-        } else {
-            // otherwise set it as the current working directory and add it to
-            // the directory stack
-            dir_stack.push(name);
-            let path = path_from_stack(&dir_stack);
-            debug!("line {:03}: changing to directory {:?}", i, path);
-            // the next line will be an ls command
-            i += 2;
-            // parse the directory entries until the next cd is reached
-            let mut file_sizes = 0;
-            let mut subdirs = Vec::new();
-            while i < nlines && !lines[i].starts_with('$') {
-                let line = &lines[i];
-                if line.starts_with("dir") {
-                    // this is a subdirectory entry
-                    // grab the name and add it to the list
-                    let subdir = &line[DIR_LEN..line.len()];
+    for (i, line) in lines.into_iter().enumerate() {
+        match line {
+            Line::Cd(name) => {
+                // each cd ends the listing of the current working directory
+                listings.extend(listing.take());
+                if name == ".." {
+                    // if this is a cd into the parent directory, pop the new
+                    // current working directory off the directory stack
+                    if dir_stack.len() < 2 {
+                        let reason = String::from("no parent directory to change to");
+                        return Err(parse_error(i + 1, reason).into());
+                    }
+                    dir_stack.pop();
                     debug!(
-                        "line {:03}: directory {:?} has sub-directory {}",
-                        i, path, subdir
+                        "line {:03}: changing to parent directory {:?}",
+                        i,
+                        path_from_stack(&dir_stack)
                     );
-                    subdirs.push(subdir);
-                    i += 1;
                 } else {
-                    // otherwise this is a file entry
-                    // grab the file size and add it to the sum
-                    let sep = line.find(' ').unwrap();
-                    let size = line[..sep].parse::<u64>().unwrap();
-                    let file = &line[(sep + 1)..line.len()];
-                    debug!(
-                        "line {:03}: directory {:?} has file {} with size {}",
-                        i, path, file, size
-                    );
-                    file_sizes += size;
-                    i += 1;
+                    // otherwise set it as the current working directory and
+                    // start its listing
+                    if name == "/" {
+                        dir_stack.clear();
+                    }
+                    dir_stack.push(name);
+                    let path = path_from_stack(&dir_stack);
+                    debug!("line {:03}: changing to directory {:?}", i, path);
+                    unlisted.remove(&path);
+                    listing = Some(DirListing::new(path, 0, Vec::new()));
                 }
             }
-            // finally, create the directory listing object and add to the list
-            let listing = DirListing::new(path, file_sizes, subdirs);
-            debug!("adding new listing {:?}", listing);
-            listings.push(listing);
+            Line::Ls => {}
+            Line::Dir(subdir) => {
+                // this is a subdirectory entry, add it to the list
+                let Some(listing) = listing.as_mut() else {
+                    let reason = String::from("expected a cd before the directory entries");
+                    return Err(parse_error(i + 1, reason).into());
+                };
+                debug!(
+                    "line {:03}: directory {:?} has sub-directory {}",
+                    i, listing.path, subdir
+                );
+                unlisted.entry(listing.path.join(subdir)).or_insert(i + 1);
+                listing.subdirs.push(subdir);
+            }
+            Line::File(size, file) => {
+                // otherwise this is a file entry, add its size to the sum
+                let Some(listing) = listing.as_mut() else {
+                    let reason = String::from("expected a cd before the directory entries");
+                    return Err(parse_error(i + 1, reason).into());
+                };
+                debug!(
+                    "line {:03}: directory {:?} has file {} with size {}",
+                    i, listing.path, file, size
+                );
+                listing.file_sizes += size;
+            }
         }
     }
+    listings.extend(listing);
 
-    listings
+    // the size of a directory is only known once all of its sub-directories
+    // have been listed
+    if let Some((path, &line)) = unlisted.iter().min_by_key(|(_, &line)| line) {
+        let reason = format!("directory {:?} is never listed", path);
+        return Err(parse_error(line, reason).into());
+    }
+
+    Ok(listings)
 }
 
 fn calculate_dir_sizes<'a>(listings: &'a [DirListing<'a>]) -> HashMap<&'a PathBuf, u64> {
@@ -186,42 +221,166 @@ fn calculate_dir_sizes<'a>(listings: &'a [DirListing<'a>]) -> HashMap<&'a PathBu
     sizes
 }
 
-pub fn run(input: String) -> Result<Solution> {
-    let mut solution = Solution::new();
-    // parse the directory listings out of the input
-    let listings = parse_dir_listings(&input);
-    // and calculate the size of each directory in the tree
-    let dir_sizes = calculate_dir_sizes(&listings);
-
-    // part 1: Find all of the directories with a total size of at most 100000.
-    // What is the sum of the total sizes of those directories?
-    let max_size = 100000;
-    let dir_size_sum = dir_sizes
-        .iter()
-        .filter(|(_, &size)| size <= max_size)
-        .map(|(_, &size)| size)
-        .sum::<u64>();
-    solution.set_part_1(dir_size_sum);
-
-    // part 2: Find the smallest directory that, if deleted, would free up
-    // enough space on the filesystem to run the update. What is the total size
-    // of that directory?
-    let space_available = 70000000;
-    let update_space = 30000000;
-    let max_space_for_update = space_available - update_space;
-    let total_size = *dir_sizes.get(&PathBuf::from("/")).unwrap() as i64;
-    let space_to_delete = total_size - max_space_for_update;
-    // we need a directory that is larger than the space needed to delete but
-    // to minimize this gap, use the difference as the sort key and find the
-    // smallest negative number
-    let (dir_to_delete, _) = dir_sizes
+fn render_tree_rec(
+    listings: &HashMap<&PathBuf, &DirListing<'_>>,
+    dir_sizes: &HashMap<&PathBuf, u64>,
+    path: &PathBuf,
+    depth: usize,
+    lines: &mut Vec<String>,
+) {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| String::from("/"));
+    let size = dir_sizes.get(path).copied().unwrap_or(0);
+    lines.push(format!(
+        "{}- {} (dir, size={})",
+        "  ".repeat(depth),
+        name,
+        size
+    ));
+    if let Some(listing) = listings.get(path) {
+        for subdir in listing.subdirs.iter() {
+            let subdir_path = path.join(subdir);
+            render_tree_rec(listings, dir_sizes, &subdir_path, depth + 1, lines);
+        }
+    }
+}
+
+/// renders the directory tree along with the total size of each directory
+fn render_tree(listings: &[DirListing<'_>], dir_sizes: &HashMap<&PathBuf, u64>) -> String {
+    let listings = listings
         .iter()
-        .map(|(path, &size)| (path, space_to_delete - (size as i64)))
-        .filter(|(_, size)| *size <= 0)
-        .max_by_key(|(_, size)| *size)
-        .unwrap();
-    let deleted_dir_size = *dir_sizes.get(dir_to_delete).unwrap();
-    solution.set_part_2(deleted_dir_size);
-
-    Ok(solution)
+        .map(|listing| (&listing.path, listing))
+        .collect::<HashMap<_, _>>();
+    let mut lines = Vec::new();
+    render_tree_rec(&listings, dir_sizes, &PathBuf::from("/"), 0, &mut lines);
+    lines.join("\n")
+}
+
+/// the size of each directory in the tree, along with the rendered tree
+pub struct FileSystem {
+    dir_sizes: HashMap<PathBuf, u64>,
+    tree: String,
+}
+
+pub struct Day7;
+
+impl Puzzle for Day7 {
+    type Parsed = FileSystem;
+
+    const DAY: usize = 7;
+    const TITLE: &'static str = "No Space Left On Device";
+    const METADATA: Metadata = Metadata {
+        algorithm: "directory tree",
+        complexity: "O(n)",
+        tags: &["trees", "parsing"],
+    };
+    const SAMPLE_ANSWERS: [Option<&'static str>; 2] = [Some("95437"), Some("24933642")];
+    const ANSWERS: [Option<&'static str>; 2] = [Some("919137"), Some("2877389")];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Parsed> {
+        // parse the directory listings out of the input
+        let listings = parse_dir_listings(input)?;
+        // and calculate the size of each directory in the tree
+        let dir_sizes = calculate_dir_sizes(&listings);
+        let tree = render_tree(&listings, &dir_sizes);
+        let dir_sizes = dir_sizes
+            .into_iter()
+            .map(|(path, size)| (path.clone(), size))
+            .collect();
+        Ok(FileSystem { dir_sizes, tree })
+    }
+
+    fn part_1(fs: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 1: Find all of the directories with a total size of at most
+        // 100000. What is the sum of the total sizes of those directories?
+        let dir_sizes = &fs.dir_sizes;
+        let max_size = 100000;
+        let dir_size_sum = dir_sizes
+            .iter()
+            .filter(|(_, &size)| size <= max_size)
+            .map(|(_, &size)| size)
+            .sum::<u64>();
+        explain!(
+            "{} of {} directories are at most {} in size",
+            dir_sizes.values().filter(|&&size| size <= max_size).count(),
+            dir_sizes.len(),
+            max_size
+        );
+        Ok(dir_size_sum.into())
+    }
+
+    fn part_2(fs: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 2: Find the smallest directory that, if deleted, would free up
+        // enough space on the filesystem to run the update. What is the total
+        // size of that directory?
+        let dir_sizes = &fs.dir_sizes;
+        let space_available = 70000000;
+        let update_space = 30000000;
+        let max_space_for_update = space_available - update_space;
+        let total_size = *dir_sizes.get(&PathBuf::from("/")).unwrap() as i64;
+        let space_to_delete = total_size - max_space_for_update;
+        explain!(
+            "{} is in use, so at least {} must be freed",
+            total_size,
+            space_to_delete
+        );
+        // we need a directory that is larger than the space needed to delete but
+        // to minimize this gap, use the difference as the sort key and find the
+        // smallest negative number
+        let (dir_to_delete, _) = dir_sizes
+            .iter()
+            .map(|(path, &size)| (path, space_to_delete - (size as i64)))
+            .filter(|(_, size)| *size <= 0)
+            .max_by_key(|(_, size)| *size)
+            .unwrap();
+        let deleted_dir_size = *dir_sizes.get(dir_to_delete).unwrap();
+        explain!(
+            "chose directory {} of size {}",
+            dir_to_delete.to_string_lossy(),
+            deleted_dir_size
+        );
+        Ok(deleted_dir_size.into())
+    }
+
+    fn extras(fs: &Self::Parsed, _solution: &Solution) -> Vec<(String, String)> {
+        vec![(String::from("directory tree"), fs.tree.clone())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malformed_output() {
+        let input = "$ cd /\n$ ls\ndir a\n14848514 b.txt\nx c.dat";
+        let e = Day7::parse(PuzzleInput::new(7, input)).err().unwrap();
+        assert_eq!(
+            e.to_string(),
+            "day 7 input, line 5: invalid file size \"x\": invalid digit found in string"
+        );
+        let e = Day7::parse(PuzzleInput::new(7, "$ ls\ndir a"))
+            .err()
+            .unwrap();
+        assert_eq!(
+            e.to_string(),
+            "day 7 input, line 2: expected a cd before the directory entries"
+        );
+        let e = Day7::parse(PuzzleInput::new(7, "$ cd /\n$ cd .."))
+            .err()
+            .unwrap();
+        assert_eq!(
+            e.to_string(),
+            "day 7 input, line 2: no parent directory to change to"
+        );
+        let e = Day7::parse(PuzzleInput::new(7, "$ cd /\n$ ls\ndir a"))
+            .err()
+            .unwrap();
+        assert_eq!(
+            e.to_string(),
+            "day 7 input, line 3: directory \"/a\" is never listed"
+        );
+    }
 }
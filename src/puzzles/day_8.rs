@@ -3,98 +3,87 @@
 ** https://adventofcode.com/2022/day/8
 */
 
-use crate::types::Solution;
-use crate::utils;
+use crate::prelude::*;
 
-use anyhow::Result;
-use log::debug;
-
-use std::cmp;
-
-#[cfg(feature = "sample")]
-const SIZE: usize = 5;
-#[cfg(not(feature = "sample"))]
-const SIZE: usize = 99;
-
-const fn is_exterior(row: usize, col: usize) -> bool {
-    row == 0 || col == 0 || row == SIZE - 1 || col == SIZE - 1
+fn is_exterior(heights: &Grid<u32>, row: usize, col: usize) -> bool {
+    row == 0 || col == 0 || row == heights.height() - 1 || col == heights.width() - 1
 }
 
-fn is_visible_up(heights: &[[u32; SIZE]; SIZE], row: usize, col: usize) -> bool {
-    let height = heights[row][col];
-    (0..row).all(|i| heights[i][col] < height)
+fn is_visible_up(heights: &Grid<u32>, row: usize, col: usize) -> bool {
+    let height = heights[(row, col)];
+    (0..row).all(|i| heights[(i, col)] < height)
 }
 
-fn is_visible_down(heights: &[[u32; SIZE]; SIZE], row: usize, col: usize) -> bool {
-    let height = heights[row][col];
-    ((row + 1)..SIZE).all(|i| heights[i][col] < height)
+fn is_visible_down(heights: &Grid<u32>, row: usize, col: usize) -> bool {
+    let height = heights[(row, col)];
+    ((row + 1)..heights.height()).all(|i| heights[(i, col)] < height)
 }
 
-fn is_visible_left(heights: &[[u32; SIZE]; SIZE], row: usize, col: usize) -> bool {
-    let height = heights[row][col];
-    (0..col).all(|i| heights[row][i] < height)
+fn is_visible_left(heights: &Grid<u32>, row: usize, col: usize) -> bool {
+    let height = heights[(row, col)];
+    (0..col).all(|i| heights[(row, i)] < height)
 }
 
-fn is_visible_right(heights: &[[u32; SIZE]; SIZE], row: usize, col: usize) -> bool {
-    let height = heights[row][col];
-    ((col + 1)..SIZE).all(|i| heights[row][i] < height)
+fn is_visible_right(heights: &Grid<u32>, row: usize, col: usize) -> bool {
+    let height = heights[(row, col)];
+    ((col + 1)..heights.width()).all(|i| heights[(row, i)] < height)
 }
 
-fn is_visible(heights: &[[u32; SIZE]; SIZE], row: usize, col: usize) -> bool {
+fn is_visible(heights: &Grid<u32>, row: usize, col: usize) -> bool {
     // check left/right first for better cache performance
-    is_exterior(row, col)
+    is_exterior(heights, row, col)
         || is_visible_left(heights, row, col)
         || is_visible_right(heights, row, col)
         || is_visible_up(heights, row, col)
         || is_visible_down(heights, row, col)
 }
 
-fn viewing_distance_up(heights: &[[u32; SIZE]; SIZE], row: usize, col: usize) -> u64 {
-    let height = heights[row][col];
+fn viewing_distance_up(heights: &Grid<u32>, row: usize, col: usize) -> u64 {
+    let height = heights[(row, col)];
     let mut dist = 1;
     let mut i = row as i64 - 1;
-    while i > 0 && heights[i as usize][col] < height {
+    while i > 0 && heights[(i as usize, col)] < height {
         dist += 1;
         i -= 1;
     }
     dist
 }
 
-fn viewing_distance_down(heights: &[[u32; SIZE]; SIZE], row: usize, col: usize) -> u64 {
-    let height = heights[row][col];
+fn viewing_distance_down(heights: &Grid<u32>, row: usize, col: usize) -> u64 {
+    let height = heights[(row, col)];
     let mut dist = 1;
     let mut i = row as i64 + 1;
-    while (i as usize) < SIZE - 1 && heights[i as usize][col] < height {
+    while (i as usize) < heights.height() - 1 && heights[(i as usize, col)] < height {
         dist += 1;
         i += 1;
     }
     dist
 }
 
-fn viewing_distance_left(heights: &[[u32; SIZE]; SIZE], row: usize, col: usize) -> u64 {
-    let height = heights[row][col];
+fn viewing_distance_left(heights: &Grid<u32>, row: usize, col: usize) -> u64 {
+    let height = heights[(row, col)];
     let mut dist = 1;
     let mut j = col as i64 - 1;
-    while j > 0 && heights[row][j as usize] < height {
+    while j > 0 && heights[(row, j as usize)] < height {
         dist += 1;
         j -= 1;
     }
     dist
 }
 
-fn viewing_distance_right(heights: &[[u32; SIZE]; SIZE], row: usize, col: usize) -> u64 {
-    let height = heights[row][col];
+fn viewing_distance_right(heights: &Grid<u32>, row: usize, col: usize) -> u64 {
+    let height = heights[(row, col)];
     let mut dist = 1;
     let mut j = col as i64 + 1;
-    while (j as usize) < SIZE - 1 && heights[row][j as usize] < height {
+    while (j as usize) < heights.width() - 1 && heights[(row, j as usize)] < height {
         dist += 1;
         j += 1;
     }
     dist
 }
 
-fn scenic_score(heights: &[[u32; SIZE]; SIZE], row: usize, col: usize) -> u64 {
-    if is_exterior(row, col) {
+fn scenic_score(heights: &Grid<u32>, row: usize, col: usize) -> u64 {
+    if is_exterior(heights, row, col) {
         debug!("tree ({},{}) is exterior with scenic score 0", row, col);
         0
     } else {
@@ -114,38 +103,47 @@ fn scenic_score(heights: &[[u32; SIZE]; SIZE], row: usize, col: usize) -> u64 {
     }
 }
 
-pub fn run(input: String) -> Result<Solution> {
-    let mut solution = Solution::new();
-    let mut tree_heights = [[0; SIZE]; SIZE];
-    // parse the tree hights as a 2D array
-    for (i, line) in utils::split_lines(&input).enumerate() {
-        for (j, height) in line.chars().enumerate() {
-            tree_heights[i][j] = height.to_digit(10).unwrap();
-        }
+pub struct Day8;
+
+impl Puzzle for Day8 {
+    type Parsed = Grid<u32>;
+
+    const DAY: usize = 8;
+    const TITLE: &'static str = "Treetop Tree House";
+    const METADATA: Metadata = Metadata {
+        algorithm: "line of sight",
+        complexity: "O(n * (w + h))",
+        tags: &["grid"],
+    };
+    const SAMPLE_ANSWERS: [Option<&'static str>; 2] = [Some("21"), Some("8")];
+    const ANSWERS: [Option<&'static str>; 2] = [Some("1703"), Some("496650")];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Parsed> {
+        // parse the tree heights as a grid
+        input.grid(|c| {
+            c.to_digit(10)
+                .ok_or_else(|| format!("invalid tree height {:?}", c))
+        })
     }
 
-    // part 1: Consider your map; how many trees are visible from outside the
-    // grid?
-    let mut n_visible = 0u64;
-    for i in 0..SIZE {
-        for j in 0..SIZE {
-            if is_visible(&tree_heights, i, j) {
-                n_visible += 1;
-            }
-        }
+    fn part_1(tree_heights: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 1: Consider your map; how many trees are visible from outside
+        // the grid?
+        let n_visible = tree_heights
+            .coords()
+            .filter(|c| is_visible(tree_heights, c.row, c.col))
+            .count();
+        Ok(n_visible.into())
     }
-    solution.set_part_1(n_visible);
-
-    // part 2: Consider each tree on your map. What is the highest scenic score
-    // possible for any tree?
-    let mut most_scenic = 0;
-    for i in 0..SIZE {
-        for j in 0..SIZE {
-            let score = scenic_score(&tree_heights, i, j);
-            most_scenic = cmp::max(most_scenic, score);
-        }
-    }
-    solution.set_part_2(most_scenic);
 
-    Ok(solution)
+    fn part_2(tree_heights: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 2: Consider each tree on your map. What is the highest scenic
+        // score possible for any tree?
+        let most_scenic = tree_heights
+            .coords()
+            .map(|c| scenic_score(tree_heights, c.row, c.col))
+            .max()
+            .unwrap_or(0);
+        Ok(most_scenic.into())
+    }
 }
@@ -3,54 +3,38 @@
 ** https://adventofcode.com/2022/day/9
 */
 
-use crate::types::{Point, Solution};
-use crate::utils;
-
-use anyhow::Result;
-use log::debug;
-
-use std::collections::HashSet;
+use crate::prelude::*;
+use crate::viz::{Animation, Canvas, Color, Visualize};
 
 const N_KNOTS: usize = 10;
 
 #[derive(Debug)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl From<char> for Direction {
-    fn from(c: char) -> Self {
-        match c {
-            'U' => Self::Up,
-            'D' => Self::Down,
-            'L' => Self::Left,
-            'R' => Self::Right,
-            _ => unreachable!(),
-        }
-    }
-}
-
-#[derive(Debug)]
-struct Motion {
+pub struct Motion {
     direction: Direction,
     length: i64,
 }
 
-impl From<&str> for Motion {
-    fn from(s: &str) -> Self {
-        let direction = Direction::from(s.chars().next().unwrap());
-        let length = s[2..].parse().unwrap();
-        Self { direction, length }
+impl TryFrom<&str> for Motion {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let (direction, length) = s
+            .split_once(' ')
+            .ok_or_else(|| format!("expected a direction and a length, found {:?}", s))?;
+        let mut direction_chars = direction.chars();
+        let direction = match (direction_chars.next(), direction_chars.next()) {
+            (Some(c), None) => Direction::try_from(c)?,
+            _ => return Err(format!("invalid direction {:?}", direction)),
+        };
+        let length = utils::parse_field(length, "length")?;
+        Ok(Self { direction, length })
     }
 }
 
 struct Rope {
     head: Point,
     tail: Point,
-    tail_positions: HashSet<Point>,
+    tail_positions: SparseGrid<()>,
 }
 
 impl Rope {
@@ -58,128 +42,183 @@ impl Rope {
         Self {
             head: Point::origin(),
             tail: Point::origin(),
-            tail_positions: HashSet::new(),
+            tail_positions: SparseGrid::new(),
         }
     }
 
     fn ends_adjacent(&self) -> bool {
-        (self.head.x - self.tail.x).abs() <= 1 && (self.head.y - self.tail.y).abs() <= 1
+        self.head == self.tail || self.head.neighbors8().contains(&self.tail)
     }
 
-    fn move_head(&mut self, direction: &Direction) {
-        match direction {
-            Direction::Up => self.head.y += 1,
-            Direction::Down => self.head.y -= 1,
-            Direction::Left => self.head.x -= 1,
-            Direction::Right => self.head.x += 1,
-        }
+    fn move_head(&mut self, direction: Direction) {
+        self.head = self.head.step(direction);
     }
 
     fn move_tail(&mut self) {
         // no motion necessary if the head and tail are adjacent
         if !self.ends_adjacent() {
-            let dx = self.head.x - self.tail.x;
-            let dy = self.head.y - self.tail.y;
             // if the head is 2 steps directly up/down/left/right from the tail
             // it must also move 1 step in that direction; otherwise, the tail
             // moves 1 step diagonally
-            self.tail.x += dx.signum();
-            self.tail.y += dy.signum();
+            self.tail += (self.head - self.tail).signum();
         }
     }
 
     fn make_move(&mut self, motion: &Motion) {
         debug!("motion: {:?}", motion);
         for _ in 0..motion.length {
-            self.move_head(&motion.direction);
+            self.move_head(motion.direction);
             debug!("head @ {} tail @ {}", self.head, self.tail);
             self.move_tail();
             debug!("head @ {} tail @ {}", self.head, self.tail);
             // track the new tail position
-            self.tail_positions.insert(self.tail);
+            self.tail_positions.insert(self.tail, ());
         }
     }
 }
 
 struct KnottedRope {
     knots: [Point; N_KNOTS],
-    tail_positions: HashSet<Point>,
+    tail_positions: SparseGrid<()>,
 }
 
 impl KnottedRope {
     fn new() -> Self {
         Self {
             knots: [Point::origin(); N_KNOTS],
-            tail_positions: HashSet::new(),
+            tail_positions: SparseGrid::new(),
         }
     }
 
     fn knots_adjacent(&self, i: usize, j: usize) -> bool {
         let a = self.knots[i];
         let b = self.knots[j];
-        (a.x - b.x).abs() <= 1 && (a.y - b.y).abs() <= 1
+        a == b || a.neighbors8().contains(&b)
     }
 
-    fn move_head(&mut self, direction: &Direction) {
-        match direction {
-            Direction::Up => self.knots[0].y += 1,
-            Direction::Down => self.knots[0].y -= 1,
-            Direction::Left => self.knots[0].x -= 1,
-            Direction::Right => self.knots[0].x += 1,
-        }
+    fn move_head(&mut self, direction: Direction) {
+        self.knots[0] = self.knots[0].step(direction);
     }
 
     fn move_knot(&mut self, index: usize) {
         // no motion necessary if the head and tail are adjacent
         if !self.knots_adjacent(index - 1, index) {
-            let dx = self.knots[index - 1].x - self.knots[index].x;
-            let dy = self.knots[index - 1].y - self.knots[index].y;
             // if the head is 2 steps directly up/down/left/right from the tail
             // it must also move 1 step in that direction; otherwise, the tail
             // moves 1 step diagonally
-            self.knots[index].x += dx.signum();
-            self.knots[index].y += dy.signum();
+            let delta = self.knots[index - 1] - self.knots[index];
+            self.knots[index] += delta.signum();
         }
     }
 
     fn make_move(&mut self, motion: &Motion) {
         debug!("motion: {:?}", motion);
         for _ in 0..motion.length {
-            self.move_head(&motion.direction);
+            self.move_head(motion.direction);
             for i in 1..N_KNOTS {
                 self.move_knot(i);
             }
             // track the new tail position
-            self.tail_positions.insert(self.knots[N_KNOTS - 1]);
+            self.tail_positions.insert(self.knots[N_KNOTS - 1], ());
         }
     }
 }
 
-pub fn run(input: String) -> Result<Solution> {
-    let mut solution = Solution::new();
-    // parse the motions
-    let motions = utils::split_lines(&input)
-        .map(Motion::from)
-        .collect::<Vec<_>>();
-
-    // part 1: Simulate your complete hypothetical series of motions. How many
-    // positions does the tail of the rope visit at least once?
-    let mut rope = Rope::new();
-    for motion in motions.iter() {
-        rope.make_move(motion);
+impl Visualize for KnottedRope {
+    fn visualize(&self) -> Animation {
+        let mut canvas = Canvas::covering(self.tail_positions.points().chain(self.knots.iter()), 4);
+        canvas.draw_cells(self.tail_positions.points(), Color::Gray);
+        canvas.draw_cells(&self.knots[1..], Color::White);
+        canvas.draw_cell(&self.knots[0], Color::Red);
+        Animation::from(canvas)
     }
-    let tail_positions = rope.tail_positions.len();
-    solution.set_part_1(tail_positions);
+}
 
-    // part 2: Simulate your complete series of motions on a larger rope with
-    // ten knots. How many positions does the tail of the rope visit at least
-    // once?
+/// the knotted rope of part 2 once it has made all of the motions
+fn simulate_knotted_rope(motions: &[Motion]) -> KnottedRope {
     let mut knotted_rope = KnottedRope::new();
     for motion in motions.iter() {
         knotted_rope.make_move(motion);
     }
-    let tail_positions = knotted_rope.tail_positions.len();
-    solution.set_part_2(tail_positions);
+    knotted_rope
+}
+
+/// renders the positions visited by the tail of the knotted rope of part 2
+pub fn visualize(input: &str) -> Result<Animation> {
+    Ok(simulate_knotted_rope(&Day9::parse(PuzzleInput::new(9, input))?).visualize())
+}
+
+pub struct Day9;
+
+impl Puzzle for Day9 {
+    type Parsed = Vec<Motion>;
+
+    const DAY: usize = 9;
+    const TITLE: &'static str = "Rope Bridge";
+    const METADATA: Metadata = Metadata {
+        algorithm: "rope simulation",
+        complexity: "O(steps * knots)",
+        tags: &["simulation", "grid"],
+    };
+    const SAMPLE_ANSWERS: [Option<&'static str>; 2] = [Some("13"), Some("1")];
+    const ANSWERS: [Option<&'static str>; 2] = [Some("6503"), Some("2724")];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Parsed> {
+        // parse the motions
+        input.parse_lines(Motion::try_from)
+    }
+
+    fn part_1(motions: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 1: Simulate your complete hypothetical series of motions. How
+        // many positions does the tail of the rope visit at least once?
+        let mut rope = Rope::new();
+        for motion in motions.iter() {
+            rope.make_move(motion);
+        }
+        Ok(rope.tail_positions.len().into())
+    }
+
+    fn part_2(motions: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        // part 2: Simulate your complete series of motions on a larger rope
+        // with ten knots. How many positions does the tail of the rope visit at
+        // least once?
+        let knotted_rope = simulate_knotted_rope(motions);
+        Ok(knotted_rope.tail_positions.len().into())
+    }
+
+    fn artifacts(motions: &Self::Parsed, _solution: &Solution) -> Vec<Artifact> {
+        vec![Artifact::Image {
+            name: "tail-positions",
+            animation: simulate_knotted_rope(motions).visualize(),
+        }]
+    }
+}
 
-    Ok(solution)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_INPUT: &str = "\
+R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2";
+
+    #[test]
+    fn render_tail_positions() {
+        let motions = Day9::parse(PuzzleInput::new(9, SAMPLE_INPUT)).unwrap();
+        let mut rope = Rope::new();
+        for motion in motions.iter() {
+            rope.make_move(motion);
+        }
+        let tail_positions = ["..##.", "...##", ".####", "....#", "####."];
+        assert_eq!(
+            rope.tail_positions.render('.', |_| '#'),
+            tail_positions.join("\n")
+        );
+    }
 }
@@ -2,42 +2,501 @@
 ** src/puzzles/mod.rs
 */
 
-mod day_1;
-mod day_10;
-mod day_11;
-mod day_12;
-mod day_13;
-mod day_14;
-mod day_15;
-mod day_16;
-mod day_2;
-mod day_3;
-mod day_4;
-mod day_5;
-mod day_6;
-mod day_7;
-mod day_8;
-mod day_9;
-
-use crate::types::Puzzle;
-
-pub const N_DAYS: usize = 16;
-
-pub const DAYS: [Puzzle; N_DAYS] = [
-    day_1::run,
-    day_2::run,
-    day_3::run,
-    day_4::run,
-    day_5::run,
-    day_6::run,
-    day_7::run,
-    day_8::run,
-    day_9::run,
-    day_10::run,
-    day_11::run,
-    day_12::run,
-    day_13::run,
-    day_14::run,
-    day_15::run,
-    day_16::run,
+use crate::artifacts;
+use crate::bench::{self, SplitBench};
+use crate::context::Context;
+use crate::input::PuzzleInput;
+use crate::parts::Parts;
+use crate::pool;
+use crate::trace;
+use crate::types::{Answer, Error, Metadata, Puzzle, Solution, Solver};
+use crate::viz::Visualizer;
+
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+
+use std::fmt;
+use std::time::Instant;
+
+pub const N_DAYS: usize = 25;
+
+/// a solved day, as added to REGISTRY by register_days!
+#[derive(Clone, Copy)]
+pub struct Registration {
+    pub day: usize,
+    /// see Puzzle::TITLE and Puzzle::METADATA
+    pub title: &'static str,
+    pub metadata: Metadata,
+    pub solve: Solver,
+    /// see bench::bench_split
+    pub bench: SplitBench,
+    /// see Puzzle::SAMPLE_ANSWERS and Puzzle::ANSWERS
+    pub sample_answers: [Option<&'static str>; 2],
+    pub answers: [Option<&'static str>; 2],
+}
+
+/// registers the solved days, declaring the module of each day and adding its
+/// puzzle to REGISTRY, so that a new day only needs its own line here; days
+/// listed as stubs, along with their titles, are run along with the solved
+/// days, and are reported as not implemented rather than being skipped
+macro_rules! register_days {
+    (
+        $($day:literal => $module:ident::$puzzle:ident,)*
+        stubs:
+        $($stub:literal => $title:literal,)*
+    ) => {
+        $(mod $module;)*
+
+        /// the solved days, in order
+        pub const REGISTRY: &[Registration] = &[
+            $(Registration {
+                day: {
+                    assert!(
+                        <$module::$puzzle as Puzzle>::DAY == $day,
+                        "registered as the wrong day"
+                    );
+                    $day
+                },
+                title: <$module::$puzzle as Puzzle>::TITLE,
+                metadata: <$module::$puzzle as Puzzle>::METADATA,
+                solve: solve::<$module::$puzzle>,
+                bench: bench::bench_split::<$module::$puzzle>,
+                sample_answers: <$module::$puzzle as Puzzle>::SAMPLE_ANSWERS,
+                answers: <$module::$puzzle as Puzzle>::ANSWERS,
+            }),*
+        ];
+
+        /// the days which are not solved yet, along with their titles and
+        /// their stub solvers
+        pub const STUBS: &[(usize, &str, Solver)] = &[$(($stub, $title, stub::<$stub>)),*];
+    };
+}
+
+register_days! {
+    1 => day_1::Day1,
+    2 => day_2::Day2,
+    3 => day_3::Day3,
+    4 => day_4::Day4,
+    5 => day_5::Day5,
+    6 => day_6::Day6,
+    7 => day_7::Day7,
+    8 => day_8::Day8,
+    9 => day_9::Day9,
+    10 => day_10::Day10,
+    11 => day_11::Day11,
+    12 => day_12::Day12,
+    13 => day_13::Day13,
+    14 => day_14::Day14,
+    15 => day_15::Day15,
+    16 => day_16::Day16,
+    22 => day_22::Day22,
+    25 => day_25::Day25,
+    stubs:
+    17 => "Pyroclastic Flow",
+    18 => "Boiling Boulders",
+    19 => "Not Enough Minerals",
+    20 => "Grove Positioning System",
+    21 => "Monkey Math",
+    23 => "Unstable Diffusion",
+    24 => "Blizzard Basin",
+}
+
+/// the registered day, title and solver at the index of REGISTRY and then of
+/// STUBS
+const fn registered(i: usize) -> (usize, &'static str, Solver) {
+    if i < REGISTRY.len() {
+        (REGISTRY[i].day, REGISTRY[i].title, REGISTRY[i].solve)
+    } else {
+        STUBS[i - REGISTRY.len()]
+    }
+}
+
+/// the solver of each day, with the stub of each stubbed day and the
+/// unsolved placeholder for the days which are neither; registering a day
+/// twice, or a day out of range, fails to compile
+pub const DAYS: [Solver; N_DAYS] = {
+    let mut days = [unsolved as Solver; N_DAYS];
+    let mut registered_days = [false; N_DAYS];
+    let mut i = 0;
+    while i < REGISTRY.len() + STUBS.len() {
+        let (day, _, solve) = registered(i);
+        assert!(day >= 1 && day <= N_DAYS, "registered day out of range");
+        assert!(!registered_days[day - 1], "day registered twice");
+        registered_days[day - 1] = true;
+        days[day - 1] = solve;
+        i += 1;
+    }
+    days
+};
+
+/// the title of each day's puzzle, as registered, which is empty for the
+/// days which are neither solved nor stubbed
+pub const TITLES: [&str; N_DAYS] = {
+    let mut titles = [""; N_DAYS];
+    let mut i = 0;
+    while i < REGISTRY.len() + STUBS.len() {
+        let (day, title, _) = registered(i);
+        titles[day - 1] = title;
+        i += 1;
+    }
+    titles
+};
+
+/// the heading for the day, such as "Day 13: Distress Signal"
+pub fn title(day: usize) -> String {
+    match TITLES[day - 1] {
+        "" => format!("Day {}", day),
+        title => format!("Day {}: {}", day, title),
+    }
+}
+
+/// how the solved day works, see Puzzle::METADATA
+pub fn metadata(day: usize) -> Option<&'static Metadata> {
+    registration(day).map(|registration| &registration.metadata)
+}
+
+/// the days whose metadata has the given tag
+pub fn days_with_tag(tag: &str) -> Vec<usize> {
+    REGISTRY
+        .iter()
+        .filter(|registration| registration.metadata.has_tag(tag))
+        .map(|registration| registration.day)
+        .collect()
+}
+
+/// every tag used in the metadata, sorted
+pub fn tags() -> Vec<&'static str> {
+    REGISTRY
+        .iter()
+        .flat_map(|registration| registration.metadata.tags.iter().copied())
+        .sorted()
+        .dedup()
+        .collect()
+}
+
+/// the name of the solver in DAYS for each day
+pub const DEFAULT_VARIANT: &str = "default";
+/// runs the default solver and checks that every other variant agrees
+pub const ALL_VARIANTS: &str = "all";
+
+/// alternative solvers, as (day, name, solver), which must produce the same
+/// answers as the default solver for the day
+pub const VARIANTS: [(usize, &str, Solver); 2] = [
+    (12, "dijkstra", solve::<day_12::Day12Dijkstra>),
+    (15, "perimeter", solve::<day_15::Day15Perimeter>),
+];
+
+/// lists all solvers for the given day, starting with the default
+pub fn variants(day: usize) -> Vec<(&'static str, Solver)> {
+    let mut variants = vec![(DEFAULT_VARIANT, DAYS[day - 1])];
+    variants.extend(
+        VARIANTS
+            .iter()
+            .filter(|(d, _, _)| *d == day)
+            .map(|&(_, name, puzzle)| (name, puzzle)),
+    );
+    variants
+}
+
+/// parses a solver variant from the command line, which is either the default,
+/// all variants, or the name of one of VARIANTS
+pub fn parse_variant(s: &str) -> Result<String> {
+    let names = [DEFAULT_VARIANT, ALL_VARIANTS]
+        .into_iter()
+        .chain(VARIANTS.iter().map(|&(_, name, _)| name))
+        .unique()
+        .collect::<Vec<_>>();
+    if names.contains(&s) {
+        Ok(s.to_string())
+    } else {
+        Err(anyhow!(
+            "invalid variant {}, expected one of {}",
+            s,
+            names.join(", ")
+        ))
+    }
+}
+
+/// runs every solver for the given day on the same input and returns the
+/// names of the variants whose answers differ from those of the default
+pub fn diverging_variants(
+    day: usize,
+    input: &str,
+    parts: Parts,
+    context: &Context,
+) -> Result<Vec<&'static str>> {
+    let answers = |solution: Solution| {
+        [solution.part_1, solution.part_2].map(|answer| answer.map(|a| a.to_string()))
+    };
+    let mut variants = variants(day).into_iter();
+    let (_, default) = variants.next().unwrap();
+    let expected = answers(default(input, parts, context)?);
+    let mut diverging = Vec::new();
+    for (name, puzzle) in variants {
+        if answers(puzzle(input, parts, context)?) != expected {
+            diverging.push(name);
+        }
+    }
+    Ok(diverging)
+}
+
+/// the benchmark of a solved day, which parses the input once and times only
+/// the solving
+pub fn split_bench(day: usize) -> Option<SplitBench> {
+    registration(day).map(|registration| registration.bench)
+}
+
+/// days which can render their solved state, see viz::Visualize
+pub const VISUALIZERS: [(usize, Visualizer); 3] = [
+    (9, day_9::visualize),
+    (12, day_12::visualize),
+    (14, day_14::visualize),
 ];
+
+pub fn visualizer(day: usize) -> Option<Visualizer> {
+    VISUALIZERS
+        .iter()
+        .find(|(d, _)| *d == day)
+        .map(|&(_, visualizer)| visualizer)
+}
+
+/// solves the selected parts of the puzzle, parsing the input only once
+pub fn solve<P: Puzzle>(input: &str, parts: Parts, context: &Context) -> Result<Solution> {
+    let tstart = Instant::now();
+    let parsed = {
+        let _span = trace::span("parse", "phase");
+        P::parse(PuzzleInput::new(P::DAY, input))?
+    };
+    let parse_seconds = tstart.elapsed().as_secs_f64();
+    let mut solution = solve_parsed::<P>(&parsed, parts, context)?;
+    solution.parse_seconds = Some(parse_seconds);
+    Ok(solution)
+}
+
+/// solves the part, along with the time taken to solve it
+fn timed_part<F>(part: F) -> (Result<Answer>, f64)
+where
+    F: FnOnce() -> Result<Answer>,
+{
+    let tstart = Instant::now();
+    let answer = part();
+    (answer, tstart.elapsed().as_secs_f64())
+}
+
+/// solves the selected parts of the parsed input; the parts are independent,
+/// so they may be solved in parallel, see pool::join
+pub fn solve_parsed<P: Puzzle>(
+    parsed: &P::Parsed,
+    parts: Parts,
+    context: &Context,
+) -> Result<Solution> {
+    let (part_1, part_2) = pool::join(
+        || {
+            parts.includes(1).then(|| {
+                let _span = trace::span("part 1", "phase");
+                timed_part(|| P::part_1(parsed, context))
+            })
+        },
+        || {
+            (parts.includes(2) && P::PARTS == 2).then(|| {
+                let _span = trace::span("part 2", "phase");
+                timed_part(|| P::part_2(parsed, context))
+            })
+        },
+    );
+    let mut solution = Solution::new();
+    if let Some((answer, seconds)) = part_1 {
+        solution.part_1 = Some(answer?);
+        solution.part_seconds[0] = Some(seconds);
+    }
+    if let Some((answer, seconds)) = part_2 {
+        solution.part_2 = Some(answer?);
+        solution.part_seconds[1] = Some(seconds);
+    }
+    solution.extras = P::extras(parsed, &solution);
+    if artifacts::is_enabled() {
+        let _span = trace::span("artifacts", "phase");
+        solution.artifacts = P::artifacts(parsed, &solution);
+    }
+    Ok(solution)
+}
+
+/// placeholder for days which have not been solved yet
+fn unsolved(_input: &str, _parts: Parts, _context: &Context) -> Result<Solution> {
+    Ok(Solution::new())
+}
+
+/// the solver of a stubbed day, which fails as not implemented
+fn stub<const DAY: usize>(_input: &str, _parts: Parts, _context: &Context) -> Result<Solution> {
+    Err(Error::Unimplemented { day: DAY }.into())
+}
+
+pub fn registration(day: usize) -> Option<&'static Registration> {
+    REGISTRY.iter().find(|registration| registration.day == day)
+}
+
+/// whether the day has a solver, rather than the unsolved placeholder
+pub fn is_implemented(day: usize) -> bool {
+    registration(day).is_some()
+}
+
+/// whether the day is registered as a stub, see register_days!
+pub fn is_stub(day: usize) -> bool {
+    STUBS.iter().any(|&(stub, _, _)| stub == day)
+}
+
+/// the days before the latest solved day which have no solver, such as those
+/// skipped while catching up
+pub fn gaps() -> Vec<usize> {
+    let latest = REGISTRY.iter().map(|r| r.day).max().unwrap_or(0);
+    (1..latest).filter(|&day| !is_implemented(day)).collect()
+}
+
+/// formats the implemented days compactly, with runs of days as ranges, such
+/// as "1-16, 22, 25"
+pub fn implemented_days() -> String {
+    format_days((1..=N_DAYS).filter(|&day| is_implemented(day)))
+}
+
+/// formats the days compactly, with runs of days as ranges
+pub fn format_days<I: IntoIterator<Item = usize>>(days: I) -> String {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for day in days {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == day => *end = day,
+            _ => ranges.push((day, day)),
+        }
+    }
+    ranges
+        .into_iter()
+        .map(|(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{}-{}", start, end)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// parses a day from the command line, either a number from 1 to N_DAYS or
+/// "latest" for the last day
+pub fn parse_day(s: &str) -> Result<usize> {
+    if s == "latest" {
+        return Ok(N_DAYS);
+    }
+    match s.parse::<usize>() {
+        Ok(day) if (1..=N_DAYS).contains(&day) => Ok(day),
+        _ => Err(anyhow!(
+            "invalid day {}, expected 1-{} or \"latest\" (implemented: {})",
+            s,
+            N_DAYS,
+            implemented_days()
+        )),
+    }
+}
+
+/// a selection of days, in order and without repeats
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Days(pub Vec<usize>);
+
+impl fmt::Display for Days {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0.iter().join(","))
+    }
+}
+
+/// parses a selection of days, as a comma-separated list of days, as accepted
+/// by parse_day, and ranges of days, such as "1-10" or "3,5,13"
+pub fn parse_days(s: &str) -> Result<Days> {
+    let mut days = Vec::new();
+    for item in s.split(',') {
+        match item.split_once('-') {
+            Some((first, last)) => {
+                let (first, last) = (parse_day(first)?, parse_day(last)?);
+                if first > last {
+                    return Err(anyhow!("invalid range of days {}", item));
+                }
+                days.extend(first..=last);
+            }
+            None => days.push(parse_day(item)?),
+        }
+    }
+    days.sort();
+    days.dedup();
+    Ok(Days(days))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day_arguments() {
+        assert_eq!(parse_day("1").unwrap(), 1);
+        assert_eq!(parse_day("25").unwrap(), 25);
+        assert_eq!(parse_day("latest").unwrap(), N_DAYS);
+        assert!(parse_day("0").is_err());
+        assert!(parse_day("26").is_err());
+        assert_eq!(parse_days("5").unwrap(), Days(vec![5]));
+        assert_eq!(parse_days("1-4").unwrap(), Days(vec![1, 2, 3, 4]));
+        assert_eq!(parse_days("13,3,5,3").unwrap(), Days(vec![3, 5, 13]));
+        assert_eq!(
+            parse_days("1-2,24-latest").unwrap(),
+            Days(vec![1, 2, 24, 25])
+        );
+        assert_eq!(parse_days("3,5,13").unwrap().to_string(), "3,5,13");
+        assert!(parse_days("10-1").is_err());
+        assert!(parse_days("1-26").is_err());
+        assert!(parse_days("1,,2").is_err());
+        assert!(parse_day("one").is_err());
+        assert!(is_implemented(16));
+        assert!(!is_implemented(17));
+        assert!(!is_implemented(0));
+        assert_eq!(implemented_days(), "1-16, 22, 25");
+        assert_eq!(gaps(), vec![17, 18, 19, 20, 21, 23, 24]);
+        assert_eq!(format_days(gaps()), "17-21, 23-24");
+        assert!(is_stub(17) && !is_stub(16));
+        assert_eq!(parse_variant("dijkstra").unwrap(), "dijkstra");
+        assert_eq!(parse_variant("all").unwrap(), "all");
+        assert_eq!(
+            parse_variant("dijkstr").unwrap_err().to_string(),
+            "invalid variant dijkstr, expected one of default, all, dijkstra, perimeter"
+        );
+        assert_eq!(title(13), "Day 13: Distress Signal");
+        assert_eq!(title(17), "Day 17: Pyroclastic Flow");
+        assert!(TITLES.iter().all(|title| !title.is_empty()));
+    }
+
+    #[test]
+    fn metadata_for_solved_days() {
+        for day in 1..=N_DAYS {
+            assert_eq!(metadata(day).is_some(), is_implemented(day), "day {}", day);
+        }
+        assert_eq!(days_with_tag("graph"), vec![12, 16]);
+        assert!(days_with_tag("nonexistent").is_empty());
+        assert!(tags().contains(&"simulation"));
+    }
+
+    #[test]
+    fn phase_timings() {
+        let input = "1000\n2000\n\n3000\n\n4000";
+        let solution = solve::<day_1::Day1>(input, Parts::Only(2), &Context::none()).unwrap();
+        assert!(solution.parse_seconds.is_some());
+        assert!(solution.part_seconds[0].is_none());
+        assert!(solution.part_seconds[1].is_some());
+    }
+
+    #[test]
+    fn stubbed_days() {
+        let Err(err) = DAYS[17 - 1]("", Parts::Both, &Context::none()) else {
+            panic!("day 17 is a stub");
+        };
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::Unimplemented { day: 17 })
+        ));
+        assert_eq!(err.to_string(), "day 17: not implemented");
+    }
+}
@@ -0,0 +1,161 @@
+/*
+** src/report.rs
+*/
+
+use crate::bench::format_time;
+use crate::history::DayRecord;
+use crate::puzzles;
+
+use anyhow::{anyhow, Result};
+
+use std::fs;
+use std::path::Path;
+
+/// the format of the timing report written with --report
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Markdown,
+    Csv,
+}
+
+impl Format {
+    /// Markdown for .md files and CSV for .csv files
+    pub fn for_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("md") | Some("markdown") => Ok(Self::Markdown),
+            Some("csv") => Ok(Self::Csv),
+            _ => Err(anyhow!(
+                "{}: reports are written as .md or .csv",
+                path.to_string_lossy()
+            )),
+        }
+    }
+}
+
+/// escapes an answer for a Markdown table cell, where multi-line answers such
+/// as the day 10 image are kept on one row
+fn markdown_cell(answer: &Option<String>) -> String {
+    match answer {
+        Some(answer) => format!(
+            "`{}`",
+            answer
+                .trim_matches('\n')
+                .replace('|', "\\|")
+                .replace('\n', "`<br>`")
+        ),
+        None => String::from("-"),
+    }
+}
+
+/// quotes a CSV field if it contains a separator, quote, or newline
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// renders the answers and times of the solved days as a table
+pub fn render(format: Format, records: &[DayRecord]) -> String {
+    match format {
+        Format::Markdown => {
+            let mut lines = vec![
+                String::from("| Day | Part 1 | Part 2 | Time |"),
+                String::from("|:----|:-------|:-------|-----:|"),
+            ];
+            for record in records.iter() {
+                lines.push(format!(
+                    "| {} | {} | {} | {} |",
+                    puzzles::title(record.day),
+                    markdown_cell(&record.part_1),
+                    markdown_cell(&record.part_2),
+                    format_time(record.seconds)
+                ));
+            }
+            let total = records.iter().map(|record| record.seconds).sum::<f64>();
+            lines.push(format!("| **Total** | | | **{}** |", format_time(total)));
+            lines.join("\n") + "\n"
+        }
+        Format::Csv => {
+            let mut csv = String::from("day,part_1,part_2,seconds\n");
+            for record in records.iter() {
+                let answer = |part: &Option<String>| csv_field(part.as_deref().unwrap_or(""));
+                csv.push_str(&format!(
+                    "{},{},{},{}\n",
+                    record.day,
+                    answer(&record.part_1),
+                    answer(&record.part_2),
+                    record.seconds
+                ));
+            }
+            csv
+        }
+    }
+}
+
+/// writes the report of the solved days to the file, replacing it
+pub fn write(path: &Path, format: Format, records: &[DayRecord]) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, render(format, records))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records() -> Vec<DayRecord> {
+        vec![
+            DayRecord {
+                day: 1,
+                seconds: 0.0005,
+                part_1: Some(String::from("24000")),
+                part_2: Some(String::from("45000")),
+            },
+            DayRecord {
+                day: 10,
+                seconds: 0.25,
+                part_1: Some(String::from("13140")),
+                part_2: Some(String::from("\n#.|\n.#\"")),
+            },
+        ]
+    }
+
+    #[test]
+    fn formats_from_path() {
+        assert_eq!(
+            Format::for_path(Path::new("README.md")).unwrap(),
+            Format::Markdown
+        );
+        assert_eq!(
+            Format::for_path(Path::new("times.csv")).unwrap(),
+            Format::Csv
+        );
+        assert!(Format::for_path(Path::new("times.txt")).is_err());
+    }
+
+    #[test]
+    fn render_markdown() {
+        assert_eq!(
+            render(Format::Markdown, &records()),
+            "| Day | Part 1 | Part 2 | Time |\n\
+             |:----|:-------|:-------|-----:|\n\
+             | Day 1: Calorie Counting | `24000` | `45000` | 500µs |\n\
+             | Day 10: Cathode-Ray Tube | `13140` | `#.\\|`<br>`.#\"` | 250.0ms |\n\
+             | **Total** | | | **250.5ms** |\n"
+        );
+    }
+
+    #[test]
+    fn render_csv() {
+        assert_eq!(
+            render(Format::Csv, &records()),
+            "day,part_1,part_2,seconds\n\
+             1,24000,45000,0.0005\n\
+             10,13140,\"\n#.|\n.#\"\"\",0.25\n"
+        );
+    }
+}
@@ -0,0 +1,272 @@
+/*
+** src/results.rs
+*/
+
+use crate::client;
+use crate::history::DayRecord;
+use crate::json::Json;
+use crate::manifest;
+use crate::puzzles;
+
+use anyhow::{anyhow, Result};
+
+use std::env;
+use std::fs;
+use std::thread;
+
+/// identifies the format of the exported results, so that other runners can
+/// recognize the file
+pub const SCHEMA: &str = "aoc2022-results";
+/// bumped whenever a field is removed or changes meaning; new fields may be
+/// added without a bump
+pub const SCHEMA_VERSION: usize = 1;
+
+/// the machine the results were measured on
+#[derive(Debug, PartialEq)]
+pub struct Machine {
+    pub os: String,
+    pub arch: String,
+    pub cpu: Option<String>,
+    pub threads: usize,
+}
+
+impl Machine {
+    pub fn current() -> Self {
+        Self {
+            os: env::consts::OS.to_string(),
+            arch: env::consts::ARCH.to_string(),
+            cpu: cpu_model(),
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+
+    fn from_json(json: &Json) -> Option<Self> {
+        Some(Self {
+            os: json.get("os")?.as_str()?.to_string(),
+            arch: json.get("arch")?.as_str()?.to_string(),
+            cpu: json
+                .get("cpu")
+                .and_then(|cpu| cpu.as_str())
+                .map(|cpu| cpu.to_string()),
+            threads: json.get("threads")?.as_i64()? as usize,
+        })
+    }
+
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("os", Json::from(self.os.as_str())),
+            ("arch", Json::from(self.arch.as_str())),
+            ("cpu", Json::from(self.cpu.clone())),
+            ("threads", Json::from(self.threads)),
+        ])
+    }
+}
+
+/// the CPU model name, where the OS makes it available
+fn cpu_model() -> Option<String> {
+    let cpuinfo = fs::read_to_string("/proc/cpuinfo").ok()?;
+    cpuinfo
+        .lines()
+        .find(|line| line.starts_with("model name"))
+        .and_then(|line| line.split_once(':'))
+        .map(|(_, model)| model.trim().to_string())
+}
+
+/// answers are shared as digests when requested, so that results can be
+/// compared without giving away the answers to anyone else's runner
+fn answer_json(answer: &str, hash: bool) -> Json {
+    if hash {
+        Json::from(format!("sha256:{}", manifest::sha256(answer.as_bytes())))
+    } else {
+        Json::from(answer)
+    }
+}
+
+/// builds the shareable results document for the days
+///
+/// ```text
+/// { "schema": "aoc2022-results", "version": 1, "timestamp": <unix seconds>,
+///   "revision": <git revision or null>, "machine": { "os", "arch", "cpu",
+///   "threads" }, "days": [ { "day", "title", "seconds",
+///   "parts": [ { "part", "answer" } ] } ] }
+/// ```
+pub fn export(
+    records: &[DayRecord],
+    machine: &Machine,
+    timestamp: i64,
+    revision: Option<String>,
+    hash_answers: bool,
+) -> Json {
+    let days = records
+        .iter()
+        .map(|record| {
+            let parts = [&record.part_1, &record.part_2]
+                .into_iter()
+                .enumerate()
+                .filter_map(|(i, answer)| {
+                    answer.as_ref().map(|answer| {
+                        Json::object([
+                            ("part", Json::from(i + 1)),
+                            ("answer", answer_json(answer, hash_answers)),
+                        ])
+                    })
+                })
+                .collect::<Vec<_>>();
+            Json::object([
+                ("day", Json::from(record.day)),
+                ("title", Json::from(puzzles::TITLES[record.day - 1])),
+                ("seconds", Json::from(record.seconds)),
+                ("parts", Json::Array(parts)),
+            ])
+        })
+        .collect::<Vec<_>>();
+    Json::object([
+        ("schema", Json::from(SCHEMA)),
+        ("version", Json::from(SCHEMA_VERSION)),
+        ("timestamp", Json::from(timestamp)),
+        ("revision", Json::from(revision)),
+        ("machine", machine.to_json()),
+        ("days", Json::Array(days)),
+    ])
+}
+
+/// results read back from the shareable schema, such as those of a peer
+#[derive(Debug, PartialEq)]
+pub struct Imported {
+    pub machine: Machine,
+    pub days: Vec<DayRecord>,
+}
+
+/// reads a results document written by export, of this or an older version
+pub fn import(json: &Json) -> Result<Imported> {
+    if json.get("schema").and_then(|schema| schema.as_str()) != Some(SCHEMA) {
+        return Err(anyhow!("not an {} document", SCHEMA));
+    }
+    let version = json
+        .get("version")
+        .and_then(|version| version.as_i64())
+        .ok_or_else(|| anyhow!("missing schema version"))?;
+    if version < 1 || version as usize > SCHEMA_VERSION {
+        return Err(anyhow!(
+            "unsupported schema version {}, expected at most {}",
+            version,
+            SCHEMA_VERSION
+        ));
+    }
+    let invalid = || anyhow!("invalid {} document", SCHEMA);
+    let machine = json
+        .get("machine")
+        .and_then(Machine::from_json)
+        .ok_or_else(invalid)?;
+    let days = json
+        .get("days")
+        .and_then(|days| days.as_array())
+        .ok_or_else(invalid)?
+        .iter()
+        .map(|day| {
+            let answer = |part| {
+                day.get("parts")?
+                    .as_array()?
+                    .iter()
+                    .find(|p| p.get("part").and_then(|p| p.as_i64()) == Some(part))?
+                    .get("answer")?
+                    .as_str()
+                    .map(|answer| answer.to_string())
+            };
+            Some(DayRecord {
+                day: day.get("day")?.as_i64()? as usize,
+                seconds: day.get("seconds")?.as_f64()?,
+                part_1: answer(1),
+                part_2: answer(2),
+            })
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(invalid)?;
+    Ok(Imported { machine, days })
+}
+
+/// where the results of the named machine are shared, under the endpoint
+pub fn shared_url(endpoint: &str, name: &str) -> String {
+    format!("{}/{}.json", endpoint.trim_end_matches('/'), name)
+}
+
+/// uploads the results to the endpoint under the name, with a PUT request,
+/// such as to a WebDAV directory or a storage bucket
+pub fn publish(endpoint: &str, name: &str, results: &Json) -> Result<()> {
+    client::put_json(&shared_url(endpoint, name), &results.to_string())
+}
+
+/// downloads the results of the named machine from the endpoint
+pub fn fetch(endpoint: &str, name: &str) -> Result<Imported> {
+    let body = client::get_public(&shared_url(endpoint, name))?;
+    import(&Json::parse(&body)?).map_err(|e| anyhow!("results of {}: {}", name, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_results() {
+        let records = [DayRecord {
+            day: 25,
+            seconds: 0.5,
+            part_1: Some(String::from("abc")),
+            part_2: None,
+        }];
+        let machine = Machine {
+            os: String::from("linux"),
+            arch: String::from("x86_64"),
+            cpu: None,
+            threads: 8,
+        };
+        let plain = export(&records, &machine, 1670000000, None, false);
+        assert_eq!(
+            plain.to_string(),
+            "{\"days\":[{\"day\":25,\"parts\":[{\"answer\":\"abc\",\"part\":1}],\
+             \"seconds\":0.5,\"title\":\"Full of Hot Air\"}],\
+             \"machine\":{\"arch\":\"x86_64\",\"cpu\":null,\"os\":\"linux\",\"threads\":8},\
+             \"revision\":null,\"schema\":\"aoc2022-results\",\"timestamp\":1670000000,\
+             \"version\":1}"
+        );
+        let hashed = export(&records, &machine, 1670000000, None, true);
+        let day = &hashed.get("days").unwrap().as_array().unwrap()[0];
+        let part = &day.get("parts").unwrap().as_array().unwrap()[0];
+        assert_eq!(
+            part.get("answer").unwrap().as_str(),
+            Some("sha256:ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+    }
+
+    #[test]
+    fn import_results() {
+        let records = vec![DayRecord {
+            day: 1,
+            seconds: 0.25,
+            part_1: Some(String::from("24000")),
+            part_2: Some(String::from("45000")),
+        }];
+        let machine = Machine {
+            os: String::from("linux"),
+            arch: String::from("aarch64"),
+            cpu: Some(String::from("Neoverse-N1")),
+            threads: 4,
+        };
+        let json = export(&records, &machine, 1670000000, None, false);
+        let imported = import(&Json::parse(&json.to_string()).unwrap()).unwrap();
+        assert_eq!(
+            imported,
+            Imported {
+                machine,
+                days: records
+            }
+        );
+        assert!(import(&Json::parse("{\"schema\":\"other\"}").unwrap()).is_err());
+        let newer = "{\"schema\":\"aoc2022-results\",\"version\":2}";
+        assert!(import(&Json::parse(newer).unwrap()).is_err());
+        assert_eq!(
+            shared_url("https://example.com/aoc/", "ian"),
+            "https://example.com/aoc/ian.json"
+        );
+    }
+}
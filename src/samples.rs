@@ -0,0 +1,60 @@
+/*
+** src/samples.rs
+*/
+
+/// the sample inputs, built into the binary with the embed-samples feature so
+/// that sample runs work outside of the repository checkout
+#[cfg(feature = "embed-samples")]
+const SAMPLES: [(usize, &str); 18] = [
+    (1, include_str!("../input/D1.dbg.txt")),
+    (2, include_str!("../input/D2.dbg.txt")),
+    (3, include_str!("../input/D3.dbg.txt")),
+    (4, include_str!("../input/D4.dbg.txt")),
+    (5, include_str!("../input/D5.dbg.txt")),
+    (6, include_str!("../input/D6.dbg.txt")),
+    (7, include_str!("../input/D7.dbg.txt")),
+    (8, include_str!("../input/D8.dbg.txt")),
+    (9, include_str!("../input/D9.dbg.txt")),
+    (10, include_str!("../input/D10.dbg.txt")),
+    (11, include_str!("../input/D11.dbg.txt")),
+    (12, include_str!("../input/D12.dbg.txt")),
+    (13, include_str!("../input/D13.dbg.txt")),
+    (14, include_str!("../input/D14.dbg.txt")),
+    (15, include_str!("../input/D15.dbg.txt")),
+    (16, include_str!("../input/D16.dbg.txt")),
+    (22, include_str!("../input/D22.dbg.txt")),
+    (25, include_str!("../input/D25.dbg.txt")),
+];
+
+/// the embedded sample input for the day, if the feature is enabled and the
+/// day has a sample
+#[cfg(feature = "embed-samples")]
+pub fn embedded(day: usize) -> Option<&'static str> {
+    SAMPLES
+        .iter()
+        .find(|&&(d, _)| d == day)
+        .map(|&(_, input)| input)
+}
+
+#[cfg(not(feature = "embed-samples"))]
+pub fn embedded(_day: usize) -> Option<&'static str> {
+    None
+}
+
+#[cfg(all(test, feature = "embed-samples"))]
+mod tests {
+    use super::*;
+
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn embedded_samples_match_inputs() {
+        let input_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("input");
+        for &(day, input) in SAMPLES.iter() {
+            let path = input_dir.join(format!("D{}.dbg.txt", day));
+            assert_eq!(fs::read_to_string(path).unwrap(), input);
+        }
+        assert!(embedded(17).is_none());
+    }
+}
@@ -0,0 +1,180 @@
+/*
+** src/sandbox.rs
+*/
+
+use crate::history::DayRecord;
+use crate::porcelain;
+use crate::types::Error;
+
+use anyhow::{anyhow, Result};
+use log::debug;
+
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+use std::path::Path;
+use std::process::{Command, ExitStatus};
+
+/// resource limits for a day solved in a child process
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// address space of the child, in bytes
+    pub memory_bytes: Option<u64>,
+    /// CPU time of the child, in seconds
+    pub cpu_seconds: Option<u64>,
+}
+
+impl Limits {
+    pub fn new(memory_mb: Option<u64>, cpu_seconds: Option<u64>) -> Self {
+        Self {
+            memory_bytes: memory_mb.map(|mb| mb * 1024 * 1024),
+            cpu_seconds,
+        }
+    }
+
+    /// applies the limits to the calling process
+    fn apply(&self) -> std::io::Result<()> {
+        let set = |resource, soft: u64, hard: u64| {
+            let limit = libc::rlimit {
+                rlim_cur: soft as libc::rlim_t,
+                rlim_max: hard as libc::rlim_t,
+            };
+            // SAFETY: the limit is a valid rlimit for the duration of the call
+            if unsafe { libc::setrlimit(resource, &limit) } != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        };
+        if let Some(bytes) = self.memory_bytes {
+            set(libc::RLIMIT_AS, bytes, bytes)?;
+        }
+        if let Some(seconds) = self.cpu_seconds {
+            // SIGXCPU is sent at the soft limit, and SIGKILL at the hard
+            set(libc::RLIMIT_CPU, seconds, seconds + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// the CPU limit which the child exceeded, if it was killed for exceeding it
+fn exceeded_cpu_limit(status: ExitStatus, limits: &Limits) -> Option<u64> {
+    match status.signal() {
+        // SIGKILL is sent at the hard limit, if SIGXCPU did not stop it
+        Some(libc::SIGXCPU) | Some(libc::SIGKILL) => limits.cpu_seconds,
+        _ => None,
+    }
+}
+
+/// explains why a child which was killed or failed did not finish, other
+/// than by exceeding its CPU limit
+fn failure(status: ExitStatus, limits: &Limits, stderr: &str) -> String {
+    match status.signal() {
+        // allocation failures abort once the address space is exhausted
+        Some(libc::SIGABRT) if limits.memory_bytes.is_some() => format!(
+            "aborted, likely exceeding the memory limit of {}MB",
+            limits.memory_bytes.unwrap() / (1024 * 1024)
+        ),
+        Some(signal) => format!("killed by signal {}", signal),
+        None => stderr
+            .lines()
+            .last()
+            .map(|line| line.to_string())
+            .unwrap_or_else(|| status.to_string()),
+    }
+}
+
+/// solves the day in a child process of the given binary, under the limits,
+/// passing along the given arguments; returns None if the day has no input
+pub fn solve(
+    binary: &Path,
+    day: usize,
+    args: &[String],
+    limits: Limits,
+) -> Result<Option<DayRecord>> {
+    let mut command = Command::new(binary);
+    command.arg(day.to_string()).arg("--porcelain").args(args);
+    // SAFETY: setrlimit is async-signal-safe, and apply does not allocate
+    // before calling it
+    unsafe {
+        command.pre_exec(move || limits.apply());
+    }
+    debug!("solving day {} in a child process with {:?}", day, limits);
+    let output = command
+        .output()
+        .map_err(|e| anyhow!("failed to run {}: {}", binary.to_string_lossy(), e))?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if let Some(seconds) = exceeded_cpu_limit(output.status, &limits) {
+        return Err(Error::Timeout { day, seconds }.into());
+    }
+    if !output.status.success() {
+        return Err(anyhow!("{}", failure(output.status, &limits, &stderr)));
+    }
+    let records = porcelain::parse(&String::from_utf8(output.stdout)?)?;
+    Ok(records.into_iter().find(|record| record.day == day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_from_megabytes() {
+        assert_eq!(
+            Limits::new(Some(512), Some(10)),
+            Limits {
+                memory_bytes: Some(512 * 1024 * 1024),
+                cpu_seconds: Some(10),
+            }
+        );
+        assert_eq!(Limits::new(None, None), Limits::default());
+    }
+
+    #[test]
+    fn failure_reasons() {
+        let limits = Limits::new(Some(64), Some(1));
+        let killed = |signal| ExitStatus::from_raw(signal);
+        assert_eq!(exceeded_cpu_limit(killed(libc::SIGXCPU), &limits), Some(1));
+        assert_eq!(exceeded_cpu_limit(killed(libc::SIGKILL), &limits), Some(1));
+        assert_eq!(
+            exceeded_cpu_limit(killed(libc::SIGKILL), &Limits::default()),
+            None
+        );
+        assert_eq!(
+            Error::Timeout {
+                day: 16,
+                seconds: 1
+            }
+            .to_string(),
+            "day 16: exceeded the CPU limit of 1s"
+        );
+        assert_eq!(
+            failure(killed(libc::SIGABRT), &limits, ""),
+            "aborted, likely exceeding the memory limit of 64MB"
+        );
+        assert_eq!(
+            failure(killed(libc::SIGSEGV), &Limits::default(), ""),
+            "killed by signal 11"
+        );
+        assert_eq!(
+            failure(
+                ExitStatus::from_raw(1 << 8),
+                &limits,
+                "log\nError: no solution found\n"
+            ),
+            "Error: no solution found"
+        );
+    }
+
+    #[test]
+    fn cpu_limit_kills_child() {
+        let limits = Limits::new(None, Some(1));
+        let mut command = Command::new("sh");
+        command.args(["-c", "while :; do :; done"]);
+        unsafe {
+            command.pre_exec(move || limits.apply());
+        }
+        let status = command.status().unwrap();
+        assert!(matches!(
+            status.signal(),
+            Some(libc::SIGXCPU) | Some(libc::SIGKILL)
+        ));
+    }
+}
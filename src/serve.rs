@@ -0,0 +1,323 @@
+/*
+** src/serve.rs
+*/
+
+use crate::cancel::Cancel;
+use crate::context::Context;
+use crate::json::Json;
+use crate::parts::Parts;
+use crate::puzzles;
+use crate::types::{Error, Solver};
+
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::panic;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// the largest input accepted, well above the size of any real input
+const MAX_BODY_BYTES: usize = 4 * 1024 * 1024;
+/// how long a client may take to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+/// how long a day may take to solve before the request times out
+const SOLVE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// the parts of an HTTP request which the server looks at
+#[derive(Debug)]
+struct Request {
+    method: String,
+    path: String,
+    body: String,
+}
+
+/// a response as its status code and JSON body
+type Response = (u16, Json);
+
+fn error(status: u16, message: impl ToString) -> Response {
+    (
+        status,
+        Json::object([("error", Json::from(message.to_string()))]),
+    )
+}
+
+/// reads the request line, the headers, and a body of Content-Length bytes
+fn read_request(reader: &mut impl BufRead) -> Result<Request> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut words = line.split_whitespace();
+    let (method, path) = match (words.next(), words.next()) {
+        (Some(method), Some(path)) => (method.to_string(), path.to_string()),
+        _ => return Err(anyhow!("invalid request line")),
+    };
+    let mut content_length = 0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(anyhow!("unterminated headers"));
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value
+                    .trim()
+                    .parse::<usize>()
+                    .map_err(|_| anyhow!("invalid Content-Length"))?;
+            }
+        }
+    }
+    if content_length > MAX_BODY_BYTES {
+        return Err(anyhow!("input of {} bytes is too large", content_length));
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    let body = String::from_utf8(body).map_err(|_| anyhow!("input is not UTF-8"))?;
+    Ok(Request { method, path, body })
+}
+
+/// parses the parts to solve from the query, such as "part=1"
+fn parse_parts(query: Option<&str>) -> Result<Parts> {
+    let part = query
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .find_map(|param| param.strip_prefix("part="));
+    match part {
+        None => Ok(Parts::Both),
+        Some("1") => Ok(Parts::Only(1)),
+        Some("2") => Ok(Parts::Only(2)),
+        Some(part) => Err(anyhow!("invalid part {}", part)),
+    }
+}
+
+/// solves POST /solve/<day>, optionally with ?part=1 or ?part=2, on the input
+/// in the request body
+fn handle(request: &Request) -> Response {
+    let (path, query) = match request.path.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (request.path.as_str(), None),
+    };
+    let Some(day) = path.strip_prefix("/solve/") else {
+        return error(404, format!("no such endpoint {}", path));
+    };
+    if request.method != "POST" {
+        return error(405, "solve the input with a POST request");
+    }
+    let day = match puzzles::parse_day(day) {
+        Ok(day) if puzzles::is_implemented(day) => day,
+        Ok(day) => return error(404, Error::Unimplemented { day }.to_string()),
+        Err(e) => return error(404, e),
+    };
+    let parts = match parse_parts(query) {
+        Ok(parts) => parts,
+        Err(e) => return error(400, e),
+    };
+    let input = request.body.clone();
+    solve_within(puzzles::DAYS[day - 1], day, input, parts, SOLVE_TIMEOUT)
+}
+
+/// solves the input on its own thread, giving up on it once the timeout has
+/// passed; the solve is then cancelled, which stops the slowest days early
+fn solve_within(
+    puzzle: Solver,
+    day: usize,
+    input: String,
+    parts: Parts,
+    timeout: Duration,
+) -> Response {
+    let cancel = Cancel::new();
+    let context = Context::none().with_cancel(cancel.clone());
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        // a malformed input can panic in the parsers, which must not take
+        // down the server; the context is dropped along with the thread
+        let solve = panic::AssertUnwindSafe(|| puzzle(&input, parts, &context));
+        let result = panic::catch_unwind(solve);
+        let _ = tx.send(result);
+    });
+    match rx.recv_timeout(timeout) {
+        Ok(Ok(Ok(solution))) => {
+            let mut json = Json::from(&solution);
+            if let Json::Object(map) = &mut json {
+                map.insert(String::from("day"), Json::from(day));
+            }
+            (200, json)
+        }
+        Ok(Ok(Err(e))) => error(422, e),
+        Ok(Err(_)) | Err(mpsc::RecvTimeoutError::Disconnected) => {
+            error(422, format!("day {} failed on the input", day))
+        }
+        Err(mpsc::RecvTimeoutError::Timeout) => {
+            // the limit is on the wall-clock time of the request, unlike the
+            // CPU limit of Error::Timeout
+            cancel.cancel();
+            error(
+                503,
+                format!("day {}: took longer than {}ms", day, timeout.as_millis()),
+            )
+        }
+    }
+}
+
+fn status_text(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        422 => "Unprocessable Entity",
+        503 => "Service Unavailable",
+        _ => "Error",
+    }
+}
+
+fn respond(stream: &mut impl Write, (status, json): Response) -> Result<()> {
+    let body = format!("{}\n", json);
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        status_text(status),
+        body.len(),
+        body
+    )?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// answers a single request on the connection, then closes it
+fn serve_connection(mut stream: TcpStream) -> Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let request = read_request(&mut BufReader::new(&mut stream));
+    let response = match &request {
+        Ok(request) => handle(request),
+        Err(e) => error(400, e),
+    };
+    if let Ok(request) = &request {
+        info!("{} {} {}", request.method, request.path, response.0);
+    }
+    respond(&mut stream, response)
+}
+
+/// answers requests on the listener, each on its own thread so that a slow day
+/// does not hold up the others; a failed connection is skipped rather than
+/// stopping the server
+pub fn serve_on(listener: TcpListener) -> Result<()> {
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                warn!("failed to accept a connection: {}", e);
+                continue;
+            }
+        };
+        thread::spawn(move || {
+            if let Err(e) = serve_connection(stream) {
+                warn!("failed to answer a request: {}", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+/// serves the solvers over HTTP at the address, such as 127.0.0.1:8022
+pub fn serve(addr: &str) -> Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    info!("serving on http://{}", listener.local_addr()?);
+    serve_on(listener)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::types::Solution;
+
+    use std::io::{Cursor, Read};
+
+    fn request(method: &str, path: &str, body: &str) -> Request {
+        Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn parse_requests() {
+        let raw =
+            "POST /solve/1 HTTP/1.1\r\nHost: localhost\r\ncontent-length: 5\r\n\r\n1\n\n2\nextra";
+        let request = read_request(&mut Cursor::new(raw)).unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/solve/1");
+        assert_eq!(request.body, "1\n\n2\n");
+        assert!(read_request(&mut Cursor::new("POST\r\n\r\n")).is_err());
+        assert!(read_request(&mut Cursor::new("POST / HTTP/1.1\r\n")).is_err());
+    }
+
+    #[test]
+    fn handle_requests() {
+        let (status, json) = handle(&request("POST", "/solve/1", "1\n2\n\n4\n\n5"));
+        assert_eq!(status, 200);
+        assert_eq!(json.get("day").and_then(|d| d.as_i64()), Some(1));
+        assert_eq!(json.get("part_1").and_then(|a| a.as_i64()), Some(5));
+        assert_eq!(json.get("part_2").and_then(|a| a.as_i64()), Some(12));
+        let (status, json) = handle(&request("POST", "/solve/1?part=2", "1\n2\n\n4\n\n5"));
+        assert_eq!(status, 200);
+        assert!(json.get("part_1").unwrap().is_null());
+        assert_eq!(handle(&request("POST", "/solve/1", "one")).0, 422);
+        assert_eq!(handle(&request("GET", "/solve/1", "")).0, 405);
+        assert_eq!(handle(&request("POST", "/solve/17", "")).0, 404);
+        assert_eq!(handle(&request("POST", "/solve/x", "")).0, 404);
+        assert_eq!(handle(&request("POST", "/", "")).0, 404);
+        assert_eq!(handle(&request("POST", "/solve/1?part=3", "")).0, 400);
+    }
+
+    /// a day which runs until it is cancelled
+    fn until_cancelled(_input: &str, _parts: Parts, context: &Context) -> Result<Solution> {
+        while !context.cancel.is_cancelled() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        Err(Error::NoSolution.into())
+    }
+
+    #[test]
+    fn solve_timeout() {
+        let timeout = Duration::from_millis(10);
+        let (status, json) = solve_within(until_cancelled, 16, String::new(), Parts::Both, timeout);
+        assert_eq!(status, 503);
+        assert_eq!(
+            json.get("error").and_then(|e| e.as_str()),
+            Some("day 16: took longer than 10ms")
+        );
+    }
+
+    #[test]
+    fn serve_over_tcp() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || serve_on(listener));
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let body = "1\n2\n\n4\n\n5";
+        write!(
+            stream,
+            "POST /solve/1 HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+        assert!(
+            response.ends_with("\"part_1\":5,\"part_2\":12}\n"),
+            "{}",
+            response
+        );
+    }
+}
@@ -0,0 +1,168 @@
+/*
+** src/status.rs
+*/
+
+use crate::answers::Answers;
+use crate::calendar::Stars;
+use crate::client;
+use crate::puzzles::{self, N_DAYS};
+
+use std::path::Path;
+
+/// how far along a day is
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DayStatus {
+    pub day: usize,
+    pub implemented: bool,
+    /// whether the real input is in the input directory
+    pub has_input: bool,
+    /// the number of parts whose answers are known to be correct, from 0 to 2
+    pub stars: u8,
+}
+
+/// inspects every day, given the input directory and the verified answers to
+/// the real inputs
+pub fn collect(input_dir: &Path, answers: &Answers) -> Vec<DayStatus> {
+    let stars = Stars::from_answers(answers);
+    (1..=N_DAYS)
+        .map(|day| DayStatus {
+            day,
+            implemented: puzzles::is_implemented(day),
+            has_input: input_dir.join(format!("D{}.txt", day)).exists(),
+            stars: stars.get(day),
+        })
+        .collect()
+}
+
+fn check(done: bool) -> &'static str {
+    if done {
+        "✓"
+    } else {
+        ""
+    }
+}
+
+/// renders the days as a Markdown table for the README, with a link to each
+/// puzzle and the total stars
+pub fn markdown(statuses: &[DayStatus]) -> String {
+    let mut lines = vec![
+        String::from("| Day | Puzzle | Solved | Input | Stars |"),
+        String::from("|----:|:-------|:------:|:-----:|:------|"),
+    ];
+    for status in statuses.iter() {
+        lines.push(format!(
+            "| {} | [{}]({}/day/{}) | {} | {} | {} |",
+            status.day,
+            puzzles::TITLES[status.day - 1],
+            client::BASE_URL,
+            status.day,
+            check(status.implemented),
+            check(status.has_input),
+            "⭐".repeat(status.stars as usize)
+        ));
+    }
+    let stars = statuses.iter().map(|s| s.stars as usize).sum::<usize>();
+    lines.push(format!("\n**{}/{} stars**", stars, statuses.len() * 2));
+    lines.join("\n") + "\n"
+}
+
+/// lists the days, such as "3, 5, 13", or "none"
+fn list_days<'a>(statuses: impl Iterator<Item = &'a DayStatus>) -> String {
+    let days = statuses
+        .map(|status| status.day.to_string())
+        .collect::<Vec<_>>();
+    if days.is_empty() {
+        String::from("none")
+    } else {
+        days.join(", ")
+    }
+}
+
+/// summarizes the days for the console, with the days which need attention
+pub fn summary(statuses: &[DayStatus]) -> String {
+    let count = |f: fn(&DayStatus) -> bool| statuses.iter().filter(|s| f(s)).count();
+    let stars = statuses.iter().map(|s| s.stars as usize).sum::<usize>();
+    [
+        format!(
+            "{}/{} days implemented, {} with inputs, {}/{} stars verified",
+            count(|s| s.implemented),
+            statuses.len(),
+            count(|s| s.has_input),
+            stars,
+            statuses.len() * 2
+        ),
+        format!(
+            "implemented without an input: {}",
+            list_days(statuses.iter().filter(|s| s.implemented && !s.has_input))
+        ),
+        format!(
+            "implemented without verified answers: {}",
+            list_days(statuses.iter().filter(|s| s.implemented && s.stars < 2))
+        ),
+    ]
+    .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statuses() -> Vec<DayStatus> {
+        vec![
+            DayStatus {
+                day: 1,
+                implemented: true,
+                has_input: true,
+                stars: 2,
+            },
+            DayStatus {
+                day: 2,
+                implemented: true,
+                has_input: false,
+                stars: 1,
+            },
+            DayStatus {
+                day: 3,
+                implemented: false,
+                has_input: false,
+                stars: 0,
+            },
+        ]
+    }
+
+    #[test]
+    fn collect_statuses() {
+        let mut answers = Answers::new();
+        answers.set(1, 1, String::from("24000"));
+        let input_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("input");
+        let statuses = collect(&input_dir, &answers);
+        assert_eq!(statuses.len(), N_DAYS);
+        assert!(statuses[0].implemented);
+        assert_eq!(statuses[0].stars, 1);
+        assert!(!statuses[16].implemented);
+        assert!(!statuses[16].has_input);
+    }
+
+    #[test]
+    fn render_markdown() {
+        assert_eq!(
+            markdown(&statuses()),
+            "| Day | Puzzle | Solved | Input | Stars |\n\
+             |----:|:-------|:------:|:-----:|:------|\n\
+             | 1 | [Calorie Counting](https://adventofcode.com/2022/day/1) | ✓ | ✓ | ⭐⭐ |\n\
+             | 2 | [Rock Paper Scissors](https://adventofcode.com/2022/day/2) | ✓ |  | ⭐ |\n\
+             | 3 | [Rucksack Reorganization](https://adventofcode.com/2022/day/3) |  |  |  |\n\
+             \n**3/6 stars**\n"
+        );
+    }
+
+    #[test]
+    fn render_summary() {
+        assert_eq!(
+            summary(&statuses()),
+            "2/3 days implemented, 1 with inputs, 3/6 stars verified\n\
+             implemented without an input: 2\n\
+             implemented without verified answers: 2"
+        );
+    }
+}
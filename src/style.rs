@@ -0,0 +1,81 @@
+/*
+** src/style.rs
+*/
+
+use std::env;
+use std::fmt;
+use std::io::{self, IsTerminal};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// the console output is only colored once coloring has been enabled
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+const BOLD: &str = "1";
+const DIM: &str = "2";
+const BOLD_GREEN: &str = "1;32";
+
+/// starts coloring the console output
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// colors are only wanted on a terminal, and never with NO_COLOR set, see
+/// https://no-color.org
+pub fn is_wanted() -> bool {
+    env::var_os("NO_COLOR").is_none() && io::stdout().is_terminal()
+}
+
+/// wraps each line in the escape codes, so that multi-line answers such as
+/// the day 10 image are colored on every line
+fn paint(code: &str, s: impl fmt::Display) -> String {
+    let s = s.to_string();
+    if !is_enabled() {
+        return s;
+    }
+    s.split('\n')
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("\x1b[{}m{}\x1b[0m", code, line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// the title of a day
+pub fn header(s: impl fmt::Display) -> String {
+    paint(BOLD, s)
+}
+
+/// an answer
+pub fn answer(s: impl fmt::Display) -> String {
+    paint(BOLD_GREEN, s)
+}
+
+/// secondary details, such as timings and skipped parts
+pub fn dim(s: impl fmt::Display) -> String {
+    paint(DIM, s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paint_lines() {
+        assert_eq!(paint(DIM, "1.5ms"), "1.5ms");
+        enable();
+        assert_eq!(paint(DIM, "1.5ms"), "\x1b[2m1.5ms\x1b[0m");
+        assert_eq!(
+            paint(BOLD_GREEN, "\n#.\n.#"),
+            "\n\x1b[1;32m#.\x1b[0m\n\x1b[1;32m.#\x1b[0m"
+        );
+        ENABLED.store(false, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,260 @@
+/*
+** src/submit.rs
+*/
+
+use crate::client;
+use crate::json::Json;
+
+use anyhow::{anyhow, Result};
+use regex::Regex;
+
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const PROJECT_DIR: &str = env!("CARGO_MANIFEST_DIR");
+
+/// the response to an answer submission
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// wrong, without a hint of which way
+    Incorrect,
+    /// submitted too soon after the last submission, with the time left to
+    /// wait, such as "39s"
+    RateLimited(Option<String>),
+    /// the part was already solved, or part 1 is not yet solved
+    WrongLevel,
+    /// a response which was not recognized
+    Unknown,
+}
+
+impl Outcome {
+    /// parses the article of the response page
+    pub fn parse(html: &str) -> Self {
+        if client::is_correct_response(html) {
+            Self::Correct
+        } else if html.contains("your answer is too high") {
+            Self::TooHigh
+        } else if html.contains("your answer is too low") {
+            Self::TooLow
+        } else if html.contains("That's not the right answer") {
+            Self::Incorrect
+        } else if html.contains("You gave an answer too recently") {
+            let wait = Regex::new(r"You have (.+?) left to wait")
+                .unwrap()
+                .captures(html)
+                .map(|captures| captures[1].to_string());
+            Self::RateLimited(wait)
+        } else if html.contains("You don't seem to be solving the right level") {
+            Self::WrongLevel
+        } else {
+            Self::Unknown
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Correct => "correct",
+            Self::TooHigh => "too high",
+            Self::TooLow => "too low",
+            Self::Incorrect => "incorrect",
+            Self::RateLimited(_) => "rate limited",
+            Self::WrongLevel => "wrong level",
+            Self::Unknown => "unknown",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "correct" => Some(Self::Correct),
+            "too high" => Some(Self::TooHigh),
+            "too low" => Some(Self::TooLow),
+            "incorrect" => Some(Self::Incorrect),
+            "rate limited" => Some(Self::RateLimited(None)),
+            "wrong level" => Some(Self::WrongLevel),
+            "unknown" => Some(Self::Unknown),
+            _ => None,
+        }
+    }
+
+    /// whether the outcome says anything about the answer, as opposed to the
+    /// submission not being judged
+    pub fn is_verdict(&self) -> bool {
+        matches!(
+            self,
+            Self::Correct | Self::TooHigh | Self::TooLow | Self::Incorrect
+        )
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::RateLimited(Some(wait)) => write!(f, "rate limited, {} left to wait", wait),
+            _ => write!(f, "{}", self.name()),
+        }
+    }
+}
+
+/// an answer which was submitted, and its outcome
+#[derive(Clone, Debug, PartialEq)]
+pub struct Submission {
+    /// unix timestamp of the submission
+    pub timestamp: i64,
+    pub day: usize,
+    pub part: usize,
+    pub answer: String,
+    pub outcome: Outcome,
+}
+
+impl Submission {
+    pub fn to_json(&self) -> Json {
+        Json::object([
+            ("timestamp", Json::from(self.timestamp)),
+            ("day", Json::from(self.day)),
+            ("part", Json::from(self.part)),
+            ("answer", Json::from(self.answer.as_str())),
+            ("outcome", Json::from(self.outcome.name())),
+        ])
+    }
+
+    pub fn from_json(json: &Json) -> Result<Self> {
+        let invalid = || anyhow!("invalid submission");
+        let int = |key| json.get(key).and_then(|n| n.as_i64()).ok_or_else(invalid);
+        let string = |key| json.get(key).and_then(|s| s.as_str()).ok_or_else(invalid);
+        Ok(Self {
+            timestamp: int("timestamp")?,
+            day: int("day")? as usize,
+            part: int("part")? as usize,
+            answer: string("answer")?.to_string(),
+            outcome: Outcome::from_name(string("outcome")?).ok_or_else(invalid)?,
+        })
+    }
+}
+
+/// the git-ignored file which submissions are recorded to, one JSON object
+/// per line
+pub fn submissions_path() -> PathBuf {
+    Path::new(PROJECT_DIR)
+        .join(".aoc")
+        .join("submissions.jsonl")
+}
+
+/// appends the submission to the submissions file
+pub fn append(path: &Path, submission: &Submission) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", submission.to_json())?;
+    Ok(())
+}
+
+/// loads every recorded submission, oldest first
+pub fn load(path: &Path) -> Result<Vec<Submission>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    fs::read_to_string(path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(i, line)| {
+            Json::parse(line)
+                .and_then(|json| Submission::from_json(&json))
+                .map_err(|e| anyhow!("{} line {}: {}", path.to_string_lossy(), i + 1, e))
+        })
+        .collect()
+}
+
+/// the earlier submission which already judged the part, either as solved or
+/// as having the same answer, which there is no point in submitting again
+pub fn judged<'a>(
+    submissions: &'a [Submission],
+    day: usize,
+    part: usize,
+    answer: &str,
+) -> Option<&'a Submission> {
+    submissions
+        .iter()
+        .filter(|s| s.day == day && s.part == part && s.outcome.is_verdict())
+        .find(|s| s.outcome == Outcome::Correct || s.answer == answer)
+}
+
+/// submits the answer to the part of the day
+pub fn submit(day: usize, part: usize, answer: &str) -> Result<Outcome> {
+    let url = format!("{}/day/{}/answer", client::BASE_URL, day);
+    let level = part.to_string();
+    let html = client::post_form(
+        &url,
+        &client::session()?,
+        &[("level", &level), ("answer", answer)],
+    )?;
+    Ok(Outcome::parse(&html))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_outcomes() {
+        let article = |text: &str| format!("<main><article><p>{}</p></article></main>", text);
+        assert_eq!(
+            Outcome::parse(&article(
+                "That's the right answer! You are one gold star closer."
+            )),
+            Outcome::Correct
+        );
+        assert_eq!(
+            Outcome::parse(&article(
+                "That's not the right answer; your answer is too high."
+            )),
+            Outcome::TooHigh
+        );
+        assert_eq!(
+            Outcome::parse(&article("That's not the right answer. If you're stuck...")),
+            Outcome::Incorrect
+        );
+        let limited = Outcome::parse(&article(
+            "You gave an answer too recently; you have to wait after submitting an answer \
+             before trying again.  You have 39s left to wait.",
+        ));
+        assert_eq!(limited, Outcome::RateLimited(Some(String::from("39s"))));
+        assert_eq!(limited.to_string(), "rate limited, 39s left to wait");
+        assert_eq!(
+            Outcome::parse(&article(
+                "You don't seem to be solving the right level.  Did you already complete it?"
+            )),
+            Outcome::WrongLevel
+        );
+        assert_eq!(Outcome::parse("<html></html>"), Outcome::Unknown);
+    }
+
+    #[test]
+    fn judged_submissions() {
+        let submission = |part, answer: &str, outcome| Submission {
+            timestamp: 1670000000,
+            day: 15,
+            part,
+            answer: answer.to_string(),
+            outcome,
+        };
+        let submissions = [
+            submission(1, "100", Outcome::RateLimited(None)),
+            submission(1, "200", Outcome::TooHigh),
+            submission(2, "300", Outcome::Correct),
+        ];
+        let json = Json::parse(&submissions[1].to_json().to_string()).unwrap();
+        assert_eq!(Submission::from_json(&json).unwrap(), submissions[1]);
+        // rate limited submissions were never judged
+        assert_eq!(judged(&submissions, 15, 1, "100"), None);
+        assert_eq!(judged(&submissions, 15, 1, "200"), Some(&submissions[1]));
+        assert_eq!(judged(&submissions, 15, 1, "150"), None);
+        assert_eq!(judged(&submissions, 15, 2, "301"), Some(&submissions[2]));
+    }
+}
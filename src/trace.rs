@@ -0,0 +1,126 @@
+/*
+** src/trace.rs
+*/
+
+use crate::json;
+
+use anyhow::Result;
+
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// a completed span, in microseconds since tracing was enabled
+struct Event {
+    name: String,
+    category: &'static str,
+    start: u64,
+    duration: u64,
+    thread: u64,
+}
+
+struct Trace {
+    epoch: Instant,
+    events: Vec<Event>,
+}
+
+/// spans are only recorded once tracing has been enabled
+static TRACE: Mutex<Option<Trace>> = Mutex::new(None);
+static NEXT_THREAD_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    /// small sequential thread IDs, which display better than the OS IDs
+    static THREAD_ID: u64 = NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed);
+}
+
+/// starts recording spans
+pub fn enable() {
+    let mut trace = TRACE.lock().unwrap();
+    if trace.is_none() {
+        *trace = Some(Trace {
+            epoch: Instant::now(),
+            events: Vec::new(),
+        });
+    }
+}
+
+/// a span which is recorded when dropped
+pub struct Span {
+    name: String,
+    category: &'static str,
+    start: Instant,
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let end = Instant::now();
+        if let Some(trace) = TRACE.lock().unwrap().as_mut() {
+            let start = self.start.saturating_duration_since(trace.epoch);
+            trace.events.push(Event {
+                name: std::mem::take(&mut self.name),
+                category: self.category,
+                start: start.as_micros() as u64,
+                duration: (end - self.start).as_micros() as u64,
+                thread: THREAD_ID.with(|id| *id),
+            });
+        }
+    }
+}
+
+/// starts a span with the given name and category, such as "day" or "solve"
+pub fn span<S: Into<String>>(name: S, category: &'static str) -> Span {
+    Span {
+        name: name.into(),
+        category,
+        start: Instant::now(),
+    }
+}
+
+/// renders the recorded spans in the Chrome trace event format, which can be
+/// loaded into chrome://tracing or Perfetto
+pub fn to_json() -> String {
+    let trace = TRACE.lock().unwrap();
+    let events = trace
+        .as_ref()
+        .map(|trace| trace.events.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .map(|event| {
+            format!(
+                "{{\"name\":{},\"cat\":{},\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":1,\"tid\":{}}}",
+                json::escape(&event.name),
+                json::escape(event.category),
+                event.start,
+                event.duration,
+                event.thread
+            )
+        })
+        .collect::<Vec<_>>();
+    format!("{{\"traceEvents\":[\n{}\n]}}\n", events.join(",\n"))
+}
+
+/// writes the recorded spans to the given file
+pub fn write(path: &Path) -> Result<()> {
+    fs::write(path, to_json())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_spans() {
+        enable();
+        {
+            let _outer = span("day 1", "day");
+            let _inner = span("solve \"1\"", "solve");
+        }
+        let json = to_json();
+        assert!(json.starts_with("{\"traceEvents\":["));
+        assert!(json.contains("\"name\":\"day 1\",\"cat\":\"day\",\"ph\":\"X\""));
+        assert!(json.contains("\"name\":\"solve \\\"1\\\"\",\"cat\":\"solve\""));
+    }
+}
@@ -2,16 +2,35 @@
 ** src/types.rs
 */
 
+use crate::artifacts::Artifact;
+use crate::context::Context;
+use crate::input::PuzzleInput;
+use crate::json::Json;
+use crate::ocr;
+use crate::parts::Parts;
+use crate::utils;
+
 use anyhow::Result;
 
+use std::collections::HashMap;
 use std::error;
 use std::fmt;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Sub};
 
 /// sum type for all possible puzzle answers
+#[derive(Clone, Debug)]
 pub enum Answer {
     Int(i64),
     UInt(u64),
     Str(String),
+    /// compound answers, such as coordinates, before they are scored
+    Pair(Box<Answer>, Box<Answer>),
+    /// letters drawn in pixels, such as on day 10's CRT, which are answered
+    /// with the letters but can still be shown as drawn
+    Letters {
+        text: String,
+        pixels: String,
+    },
 }
 
 impl From<i64> for Answer {
@@ -50,12 +69,171 @@ impl From<String> for Answer {
     }
 }
 
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Self::Str(s.to_string())
+    }
+}
+
+/// yes/no answers are written out as words, as they would be submitted
+impl From<bool> for Answer {
+    fn from(b: bool) -> Self {
+        Self::Str(b.to_string())
+    }
+}
+
+impl<A, B> From<(A, B)> for Answer
+where
+    A: Into<Answer>,
+    B: Into<Answer>,
+{
+    fn from((a, b): (A, B)) -> Self {
+        Self::Pair(Box::new(a.into()), Box::new(b.into()))
+    }
+}
+
+impl From<Point> for Answer {
+    fn from(point: Point) -> Self {
+        Self::from((point.x, point.y))
+    }
+}
+
+impl Answer {
+    /// the letters drawn in the pixels, given as rows of '#' and '.', or the
+    /// pixels themselves, on a new line, if they are not all letters
+    pub fn letters(pixels: &str) -> Self {
+        match ocr::decode(pixels) {
+            Some(text) => Self::Letters {
+                text,
+                pixels: pixels.to_string(),
+            },
+            None => Self::Str(format!("\n{}", pixels)),
+        }
+    }
+
+    /// the pixels of an answer drawn in letters
+    pub fn pixels(&self) -> Option<&str> {
+        match self {
+            Self::Letters { pixels, .. } => Some(pixels),
+            _ => None,
+        }
+    }
+
+    /// the value of a numeric answer, wide enough for either sign
+    fn as_i128(&self) -> Option<i128> {
+        match self {
+            Self::Int(x) => Some(*x as i128),
+            Self::UInt(x) => Some(*x as i128),
+            _ => None,
+        }
+    }
+}
+
+/// answers compare by value, so Int(5) and UInt(5) are equal
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Str(a), Self::Str(b)) => a == b,
+            (Self::Pair(a1, b1), Self::Pair(a2, b2)) => a1 == a2 && b1 == b2,
+            (Self::Letters { text: a, .. }, Self::Letters { text: b, .. }) => a == b,
+            _ => self.as_i128().is_some_and(|a| other.as_i128() == Some(a)),
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+// answers compare against plain values in both directions, so that tests can
+// write assert_eq!(solution.part_1.unwrap(), 5905)
+macro_rules! answer_eq {
+    ($($t:ty),*) => {
+        $(
+            impl PartialEq<$t> for Answer {
+                fn eq(&self, other: &$t) -> bool {
+                    self.as_i128() == Some(*other as i128)
+                }
+            }
+
+            impl PartialEq<Answer> for $t {
+                fn eq(&self, other: &Answer) -> bool {
+                    other == self
+                }
+            }
+        )*
+    };
+}
+
+answer_eq!(i32, i64, u32, u64, usize);
+
+impl PartialEq<str> for Answer {
+    fn eq(&self, other: &str) -> bool {
+        matches!(self, Self::Str(s) | Self::Letters { text: s, .. } if s == other)
+    }
+}
+
+impl PartialEq<&str> for Answer {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+impl PartialEq<String> for Answer {
+    fn eq(&self, other: &String) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialEq<bool> for Answer {
+    fn eq(&self, other: &bool) -> bool {
+        self == other.to_string().as_str()
+    }
+}
+
+impl PartialEq<Answer> for &str {
+    fn eq(&self, other: &Answer) -> bool {
+        other == self
+    }
+}
+
+impl From<&Answer> for Json {
+    fn from(answer: &Answer) -> Self {
+        match answer {
+            Answer::Int(x) => Self::from(*x),
+            Answer::UInt(x) => Self::from(*x),
+            Answer::Str(x) => Self::from(x.as_str()),
+            Answer::Pair(a, b) => Self::Array(vec![Self::from(&**a), Self::from(&**b)]),
+            Answer::Letters { text, .. } => Self::from(text.as_str()),
+        }
+    }
+}
+
+/// the answers and extras, as returned by the solving server
+impl From<&Solution> for Json {
+    fn from(solution: &Solution) -> Self {
+        Self::object([
+            ("part_1", solution.part_1.as_ref().map(Json::from).into()),
+            ("part_2", solution.part_2.as_ref().map(Json::from).into()),
+            (
+                "extras",
+                Self::object(
+                    solution
+                        .extras
+                        .iter()
+                        .map(|(label, contents)| (label.as_str(), Json::from(contents.as_str()))),
+                ),
+            ),
+        ])
+    }
+}
+
 impl fmt::Display for Answer {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::Int(x) => write!(f, "{}", x),
             Self::UInt(x) => write!(f, "{}", x),
             Self::Str(x) => write!(f, "{}", x),
+            Self::Pair(a, b) => write!(f, "({},{})", a, b),
+            Self::Letters { text, .. } => write!(f, "{}", text),
         }
     }
 }
@@ -64,6 +242,18 @@ impl fmt::Display for Answer {
 pub struct Solution {
     pub part_1: Option<Answer>,
     pub part_2: Option<Answer>,
+    /// auxiliary outputs which are not answers, stored as (label, contents)
+    pub extras: Vec<(String, String)>,
+    /// the steps recorded with explain! while solving, shown with --explain
+    pub explanation: Vec<String>,
+    /// renderings of the solved state, only produced once artifacts have been
+    /// enabled, see artifacts::enable
+    pub artifacts: Vec<Artifact>,
+    /// the time taken to parse the input, separate from solving the parts
+    pub parse_seconds: Option<f64>,
+    /// the time taken to solve each part from the parsed input, for the parts
+    /// which were solved
+    pub part_seconds: [Option<f64>; 2],
 }
 
 impl Solution {
@@ -71,6 +261,11 @@ impl Solution {
         Self {
             part_1: None,
             part_2: None,
+            extras: Vec::new(),
+            explanation: Vec::new(),
+            artifacts: Vec::new(),
+            parse_seconds: None,
+            part_seconds: [None, None],
         }
     }
 
@@ -87,21 +282,209 @@ impl Solution {
     {
         self.part_2 = Some(answer.into());
     }
+
+    pub fn add_extra<T>(&mut self, label: &str, contents: T)
+    where
+        T: fmt::Display,
+    {
+        self.extras.push((label.to_string(), contents.to_string()));
+    }
+
+    /// a solution with only the answer to part 1, as in
+    /// `Solution::of(part_1).and(part_2)`
+    pub fn of<T>(part_1: T) -> Self
+    where
+        T: Into<Answer>,
+    {
+        Self::new().with_part_1(part_1)
+    }
+
+    /// adds the answer to part 2, following Solution::of
+    pub fn and<T>(self, part_2: T) -> Self
+    where
+        T: Into<Answer>,
+    {
+        self.with_part_2(part_2)
+    }
+
+    pub fn with_part_1<T>(mut self, answer: T) -> Self
+    where
+        T: Into<Answer>,
+    {
+        self.set_part_1(answer);
+        self
+    }
+
+    pub fn with_part_2<T>(mut self, answer: T) -> Self
+    where
+        T: Into<Answer>,
+    {
+        self.set_part_2(answer);
+        self
+    }
+
+    pub fn with_extra<T>(mut self, label: &str, contents: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        self.add_extra(label, contents);
+        self
+    }
 }
 
-/// standard puzzle function type
-pub type Puzzle = fn(String) -> Result<Solution>;
+/// the answers to both parts, as (part 1, part 2)
+impl<A, B> From<(A, B)> for Solution
+where
+    A: Into<Answer>,
+    B: Into<Answer>,
+{
+    fn from((part_1, part_2): (A, B)) -> Self {
+        Self::of(part_1).and(part_2)
+    }
+}
+
+impl Default for Solution {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// describes how a day is solved, for listings and for selecting days by tag
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Metadata {
+    /// short name of the approach, such as "dijkstra"
+    pub algorithm: &'static str,
+    /// time complexity in terms of the input
+    pub complexity: &'static str,
+    /// kinds of puzzle, such as "graph" or "simulation"
+    pub tags: &'static [&'static str],
+}
+
+impl Metadata {
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(&tag)
+    }
+}
+
+impl fmt::Display for Metadata {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}, {} [{}]",
+            self.algorithm,
+            self.complexity,
+            self.tags.join(", ")
+        )
+    }
+}
+
+/// standard solver function type, solving the given parts of the input with
+/// the settings of the context, see context::Context
+pub type Solver = fn(&str, Parts, &Context) -> Result<Solution>;
+
+/// a day's puzzle, whose input is parsed once and whose parts are then solved
+/// from the parsed input, so that each part can be run and timed on its own
+pub trait Puzzle {
+    /// the parsed input, shared by both parts
+    type Parsed: Sync;
+
+    /// the day of the puzzle, which numbers the errors of its parsers
+    const DAY: usize;
+
+    /// the title of the puzzle, such as "Distress Signal"
+    const TITLE: &'static str;
+
+    /// how the puzzle is solved, see Metadata
+    const METADATA: Metadata;
+
+    /// the number of parts, as the last day has only the one
+    const PARTS: usize = 2;
+
+    /// the answers to the sample input, as given in the puzzle description,
+    /// as [part 1, part 2]
+    const SAMPLE_ANSWERS: [Option<&'static str>; 2] = [None, None];
+
+    /// the verified answers to the real input, which runs are checked against
+    /// with --check
+    const ANSWERS: [Option<&'static str>; 2] = [None, None];
+
+    fn parse(input: PuzzleInput) -> Result<Self::Parsed>;
+
+    fn part_1(parsed: &Self::Parsed, context: &Context) -> Result<Answer>;
+
+    fn part_2(_parsed: &Self::Parsed, _context: &Context) -> Result<Answer> {
+        Err(Error::NoSolution.into())
+    }
+
+    /// auxiliary outputs which are not answers, as (label, contents), given
+    /// the solved parts
+    fn extras(_parsed: &Self::Parsed, _solution: &Solution) -> Vec<(String, String)> {
+        Vec::new()
+    }
 
-/// custom error type
+    /// renderings of the solved state, such as its final grid; only called
+    /// once artifacts have been enabled
+    fn artifacts(_parsed: &Self::Parsed, _solution: &Solution) -> Vec<Artifact> {
+        Vec::new()
+    }
+}
+
+/// custom error type, whose variants the runner reports differently: missing
+/// inputs and unimplemented days are skipped, while the others are failures
 #[derive(Debug)]
 pub enum Error {
     NoSolution,
+    /// the day has no input to solve
+    MissingInput {
+        day: usize,
+    },
+    /// the day has no solver
+    Unimplemented {
+        day: usize,
+    },
+    /// a malformed line of the input, numbered from 1
+    Parse {
+        day: usize,
+        line: usize,
+        reason: String,
+    },
+    /// the day was stopped for exceeding its CPU time limit, in seconds
+    Timeout {
+        day: usize,
+        seconds: u64,
+    },
+}
+
+impl Error {
+    /// the category of the error, as reported in the events of a run
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::NoSolution => "no_solution",
+            Self::MissingInput { .. } => "missing_input",
+            Self::Unimplemented { .. } => "unimplemented",
+            Self::Parse { .. } => "parse",
+            Self::Timeout { .. } => "timeout",
+        }
+    }
+
+    /// whether the day should be skipped rather than failing the run
+    pub fn is_skip(&self) -> bool {
+        matches!(self, Self::MissingInput { .. } | Self::Unimplemented { .. })
+    }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::NoSolution => write!(f, "no solution found"),
+            Self::MissingInput { day } => write!(f, "missing input for day {}", day),
+            Self::Unimplemented { day } => write!(f, "day {}: not implemented", day),
+            Self::Parse { day, line, reason } => {
+                write!(f, "day {} input, line {}: {}", day, line, reason)
+            }
+            Self::Timeout { day, seconds } => {
+                write!(f, "day {}: exceeded the CPU limit of {}s", day, seconds)
+            }
         }
     }
 }
@@ -110,6 +493,10 @@ impl error::Error for Error {
     fn description(&self) -> &str {
         match self {
             Self::NoSolution => "no solution found",
+            Self::MissingInput { .. } => "missing input",
+            Self::Unimplemented { .. } => "not implemented",
+            Self::Parse { .. } => "malformed input",
+            Self::Timeout { .. } => "timed out",
         }
     }
 }
@@ -129,6 +516,37 @@ impl Point {
         Self::new(0, 0)
     }
 
+    /// the point a single step away in the direction
+    pub fn step(self, direction: Direction) -> Self {
+        self + direction.delta()
+    }
+
+    /// the sign of each coordinate, which is the step towards a point in the
+    /// same direction along each axis
+    pub fn signum(self) -> Self {
+        Self::new(self.x.signum(), self.y.signum())
+    }
+
+    /// the points a single step away up, down, left and right
+    pub fn neighbors4(self) -> [Self; 4] {
+        Direction::ALL.map(|direction| self.step(direction))
+    }
+
+    /// the points a single step away, including diagonally
+    pub fn neighbors8(self) -> [Self; 8] {
+        [
+            (-1, -1),
+            (0, -1),
+            (1, -1),
+            (-1, 0),
+            (1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+        ]
+        .map(|(dx, dy)| self + Self::new(dx, dy))
+    }
+
     pub fn manhattan_distance(point_a: Self, point_b: Self) -> i64 {
         let dx = point_a.x - point_b.x;
         let dy = point_a.y - point_b.y;
@@ -136,8 +554,543 @@ impl Point {
     }
 }
 
+impl Add for Point {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Point {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<i64> for Point {
+    type Output = Self;
+
+    fn mul(self, n: i64) -> Self {
+        Self::new(self.x * n, self.y * n)
+    }
+}
+
 impl fmt::Display for Point {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "({},{})", self.x, self.y)
     }
 }
+
+/// a direction on a grid, with up towards the first row
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Self; 4] = [Self::Up, Self::Down, Self::Left, Self::Right];
+
+    pub fn turn_left(self) -> Self {
+        match self {
+            Self::Up => Self::Left,
+            Self::Down => Self::Right,
+            Self::Left => Self::Down,
+            Self::Right => Self::Up,
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Self::Up => Self::Right,
+            Self::Down => Self::Left,
+            Self::Left => Self::Up,
+            Self::Right => Self::Down,
+        }
+    }
+
+    pub fn opposite(self) -> Self {
+        match self {
+            Self::Up => Self::Down,
+            Self::Down => Self::Up,
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+
+    /// the change in position of a single step, with y increasing downwards
+    /// as for the rows of a grid
+    pub fn delta(self) -> Point {
+        match self {
+            Self::Up => Point::new(0, -1),
+            Self::Down => Point::new(0, 1),
+            Self::Left => Point::new(-1, 0),
+            Self::Right => Point::new(1, 0),
+        }
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = String;
+
+    /// parses U/D/L/R or ^/v/</>
+    fn try_from(c: char) -> std::result::Result<Self, Self::Error> {
+        match c {
+            'U' | '^' => Ok(Self::Up),
+            'D' | 'v' => Ok(Self::Down),
+            'L' | '<' => Ok(Self::Left),
+            'R' | '>' => Ok(Self::Right),
+            _ => Err(format!("invalid direction {:?}", c)),
+        }
+    }
+}
+
+/// a position in a grid, as its row and column
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Coord {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Coord {
+    pub fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+}
+
+impl From<(usize, usize)> for Coord {
+    fn from((row, col): (usize, usize)) -> Self {
+        Self::new(row, col)
+    }
+}
+
+impl fmt::Display for Coord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", self.row, self.col)
+    }
+}
+
+/// a rectangular grid of cells, stored row by row
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// a grid with every cell set to the value
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// parses each character of the day's input as a cell, with the lines of
+    /// the input as the rows, which must all be the same length
+    pub fn from_input<F>(day: usize, input: &str, parse: F) -> Result<Self>
+    where
+        F: Fn(char) -> std::result::Result<T, String>,
+    {
+        let rows = utils::parse_lines(day, input, |line| {
+            line.chars()
+                .map(&parse)
+                .collect::<std::result::Result<Vec<_>, _>>()
+        })?;
+        let width = rows.first().map_or(0, |row| row.len());
+        if let Some(i) = rows.iter().position(|row| row.len() != width) {
+            return Err(Error::Parse {
+                day,
+                line: i + 1,
+                reason: format!("expected {} cells, found {}", width, rows[i].len()),
+            }
+            .into());
+        }
+        Ok(Self {
+            width,
+            height: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, coord: Coord) -> bool {
+        coord.row < self.height && coord.col < self.width
+    }
+
+    pub fn get(&self, coord: Coord) -> Option<&T> {
+        self.contains(coord)
+            .then(|| &self.cells[coord.row * self.width + coord.col])
+    }
+
+    pub fn get_mut(&mut self, coord: Coord) -> Option<&mut T> {
+        if self.contains(coord) {
+            Some(&mut self.cells[coord.row * self.width + coord.col])
+        } else {
+            None
+        }
+    }
+
+    /// the coordinate a single step away in the direction, if it is inside
+    /// the grid
+    pub fn step(&self, coord: Coord, direction: Direction) -> Option<Coord> {
+        let delta = direction.delta();
+        let row = coord.row.checked_add_signed(delta.y as isize)?;
+        let col = coord.col.checked_add_signed(delta.x as isize)?;
+        Some(Coord::new(row, col)).filter(|&next| self.contains(next))
+    }
+
+    /// the coordinates above, below, left and right of the cell, in that
+    /// order, which are inside the grid
+    pub fn neighbors(&self, coord: Coord) -> impl Iterator<Item = Coord> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| self.step(coord, direction))
+    }
+
+    /// the coordinates of every cell, row by row
+    pub fn coords(&self) -> impl Iterator<Item = Coord> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| Coord::new(row, col)))
+    }
+
+    /// the cells along with their coordinates, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> {
+        self.coords().zip(self.cells.iter())
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// a grid of the same size with the function applied to each cell
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+impl<T> Index<Coord> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, coord: Coord) -> &T {
+        self.get(coord)
+            .unwrap_or_else(|| panic!("{} is outside of the grid", coord))
+    }
+}
+
+impl<T> IndexMut<Coord> for Grid<T> {
+    fn index_mut(&mut self, coord: Coord) -> &mut T {
+        self.get_mut(coord)
+            .unwrap_or_else(|| panic!("{} is outside of the grid", coord))
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        &self[Coord::new(row, col)]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        &mut self[Coord::new(row, col)]
+    }
+}
+
+/// a grid which only stores the cells which have been set, at any points, and
+/// tracks the bounds of those points as they are inserted
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+    /// the top-left and bottom-right corners of the points set so far
+    bounds: Option<(Point, Point)>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        self.cells.contains_key(point)
+    }
+
+    pub fn get(&self, point: &Point) -> Option<&T> {
+        self.cells.get(point)
+    }
+
+    /// sets the cell, returning its previous value, and widens the bounds to
+    /// cover it
+    pub fn insert(&mut self, point: Point, value: T) -> Option<T> {
+        self.bounds = Some(match self.bounds {
+            Some((min, max)) => (
+                Point::new(min.x.min(point.x), min.y.min(point.y)),
+                Point::new(max.x.max(point.x), max.y.max(point.y)),
+            ),
+            None => (point, point),
+        });
+        self.cells.insert(point, value)
+    }
+
+    /// the top-left and bottom-right corners of the cells, or None if no cell
+    /// has been set
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        self.bounds
+    }
+
+    /// the points of the cells which have been set, in no particular order
+    pub fn points(&self) -> impl Iterator<Item = &Point> {
+        self.cells.keys()
+    }
+
+    /// the cells which have been set along with their points, in no
+    /// particular order
+    pub fn iter(&self) -> impl Iterator<Item = (&Point, &T)> {
+        self.cells.iter()
+    }
+
+    /// the cells within the bounds as text, a line per row from the top, with
+    /// the character for each cell which has been set and the empty character
+    /// everywhere else
+    pub fn render<F>(&self, empty: char, cell: F) -> String
+    where
+        F: Fn(&T) -> char,
+    {
+        let Some((min, max)) = self.bounds else {
+            return String::new();
+        };
+        (min.y..=max.y)
+            .map(|y| {
+                (min.x..=max.x)
+                    .map(|x| self.cells.get(&Point::new(x, y)).map_or(empty, &cell))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pair_answers() {
+        let answer = Answer::from(Point::new(14, -11));
+        assert_eq!(answer.to_string(), "(14,-11)");
+        assert_eq!(Json::from(&answer).to_string(), "[14,-11]");
+        let nested = Answer::from((String::from("a"), (1u64, 2u64)));
+        assert_eq!(nested.to_string(), "(a,(1,2))");
+        assert_eq!(Json::from(&nested).to_string(), "[\"a\",[1,2]]");
+    }
+
+    #[test]
+    fn letter_answers() {
+        let pixels = [
+            "####.#..#",
+            "...#.#..#",
+            "..#..####",
+            ".#...#..#",
+            "#....#..#",
+            "####.#..#",
+        ]
+        .join("\n");
+        let answer = Answer::letters(&pixels);
+        assert_eq!(answer, "ZH");
+        assert_eq!(answer.pixels(), Some(pixels.as_str()));
+        assert_eq!(Json::from(&answer).to_string(), "\"ZH\"");
+        // pixels which are not letters are answered as drawn
+        let answer = Answer::letters("#.#.");
+        assert_eq!(answer, "\n#.#.");
+        assert_eq!(answer.pixels(), None);
+    }
+
+    #[test]
+    fn solution_json() {
+        let solution = Solution::new()
+            .with_part_1(24000u64)
+            .with_extra("schedule", "AA -> DD");
+        assert_eq!(
+            Json::from(&solution).to_string(),
+            r#"{"extras":{"schedule":"AA -> DD"},"part_1":24000,"part_2":null}"#
+        );
+    }
+
+    #[test]
+    fn build_solutions() {
+        let solution = Solution::of(24000).and("CMZ");
+        assert_eq!(solution.part_1.unwrap(), 24000);
+        assert_eq!(solution.part_2.unwrap(), "CMZ");
+        let solution = Solution::from((1, 2u64));
+        assert_eq!(solution.part_1.unwrap(), 1);
+        assert_eq!(solution.part_2.unwrap(), 2);
+        let solution = Solution::new().with_part_2(5);
+        assert!(solution.part_1.is_none());
+    }
+
+    #[test]
+    fn compare_answers() {
+        assert_eq!(Answer::from(5905), 5905);
+        assert_eq!(Answer::from(5905u64), 5905);
+        assert_eq!(5905usize, Answer::from(5905i64));
+        assert_ne!(Answer::from(-1), u64::MAX);
+        assert_eq!(Answer::from("CMZ"), "CMZ");
+        assert_eq!("CMZ", Answer::from(String::from("CMZ")));
+        assert_ne!(Answer::from("5"), 5);
+        assert_eq!(Answer::from(true), true);
+        assert_eq!(Answer::from((1, 2u64)), Answer::from((1u32, 2)));
+        assert_ne!(Answer::from((1, 2)), Answer::from((2, 1)));
+    }
+
+    #[test]
+    fn error_kinds() {
+        let err: anyhow::Error = Error::MissingInput { day: 3 }.into();
+        assert_eq!(err.to_string(), "missing input for day 3");
+        let err = err.downcast_ref::<Error>().unwrap();
+        assert_eq!(err.kind(), "missing_input");
+        assert!(err.is_skip());
+        assert!(Error::Unimplemented { day: 17 }.is_skip());
+        let timeout = Error::Timeout {
+            day: 16,
+            seconds: 1,
+        };
+        assert_eq!(timeout.kind(), "timeout");
+        assert!(!timeout.is_skip());
+        assert!(!Error::NoSolution.is_skip());
+    }
+
+    fn digit(c: char) -> std::result::Result<u32, String> {
+        c.to_digit(10)
+            .ok_or_else(|| format!("invalid digit {:?}", c))
+    }
+
+    #[test]
+    fn grid_from_input() {
+        let grid = Grid::from_input(8, "123\n456", digit).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 0)], 4);
+        assert_eq!(grid[Coord::new(0, 2)], 3);
+        assert_eq!(grid.get(Coord::new(2, 0)), None);
+        assert_eq!(grid.rows().collect::<Vec<_>>(), [[1, 2, 3], [4, 5, 6]]);
+        let err = Grid::from_input(8, "123\n45", digit).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "day 8 input, line 2: expected 3 cells, found 2"
+        );
+        let err = Grid::from_input(8, "123\n4x6", digit).unwrap_err();
+        assert_eq!(err.to_string(), "day 8 input, line 2: invalid digit 'x'");
+    }
+
+    #[test]
+    fn grid_neighbors() {
+        let grid = Grid::new(3, 2, 0);
+        let neighbors = |row, col| grid.neighbors(Coord::new(row, col)).collect::<Vec<_>>();
+        assert_eq!(neighbors(0, 0), [Coord::new(1, 0), Coord::new(0, 1)]);
+        assert_eq!(
+            neighbors(1, 1),
+            [Coord::new(0, 1), Coord::new(1, 0), Coord::new(1, 2)]
+        );
+        assert_eq!(grid.coords().count(), 6);
+    }
+
+    #[test]
+    fn sparse_grid() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+        assert_eq!(grid.render('.', |_| '#'), "");
+        grid.insert(Point::new(2, -1), 'a');
+        grid.insert(Point::new(-1, 1), 'b');
+        assert_eq!(grid.insert(Point::new(2, -1), 'c'), Some('a'));
+        assert_eq!(grid.len(), 2);
+        assert_eq!(grid.bounds(), Some((Point::new(-1, -1), Point::new(2, 1))));
+        assert_eq!(grid.get(&Point::new(-1, 1)), Some(&'b'));
+        assert!(!grid.contains(&Point::origin()));
+        assert_eq!(grid.render('.', |&c| c), "...c\n....\nb...");
+    }
+
+    #[test]
+    fn direction_turns() {
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!(direction.turn_right().turn_right(), direction.opposite());
+            let delta = direction.delta();
+            let opposite = direction.opposite().delta();
+            assert_eq!(delta + opposite, Point::origin());
+        }
+        assert_eq!(Direction::try_from('^'), Ok(Direction::Up));
+        assert_eq!(Point::origin().step(Direction::Down), Point::new(0, 1));
+        assert!(Direction::try_from('x').is_err());
+    }
+
+    #[test]
+    fn point_arithmetic() {
+        let a = Point::new(3, -2);
+        let b = Point::new(-1, 5);
+        assert_eq!(a + b, Point::new(2, 3));
+        assert_eq!(a - b, Point::new(4, -7));
+        assert_eq!(a * 3, Point::new(9, -6));
+        assert_eq!((a - b).signum(), Point::new(1, -1));
+        let mut c = a;
+        c += b;
+        assert_eq!(c, a + b);
+
+        let neighbors = a.neighbors8();
+        assert!(neighbors
+            .iter()
+            .all(|&n| n != a && (n.x - a.x).abs() <= 1 && (n.y - a.y).abs() <= 1));
+        assert!(a.neighbors4().iter().all(|n| neighbors.contains(n)));
+        assert!(a
+            .neighbors4()
+            .iter()
+            .all(|&n| Point::manhattan_distance(a, n) == 1));
+    }
+}
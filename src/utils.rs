@@ -2,13 +2,32 @@
 ** src/utils.rs
 */
 
+use crate::explain;
+#[cfg(unix)]
+use crate::mmap::Mmap;
+use crate::pool;
+use crate::types::Error;
+
 use anyhow::Result;
 
+use std::fmt;
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
+use std::iter::Map;
+use std::ops::Deref;
 use std::path::Path;
 use std::str::{FromStr, Split};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub mod graph;
+pub mod math;
+mod queue;
+mod search;
+
+pub use crate::captures;
+pub use queue::PriorityQueue;
+pub use search::{astar, bfs, dfs, dijkstra, DistanceMap, SearchTree};
 
 /// reads the contents of a file into a string
 pub fn read_file(path: &Path) -> Result<String> {
@@ -19,11 +38,111 @@ pub fn read_file(path: &Path) -> Result<String> {
     Ok(contents)
 }
 
+/// puzzle input, either read into memory or memory-mapped
+pub enum Input {
+    Owned(String),
+    #[cfg(unix)]
+    Mapped(Mmap),
+}
+
+impl Input {
+    /// reads the input from a file into memory
+    pub fn read(path: &Path) -> Result<Self> {
+        Ok(Self::Owned(read_file(path)?))
+    }
+
+    /// memory-maps the input from a file, or reads it into memory on
+    /// platforms without memory mapping
+    ///
+    /// # Safety
+    ///
+    /// the file must not be modified or truncated while the input is alive,
+    /// see Mmap::open
+    #[cfg(unix)]
+    pub unsafe fn map(path: &Path) -> Result<Self> {
+        // SAFETY: upheld by the caller
+        let mmap = unsafe { Mmap::open(path)? };
+        // validate once here so that derefs are free
+        mmap.as_str()?;
+        Ok(Self::Mapped(mmap))
+    }
+
+    /// memory-maps the input from a file, or reads it into memory on
+    /// platforms without memory mapping
+    ///
+    /// # Safety
+    ///
+    /// always safe here, as the input is read into memory
+    #[cfg(not(unix))]
+    pub unsafe fn map(path: &Path) -> Result<Self> {
+        Self::read(path)
+    }
+}
+
+impl Deref for Input {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match self {
+            Self::Owned(s) => s,
+            // SAFETY: the contents were validated as UTF-8 when mapped, and the
+            // file is not changed while mapped, as required by map
+            #[cfg(unix)]
+            Self::Mapped(mmap) => unsafe { std::str::from_utf8_unchecked(mmap.as_bytes()) },
+        }
+    }
+}
+
 /// splits a string by newlines
 pub fn split_lines(input: &str) -> impl Iterator<Item = &str> {
     input.split('\n')
 }
 
+/// parses each line of the day's input, numbering the lines from 1 in the
+/// errors of the parser, see types::Error::Parse
+pub fn parse_lines<'a, T, F>(day: usize, input: &'a str, parse: F) -> Result<Vec<T>>
+where
+    F: Fn(&'a str) -> std::result::Result<T, String>,
+{
+    parse_lines_from(day, 1, input, parse)
+}
+
+/// parses each line of a section of the day's input which starts at the given
+/// line, see parse_lines
+pub fn parse_lines_from<'a, T, F>(
+    day: usize,
+    first_line: usize,
+    input: &'a str,
+    parse: F,
+) -> Result<Vec<T>>
+where
+    F: Fn(&'a str) -> std::result::Result<T, String>,
+{
+    split_lines(input)
+        .enumerate()
+        .map(|(i, line)| {
+            parse(line).map_err(|reason| {
+                Error::Parse {
+                    day,
+                    line: first_line + i,
+                    reason,
+                }
+                .into()
+            })
+        })
+        .collect()
+}
+
+/// parses a field of a line, describing what was expected if it is malformed
+pub fn parse_field<T>(s: &str, name: &str) -> std::result::Result<T, String>
+where
+    T: FromStr,
+    <T as FromStr>::Err: fmt::Display,
+{
+    s.parse()
+        .map_err(|e| format!("invalid {} {:?}: {}", name, s, e))
+}
+
 /// splits a string by chunks of newlines, separated by double newlines
 pub fn split_lines_double(input: &str) -> impl Iterator<Item = Split<'_, char>> {
     input.split("\n\n").map(|chunk| chunk.split('\n'))
@@ -51,75 +170,295 @@ pub fn find_char(s: &str, c: char) -> Option<usize> {
     s.chars().position(|cc| cc == c)
 }
 
-/// iterator adapter to group an iterator into 2-tuples
-pub struct GroupBy2Iterator<I> {
-    iter: I,
+/// every integer in the string, with its sign, such as the coordinates in
+/// "x=-2, y=15"; a dash between two numbers, as in "2-4", is not a sign, and
+/// numbers which do not fit in T are skipped
+pub fn numbers<T>(s: &str) -> Vec<T>
+where
+    T: FromStr,
+{
+    let bytes = s.as_bytes();
+    let mut numbers = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let mut start = i;
+        if start > 0
+            && bytes[start - 1] == b'-'
+            && !(start > 1 && bytes[start - 2].is_ascii_digit())
+        {
+            start -= 1;
+        }
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        if let Ok(n) = s[start..i].parse() {
+            numbers.push(n);
+        }
+    }
+    numbers
 }
 
-impl<I> GroupBy2Iterator<I> {
-    pub fn new(iter: I) -> Self {
-        Self { iter }
-    }
+/// matches the string against the regex, which is compiled once, and parses
+/// its groups as the types of the tuple, giving None if the string does not
+/// match or a group does not parse:
+///
+/// utils::captures!(line, r"x=(-?\d+), y=(-?\d+)", (i64, i64))
+#[macro_export]
+macro_rules! captures {
+    ($s:expr, $re:literal, ($($t:ty),+ $(,)?)) => {{
+        fn regex() -> &'static ::regex::Regex {
+            static RE: std::sync::OnceLock<::regex::Regex> = std::sync::OnceLock::new();
+            RE.get_or_init(|| ::regex::Regex::new($re).unwrap())
+        }
+        regex()
+            .captures($s)
+            .and_then(|captures| {
+                let mut groups = captures.iter().skip(1);
+                Some(($(groups.next()??.as_str().parse::<$t>().ok()?,)+))
+            })
+    }};
 }
 
-impl<'a, I, T> Iterator for GroupBy2Iterator<I>
-where
-    T: 'a,
-    I: Iterator<Item = &'a T>,
-{
-    type Item = (&'a T, &'a T);
-    fn next(&mut self) -> Option<Self::Item> {
-        let x = self.iter.next();
-        let y = self.iter.next();
-        if let (Some(a), Some(b)) = (x, y) {
-            Some((a, b))
-        } else {
-            None
+/// the Levenshtein distance between the strings, i.e. the minimum number of
+/// single-character insertions, deletions, and substitutions to turn one into
+/// the other
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    // only the previous row of the table is needed
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
         }
     }
+    row[b.len()]
 }
 
-pub trait GroupBy2<T>: Iterator<Item = T> + Sized {
-    fn group_by_2(self) -> GroupBy2Iterator<Self> {
-        GroupBy2Iterator::new(self)
+/// the longest common subsequence of the strings, i.e. the longest string
+/// whose characters appear in both in order, though not necessarily adjacent
+pub fn longest_common_subsequence(a: &str, b: &str) -> String {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    // lengths[i][j] is the LCS length of the suffixes a[i..] and b[j..]
+    let mut lengths = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lengths[i][j] = if a[i] == b[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+    // walk the table forwards to recover the subsequence itself
+    let mut lcs = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            lcs.push(a[i]);
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
     }
+    lcs
 }
 
-impl<T, I: Iterator<Item = T>> GroupBy2<T> for I {}
+/// maps the function over the items on the given number of threads, see
+/// pool::map, calling progress with the number of completed items and the
+/// total after each item, such as a Progress::tracker
+///
+/// the explanation steps of each item are handed back to the calling thread,
+/// in the order of the items
+pub fn par_map_with_progress<T, R, F, P>(items: &[T], threads: usize, f: F, progress: P) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+    P: Fn(usize, usize) + Sync,
+{
+    let completed = AtomicUsize::new(0);
+    let results = pool::map(items, threads, false, |item| {
+        let result = f(item);
+        progress(completed.fetch_add(1, Ordering::Relaxed) + 1, items.len());
+        (result, explain::take())
+    });
+    results
+        .into_iter()
+        .map(|(result, steps)| {
+            for step in steps {
+                explain::step(step);
+            }
+            result
+        })
+        .collect()
+}
 
-/// iterator adapter to group an iterator into 3-tuples
-pub struct GroupBy3Iterator<I> {
+/// iterator adapter to group an iterator into arrays of N items, dropping
+/// any items left over at the end
+pub struct GroupByNIterator<I, const N: usize> {
     iter: I,
 }
 
-impl<I> GroupBy3Iterator<I> {
+impl<I, const N: usize> GroupByNIterator<I, N> {
     pub fn new(iter: I) -> Self {
         Self { iter }
     }
 }
 
-impl<'a, I, T> Iterator for GroupBy3Iterator<I>
+impl<I, const N: usize> Iterator for GroupByNIterator<I, N>
 where
-    T: 'a,
-    I: Iterator<Item = &'a T>,
+    I: Iterator,
 {
-    type Item = (&'a T, &'a T, &'a T);
+    type Item = [I::Item; N];
     fn next(&mut self) -> Option<Self::Item> {
-        let x = self.iter.next();
-        let y = self.iter.next();
-        let z = self.iter.next();
-        if let (Some(a), Some(b), Some(c)) = (x, y, z) {
-            Some((a, b, c))
-        } else {
-            None
+        // fill the group in place, without taking any more items once the
+        // iterator runs out
+        let mut done = false;
+        let group: [Option<I::Item>; N] = std::array::from_fn(|_| {
+            let item = if done { None } else { self.iter.next() };
+            done = item.is_none();
+            item
+        });
+        if done {
+            return None;
         }
+        Some(group.map(Option::unwrap))
+    }
+}
+
+pub trait GroupByN<T>: Iterator<Item = T> + Sized {
+    fn group_by_n<const N: usize>(self) -> GroupByNIterator<Self, N> {
+        GroupByNIterator::new(self)
+    }
+}
+
+impl<T, I: Iterator<Item = T>> GroupByN<T> for I {}
+
+/// iterator adapter to group an iterator into 2-tuples
+pub type GroupBy2Iterator<I, T> = Map<GroupByNIterator<I, 2>, fn([T; 2]) -> (T, T)>;
+
+pub trait GroupBy2<T>: Iterator<Item = T> + Sized {
+    fn group_by_2(self) -> GroupBy2Iterator<Self, T> {
+        let tuple: fn([T; 2]) -> (T, T) = |[a, b]| (a, b);
+        self.group_by_n::<2>().map(tuple)
     }
 }
 
+impl<T, I: Iterator<Item = T>> GroupBy2<T> for I {}
+
+/// iterator adapter to group an iterator into 3-tuples
+pub type GroupBy3Iterator<I, T> = Map<GroupByNIterator<I, 3>, fn([T; 3]) -> (T, T, T)>;
+
 pub trait GroupBy3<T>: Iterator<Item = T> + Sized {
-    fn group_by_3(self) -> GroupBy3Iterator<Self> {
-        GroupBy3Iterator::new(self)
+    fn group_by_3(self) -> GroupBy3Iterator<Self, T> {
+        let tuple: fn([T; 3]) -> (T, T, T) = |[a, b, c]| (a, b, c);
+        self.group_by_n::<3>().map(tuple)
     }
 }
 
 impl<T, I: Iterator<Item = T>> GroupBy3<T> for I {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_errors() {
+        let parsed = parse_lines(1, "1\n2\n3", |s| parse_field::<u8>(s, "count")).unwrap();
+        assert_eq!(parsed, vec![1, 2, 3]);
+        let e = parse_lines(1, "1\n-2", |s| parse_field::<u8>(s, "count")).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "day 1 input, line 2: invalid count \"-2\": invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn extract_numbers() {
+        let line = "Sensor at x=2, y=-18: closest beacon is at x=-2, y=15";
+        assert_eq!(numbers::<i64>(line), [2, -18, -2, 15]);
+        assert_eq!(numbers::<u32>("2-4,6-8"), [2, 4, 6, 8]);
+        assert_eq!(numbers::<u64>("  Starting items: 79, 98"), [79, 98]);
+        // negative numbers do not fit in unsigned types
+        assert_eq!(numbers::<u64>("x=-5, y=7"), [7]);
+        assert_eq!(numbers::<u8>("300 and 25"), [25]);
+        assert!(numbers::<i64>("no numbers - here").is_empty());
+    }
+
+    #[test]
+    fn regex_captures() {
+        for (line, expected) in [("x=3, y=-4", Some((3, -4))), ("x=5, y=6", Some((5, 6)))] {
+            assert_eq!(
+                captures!(line, r"^x=(-?\d+), y=(-?\d+)$", (i64, i64)),
+                expected
+            );
+        }
+        assert_eq!(
+            captures!("x=3", r"^x=(-?\d+), y=(-?\d+)$", (i64, i64)),
+            None
+        );
+        // a group which does not parse
+        assert_eq!(captures!("x=300", r"^x=(\d+)$", (u8,)), None);
+        let pair = captures!("AA -> BB", r"(\w+) -> (\w+)", (String, String));
+        assert_eq!(pair, Some((String::from("AA"), String::from("BB"))));
+    }
+
+    #[test]
+    fn group_iterators() {
+        let items = [1, 2, 3, 4, 5, 6, 7];
+        let groups = items.iter().group_by_n::<3>().collect::<Vec<_>>();
+        // the leftover item is dropped
+        assert_eq!(groups, [[&1, &2, &3], [&4, &5, &6]]);
+        let pairs = items.iter().group_by_2().collect::<Vec<_>>();
+        assert_eq!(pairs, [(&1, &2), (&3, &4), (&5, &6)]);
+        let triples = items.iter().group_by_3().collect::<Vec<_>>();
+        assert_eq!(triples, [(&1, &2, &3), (&4, &5, &6)]);
+    }
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("flaw", "lawn"), 2);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("abc", ""), 3);
+        assert_eq!(edit_distance("same", "same"), 0);
+        assert_eq!(edit_distance("naïve", "naive"), 1);
+    }
+
+    #[test]
+    fn longest_common_subsequences() {
+        assert_eq!(longest_common_subsequence("ABCBDAB", "BDCABA").len(), 4);
+        assert_eq!(longest_common_subsequence("AGGTAB", "GXTXAYB"), "GTAB");
+        assert_eq!(longest_common_subsequence("abc", "def"), "");
+        assert_eq!(longest_common_subsequence("", "abc"), "");
+        assert_eq!(longest_common_subsequence("abc", "abc"), "abc");
+    }
+
+    #[test]
+    fn par_map_progress() {
+        let items = (0..50).collect::<Vec<u64>>();
+        let reported = std::sync::Mutex::new(Vec::new());
+        let output = par_map_with_progress(
+            &items,
+            4,
+            |n| n * 2,
+            |completed, total| reported.lock().unwrap().push((completed, total)),
+        );
+        assert_eq!(output, items.iter().map(|n| n * 2).collect::<Vec<_>>());
+        let mut reported = reported.into_inner().unwrap();
+        reported.sort();
+        assert_eq!(reported, (1..=50).map(|n| (n, 50)).collect::<Vec<_>>());
+    }
+}
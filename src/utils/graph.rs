@@ -0,0 +1,304 @@
+/*
+** src/utils/graph.rs
+*/
+
+use crate::utils::search::{self, DistanceMap};
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+/// a directed graph with weighted edges, stored as the list of edges leaving
+/// each node; nodes are numbered in the order they are added, which numbers
+/// the rows and columns of the all-pairs distances
+#[derive(Clone, Debug)]
+pub struct Graph<N> {
+    nodes: Vec<N>,
+    indices: HashMap<N, usize>,
+    edges: Vec<Vec<(usize, u64)>>,
+}
+
+impl<N> Graph<N>
+where
+    N: Clone + Eq + Hash,
+{
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            indices: HashMap::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// the nodes, in the order they were added
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    /// the number of the node, if it is in the graph
+    pub fn index(&self, node: &N) -> Option<usize> {
+        self.indices.get(node).copied()
+    }
+
+    /// adds the node if it is not in the graph yet, returning its number
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(index) = self.index(&node) {
+            return index;
+        }
+        self.indices.insert(node.clone(), self.nodes.len());
+        self.nodes.push(node);
+        self.edges.push(Vec::new());
+        self.nodes.len() - 1
+    }
+
+    /// adds an edge, along with either of its nodes which are not in the
+    /// graph yet
+    pub fn add_edge(&mut self, from: N, to: N, weight: u64) {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+        self.edges[from].push((to, weight));
+    }
+
+    /// the nodes which the node has edges to, along with their weights
+    pub fn neighbors(&self, node: &N) -> impl Iterator<Item = (&N, u64)> {
+        let edges = match self.index(node) {
+            Some(index) => &self.edges[index][..],
+            None => &[],
+        };
+        edges
+            .iter()
+            .map(move |&(to, weight)| (&self.nodes[to], weight))
+    }
+
+    /// the shortest distance from each node to every other, by their numbers,
+    /// which is u64::MAX where there is no path
+    pub fn floyd_warshall(&self) -> Vec<Vec<u64>> {
+        let n = self.len();
+        let mut distances = vec![vec![u64::MAX; n]; n];
+        for (from, edges) in self.edges.iter().enumerate() {
+            distances[from][from] = 0;
+            for &(to, weight) in edges {
+                distances[from][to] = distances[from][to].min(weight);
+            }
+        }
+        // allow each node in turn on the paths between every other pair
+        for k in 0..n {
+            let through = distances[k].clone();
+            for row in distances.iter_mut() {
+                let dik = row[k];
+                if dik == u64::MAX {
+                    continue;
+                }
+                for (dij, &dkj) in row.iter_mut().zip(through.iter()) {
+                    if dkj != u64::MAX && dik + dkj < *dij {
+                        *dij = dik + dkj;
+                    }
+                }
+            }
+        }
+        distances
+    }
+
+    /// the shortest distance from the start to each node reachable from it,
+    /// see utils::dijkstra
+    pub fn dijkstra(&self, start: &N) -> DistanceMap<N> {
+        let Some(start) = self.index(start) else {
+            return DistanceMap::new();
+        };
+        search::dijkstra(start, |&from| self.edges[from].iter().copied())
+            .into_iter()
+            .map(|(index, distance)| (self.nodes[index].clone(), distance))
+            .collect()
+    }
+
+    /// the nodes ordered so that every edge goes forwards, or None if the
+    /// graph has a cycle
+    pub fn toposort(&self) -> Option<Vec<N>> {
+        let mut in_degrees = vec![0; self.len()];
+        for &(to, _) in self.edges.iter().flatten() {
+            in_degrees[to] += 1;
+        }
+        let mut queue = (0..self.len())
+            .filter(|&index| in_degrees[index] == 0)
+            .collect::<VecDeque<_>>();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(from) = queue.pop_front() {
+            order.push(self.nodes[from].clone());
+            for &(to, _) in self.edges[from].iter() {
+                in_degrees[to] -= 1;
+                if in_degrees[to] == 0 {
+                    queue.push_back(to);
+                }
+            }
+        }
+        // the nodes of a cycle never run out of incoming edges
+        (order.len() == self.len()).then_some(order)
+    }
+
+    /// the strongly connected components, within which every node can reach
+    /// every other, in reverse topological order
+    pub fn strongly_connected_components(&self) -> Vec<Vec<N>> {
+        let mut tarjan = Tarjan::new(&self.edges);
+        for index in 0..self.len() {
+            if tarjan.order[index].is_none() {
+                tarjan.visit(index);
+            }
+        }
+        tarjan
+            .components
+            .into_iter()
+            .map(|component| {
+                component
+                    .into_iter()
+                    .map(|index| self.nodes[index].clone())
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl<N> Default for Graph<N>
+where
+    N: Clone + Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// the state of Tarjan's algorithm for strongly connected components
+struct Tarjan<'a> {
+    edges: &'a [Vec<(usize, u64)>],
+    /// the order in which each node was first visited
+    order: Vec<Option<usize>>,
+    /// the earliest visited node reachable from each node on the stack
+    low: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    visited: usize,
+    components: Vec<Vec<usize>>,
+}
+
+impl<'a> Tarjan<'a> {
+    fn new(edges: &'a [Vec<(usize, u64)>]) -> Self {
+        Self {
+            edges,
+            order: vec![None; edges.len()],
+            low: vec![0; edges.len()],
+            on_stack: vec![false; edges.len()],
+            stack: Vec::new(),
+            visited: 0,
+            components: Vec::new(),
+        }
+    }
+
+    fn visit(&mut self, node: usize) {
+        self.order[node] = Some(self.visited);
+        self.low[node] = self.visited;
+        self.visited += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+
+        let edges = self.edges;
+        for &(next, _) in edges[node].iter() {
+            match self.order[next] {
+                None => {
+                    self.visit(next);
+                    self.low[node] = self.low[node].min(self.low[next]);
+                }
+                Some(order) if self.on_stack[next] => {
+                    self.low[node] = self.low[node].min(order);
+                }
+                Some(_) => {}
+            }
+        }
+
+        // the node is the first visited of its component, which is the rest
+        // of the stack
+        if self.order[node] == Some(self.low[node]) {
+            let mut component = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack[member] = false;
+                component.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a -> b -> c -> a is a cycle, which leads on to d
+    fn cycle() -> Graph<&'static str> {
+        let mut graph = Graph::new();
+        graph.add_edge("a", "b", 1);
+        graph.add_edge("b", "c", 2);
+        graph.add_edge("c", "a", 3);
+        graph.add_edge("c", "d", 4);
+        graph
+    }
+
+    #[test]
+    fn build_graph() {
+        let mut graph = cycle();
+        assert_eq!(graph.len(), 4);
+        assert_eq!(graph.nodes(), ["a", "b", "c", "d"]);
+        assert_eq!(graph.index(&"c"), Some(2));
+        assert_eq!(graph.add_node("a"), 0);
+        let neighbors = graph.neighbors(&"c").collect::<Vec<_>>();
+        assert_eq!(neighbors, [(&"a", 3), (&"d", 4)]);
+        assert_eq!(graph.neighbors(&"e").count(), 0);
+    }
+
+    #[test]
+    fn shortest_distances() {
+        let graph = cycle();
+        let distances = graph.floyd_warshall();
+        assert_eq!(distances[0], [0, 1, 3, 7]);
+        assert_eq!(distances[1][0], 5);
+        assert_eq!(distances[3], [u64::MAX, u64::MAX, u64::MAX, 0]);
+        let distances = graph.dijkstra(&"b");
+        assert_eq!(distances[&"a"], 5);
+        assert_eq!(distances[&"d"], 6);
+        assert!(graph.dijkstra(&"e").is_empty());
+    }
+
+    #[test]
+    fn topological_order() {
+        assert_eq!(cycle().toposort(), None);
+        let mut graph = Graph::new();
+        graph.add_edge("shirt", "tie", 1);
+        graph.add_edge("tie", "jacket", 1);
+        graph.add_edge("trousers", "shoes", 1);
+        graph.add_edge("trousers", "belt", 1);
+        graph.add_edge("belt", "jacket", 1);
+        let order = graph.toposort().unwrap();
+        let position = |node| order.iter().position(|n| *n == node).unwrap();
+        assert_eq!(order.len(), 6);
+        assert!(position("shirt") < position("tie"));
+        assert!(position("belt") < position("jacket"));
+        assert!(position("trousers") < position("shoes"));
+    }
+
+    #[test]
+    fn components() {
+        let mut components = cycle().strongly_connected_components();
+        // d is reached from the cycle, so comes first
+        assert_eq!(components.len(), 2);
+        assert_eq!(components[0], ["d"]);
+        components[1].sort();
+        assert_eq!(components[1], ["a", "b", "c"]);
+    }
+}
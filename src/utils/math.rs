@@ -0,0 +1,93 @@
+/*
+** src/utils/math.rs
+*/
+
+/// the greatest common divisor
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// the least common multiple, which is 0 if either number is 0, or None if it
+/// overflows
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// the gcd of a and b, along with x and y such that a*x + b*y is the gcd
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a % b);
+        (g, y, x - (a / b) * y)
+    }
+}
+
+/// solves the congruences, given as (remainder, modulus) with positive
+/// moduli, by the Chinese Remainder Theorem; the moduli need not be coprime
+///
+/// gives the smallest non-negative solution along with the lcm of the moduli,
+/// after which the solutions repeat, or None if the congruences conflict, a
+/// modulus is not positive, or the lcm does not fit in an i64
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    if congruences.iter().any(|&(_, modulus)| modulus <= 0) {
+        return None;
+    }
+    let (mut x, mut m) = (0i128, 1i128);
+    for &(remainder, modulus) in congruences {
+        let (remainder, modulus) = (remainder as i128, modulus as i128);
+        // find k such that x + m*k is the remainder modulo the modulus
+        let (g, p, _) = extended_gcd(m, modulus);
+        if (remainder - x) % g != 0 {
+            return None;
+        }
+        let step = modulus / g;
+        let k = ((remainder - x) / g * p).rem_euclid(step);
+        x += m * k;
+        m *= step;
+        x = x.rem_euclid(m);
+        // keeping the lcm within an i64 also keeps the products above within
+        // an i128
+        if m > i64::MAX as i128 {
+            return None;
+        }
+    }
+    Some((i64::try_from(x).ok()?, i64::try_from(m).ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(17, 5), 1);
+        assert_eq!(gcd(0, 7), 7);
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(23, 19), Some(437));
+        assert_eq!(lcm(0, 7), Some(0));
+        assert_eq!(lcm(u64::MAX, 2), None);
+    }
+
+    #[test]
+    fn chinese_remainders() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // moduli which share a factor
+        assert_eq!(crt(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(1, 2), (0, 4)]), None);
+        // remainders outside of the range of their modulus
+        assert_eq!(crt(&[(-1, 5), (8, 3)]), Some((14, 15)));
+        assert_eq!(crt(&[]), Some((0, 1)));
+        // moduli which are not positive, or whose lcm overflows
+        assert_eq!(crt(&[(1, 0)]), None);
+        assert_eq!(crt(&[(1, -3)]), None);
+        assert_eq!(crt(&[(1, i64::MAX), (1, i64::MAX - 1)]), None);
+    }
+}
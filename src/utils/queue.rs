@@ -0,0 +1,102 @@
+/*
+** src/utils/queue.rs
+*/
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+/// a queue of items which are popped lowest priority first, where pushing an
+/// item again with a lower priority replaces it, as the decrease-key of the
+/// shortest path searches
+///
+/// replaced entries are left in the heap and skipped once popped, rather than
+/// being removed, and items need not be ordered as the heap holds indices
+#[derive(Clone, Debug)]
+pub struct PriorityQueue<T, P> {
+    heap: BinaryHeap<Reverse<(P, usize)>>,
+    entries: Vec<T>,
+    /// the lowest priority each item has been pushed with
+    priorities: HashMap<T, P>,
+}
+
+impl<T, P> PriorityQueue<T, P>
+where
+    T: Clone + Eq + Hash,
+    P: Copy + Ord,
+{
+    pub fn new() -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+            entries: Vec::new(),
+            priorities: HashMap::new(),
+        }
+    }
+
+    /// queues the item, unless it has already been pushed with the same or a
+    /// lower priority, returning whether it was queued
+    pub fn push(&mut self, item: T, priority: P) -> bool {
+        if self.priority(&item).is_some_and(|p| p <= priority) {
+            return false;
+        }
+        self.priorities.insert(item.clone(), priority);
+        self.heap.push(Reverse((priority, self.entries.len())));
+        self.entries.push(item);
+        true
+    }
+
+    /// removes the item with the lowest priority, along with its priority
+    pub fn pop(&mut self) -> Option<(T, P)> {
+        while let Some(Reverse((priority, i))) = self.heap.pop() {
+            // skip the entries which have been replaced by a lower priority
+            if self.priorities[&self.entries[i]] == priority {
+                return Some((self.entries[i].clone(), priority));
+            }
+        }
+        None
+    }
+
+    /// the lowest priority the item has been pushed with, which is final
+    /// once it has been popped
+    pub fn priority(&self, item: &T) -> Option<P> {
+        self.priorities.get(item).copied()
+    }
+
+    /// the lowest priority of each item which has been pushed
+    pub fn into_priorities(self) -> HashMap<T, P> {
+        self.priorities
+    }
+}
+
+impl<T, P> Default for PriorityQueue<T, P>
+where
+    T: Clone + Eq + Hash,
+    P: Copy + Ord,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decrease_key() {
+        let mut queue = PriorityQueue::new();
+        assert!(queue.push("c", 5));
+        assert!(queue.push("a", 3));
+        assert!(queue.push("b", 4));
+        // a lower priority replaces the entry, and a higher one is ignored
+        assert!(queue.push("c", 1));
+        assert!(!queue.push("a", 7));
+        assert_eq!(queue.priority(&"c"), Some(1));
+        assert_eq!(queue.pop(), Some(("c", 1)));
+        assert_eq!(queue.pop(), Some(("a", 3)));
+        assert_eq!(queue.pop(), Some(("b", 4)));
+        // the replaced entry of c is skipped
+        assert_eq!(queue.pop(), None);
+        assert_eq!(queue.into_priorities().len(), 3);
+    }
+}
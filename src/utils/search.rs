@@ -0,0 +1,234 @@
+/*
+** src/utils/search.rs
+*/
+
+use crate::utils::queue::PriorityQueue;
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+/// the distance from the start of a search to each node it reached
+pub type DistanceMap<N> = HashMap<N, u64>;
+
+/// the nodes reached by a search, along with their distances from the start
+/// and the node which each was reached from
+#[derive(Clone, Debug)]
+pub struct SearchTree<N> {
+    pub distances: DistanceMap<N>,
+    pub predecessors: HashMap<N, N>,
+}
+
+impl<N> SearchTree<N>
+where
+    N: Clone + Eq + Hash,
+{
+    fn new() -> Self {
+        Self {
+            distances: DistanceMap::new(),
+            predecessors: HashMap::new(),
+        }
+    }
+
+    /// the path from the start to the node, or None if it was not reached
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        if !self.distances.contains_key(node) {
+            return None;
+        }
+        let mut path = vec![node.clone()];
+        while let Some(previous) = self.predecessors.get(&path[path.len() - 1]) {
+            path.push(previous.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// searches breadth-first from the start, given the neighbors of a node, so
+/// that the distances are the fewest steps to each node
+pub fn bfs<N, F, I>(start: N, mut neighbors: F) -> SearchTree<N>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut tree = SearchTree::new();
+    tree.distances.insert(start.clone(), 0);
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        let distance = tree.distances[&node];
+        for next in neighbors(&node) {
+            if tree.distances.contains_key(&next) {
+                continue;
+            }
+            tree.distances.insert(next.clone(), distance + 1);
+            tree.predecessors.insert(next.clone(), node.clone());
+            queue.push_back(next);
+        }
+    }
+    tree
+}
+
+/// searches depth-first from the start, given the neighbors of a node, which
+/// are visited in the order they are given; the distances are the depths of
+/// the nodes in the search, rather than the fewest steps to them
+pub fn dfs<N, F, I>(start: N, mut neighbors: F) -> SearchTree<N>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut tree = SearchTree::new();
+    let mut stack = vec![(start, None)];
+    while let Some((node, previous)) = stack.pop() {
+        if tree.distances.contains_key(&node) {
+            continue;
+        }
+        let distance = match previous {
+            Some(previous) => {
+                let distance = tree.distances[&previous] + 1;
+                tree.predecessors.insert(node.clone(), previous);
+                distance
+            }
+            None => 0,
+        };
+        tree.distances.insert(node.clone(), distance);
+        // pushed in reverse, so that the first neighbor is visited first
+        let next = neighbors(&node).into_iter().collect::<Vec<_>>();
+        for next in next.into_iter().rev() {
+            if !tree.distances.contains_key(&next) {
+                stack.push((next, Some(node.clone())));
+            }
+        }
+    }
+    tree
+}
+
+/// the shortest distance from the start to each node reachable from it, given
+/// the neighbors of a node along with the cost of the step to each of them
+pub fn dijkstra<N, F, I>(start: N, mut neighbors: F) -> DistanceMap<N>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    let mut queue = PriorityQueue::new();
+    queue.push(start, 0);
+    while let Some((node, distance)) = queue.pop() {
+        for (next, cost) in neighbors(&node) {
+            queue.push(next, distance + cost);
+        }
+    }
+    queue.into_priorities()
+}
+
+/// the shortest path from the start to the goal, along with its cost, given
+/// the neighbors of a node along with the cost of the step to each of them,
+/// and a heuristic which never overestimates the cost from a node to the goal
+pub fn astar<N, F, I, H>(
+    start: N,
+    goal: &N,
+    mut neighbors: F,
+    heuristic: H,
+) -> Option<(Vec<N>, u64)>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+    H: Fn(&N) -> u64,
+{
+    // nodes are queued by their estimated cost, then by their distance
+    let mut queue = PriorityQueue::new();
+    let estimate = heuristic(&start);
+    queue.push(start, (estimate, 0));
+    let mut predecessors = HashMap::<N, N>::new();
+    while let Some((node, (_, distance))) = queue.pop() {
+        if node == *goal {
+            let mut path = vec![node];
+            while let Some(previous) = predecessors.get(&path[path.len() - 1]) {
+                path.push(previous.clone());
+            }
+            path.reverse();
+            return Some((path, distance));
+        }
+        for (next, cost) in neighbors(&node) {
+            let next_distance = distance + cost;
+            let estimate = next_distance + heuristic(&next);
+            if queue.push(next.clone(), (estimate, next_distance)) {
+                predecessors.insert(next, node.clone());
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a square of nodes, 0 -> 1 -> 3 and 0 -> 2 -> 3, with a tail 3 -> 4
+    fn square(node: &u32) -> Vec<u32> {
+        match node {
+            0 => vec![1, 2],
+            1 | 2 => vec![3],
+            3 => vec![4],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn bfs_paths() {
+        let tree = bfs(0, square);
+        assert_eq!(tree.distances.len(), 5);
+        assert_eq!(tree.distances[&4], 3);
+        assert_eq!(tree.path_to(&4), Some(vec![0, 1, 3, 4]));
+        assert_eq!(tree.path_to(&0), Some(vec![0]));
+        assert_eq!(tree.path_to(&5), None);
+    }
+
+    #[test]
+    fn dfs_paths() {
+        // the first neighbor is followed to the end before the second
+        let tree = dfs(0, square);
+        assert_eq!(tree.distances[&2], 1);
+        assert_eq!(tree.distances[&4], 3);
+        assert_eq!(tree.path_to(&4), Some(vec![0, 1, 3, 4]));
+        // unlike a breadth-first search, depths are not the fewest steps
+        let tree = dfs(0, |&node: &u32| match node {
+            0 => vec![1, 2],
+            1 => vec![2],
+            _ => vec![],
+        });
+        assert_eq!(tree.distances[&2], 2);
+        assert_eq!(tree.path_to(&2), Some(vec![0, 1, 2]));
+    }
+
+    #[test]
+    fn dijkstra_distances() {
+        // the direct edge from a to c is longer than the path through b
+        let edges = [("a", "b", 1), ("b", "c", 2), ("a", "c", 5), ("c", "d", 1)];
+        let distances = dijkstra("a", |&node| {
+            edges
+                .iter()
+                .filter(move |(from, _, _)| *from == node)
+                .map(|&(_, to, cost)| (to, cost))
+        });
+        assert_eq!(distances.len(), 4);
+        assert_eq!(distances["c"], 3);
+        assert_eq!(distances["d"], 4);
+        // nodes which cannot be reached are left out
+        let distances = dijkstra("d", |_| Vec::new());
+        assert_eq!(distances, DistanceMap::from([("d", 0)]));
+    }
+
+    #[test]
+    fn astar_paths() {
+        // steps along a line of numbers, towards the goal
+        let neighbors = |&n: &i64| [(n - 1, 1), (n + 1, 1)];
+        let heuristic = |n: &i64| (7 - n).unsigned_abs();
+        let (path, cost) = astar(3, &7, neighbors, heuristic).unwrap();
+        assert_eq!((path, cost), (vec![3, 4, 5, 6, 7], 4));
+        assert_eq!(astar(3, &3, neighbors, heuristic), Some((vec![3], 0)));
+        // the goal cannot be reached
+        assert_eq!(astar(3, &7, |_| Vec::new(), heuristic), None);
+    }
+}
@@ -0,0 +1,646 @@
+/*
+** src/viz.rs
+*/
+
+use crate::json::{self, Json};
+use crate::types::Point;
+
+use anyhow::{anyhow, Result};
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// the fixed palette shared by every visualization
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    White,
+    Gray,
+    Red,
+    Green,
+    Blue,
+    Yellow,
+    Orange,
+}
+
+const PALETTE: [(Color, [u8; 3], char); 8] = [
+    (Color::Black, [0x0f, 0x0f, 0x23], ' '),
+    (Color::White, [0xcc, 0xcc, 0xcc], '#'),
+    (Color::Gray, [0x66, 0x66, 0x66], '.'),
+    (Color::Red, [0xff, 0x40, 0x40], 'x'),
+    (Color::Green, [0x00, 0x99, 0x00], 'o'),
+    (Color::Blue, [0x40, 0x80, 0xff], '~'),
+    (Color::Yellow, [0xff, 0xff, 0x66], '*'),
+    (Color::Orange, [0xff, 0x99, 0x33], '+'),
+];
+/// bits needed to index the palette
+const PALETTE_BITS: u8 = 3;
+
+impl Color {
+    fn index(self) -> u8 {
+        PALETTE.iter().position(|&(c, _, _)| c == self).unwrap() as u8
+    }
+
+    fn from_index(index: u8) -> Self {
+        PALETTE[index as usize].0
+    }
+
+    /// the character used when rendering to the terminal
+    pub fn symbol(self) -> char {
+        PALETTE[self.index() as usize].2
+    }
+}
+
+/// a grid of colored cells, each drawn as a square of `scale` pixels
+#[derive(Clone)]
+pub struct Canvas {
+    pub width: usize,
+    pub height: usize,
+    scale: usize,
+    /// offset subtracted from drawn points, so that puzzle coordinates which
+    /// do not start at zero can be drawn directly
+    origin: Point,
+    cells: Vec<u8>,
+}
+
+impl Canvas {
+    pub fn new(width: usize, height: usize, scale: usize) -> Self {
+        Self {
+            width,
+            height,
+            scale: scale.max(1),
+            origin: Point::origin(),
+            cells: vec![Color::Black.index(); width * height],
+        }
+    }
+
+    /// creates a canvas covering the bounding box of the given points
+    pub fn covering<'a, I>(points: I, scale: usize) -> Self
+    where
+        I: IntoIterator<Item = &'a Point>,
+    {
+        let points = points.into_iter().collect::<Vec<_>>();
+        let min_x = points.iter().map(|p| p.x).min().unwrap_or(0);
+        let max_x = points.iter().map(|p| p.x).max().unwrap_or(0);
+        let min_y = points.iter().map(|p| p.y).min().unwrap_or(0);
+        let max_y = points.iter().map(|p| p.y).max().unwrap_or(0);
+        let mut canvas = Self::new(
+            (max_x - min_x + 1) as usize,
+            (max_y - min_y + 1) as usize,
+            scale,
+        );
+        canvas.origin = Point::new(min_x, min_y);
+        canvas
+    }
+
+    fn offset(&self, point: &Point) -> Option<usize> {
+        let x = point.x - self.origin.x;
+        let y = point.y - self.origin.y;
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            None
+        } else {
+            Some(y as usize * self.width + x as usize)
+        }
+    }
+
+    pub fn get(&self, point: &Point) -> Option<Color> {
+        self.offset(point).map(|i| Color::from_index(self.cells[i]))
+    }
+
+    /// colors a single cell, ignoring points off of the canvas
+    pub fn draw_cell(&mut self, point: &Point, color: Color) {
+        if let Some(i) = self.offset(point) {
+            self.cells[i] = color.index();
+        }
+    }
+
+    pub fn draw_cells<'a, I>(&mut self, points: I, color: Color)
+    where
+        I: IntoIterator<Item = &'a Point>,
+    {
+        for point in points {
+            self.draw_cell(point, color);
+        }
+    }
+
+    /// draws straight lines between consecutive points, which must share a
+    /// row, a column, or a diagonal
+    pub fn draw_path(&mut self, points: &[Point], color: Color) {
+        if let [point] = points {
+            self.draw_cell(point, color);
+        }
+        for pair in points.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            let dx = (b.x - a.x).signum();
+            let dy = (b.y - a.y).signum();
+            let mut point = a;
+            self.draw_cell(&point, color);
+            while point != b {
+                point = Point::new(point.x + dx, point.y + dy);
+                self.draw_cell(&point, color);
+            }
+        }
+    }
+
+    /// mirrors the canvas top-to-bottom, for puzzles whose y-axis points up;
+    /// this is done after drawing as points are not mirrored
+    pub fn flip_vertical(&mut self) {
+        self.cells = self
+            .cells
+            .chunks(self.width.max(1))
+            .rev()
+            .flatten()
+            .copied()
+            .collect();
+    }
+
+    pub fn fill(&mut self, color: Color) {
+        self.cells.fill(color.index());
+    }
+
+    /// renders the canvas as text, one character per cell
+    pub fn to_text(&self) -> String {
+        self.cells
+            .chunks(self.width.max(1))
+            .map(|row| {
+                row.iter()
+                    .map(|&c| Color::from_index(c).symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// the scaled-up pixels of the canvas, as palette indices
+    fn pixels(&self) -> Vec<u8> {
+        let mut pixels = Vec::with_capacity(self.width * self.height * self.scale * self.scale);
+        for row in self.cells.chunks(self.width.max(1)) {
+            for _ in 0..self.scale {
+                for &cell in row.iter() {
+                    pixels.extend(std::iter::repeat_n(cell, self.scale));
+                }
+            }
+        }
+        pixels
+    }
+
+    /// the size of the scaled-up canvas, which must fit in the 16-bit sizes of
+    /// a GIF
+    fn pixel_size(&self) -> Result<(u16, u16)> {
+        let (width, height) = (self.width * self.scale, self.height * self.scale);
+        match (u16::try_from(width), u16::try_from(height)) {
+            (Ok(width), Ok(height)) => Ok((width, height)),
+            _ => Err(anyhow!(
+                "a canvas of {}x{} pixels is too large for a GIF",
+                width,
+                height
+            )),
+        }
+    }
+
+    /// encodes the canvas as a still GIF
+    pub fn to_gif(&self) -> Result<Vec<u8>> {
+        Animation::from(self.clone()).to_gif()
+    }
+
+    pub fn save_gif(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_gif()?)?;
+        Ok(())
+    }
+}
+
+/// a sequence of equally-sized canvases, encoded as an animated GIF
+pub struct Animation {
+    /// delay between frames, in hundredths of a second
+    delay: u16,
+    frames: Vec<Canvas>,
+}
+
+impl Animation {
+    pub fn new(delay: u16) -> Self {
+        Self {
+            delay,
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, frame: Canvas) {
+        self.frames.push(frame);
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn last(&self) -> Option<&Canvas> {
+        self.frames.last()
+    }
+
+    pub fn to_gif(&self) -> Result<Vec<u8>> {
+        let (width, height) = self
+            .frames
+            .first()
+            .map(Canvas::pixel_size)
+            .transpose()?
+            .unwrap_or((1, 1));
+        let mut gif = Vec::new();
+        gif.extend_from_slice(b"GIF89a");
+        gif.extend_from_slice(&width.to_le_bytes());
+        gif.extend_from_slice(&height.to_le_bytes());
+        // global color table, 8-bit color resolution, 2^PALETTE_BITS entries
+        gif.push(0x80 | 0x70 | (PALETTE_BITS - 1));
+        gif.extend_from_slice(&[0, 0]);
+        for (_, rgb, _) in PALETTE.iter() {
+            gif.extend_from_slice(rgb);
+        }
+        if self.frames.len() > 1 {
+            // loop forever
+            gif.extend_from_slice(&[0x21, 0xff, 0x0b]);
+            gif.extend_from_slice(b"NETSCAPE2.0");
+            gif.extend_from_slice(&[0x03, 0x01, 0x00, 0x00, 0x00]);
+        }
+        for frame in self.frames.iter() {
+            // graphic control extension, for the frame delay
+            gif.extend_from_slice(&[0x21, 0xf9, 0x04, 0x00]);
+            gif.extend_from_slice(&self.delay.to_le_bytes());
+            gif.extend_from_slice(&[0x00, 0x00]);
+            // image descriptor, covering the whole screen
+            let (frame_width, frame_height) = frame.pixel_size()?;
+            gif.push(0x2c);
+            gif.extend_from_slice(&[0, 0, 0, 0]);
+            gif.extend_from_slice(&frame_width.to_le_bytes());
+            gif.extend_from_slice(&frame_height.to_le_bytes());
+            gif.push(0x00);
+            // image data, in sub-blocks of at most 255 bytes
+            let min_code_size = PALETTE_BITS.max(2);
+            gif.push(min_code_size);
+            for block in lzw_encode(&frame.pixels(), min_code_size).chunks(255) {
+                gif.push(block.len() as u8);
+                gif.extend_from_slice(block);
+            }
+            gif.push(0x00);
+        }
+        gif.push(0x3b);
+        Ok(gif)
+    }
+
+    pub fn save_gif(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_gif()?)?;
+        Ok(())
+    }
+
+    /// records the frames as text into an asciinema v2 cast, which can be
+    /// replayed with `asciinema play`
+    pub fn to_cast(&self) -> String {
+        let (width, height) = self.frames.iter().fold((1, 1), |(w, h), frame| {
+            (w.max(frame.width), h.max(frame.height))
+        });
+        let header = Json::object([
+            ("version", Json::from(2usize)),
+            ("width", Json::from(width)),
+            ("height", Json::from(height)),
+        ]);
+        let mut cast = format!("{}\n", header);
+        // still images are shown for a moment rather than not at all
+        let delay = if self.delay == 0 { 10 } else { self.delay };
+        for (i, frame) in self.frames.iter().enumerate() {
+            let seconds = (i * delay as usize) as f64 / 100.0;
+            // clear the screen and draw the frame from the top-left corner
+            let output = format!("\x1b[H\x1b[2J{}", frame.to_text().replace('\n', "\r\n"));
+            cast.push_str(&format!(
+                "[{:.2}, \"o\", {}]\n",
+                seconds,
+                json::escape(&output)
+            ));
+        }
+        cast
+    }
+
+    pub fn save_cast(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_cast())?;
+        Ok(())
+    }
+}
+
+impl From<Canvas> for Animation {
+    fn from(canvas: Canvas) -> Self {
+        let mut animation = Self::new(0);
+        animation.push(canvas);
+        animation
+    }
+}
+
+/// implemented by the state of a day once it has been solved, to render it
+pub trait Visualize {
+    fn visualize(&self) -> Animation;
+}
+
+/// parses and solves the input for a day, then renders its solved state
+pub type Visualizer = fn(&str) -> Result<Animation>;
+
+/// where visualizations are written to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    /// prints the final frame as text
+    Terminal,
+    /// writes every frame to a GIF file
+    Gif,
+    /// records every frame as text to an asciinema cast file
+    Cast,
+}
+
+impl FromStr for Backend {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "terminal" => Ok(Self::Terminal),
+            "gif" => Ok(Self::Gif),
+            "cast" => Ok(Self::Cast),
+            _ => Err(anyhow!(
+                "unknown visualization backend {}, expected terminal, gif or cast",
+                s
+            )),
+        }
+    }
+}
+
+/// outputs the animation with the given backend, returning the path written
+/// to for the file backends
+pub fn render(animation: &Animation, backend: Backend, path: &Path) -> Result<Option<PathBuf>> {
+    match backend {
+        Backend::Terminal => {
+            if let Some(frame) = animation.last() {
+                println!("{}", frame.to_text());
+            }
+            Ok(None)
+        }
+        Backend::Gif => {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            let path = path.with_extension("gif");
+            animation.save_gif(&path)?;
+            Ok(Some(path))
+        }
+        Backend::Cast => {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            let path = path.with_extension("cast");
+            animation.save_cast(&path)?;
+            Ok(Some(path))
+        }
+    }
+}
+
+/// packs variable-width codes into bytes, least significant bit first
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    n_bits: u8,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, width: u8) {
+        self.buffer |= (code as u32) << self.n_bits;
+        self.n_bits += width;
+        while self.n_bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.n_bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.n_bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+/// the variable-width LZW compression used by GIF
+fn lzw_encode(data: &[u8], min_code_size: u8) -> Vec<u8> {
+    const MAX_CODE: u16 = 4095;
+    let clear = 1u16 << min_code_size;
+    let end = clear + 1;
+    let mut writer = BitWriter {
+        bytes: Vec::new(),
+        buffer: 0,
+        n_bits: 0,
+    };
+    let mut table = HashMap::new();
+    let mut next_code = end + 1;
+    let mut width = min_code_size + 1;
+    writer.write(clear, width);
+
+    let mut prefix: Option<u16> = None;
+    for &byte in data.iter() {
+        let Some(code) = prefix else {
+            prefix = Some(byte as u16);
+            continue;
+        };
+        if let Some(&extended) = table.get(&(code, byte)) {
+            prefix = Some(extended);
+            continue;
+        }
+        writer.write(code, width);
+        if next_code <= MAX_CODE {
+            table.insert((code, byte), next_code);
+            // the decoder widens its codes one code later than the encoder
+            // adds them, so widen once the new code no longer fits
+            if next_code == (1 << width) && width < 12 {
+                width += 1;
+            }
+            next_code += 1;
+        } else {
+            // the table is full, so start over
+            writer.write(clear, width);
+            table.clear();
+            next_code = end + 1;
+            width = min_code_size + 1;
+        }
+        prefix = Some(byte as u16);
+    }
+    if let Some(code) = prefix {
+        writer.write(code, width);
+    }
+    writer.write(end, width);
+    writer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a straightforward GIF LZW decoder, to check the encoder against
+    fn lzw_decode(data: &[u8], min_code_size: u8) -> Vec<u8> {
+        let clear = 1usize << min_code_size;
+        let end = clear + 1;
+        let mut table: Vec<Vec<u8>> = Vec::new();
+        let reset = |table: &mut Vec<Vec<u8>>| {
+            table.clear();
+            table.extend((0..clear).map(|i| vec![i as u8]));
+            table.push(Vec::new());
+            table.push(Vec::new());
+        };
+        reset(&mut table);
+        let mut width = min_code_size + 1;
+        let (mut buffer, mut n_bits, mut pos) = (0u32, 0u8, 0);
+        let mut output = Vec::new();
+        let mut prev: Option<Vec<u8>> = None;
+        loop {
+            while n_bits < width {
+                buffer |= (data[pos] as u32) << n_bits;
+                pos += 1;
+                n_bits += 8;
+            }
+            let code = (buffer & ((1 << width) - 1)) as usize;
+            buffer >>= width;
+            n_bits -= width;
+            if code == clear {
+                reset(&mut table);
+                width = min_code_size + 1;
+                prev = None;
+                continue;
+            }
+            if code == end {
+                return output;
+            }
+            let entry = if code < table.len() {
+                table[code].clone()
+            } else {
+                let mut entry = prev.clone().unwrap();
+                entry.push(entry[0]);
+                entry
+            };
+            output.extend_from_slice(&entry);
+            if let Some(mut prev) = prev {
+                prev.push(entry[0]);
+                table.push(prev);
+                if table.len() == (1 << width) && width < 12 {
+                    width += 1;
+                }
+            }
+            prev = Some(entry);
+        }
+    }
+
+    #[test]
+    fn lzw_round_trip() {
+        // enough random pixels to fill the code table several times over
+        let mut rng = crate::generators::Rng::new(2022);
+        let inputs = [
+            vec![],
+            vec![1],
+            vec![0; 10000],
+            (0..20000).map(|i| ((i * 7 + i / 13) % 8) as u8).collect(),
+            (0..100000).map(|_| rng.index(8) as u8).collect(),
+        ];
+        for input in inputs.iter() {
+            let encoded = lzw_encode(input, 3);
+            assert_eq!(&lzw_decode(&encoded, 3), input);
+        }
+    }
+
+    #[test]
+    fn draw_path_and_cells() {
+        let mut canvas = Canvas::new(5, 3, 1);
+        canvas.draw_path(
+            &[Point::new(0, 0), Point::new(3, 0), Point::new(3, 2)],
+            Color::White,
+        );
+        canvas.draw_cells(&[Point::new(1, 2), Point::new(9, 9)], Color::Red);
+        assert_eq!(canvas.to_text(), "#### \n   # \n x # ");
+        canvas.flip_vertical();
+        assert_eq!(canvas.to_text(), " x # \n   # \n#### ");
+    }
+
+    #[test]
+    fn canvas_covering_points() {
+        let points = [Point::new(-2, 3), Point::new(1, 5)];
+        let mut canvas = Canvas::covering(&points, 1);
+        assert_eq!((canvas.width, canvas.height), (4, 3));
+        canvas.draw_cells(&points, Color::Yellow);
+        assert_eq!(canvas.get(&Point::new(-2, 3)), Some(Color::Yellow));
+        assert_eq!(canvas.get(&Point::new(0, 4)), Some(Color::Black));
+        assert_eq!(canvas.get(&Point::new(2, 4)), None);
+    }
+
+    #[test]
+    fn gif_structure() {
+        let mut canvas = Canvas::new(4, 2, 3);
+        canvas.draw_cell(&Point::new(1, 1), Color::Green);
+        let gif = canvas.to_gif().unwrap();
+        assert_eq!(&gif[..6], b"GIF89a");
+        assert_eq!(u16::from_le_bytes([gif[6], gif[7]]), 12);
+        assert_eq!(u16::from_le_bytes([gif[8], gif[9]]), 6);
+        assert_eq!(*gif.last().unwrap(), 0x3b);
+
+        // find the image data and check that it decodes to the pixels
+        let image_start = gif.iter().position(|&b| b == 0x2c).unwrap();
+        let min_code_size = gif[image_start + 10];
+        let mut data = Vec::new();
+        let mut pos = image_start + 11;
+        while gif[pos] != 0 {
+            let len = gif[pos] as usize;
+            data.extend_from_slice(&gif[(pos + 1)..(pos + 1 + len)]);
+            pos += len + 1;
+        }
+        assert_eq!(lzw_decode(&data, min_code_size), canvas.pixels());
+
+        // the scaled-up size does not fit in the 16 bits of a GIF
+        let canvas = Canvas::new(30000, 1, 3);
+        assert_eq!(
+            canvas.to_gif().unwrap_err().to_string(),
+            "a canvas of 90000x3 pixels is too large for a GIF"
+        );
+    }
+
+    #[test]
+    fn parse_backend() {
+        assert_eq!("terminal".parse::<Backend>().unwrap(), Backend::Terminal);
+        assert_eq!("gif".parse::<Backend>().unwrap(), Backend::Gif);
+        assert_eq!("cast".parse::<Backend>().unwrap(), Backend::Cast);
+        assert!("png".parse::<Backend>().is_err());
+    }
+
+    #[test]
+    fn animation_cast() {
+        let mut animation = Animation::new(5);
+        let mut canvas = Canvas::new(3, 2, 4);
+        animation.push(canvas.clone());
+        canvas.draw_cell(&Point::new(1, 1), Color::White);
+        animation.push(canvas);
+        let cast = animation.to_cast();
+        let lines = cast.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        let header = Json::parse(lines[0]).unwrap();
+        assert_eq!(header.get("version").and_then(|v| v.as_i64()), Some(2));
+        assert_eq!(header.get("width").and_then(|v| v.as_i64()), Some(3));
+        assert_eq!(header.get("height").and_then(|v| v.as_i64()), Some(2));
+        let event = Json::parse(lines[2]).unwrap();
+        let event = event.as_array().unwrap();
+        assert_eq!(event[0].as_f64(), Some(0.05));
+        assert_eq!(event[1].as_str(), Some("o"));
+        assert!(event[2].as_str().unwrap().ends_with("\r\n # "));
+    }
+
+    #[test]
+    fn animation_loops() {
+        let mut animation = Animation::new(10);
+        animation.push(Canvas::new(2, 2, 1));
+        animation.push(Canvas::new(2, 2, 1));
+        let gif = animation.to_gif().unwrap();
+        let netscape = gif.windows(11).position(|w| w == b"NETSCAPE2.0");
+        assert!(netscape.is_some());
+        assert_eq!(gif.iter().filter(|&&b| b == 0x2c).count(), 2);
+    }
+}
@@ -0,0 +1,20 @@
+/*
+** tests/alloc_stats.rs
+*/
+
+#![cfg(feature = "alloc-stats")]
+
+use aoc2022::alloc::{AllocSpan, CountingAllocator};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[test]
+fn count_allocations() {
+    let span = AllocSpan::start();
+    let v = (0..1024u64).collect::<Vec<_>>();
+    let stats = span.stats();
+    assert!(stats.allocations >= 1);
+    assert!(stats.peak_bytes >= 8 * 1024);
+    drop(v);
+}
@@ -0,0 +1,27 @@
+/*
+** tests/common/mod.rs
+*/
+
+use aoc2022::context::Context;
+use aoc2022::parts::Parts;
+use aoc2022::puzzles;
+use aoc2022::types::Solution;
+use aoc2022::utils;
+
+use std::path::Path;
+
+/// loads the sample input for the given day
+#[allow(dead_code)]
+pub fn load_sample(day: usize) -> String {
+    let input_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("input")
+        .join(format!("D{}.dbg.txt", day));
+    utils::read_file(&input_path).unwrap()
+}
+
+/// runs the given day on its sample input
+#[allow(dead_code)]
+pub fn run_sample(day: usize) -> Solution {
+    let context = Context::none().with_sample(true);
+    puzzles::DAYS[day - 1](&load_sample(day), Parts::Both, &context).unwrap()
+}
@@ -0,0 +1,41 @@
+/*
+** tests/differential.rs
+*/
+
+mod common;
+
+use aoc2022::context::Context;
+use aoc2022::parts::Parts;
+use aoc2022::puzzles;
+
+/// runs every solver variant for the given day on its sample input and checks
+/// that they all produce the same answers
+fn check_variants(day: usize) {
+    let input = common::load_sample(day);
+    let context = Context::none().with_sample(true);
+    let diverging = puzzles::diverging_variants(day, &input, Parts::Both, &context).unwrap();
+    assert!(diverging.is_empty(), "day {}: {:?} diverge", day, diverging);
+}
+
+#[test]
+fn variants_are_registered_for_solved_days() {
+    for &(day, name, _) in puzzles::VARIANTS.iter() {
+        assert!(
+            (1..=puzzles::N_DAYS).contains(&day),
+            "{}: day {}",
+            name,
+            day
+        );
+        assert_ne!(name, puzzles::DEFAULT_VARIANT);
+    }
+}
+
+#[test]
+fn day_12() {
+    check_variants(12);
+}
+
+#[test]
+fn day_15() {
+    check_variants(15);
+}
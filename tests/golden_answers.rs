@@ -0,0 +1,52 @@
+/*
+** tests/golden_answers.rs
+*/
+
+use aoc2022::answers::Answers;
+use aoc2022::context::Context;
+use aoc2022::parts::Parts;
+use aoc2022::puzzles;
+use aoc2022::utils;
+
+use std::path::Path;
+
+/// runs every day against its real input and compares against the known
+/// answers, see Answers::real, reporting every difference
+///
+/// run with `cargo test --release --test golden_answers -- --ignored`
+#[test]
+#[ignore = "runs every day on its real input, which is slow without --release"]
+fn golden_answers() {
+    let project_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let answers = Answers::real().unwrap();
+
+    let mut diffs = Vec::new();
+    for day in answers.days() {
+        if day == 0 || day > puzzles::N_DAYS {
+            diffs.push(format!("day {}: no such puzzle", day));
+            continue;
+        }
+        let input_path = project_dir.join("input").join(format!("D{}.txt", day));
+        let input = utils::read_file(&input_path).unwrap();
+        let context = Context::none().with_sample(false);
+        let solution = puzzles::DAYS[day - 1](&input, Parts::Both, &context).unwrap();
+        let computed = [solution.part_1, solution.part_2].map(|a| a.map(|a| a.to_string()));
+        for (i, computed) in computed.iter().enumerate() {
+            let part = i + 1;
+            let expected = answers.get(day, part);
+            if let Some(expected) = expected {
+                if computed.as_deref() != Some(expected) {
+                    diffs.push(format!(
+                        "day {} part {}:\n  expected: {}\n  computed: {}",
+                        day,
+                        part,
+                        expected,
+                        computed.as_deref().unwrap_or("no answer")
+                    ));
+                }
+            }
+        }
+    }
+
+    assert!(diffs.is_empty(), "answers changed:\n{}", diffs.join("\n"));
+}
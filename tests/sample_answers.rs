@@ -0,0 +1,109 @@
+/*
+** tests/sample_answers.rs
+*/
+
+mod common;
+
+use aoc2022::answers::Answers;
+
+/// runs the given day on its sample input and checks the answers against
+/// those given in the puzzle description, as declared by the day
+fn check_sample(day: usize) {
+    let answers = Answers::samples();
+    let (part_1, part_2) = (answers.get(day, 1), answers.get(day, 2));
+    let solution = common::run_sample(day);
+    let answer_1 = solution.part_1.map(|answer| answer.to_string());
+    let answer_2 = solution.part_2.map(|answer| answer.to_string());
+    assert_eq!(answer_1.as_deref(), part_1, "day {} part 1", day);
+    assert_eq!(answer_2.as_deref(), part_2, "day {} part 2", day);
+}
+
+#[test]
+fn day_1() {
+    check_sample(1);
+}
+
+#[test]
+fn day_2() {
+    check_sample(2);
+}
+
+#[test]
+fn day_3() {
+    check_sample(3);
+}
+
+#[test]
+fn day_4() {
+    check_sample(4);
+}
+
+#[test]
+fn day_5() {
+    check_sample(5);
+}
+
+#[test]
+fn day_6() {
+    check_sample(6);
+}
+
+#[test]
+fn day_7() {
+    check_sample(7);
+}
+
+#[test]
+fn day_8() {
+    check_sample(8);
+}
+
+#[test]
+fn day_9() {
+    check_sample(9);
+}
+
+#[test]
+fn day_10() {
+    check_sample(10);
+}
+
+#[test]
+fn day_11() {
+    check_sample(11);
+}
+
+#[test]
+fn day_12() {
+    check_sample(12);
+}
+
+#[test]
+fn day_13() {
+    check_sample(13);
+}
+
+#[test]
+fn day_14() {
+    check_sample(14);
+}
+
+#[test]
+fn day_15() {
+    check_sample(15);
+}
+
+#[test]
+fn day_16() {
+    check_sample(16);
+}
+
+#[test]
+fn day_22() {
+    check_sample(22);
+}
+
+#[test]
+fn day_25() {
+    check_sample(25);
+}
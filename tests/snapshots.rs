@@ -0,0 +1,74 @@
+/*
+** tests/snapshots.rs
+*/
+
+mod common;
+
+use aoc2022::types::Solution;
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// compares the output against its snapshot in tests/snapshots/
+///
+/// set UPDATE_SNAPSHOTS=1 to write new snapshots, or to overwrite existing
+/// snapshots after a deliberate formatting change
+fn assert_snapshot(name: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("snapshots")
+        .join(format!("{}.snap", name));
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "missing snapshot {}, run with UPDATE_SNAPSHOTS=1 to write it",
+            path.display()
+        )
+    });
+    assert!(
+        expected == actual,
+        "snapshot {} does not match\n--- expected\n{}\n--- actual\n{}",
+        name,
+        expected,
+        actual
+    );
+}
+
+fn extra<'a>(solution: &'a Solution, label: &str) -> &'a str {
+    solution
+        .extras
+        .iter()
+        .find(|(l, _)| l == label)
+        .map(|(_, contents)| contents.as_str())
+        .unwrap()
+}
+
+#[test]
+fn day_7_directory_tree() {
+    let solution = common::run_sample(7);
+    assert_snapshot("day_7_directory_tree", extra(&solution, "directory tree"));
+}
+
+#[test]
+fn day_10_image() {
+    let solution = common::run_sample(10);
+    let image = solution.part_2.unwrap().to_string();
+    assert_snapshot("day_10_image", &image);
+}
+
+#[test]
+fn day_16_schedules() {
+    let solution = common::run_sample(16);
+    assert_snapshot(
+        "day_16_part_1_schedule",
+        extra(&solution, "part 1 schedule"),
+    );
+    assert_snapshot(
+        "day_16_part_2_schedule",
+        extra(&solution, "part 2 schedule"),
+    );
+}
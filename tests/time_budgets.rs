@@ -0,0 +1,73 @@
+/*
+** tests/time_budgets.rs
+*/
+
+use aoc2022::context::Context;
+use aoc2022::parts::Parts;
+use aoc2022::puzzles;
+use aoc2022::utils;
+
+use std::env;
+use std::path::Path;
+use std::time::Instant;
+
+/// budget for days which do not have their own, in milliseconds
+const DEFAULT_BUDGET_MS: u64 = 500;
+/// per-day budgets for the slower days, in milliseconds
+const BUDGETS_MS: [(usize, u64); 2] = [(15, 2000), (16, 60000)];
+
+/// gets the budget for the given day, which can be overridden with
+/// AOC_TIME_BUDGET_<DAY>
+fn budget_ms(day: usize) -> u64 {
+    if let Ok(budget) = env::var(format!("AOC_TIME_BUDGET_{}", day)) {
+        return budget
+            .parse()
+            .unwrap_or_else(|_| panic!("invalid time budget for day {}: {}", day, budget));
+    }
+    BUDGETS_MS
+        .iter()
+        .find(|(d, _)| *d == day)
+        .map(|(_, budget)| *budget)
+        .unwrap_or(DEFAULT_BUDGET_MS)
+}
+
+/// runs every day with a real input and checks that it finishes within its
+/// time budget
+///
+/// timings are meaningless in debug builds, so this only runs when enabled:
+/// `AOC_TIME_BUDGETS=1 cargo test --release --test time_budgets`
+#[test]
+fn time_budgets() {
+    if env::var_os("AOC_TIME_BUDGETS").is_none() {
+        eprintln!("skipping time budgets, set AOC_TIME_BUDGETS to enable");
+        return;
+    }
+    if cfg!(debug_assertions) {
+        panic!("time budgets must be checked with --release");
+    }
+
+    let input_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("input");
+    let mut overruns = Vec::new();
+    for day in 1..=puzzles::N_DAYS {
+        // skip days without a real input
+        let input_path = input_dir.join(format!("D{}.txt", day));
+        if !input_path.exists() {
+            continue;
+        }
+        let input = utils::read_file(&input_path).unwrap();
+        let tstart = Instant::now();
+        puzzles::DAYS[day - 1](&input, Parts::Both, &Context::none().with_sample(false)).unwrap();
+        let elapsed_ms = tstart.elapsed().as_millis();
+        let budget = budget_ms(day);
+        eprintln!("day {}: {}ms (budget {}ms)", day, elapsed_ms, budget);
+        if elapsed_ms > budget as u128 {
+            overruns.push(format!("day {}: {}ms > {}ms", day, elapsed_ms, budget));
+        }
+    }
+
+    assert!(
+        overruns.is_empty(),
+        "over time budget:\n{}",
+        overruns.join("\n")
+    );
+}